@@ -9,10 +9,19 @@
 #![cfg_attr(feature = "stm32wl5x_cm0p", allow(dead_code))]
 #![cfg_attr(feature = "stm32wl5x_cm0p", allow(unused_imports))]
 
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub use pac::adc::ccr::PRESC_A as Prescaler;
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub use pac::adc::cfgr1::ALIGN_A as Alignment;
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub use pac::adc::cfgr1::RES_A as Resolution;
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub use pac::adc::cfgr1::{EXTEN_A as TrigEdge, EXTSEL_A as ExtTrig};
 #[cfg(not(feature = "stm32wl5x_cm0p"))]
 pub use pac::adc::cfgr2::{OVSR_A as OversampleRatio, OVSS_A as OversampleShift};
 
 use crate::gpio;
+use crate::rcc::{self, Peripheral};
 use crate::Ratio;
 
 use super::pac;
@@ -20,6 +29,14 @@ use core::{ptr::read_volatile, time::Duration};
 
 use embedded_hal::blocking::delay::DelayUs;
 
+#[cfg(feature = "async")]
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
 // DS13293 rev 1 table 12
 // TS ADC raw data acquired at 30 °C (± 5 °C),
 // VDDA = VREF+ = 3.3 V (± 10 mV)
@@ -56,6 +73,14 @@ pub const TS_START_TYP: Duration = Duration::from_micros(70);
 ///
 /// Value from DS13293 Rev 1 page 121 table 83 "TS characteristics"
 pub const TS_START_MAX: Duration = Duration::from_micros(120);
+/// t<sub>S_vrefint</sub> internal reference voltage minimum sampling time
+///
+/// Value from DS13293 Rev 1 "Embedded internal reference voltage"
+pub const VREF_MIN_SAMPLE: Duration = Duration::from_micros(4);
+/// t<sub>S_vbat</sub> V<sub>BAT</sub> minimum sampling time
+///
+/// Value from DS13293 Rev 1 "V<sub>BAT</sub> monitoring characteristics"
+pub const VBAT_MIN_SAMPLE: Duration = Duration::from_micros(12);
 
 /// t<sub>ADCVREG_SETUP</sub> ADC voltage regulator maximum startup time
 ///
@@ -81,6 +106,137 @@ pub const T_ADCVREG_SETUP_MICROS: u8 = T_ADCVREG_SETUP.as_micros() as u8;
 /// Channels 0-17, but without 15 and 16 because they are reserved.
 const CH_MASK: u32 = 0x27FFF;
 
+/// Number of bits to shift left-aligned data right to make it right-aligned.
+///
+/// See RM0453 rev 2 section 18.4.26 "Data alignment and resolution".
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const fn align_shift(align: Alignment, res: pac::adc::cfgr1::RES_A) -> u8 {
+    use pac::adc::cfgr1::RES_A;
+
+    match align {
+        Alignment::Right => 0,
+        Alignment::Left => match res {
+            RES_A::Bits12 => 4,
+            RES_A::Bits10 => 6,
+            RES_A::Bits8 => 8,
+            // 6-bit data is left-aligned on the byte, not the half-word
+            RES_A::Bits6 => 2,
+        },
+    }
+}
+
+/// Conversion time of a sample, including sampling and oversampling.
+///
+/// `oversamples` is the number of conversions accumulated per result.
+///
+/// See RM0453 rev 2 section 18.4.14 "Programmable resolution" and
+/// section 18.4.29 "Oversampler".
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const fn conversion_time(ts: Ts, res: Resolution, oversamples: u32, hz: u32) -> Duration {
+    if hz == 0 {
+        return Duration::MAX;
+    }
+    // successive approximation time in half cycles
+    let sar_half: u64 = match res {
+        Resolution::Bits12 => 25,
+        Resolution::Bits10 => 21,
+        Resolution::Bits8 => 17,
+        Resolution::Bits6 => 13,
+    };
+    // sample times are always in half cycles
+    let ts_half: u64 = *ts.cycles().numer() as u64;
+    let half_cycles: u64 = (ts_half + sar_half).saturating_mul(oversamples as u64);
+    Duration::from_nanos(half_cycles.saturating_mul(1_000_000_000) / (2 * hz as u64))
+}
+
+/// Base 2 logarithm for positive, normal `x`, without `libm`.
+///
+/// Accurate to about 1e-5.
+#[cfg(feature = "float")]
+fn log2(x: f32) -> f32 {
+    let bits: u32 = x.to_bits();
+    let exp: i32 = ((bits >> 23) & 0xFF) as i32 - 127;
+    // mantissa in [1, 2)
+    let m: f32 = f32::from_bits((bits & 0x007F_FFFF) | 0x3F80_0000);
+
+    // ln(m) = 2 * atanh((m - 1) / (m + 1)), series converges for t <= 1/3
+    let t: f32 = (m - 1.0) / (m + 1.0);
+    let t2: f32 = t * t;
+    let ln: f32 = 2.0 * t * (1.0 + t2 * (1.0 / 3.0 + t2 * (1.0 / 5.0 + t2 * (1.0 / 7.0))));
+
+    exp as f32 + ln * core::f32::consts::LOG2_E
+}
+
+/// Effective number of bits from the sample variance in LSB².
+///
+/// ENOB = N - log<sub>2</sub>(σ / σ<sub>q</sub>), where σ<sub>q</sub> is the
+/// ideal quantization noise of 1/√12 LSB.
+///
+/// Saturates at `bits` when the noise is below the quantization noise.
+#[cfg(feature = "float")]
+fn enob_from_variance(bits: u8, variance: f32) -> f32 {
+    let noise: f32 = 12.0 * variance;
+    if noise <= 1.0 {
+        f32::from(bits)
+    } else {
+        f32::from(bits) - 0.5 * log2(noise)
+    }
+}
+
+/// Sample variance in LSB² with Welford's algorithm.
+///
+/// Returns `0.0` with fewer than two samples.
+#[cfg(feature = "float")]
+fn sample_variance(samples: impl Iterator<Item = u16>) -> f32 {
+    let mut n: u32 = 0;
+    let mut mean: f32 = 0.0;
+    let mut m2: f32 = 0.0;
+    for sample in samples {
+        n += 1;
+        let x: f32 = f32::from(sample);
+        let delta: f32 = x - mean;
+        mean += delta / (n as f32);
+        m2 += delta * (x - mean);
+    }
+    if n < 2 {
+        0.0
+    } else {
+        m2 / ((n - 1) as f32)
+    }
+}
+
+/// Change from the `prev` to the `new` 7-bit calibration factor.
+const fn calfact_delta(prev: u8, new: u8) -> i8 {
+    ((new & 0x7F) as i8) - ((prev & 0x7F) as i8)
+}
+
+/// Stop a conversion with `ADSTP` if `ADSTART` is set.
+///
+/// This waits for `ADSTART` to clear rather than `ADSTP`, a conversion that
+/// ends between the check and the stop request clears `ADSTART` and the stop
+/// request has no effect.
+///
+/// Returns `true` if a stop was requested.
+/// Polls forever if `ADSTART` never clears, for example when the ADC clock
+/// is stopped.
+fn stop_with<A: FnMut() -> bool, S: FnOnce()>(mut adstart: A, adstp: S) -> bool {
+    if adstart() {
+        adstp();
+        while adstart() {}
+        true
+    } else {
+        false
+    }
+}
+
+/// Subtract two samples, saturating at the bounds of `i16`.
+///
+/// Only oversampled data can exceed the `i16` range.
+fn saturating_diff(pos: u16, neg: u16) -> i16 {
+    let diff: i32 = i32::from(pos) - i32::from(neg);
+    diff.clamp(i16::MIN.into(), i16::MAX.into()) as i16
+}
+
 /// Interrupt masks
 ///
 /// Used for [`Adc::set_isr`] and [`Adc::set_ier`].
@@ -120,6 +276,424 @@ pub fn vref_cal() -> u16 {
     unsafe { read_volatile(0x1FFF_75AA as *const u16) }
 }
 
+/// Calibration voltage of [`vref_cal`] in millivolts.
+const VREF_CAL_MV: u32 = 3300;
+
+/// V<sub>BAT</sub> voltage in millivolts from 12-bit samples of the
+/// V<sub>BAT</sub> and V<sub>REFINT</sub> channels.
+///
+/// V<sub>DDA</sub> is `VREF_CAL_MV * vref_cal / vref`, the V<sub>BAT</sub>
+/// sample is a third of V<sub>BAT</sub> relative to V<sub>DDA</sub>.
+fn vbat_mv(vbat: u16, vref: u16, vref_cal: u16) -> u16 {
+    if vref == 0 {
+        return u16::MAX;
+    }
+    let mv: u64 = 3 * u64::from(vbat) * u64::from(VREF_CAL_MV) * u64::from(vref_cal)
+        / (u64::from(vref) * 4095);
+    u16::try_from(mv).unwrap_or(u16::MAX)
+}
+
+/// V<sub>DDA</sub> in millivolts from a sample of the V<sub>REFINT</sub>
+/// channel with a right-aligned full scale of `max`.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+fn vdda_mv(vref: u16, vref_cal: u16, max: u16) -> u16 {
+    if vref == 0 {
+        return u16::MAX;
+    }
+    // vref_cal is a 12-bit sample
+    let mv: u64 =
+        u64::from(VREF_CAL_MV) * u64::from(vref_cal) * u64::from(max) / (u64::from(vref) * 4095);
+    u16::try_from(mv).unwrap_or(u16::MAX)
+}
+
+/// Largest right-aligned result for a resolution and oversampling
+/// configuration.
+///
+/// Oversampling accumulates `ratio` samples, then shifts the sum right by
+/// `shift`, the result is at most 16 bits.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const fn full_scale(res: Resolution, ovs: Option<(OversampleRatio, OversampleShift)>) -> u16 {
+    let bits: u8 = match res {
+        Resolution::Bits12 => 12,
+        Resolution::Bits10 => 10,
+        Resolution::Bits8 => 8,
+        Resolution::Bits6 => 6,
+    };
+    let bits: u8 = match ovs {
+        Some((ratio, shift)) => (bits + ratio as u8 + 1).saturating_sub(shift as u8),
+        None => bits,
+    };
+    if bits >= 16 {
+        u16::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// Oversampling shift that divides the sum by the ratio.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const fn avg_shift(ratio: OversampleRatio) -> OversampleShift {
+    match ratio {
+        OversampleRatio::Mul2 => OversampleShift::Shift1,
+        OversampleRatio::Mul4 => OversampleShift::Shift2,
+        OversampleRatio::Mul8 => OversampleShift::Shift3,
+        OversampleRatio::Mul16 => OversampleShift::Shift4,
+        OversampleRatio::Mul32 => OversampleShift::Shift5,
+        OversampleRatio::Mul64 => OversampleShift::Shift6,
+        OversampleRatio::Mul128 => OversampleShift::Shift7,
+        OversampleRatio::Mul256 => OversampleShift::Shift8,
+    }
+}
+
+/// Counts of a sample at `mv` millivolts, the inverse of
+/// [`Millivolts`].
+///
+/// Saturates at `max`, a V<sub>DDA</sub> of 0 is full scale.
+fn mv_counts(mv: u16, vdda_mv: u16, max: u16) -> u16 {
+    if vdda_mv == 0 {
+        return max;
+    }
+    let counts: u32 =
+        (u32::from(mv) * u32::from(max) + u32::from(vdda_mv) / 2) / u32::from(vdda_mv);
+    u16::try_from(counts).unwrap_or(u16::MAX).min(max)
+}
+
+/// Analog watchdog threshold register value for counts at a resolution.
+///
+/// The threshold registers are 12-bit, at lower resolutions the counts are
+/// compared to the most significant bits and the low bits are zero.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const fn awd_threshold(counts: u16, res: Resolution) -> u16 {
+    let shift: u8 = 12 - res_bits(res);
+    let max: u16 = full_scale(res, None);
+    let counts: u16 = if counts > max { max } else { counts };
+    counts << shift
+}
+
+/// Format of a sample returned by [`Adc::read_as`].
+///
+/// Implement this to add a format, the implementations in this module are:
+///
+/// * [`Raw`]: the sample in counts, `u16`
+/// * [`Millivolts`]: the sample in millivolts relative to the measured
+///   V<sub>DDA</sub>, `u16`
+/// * [`Normalized`]: the sample relative to full scale in `0.0..=1.0`,
+///   `f32` (requires the `float` feature)
+///
+/// # Example
+///
+/// A format for a sensor with a linear transfer function.
+///
+/// ```
+/// use stm32wlxx_hal::adc::SampleFormat;
+///
+/// /// Pressure in pascal, 0 Pa at 0 V to 100 kPa at V<sub>DDA</sub>.
+/// struct Pascal;
+///
+/// impl SampleFormat for Pascal {
+///     type Output = u32;
+///
+///     fn convert(sample: u16, max: u16, _vdda_mv: u16) -> u32 {
+///         u32::from(sample) * 100_000 / u32::from(max)
+///     }
+/// }
+///
+/// assert_eq!(Pascal::convert(2048, 4095, 3300), 50_012);
+/// ```
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub trait SampleFormat {
+    /// Type of the converted sample.
+    type Output;
+
+    /// `true` if [`convert`](Self::convert) uses `vdda_mv`.
+    ///
+    /// [`Adc::read_as`] only samples the internal voltage reference to
+    /// measure V<sub>DDA</sub> if this is `true`, otherwise `vdda_mv` is `0`.
+    const VDDA: bool = false;
+
+    /// Convert a right-aligned sample.
+    ///
+    /// * `sample`: right-aligned sample.
+    /// * `max`: full scale sample for the resolution and oversampling
+    ///   configuration, for example `4095` for 12-bit samples.
+    /// * `vdda_mv`: measured V<sub>DDA</sub> in millivolts.
+    fn convert(sample: u16, max: u16, vdda_mv: u16) -> Self::Output;
+}
+
+/// Sample in counts, see [`SampleFormat`].
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::adc::{Raw, SampleFormat};
+///
+/// assert_eq!(Raw::convert(2048, 4095, 3300), 2048);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub struct Raw;
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl SampleFormat for Raw {
+    type Output = u16;
+
+    #[inline]
+    fn convert(sample: u16, _max: u16, _vdda_mv: u16) -> u16 {
+        sample
+    }
+}
+
+/// Sample in millivolts, see [`SampleFormat`].
+///
+/// The sample is scaled by V<sub>DDA</sub> measured with the internal
+/// voltage reference, fractional millivolts are rounded towards zero.
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::adc::{Millivolts, SampleFormat};
+///
+/// assert_eq!(Millivolts::convert(2048, 4095, 3300), 1650);
+/// assert_eq!(Millivolts::convert(4095, 4095, 3000), 3000);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub struct Millivolts;
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl SampleFormat for Millivolts {
+    type Output = u16;
+    const VDDA: bool = true;
+
+    #[inline]
+    fn convert(sample: u16, max: u16, vdda_mv: u16) -> u16 {
+        let mv: u32 = u32::from(sample) * u32::from(vdda_mv) / u32::from(max.max(1));
+        u16::try_from(mv).unwrap_or(u16::MAX)
+    }
+}
+
+/// Sample relative to full scale in `0.0..=1.0`, see [`SampleFormat`].
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::adc::{Normalized, SampleFormat};
+///
+/// assert_eq!(Normalized::convert(0, 4095, 3300), 0.0);
+/// assert_eq!(Normalized::convert(4095, 4095, 3300), 1.0);
+/// assert!((Normalized::convert(2048, 4095, 3300) - 0.5).abs() < 0.001);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(all(feature = "float", not(feature = "stm32wl5x_cm0p")))]
+pub struct Normalized;
+
+#[cfg(all(feature = "float", not(feature = "stm32wl5x_cm0p")))]
+impl SampleFormat for Normalized {
+    type Output = f32;
+
+    #[inline]
+    fn convert(sample: u16, max: u16, _vdda_mv: u16) -> f32 {
+        f32::from(sample) / f32::from(max.max(1))
+    }
+}
+
+/// Battery discharge curve, for [`Adc::battery_percent`].
+///
+/// This maps a battery voltage to a state of charge with linear
+/// interpolation between points.
+///
+/// This is a rough estimate, the discharge curve depends on the load,
+/// temperature, and age of the battery.
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::adc::BatteryCurve;
+///
+/// // (millivolts, percent)
+/// const CURVE: BatteryCurve = BatteryCurve::new(&[(2000, 0), (3000, 100)]);
+/// assert_eq!(CURVE.percent(2500), 50);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BatteryCurve<'a> {
+    points: &'a [(u16, u8)],
+}
+
+impl<'a> BatteryCurve<'a> {
+    /// Typical CR2032 lithium coin cell curve under a light load.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::BatteryCurve;
+    ///
+    /// assert_eq!(BatteryCurve::CR2032.percent(3000), 100);
+    /// assert_eq!(BatteryCurve::CR2032.percent(2850), 70);
+    /// assert_eq!(BatteryCurve::CR2032.percent(1800), 0);
+    /// ```
+    pub const CR2032: BatteryCurve<'static> = BatteryCurve::new(&[
+        (2000, 0),
+        (2400, 10),
+        (2500, 20),
+        (2600, 30),
+        (2700, 40),
+        (2800, 60),
+        (2900, 80),
+        (3000, 100),
+    ]);
+
+    /// Typical single cell lithium polymer curve at rest.
+    ///
+    /// **Note:** A charged LiPo cell exceeds the V<sub>BAT</sub> maximum of
+    /// 3.6 V, this curve is for use with [`percent`](Self::percent) and a
+    /// voltage measured through an external divider.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::BatteryCurve;
+    ///
+    /// assert_eq!(BatteryCurve::LIPO.percent(4200), 100);
+    /// assert_eq!(BatteryCurve::LIPO.percent(3750), 30);
+    /// assert_eq!(BatteryCurve::LIPO.percent(3300), 0);
+    /// ```
+    pub const LIPO: BatteryCurve<'static> = BatteryCurve::new(&[
+        (3300, 0),
+        (3500, 5),
+        (3600, 10),
+        (3700, 20),
+        (3800, 40),
+        (3900, 60),
+        (4000, 80),
+        (4100, 90),
+        (4200, 100),
+    ]);
+
+    /// Create a new battery curve from `(millivolts, percent)` points.
+    ///
+    /// # Panics
+    ///
+    /// * `points` is empty
+    /// * The voltages in `points` are not strictly increasing
+    /// * A percentage in `points` is greater than 100
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::BatteryCurve;
+    ///
+    /// const CURVE: BatteryCurve = BatteryCurve::new(&[(2000, 0), (2800, 50), (3000, 100)]);
+    /// ```
+    pub const fn new(points: &'a [(u16, u8)]) -> Self {
+        core::assert!(!points.is_empty(), "battery curve has no points");
+        let mut i: usize = 0;
+        while i < points.len() {
+            core::assert!(points[i].1 <= 100, "battery curve percent exceeds 100");
+            if i > 0 {
+                core::assert!(
+                    points[i - 1].0 < points[i].0,
+                    "battery curve voltages are not strictly increasing"
+                );
+            }
+            i += 1;
+        }
+        BatteryCurve { points }
+    }
+
+    /// Interpolate the state of charge in percent from a voltage in
+    /// millivolts.
+    ///
+    /// Voltages outside of the curve saturate to the first or last point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::BatteryCurve;
+    ///
+    /// const CURVE: BatteryCurve = BatteryCurve::new(&[(2000, 0), (2800, 50), (3000, 100)]);
+    /// assert_eq!(CURVE.percent(1000), 0);
+    /// assert_eq!(CURVE.percent(2400), 25);
+    /// assert_eq!(CURVE.percent(2900), 75);
+    /// assert_eq!(CURVE.percent(9000), 100);
+    /// ```
+    pub fn percent(&self, mv: u16) -> u8 {
+        let (first_mv, first_pct) = self.points[0];
+        if mv <= first_mv {
+            return first_pct;
+        }
+        for pair in self.points.windows(2) {
+            let (lo_mv, lo_pct) = pair[0];
+            let (hi_mv, hi_pct) = pair[1];
+            if mv <= hi_mv {
+                let span: i32 = i32::from(hi_mv - lo_mv);
+                let delta: i32 = i32::from(hi_pct) - i32::from(lo_pct);
+                let pct: i32 = i32::from(lo_pct) + i32::from(mv - lo_mv) * delta / span;
+                return pct as u8;
+            }
+        }
+        self.points[self.points.len() - 1].1
+    }
+}
+
+/// ADC errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The output buffer is shorter than the number of channels.
+    Length,
+    /// The target sample rate is not achievable with the available clocks.
+    Rate,
+    /// More than two distinct sample times were requested.
+    SampleTimes,
+    /// The temperature sensor is disabled or has not finished starting up.
+    NotReady,
+}
+
+/// Returns `true` if the temperature sensor startup time has elapsed.
+fn ts_startup_elapsed(elapsed: Duration) -> bool {
+    elapsed >= TS_START_MAX
+}
+
+/// Panic if the GPIO connected to `ch` is not in analog mode.
+#[inline]
+#[track_caller]
+fn assert_analog(analog: bool, ch: Ch) {
+    assert!(
+        analog,
+        "GPIO for ADC channel {} is not in analog mode",
+        ch as u8
+    );
+}
+
+/// Sort `(Ch, Ts)` pairs into the two sample time selections.
+///
+/// Returns the `sel1` channel mask and the `sel0` and `sel1` sample times.
+fn sample_buckets(pairs: &[(Ch, Ts)]) -> Result<(u32, Ts, Ts), Error> {
+    let sel0: Ts = match pairs.first() {
+        Some((_, ts)) => *ts,
+        None => return Ok((0, Ts::default(), Ts::default())),
+    };
+    let mut sel1: Option<Ts> = None;
+    let mut mask: u32 = 0;
+
+    for (ch, ts) in pairs.iter().copied() {
+        if ts == sel0 {
+            mask &= !ch.mask();
+        } else if sel1.is_none() || sel1 == Some(ts) {
+            sel1 = Some(ts);
+            mask |= ch.mask();
+        } else {
+            return Err(Error::SampleTimes);
+        }
+    }
+
+    Ok((mask, sel0, sel1.unwrap_or(sel0)))
+}
+
 /// ADC clock mode
 ///
 /// In all synchronous clock modes, there is no jitter in the delay from a
@@ -166,57 +740,291 @@ impl Clk {
     }
 }
 
-/// ADC sample times
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(u8)]
-pub enum Ts {
-    /// 1.5 ADC clock cycles
-    Cyc1 = 0,
-    /// 3.5 ADC clock cycles
-    Cyc3 = 1,
-    /// 7.5 ADC clock cycles
-    Cyc7 = 2,
-    /// 12.5 ADC clock cycles
-    Cyc12 = 3,
-    /// 19.5 ADC clock cycles
-    Cyc19 = 4,
-    /// 39.5 ADC clock cycles
-    Cyc39 = 5,
-    /// 79.5 ADC clock cycles
-    Cyc79 = 6,
-    /// 160.5 ADC clock cycles
-    Cyc160 = 7,
+/// Maximum ADC clock frequency.
+///
+/// See DS13293 rev 1 table "ADC characteristics".
+pub(crate) const ADC_MAX_HZ: u32 = 35_000_000;
+/// Minimum ADC clock frequency.
+///
+/// See DS13293 rev 1 table "ADC characteristics".
+const ADC_MIN_HZ: u32 = 140_000;
+
+/// Returns `true` if an ADC clock frequency exceeds the datasheet maximum.
+const fn clock_too_fast(hz: u32) -> bool {
+    hz > ADC_MAX_HZ
 }
 
-impl Default for Ts {
-    /// Reset value of the sample time.
-    fn default() -> Self {
-        Ts::Cyc1
+/// Returns `true` if the sample time `ts` at an ADC clock frequency of `hz`
+/// is shorter than the datasheet minimum sampling time of `ch`.
+///
+/// Channels without a documented minimum are never too short.
+fn sample_time_short(ch: Ch, ts: Ts, hz: u32) -> bool {
+    match ch.min_sample_duration() {
+        Some(min) => ts.as_duration(hz) < min,
+        None => false,
     }
 }
 
-impl Ts {
-    /// Maximum sample time, 160.5 ADC clock cycles.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use stm32wlxx_hal::adc::Ts;
-    ///
-    /// assert_eq!(Ts::MAX, Ts::Cyc160);
-    /// ```
-    pub const MAX: Self = Self::Cyc160;
+/// Returns `true` if the sample time `ts` of the internal channel `ch` is too
+/// short to measure.
+///
+/// Internal channels are only checked if they are `enabled` in `ADC_CCR`, or
+/// selected for conversion in the `chselr` channel mask.
+fn internal_sample_time_short(ch: Ch, enabled: bool, chselr: u32, ts: Ts, hz: u32) -> bool {
+    (enabled || chselr & ch.mask() != 0) && sample_time_short(ch, ts, hz)
+}
 
-    /// Minimum sample time, 1.5 ADC clock cycles.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use stm32wlxx_hal::adc::Ts;
-    ///
-    /// assert_eq!(Ts::MIN, Ts::Cyc1);
-    /// ```
-    pub const MIN: Self = Self::Cyc1;
+/// All prescalers, ordered by divisor.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const PRESCALERS: [Prescaler; 12] = [
+    Prescaler::Div1,
+    Prescaler::Div2,
+    Prescaler::Div4,
+    Prescaler::Div6,
+    Prescaler::Div8,
+    Prescaler::Div10,
+    Prescaler::Div12,
+    Prescaler::Div16,
+    Prescaler::Div32,
+    Prescaler::Div64,
+    Prescaler::Div128,
+    Prescaler::Div256,
+];
+
+/// Prescaler divisor.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const fn presc_div(presc: Prescaler) -> u32 {
+    match presc {
+        Prescaler::Div1 => 1,
+        Prescaler::Div2 => 2,
+        Prescaler::Div4 => 4,
+        Prescaler::Div6 => 6,
+        Prescaler::Div8 => 8,
+        Prescaler::Div10 => 10,
+        Prescaler::Div12 => 12,
+        Prescaler::Div16 => 16,
+        Prescaler::Div32 => 32,
+        Prescaler::Div64 => 64,
+        Prescaler::Div128 => 128,
+        Prescaler::Div256 => 256,
+    }
+}
+
+/// Number of bits for a resolution.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+const fn res_bits(res: Resolution) -> u8 {
+    match res {
+        Resolution::Bits12 => 12,
+        Resolution::Bits10 => 10,
+        Resolution::Bits8 => 8,
+        Resolution::Bits6 => 6,
+    }
+}
+
+/// ADC clock and sampling configuration returned by [`plan`].
+///
+/// Apply the plan with [`Adc::apply_plan`].
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AdcPlan {
+    /// ADC clock source.
+    pub clk: Clk,
+    /// ADC clock prescaler.
+    ///
+    /// This is always [`Prescaler::Div1`] for the synchronous clock modes,
+    /// the prescaler only applies to the asynchronous clocks.
+    pub prescaler: Prescaler,
+    /// Sample time for all channels.
+    pub ts: Ts,
+    /// Conversion resolution.
+    pub resolution: Resolution,
+    /// ADC clock frequency in hertz.
+    pub clock_hz: u32,
+    /// Maximum sample rate in samples per second.
+    pub sps: u32,
+}
+
+/// Plan a configuration from a list of clock sources and frequencies.
+///
+/// The fastest clock within the ADC frequency limits is selected to minimize
+/// the conversion time, ties go to the source listed first.
+/// The highest resolution, then the longest sample time, that achieve the
+/// target rate are selected with that clock.
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+fn plan_from_clocks(target_sps: u32, clocks: &[(Clk, u32)]) -> Result<AdcPlan, Error> {
+    let mut best: Option<(Clk, Prescaler, u32)> = None;
+    for &(clk, src_hz) in clocks {
+        let prescalers: &[Prescaler] = match clk.ckmode() {
+            pac::adc::cfgr2::CKMODE_A::Adclk => &PRESCALERS,
+            _ => &PRESCALERS[..1],
+        };
+        for &prescaler in prescalers {
+            let hz: u32 = src_hz / presc_div(prescaler);
+            if (ADC_MIN_HZ..=ADC_MAX_HZ).contains(&hz) && best.map_or(true, |(_, _, b)| hz > b) {
+                best = Some((clk, prescaler, hz));
+            }
+        }
+    }
+    let (clk, prescaler, hz) = best.ok_or(Error::Rate)?;
+
+    for resolution in [
+        Resolution::Bits12,
+        Resolution::Bits10,
+        Resolution::Bits8,
+        Resolution::Bits6,
+    ] {
+        // conversion time is the resolution in bits plus 0.5 cycles
+        // RM0453 rev 2 section 18.4.14 "Single conversion mode"
+        let conv_half_cycles: u32 = u32::from(res_bits(resolution)) * 2 + 1;
+        for bits in (0..=7).rev() {
+            let ts: Ts = Ts::from_bits(bits);
+            let total_half_cycles: u32 = u32::from(*ts.cycles().numer()) + conv_half_cycles;
+            let sps: u32 = ((u64::from(hz) * 2) / u64::from(total_half_cycles)) as u32;
+            if sps >= target_sps {
+                return Ok(AdcPlan {
+                    clk,
+                    prescaler,
+                    ts,
+                    resolution,
+                    clock_hz: hz,
+                    sps,
+                });
+            }
+        }
+    }
+
+    Err(Error::Rate)
+}
+
+/// Plan the ADC clock and sampling configuration for a target sample rate.
+///
+/// This selects the fastest ADC clock within the ADC frequency limits
+/// to minimize the conversion time, then the highest resolution and the
+/// longest sample time that achieve the target rate.
+///
+/// Only clocks that are currently running are considered:
+///
+/// * HSI16 if it is ready
+/// * PLLP if the PLL is ready and the P output is enabled
+/// * SYSCLK
+/// * PCLK / 2 and PCLK / 4
+///
+/// [`Clk::PClk`] is never selected, it requires a 50% duty cycle PCLK.
+///
+/// The sample rate is the maximum rate of back-to-back conversions,
+/// it does not include any software overhead.
+///
+/// Returns [`Error::Rate`] if the target rate cannot be achieved.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{
+///     adc::{self, Adc, AdcPlan},
+///     pac,
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+///
+/// let plan: AdcPlan = adc::plan(100_000, &dp.RCC)?;
+/// let mut adc: Adc = Adc::new(dp.ADC, plan.clk, &mut dp.RCC);
+/// adc.apply_plan(&plan, &mut dp.RCC);
+/// # Ok::<(), stm32wlxx_hal::adc::Error>(())
+/// ```
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub fn plan(target_sps: u32, rcc: &pac::RCC) -> Result<AdcPlan, Error> {
+    let cr = rcc.cr.read();
+    let pllcfgr = rcc.pllcfgr.read();
+    let cfgr = rcc.cfgr.read();
+
+    let pclk_hz: u32 = crate::rcc::pclk2(rcc, &cfgr).to_integer();
+    let hsi_hz: u32 = if cr.hsirdy().is_ready() {
+        16_000_000
+    } else {
+        0
+    };
+    let pllp_hz: u32 = if cr.pllrdy().is_locked() && pllcfgr.pllpen().is_enabled() {
+        crate::rcc::pllpclk(rcc, &pllcfgr).to_integer()
+    } else {
+        0
+    };
+
+    plan_from_clocks(
+        target_sps,
+        &[
+            (Clk::PClkDiv2, pclk_hz / 2),
+            (Clk::PClkDiv4, pclk_hz / 4),
+            (Clk::RccSysClk, crate::rcc::sysclk(rcc, &cfgr).to_integer()),
+            (Clk::RccPllP, pllp_hz),
+            (Clk::RccHsi, hsi_hz),
+        ],
+    )
+}
+
+/// ADC sample times
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Ts {
+    /// 1.5 ADC clock cycles
+    Cyc1 = 0,
+    /// 3.5 ADC clock cycles
+    Cyc3 = 1,
+    /// 7.5 ADC clock cycles
+    Cyc7 = 2,
+    /// 12.5 ADC clock cycles
+    Cyc12 = 3,
+    /// 19.5 ADC clock cycles
+    Cyc19 = 4,
+    /// 39.5 ADC clock cycles
+    Cyc39 = 5,
+    /// 79.5 ADC clock cycles
+    Cyc79 = 6,
+    /// 160.5 ADC clock cycles
+    Cyc160 = 7,
+}
+
+impl Default for Ts {
+    /// Reset value of the sample time.
+    fn default() -> Self {
+        Ts::Cyc1
+    }
+}
+
+impl Ts {
+    /// Maximum sample time, 160.5 ADC clock cycles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::Ts;
+    ///
+    /// assert_eq!(Ts::MAX, Ts::Cyc160);
+    /// ```
+    pub const MAX: Self = Self::Cyc160;
+
+    /// Minimum sample time, 1.5 ADC clock cycles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::Ts;
+    ///
+    /// assert_eq!(Ts::MIN, Ts::Cyc1);
+    /// ```
+    pub const MIN: Self = Self::Cyc1;
+
+    const fn from_bits(bits: u8) -> Ts {
+        match bits & 0b111 {
+            0 => Ts::Cyc1,
+            1 => Ts::Cyc3,
+            2 => Ts::Cyc7,
+            3 => Ts::Cyc12,
+            4 => Ts::Cyc19,
+            5 => Ts::Cyc39,
+            6 => Ts::Cyc79,
+            _ => Ts::Cyc160,
+        }
+    }
 
     /// Number of cycles.
     ///
@@ -253,6 +1061,9 @@ impl Ts {
     ///
     /// You can get the ADC frequency with [`Adc::clock_hz`].
     ///
+    /// A sample never completes without a clock, if `hz` is `0` this returns
+    /// [`Duration::MAX`].
+    ///
     /// # Example
     ///
     /// Assuming the ADC clock frequency is 16 MHz.
@@ -271,10 +1082,16 @@ impl Ts {
     /// assert_eq!(Ts::Cyc39.as_duration(FREQ), Duration::from_nanos(2_468));
     /// assert_eq!(Ts::Cyc79.as_duration(FREQ), Duration::from_nanos(4_968));
     /// assert_eq!(Ts::Cyc160.as_duration(FREQ), Duration::from_nanos(10_031));
+    ///
+    /// // no clock
+    /// assert_eq!(Ts::Cyc1.as_duration(0), Duration::MAX);
     /// ```
     ///
     /// [`Adc::clock_hz`]: crate::adc::Adc::clock_hz
     pub const fn as_duration(&self, hz: u32) -> Duration {
+        if hz == 0 {
+            return Duration::MAX;
+        }
         let numer: u64 = (*self.cycles().numer() as u64).saturating_mul(1_000_000_000);
         let denom: u64 = (*self.cycles().denom() as u64).saturating_mul(hz as u64);
         Duration::from_nanos(numer / denom)
@@ -360,6 +1177,75 @@ pub enum Ch {
 }
 
 impl Ch {
+    /// Datasheet minimum sampling time of the internal channels.
+    ///
+    /// Returns `None` for external channels and the DAC, which have no fixed
+    /// minimum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::{self, Ch};
+    ///
+    /// assert_eq!(Ch::Vts.min_sample_duration(), Some(adc::TS_MIN_SAMPLE));
+    /// assert_eq!(Ch::Vref.min_sample_duration(), Some(adc::VREF_MIN_SAMPLE));
+    /// assert_eq!(Ch::Vbat.min_sample_duration(), Some(adc::VBAT_MIN_SAMPLE));
+    /// assert_eq!(Ch::In0.min_sample_duration(), None);
+    /// assert_eq!(Ch::Dac.min_sample_duration(), None);
+    /// ```
+    pub const fn min_sample_duration(self) -> Option<Duration> {
+        match self {
+            Ch::Vts => Some(TS_MIN_SAMPLE),
+            Ch::Vref => Some(VREF_MIN_SAMPLE),
+            Ch::Vbat => Some(VBAT_MIN_SAMPLE),
+            _ => None,
+        }
+    }
+
+    /// Shortest sample time that meets the datasheet minimum sampling time of
+    /// an internal channel at an ADC clock frequency.
+    ///
+    /// The ADC clock frequency can be obtained with [`Adc::clock_hz`].
+    ///
+    /// Returns `None` for channels without a minimum sampling time (see
+    /// [`min_sample_duration`](Self::min_sample_duration)), or if the
+    /// minimum cannot be met at this frequency, even with [`Ts::MAX`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::{Ch, Ts};
+    ///
+    /// // 5 µs at 12 MHz is 60 cycles
+    /// assert_eq!(Ch::Vts.min_sample_time(12_000_000), Some(Ts::Cyc79));
+    /// // 4 µs at 12 MHz is 48 cycles
+    /// assert_eq!(Ch::Vref.min_sample_time(12_000_000), Some(Ts::Cyc79));
+    /// // 12 µs at 12 MHz is 144 cycles
+    /// assert_eq!(Ch::Vbat.min_sample_time(12_000_000), Some(Ts::Cyc160));
+    /// // 12 µs at 16 MHz is 192 cycles
+    /// assert_eq!(Ch::Vbat.min_sample_time(16_000_000), None);
+    /// assert_eq!(Ch::Vref.min_sample_time(1_000_000), Some(Ts::Cyc7));
+    /// assert_eq!(Ch::In0.min_sample_time(12_000_000), None);
+    /// ```
+    ///
+    /// [`Adc::clock_hz`]: crate::adc::Adc::clock_hz
+    pub const fn min_sample_time(self, hz: u32) -> Option<Ts> {
+        let min: Duration = match self.min_sample_duration() {
+            Some(min) => min,
+            None => return None,
+        };
+
+        let mut bits: u8 = 0;
+        while bits <= Ts::MAX as u8 {
+            let ts: Ts = Ts::from_bits(bits);
+            if ts.as_duration(hz).as_nanos() >= min.as_nanos() {
+                return Some(ts);
+            }
+            bits += 1;
+        }
+        None
+    }
+
     /// Bitmask of the channel.
     ///
     /// # Example
@@ -373,6 +1259,262 @@ impl Ch {
     pub const fn mask(self) -> u32 {
         1 << (self as u8)
     }
+
+    /// Get a channel from its index.
+    ///
+    /// Returns `None` if the index is not a valid channel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::Ch;
+    ///
+    /// assert_eq!(Ch::from_index(0), Some(Ch::In0));
+    /// assert_eq!(Ch::from_index(14), Some(Ch::Vbat));
+    /// assert_eq!(Ch::from_index(15), None);
+    /// assert_eq!(Ch::from_index(17), Some(Ch::Dac));
+    /// assert_eq!(Ch::from_index(18), None);
+    /// ```
+    pub const fn from_index(idx: u8) -> Option<Ch> {
+        match idx {
+            0 => Some(Ch::In0),
+            1 => Some(Ch::In1),
+            2 => Some(Ch::In2),
+            3 => Some(Ch::In3),
+            4 => Some(Ch::In4),
+            5 => Some(Ch::In5),
+            6 => Some(Ch::In6),
+            7 => Some(Ch::In7),
+            8 => Some(Ch::In8),
+            9 => Some(Ch::In9),
+            10 => Some(Ch::In10),
+            11 => Some(Ch::In11),
+            12 => Some(Ch::Vts),
+            13 => Some(Ch::Vref),
+            14 => Some(Ch::Vbat),
+            17 => Some(Ch::Dac),
+            _ => None,
+        }
+    }
+}
+
+/// Iterator over the results of a conversion sequence.
+///
+/// Created with [`Adc::sequence`].
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+struct Sequence<'a> {
+    adc: &'a mut Adc,
+    remaining: u32,
+    backward: bool,
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl<'a> Iterator for Sequence<'a> {
+    type Item = (Ch, u16);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let idx: u32 = if self.backward {
+            31 - self.remaining.leading_zeros()
+        } else {
+            self.remaining.trailing_zeros()
+        };
+        self.remaining &= !(1 << idx);
+
+        let data: u16 = self.adc.poll_data();
+        if self.remaining == 0 {
+            self.adc.adc.isr.write(|w| w.eos().set_bit());
+        }
+
+        Ch::from_index(idx as u8).map(|ch| (ch, data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len: usize = self.remaining.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl<'a> Drop for Sequence<'a> {
+    fn drop(&mut self) {
+        if self.remaining != 0 {
+            self.adc.stop_conversion();
+            self.adc
+                .adc
+                .isr
+                .write(|w| w.eoc().set_bit().eos().set_bit());
+        }
+    }
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+static EOCAL_WAKER: cortex_m::interrupt::Mutex<RefCell<Option<Waker>>> =
+    cortex_m::interrupt::Mutex::new(RefCell::new(None));
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+static CCRDY_WAKER: cortex_m::interrupt::Mutex<RefCell<Option<Waker>>> =
+    cortex_m::interrupt::Mutex::new(RefCell::new(None));
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+static EOSMP_WAKER: cortex_m::interrupt::Mutex<RefCell<Option<Waker>>> =
+    cortex_m::interrupt::Mutex::new(RefCell::new(None));
+
+/// ADC interrupt handler for async operations.
+///
+/// This must be called from the `ADC` interrupt handler for
+/// [`Adc::calibrate_async`], [`Adc::wait_eosmp_async`], and
+/// [`Adc::wait_chsel_ready_async`] to make progress.
+///
+/// This masks the interrupt sources that completed and wakes the waiting
+/// task, the status flags are left for the task to read and clear.
+///
+/// # Example
+///
+/// ```no_run
+/// // call this in the ADC interrupt handler
+/// stm32wlxx_hal::adc::on_interrupt();
+/// ```
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+pub fn on_interrupt() {
+    // safety: only reads status and masks interrupt sources
+    let adc: pac::ADC = unsafe { pac::Peripherals::steal() }.ADC;
+    let isr = adc.isr.read();
+    if isr.eocal().is_complete() {
+        adc.ier.modify(|_, w| w.eocalie().disabled());
+        cortex_m::interrupt::free(|cs| {
+            if let Some(waker) = EOCAL_WAKER.borrow(cs).borrow_mut().take() {
+                waker.wake()
+            }
+        })
+    }
+    if isr.eosmp().is_at_end() && adc.ier.read().eosmpie().is_enabled() {
+        adc.ier.modify(|_, w| w.eosmpie().disabled());
+        cortex_m::interrupt::free(|cs| {
+            if let Some(waker) = EOSMP_WAKER.borrow(cs).borrow_mut().take() {
+                waker.wake()
+            }
+        })
+    }
+    if isr.ccrdy().is_complete() && adc.ier.read().ccrdyie().is_enabled() {
+        adc.ier.modify(|_, w| w.ccrdyie().disabled());
+        cortex_m::interrupt::free(|cs| {
+            if let Some(waker) = CCRDY_WAKER.borrow(cs).borrow_mut().take() {
+                waker.wake()
+            }
+        })
+    }
+}
+
+/// Future that completes when the ADC calibration finishes.
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+struct Eocal<'a> {
+    adc: &'a pac::ADC,
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a> Future for Eocal<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        crate::util::poll_flag(
+            || self.adc.isr.read().eocal().is_complete(),
+            || self.adc.isr.write(|w| w.eocal().set_bit()),
+            || {
+                cortex_m::interrupt::free(|cs| {
+                    EOCAL_WAKER
+                        .borrow(cs)
+                        .borrow_mut()
+                        .replace(cx.waker().clone())
+                });
+                self.adc.ier.modify(|_, w| w.eocalie().enabled());
+            },
+            || self.adc.ier.modify(|_, w| w.eocalie().disabled()),
+        )
+    }
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a> Drop for Eocal<'a> {
+    fn drop(&mut self) {
+        self.adc.ier.modify(|_, w| w.eocalie().disabled());
+        cortex_m::interrupt::free(|cs| EOCAL_WAKER.borrow(cs).borrow_mut().take());
+    }
+}
+
+/// Future that completes at the end of the sampling phase.
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+struct Eosmp<'a> {
+    adc: &'a pac::ADC,
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a> Future for Eosmp<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        crate::util::poll_flag(
+            || self.adc.isr.read().eosmp().is_at_end(),
+            || self.adc.isr.write(|w| w.eosmp().set_bit()),
+            || {
+                cortex_m::interrupt::free(|cs| {
+                    EOSMP_WAKER
+                        .borrow(cs)
+                        .borrow_mut()
+                        .replace(cx.waker().clone())
+                });
+                self.adc.ier.modify(|_, w| w.eosmpie().enabled());
+            },
+            || self.adc.ier.modify(|_, w| w.eosmpie().disabled()),
+        )
+    }
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a> Drop for Eosmp<'a> {
+    fn drop(&mut self) {
+        self.adc.ier.modify(|_, w| w.eosmpie().disabled());
+        cortex_m::interrupt::free(|cs| EOSMP_WAKER.borrow(cs).borrow_mut().take());
+    }
+}
+
+/// Future that completes when the channel configuration is ready.
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+struct Ccrdy<'a> {
+    adc: &'a pac::ADC,
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a> Future for Ccrdy<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        crate::util::poll_flag(
+            || self.adc.isr.read().ccrdy().is_complete(),
+            || self.adc.isr.write(|w| w.ccrdy().set_bit()),
+            || {
+                cortex_m::interrupt::free(|cs| {
+                    CCRDY_WAKER
+                        .borrow(cs)
+                        .borrow_mut()
+                        .replace(cx.waker().clone())
+                });
+                self.adc.ier.modify(|_, w| w.ccrdyie().enabled());
+            },
+            || self.adc.ier.modify(|_, w| w.ccrdyie().disabled()),
+        )
+    }
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a> Drop for Ccrdy<'a> {
+    fn drop(&mut self) {
+        self.adc.ier.modify(|_, w| w.ccrdyie().disabled());
+        cortex_m::interrupt::free(|cs| CCRDY_WAKER.borrow(cs).borrow_mut().take());
+    }
 }
 
 /// Analog to digital converter driver.
@@ -486,6 +1628,50 @@ impl Adc {
         self.adc
     }
 
+    /// Release the ADC peripheral from the driver in a low-power state.
+    ///
+    /// Unlike [`free`](Self::free) this tears down the ADC before returning
+    /// the peripheral:
+    ///
+    /// 1. Stops any conversion in progress and disables the ADC.
+    /// 2. Disables the temperature sensor, voltage reference, and VBAT
+    ///    internal channels.
+    /// 3. Disables the ADC voltage regulator.
+    /// 4. Disables the ADC clock.
+    ///
+    /// The voltage regulator and the internal channels draw current even when
+    /// the ADC is not converting, and the clock keeps the ADC registers
+    /// powered.
+    /// Use this instead of [`free`](Self::free) when the ADC is no longer
+    /// needed to reduce power consumption.
+    ///
+    /// The calibration factor is lost, and [`new`](Self::new) is required to
+    /// use the ADC again.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// // ... use ADC
+    /// let adc: pac::ADC = adc.release(&mut dp.RCC);
+    /// ```
+    pub fn release(mut self, rcc: &mut pac::RCC) -> pac::ADC {
+        self.disable();
+        self.adc
+            .ccr
+            .modify(|_, w| w.tsen().disabled().vrefen().disabled().vbaten().disabled());
+        self.disable_vreg();
+        // safety: the ADC is disabled and the driver is consumed
+        unsafe { Self::disable_clock(rcc) };
+        self.adc
+    }
+
     /// Steal the ADC peripheral from whatever is currently using it.
     ///
     /// This will **not** initialize the ADC (unlike [`new`]).
@@ -529,6 +1715,10 @@ impl Adc {
 
     /// Set the ADC clock source.
     ///
+    /// With the `defmt` feature this logs a warning if the ADC clock exceeds
+    /// the datasheet maximum, or if an enabled or selected internal channel
+    /// has a sample time shorter than the datasheet minimum at the new clock.
+    ///
     /// # Panics
     ///
     /// * (debug) ADC is enabled
@@ -550,6 +1740,14 @@ impl Adc {
     /// ```
     #[inline]
     pub fn set_clock_source(&mut self, clk: Clk, rcc: &mut pac::RCC) {
+        self.write_clock_source(clk, rcc);
+        if cfg!(feature = "defmt") {
+            self.warn_clock(rcc);
+        }
+    }
+
+    #[inline]
+    fn write_clock_source(&mut self, clk: Clk, rcc: &mut pac::RCC) {
         debug_assert!(!self.is_enabled());
         self.adc
             .cfgr2
@@ -557,6 +1755,65 @@ impl Adc {
         rcc.ccipr.modify(|_, w| w.adcsel().variant(clk.adcsel()));
     }
 
+    /// Log a warning if the ADC clock exceeds the datasheet maximum, and for
+    /// each enabled or selected internal channel with a sample time shorter
+    /// than the datasheet minimum at that clock.
+    fn warn_clock(&self, rcc: &pac::RCC) {
+        let hz: u32 = match self.clock_hz(rcc) {
+            Some(hz) => hz,
+            None => return,
+        };
+        if clock_too_fast(hz) {
+            warn!(
+                "ADC clock {} Hz exceeds the maximum of {} Hz",
+                hz, ADC_MAX_HZ
+            )
+        }
+        let ccr = self.adc.ccr.read();
+        let chselr: u32 = self.adc.chselr0().read().bits();
+        [
+            (Ch::Vts, ccr.tsen().is_enabled()),
+            (Ch::Vref, ccr.vrefen().is_enabled()),
+            (Ch::Vbat, ccr.vbaten().is_enabled()),
+        ]
+        .into_iter()
+        .filter(|&(ch, enabled)| {
+            internal_sample_time_short(ch, enabled, chselr, self.ch_sample_time(ch), hz)
+        })
+        .for_each(|(ch, _)| {
+            warn!(
+                "ADC channel {} sample time is below the datasheet minimum",
+                ch as u8
+            )
+        });
+    }
+
+    /// Apply a configuration from [`plan`].
+    ///
+    /// This sets the clock source, prescaler, resolution, and the sample time
+    /// of all channels.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is enabled
+    ///
+    /// # Example
+    ///
+    /// See [`plan`].
+    pub fn apply_plan(&mut self, plan: &AdcPlan, rcc: &mut pac::RCC) {
+        self.write_clock_source(plan.clk, rcc);
+        self.adc
+            .ccr
+            .modify(|_, w| w.presc().variant(plan.prescaler));
+        self.adc
+            .cfgr1
+            .modify(|_, w| w.res().variant(plan.resolution));
+        self.set_sample_times(0, plan.ts, plan.ts);
+        if cfg!(feature = "defmt") {
+            self.warn_clock(rcc);
+        }
+    }
+
     /// Get the ADC clock source.
     ///
     /// Returns `None` if the ADC is configured for an asynchronous clock,
@@ -599,7 +1856,7 @@ impl Adc {
     /// 2. You are responsible for re-enabling the clock before using the ADC.
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb2enr.modify(|_, w| w.adcen().disabled());
+        rcc::disable(rcc, Peripheral::Adc);
     }
 
     /// Enable the ADC clock.
@@ -607,8 +1864,7 @@ impl Adc {
     /// [`new`](crate::adc::Adc::new) will enable clocks for you.
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb2enr.modify(|_, w| w.adcen().enabled());
-        rcc.apb2enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Adc);
     }
 
     /// Pulse the ADC reset.
@@ -621,12 +1877,14 @@ impl Adc {
     /// 2. You are responsible for setting up the ADC after a reset.
     #[inline]
     pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb2rstr.modify(|_, w| w.adcrst().set_bit());
-        rcc.apb2rstr.modify(|_, w| w.adcrst().clear_bit());
+        rcc::reset(rcc, Peripheral::Adc);
     }
 
     /// Calculate the ADC clock frequency in hertz.
     ///
+    /// Returns `None` if the ADC is configured for an asynchronous clock,
+    /// but no asynchronous clock is selected (`ADCSEL` is `NOCLOCK`).
+    ///
     /// **Note:** If the ADC prescaler register erroneously returns a reserved
     /// value the code will default to an ADC prescaler of 1.
     ///
@@ -647,18 +1905,15 @@ impl Adc {
     /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
     ///
     /// let adc: Adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
-    /// assert_eq!(adc.clock_hz(&dp.RCC), 16_000_000);
+    /// assert_eq!(adc.clock_hz(&dp.RCC), Some(16_000_000));
     /// ```
-    pub fn clock_hz(&self, rcc: &pac::RCC) -> u32 {
-        use pac::{
-            adc::{ccr::PRESC_A, cfgr2::CKMODE_A},
-            rcc::ccipr::ADCSEL_A,
-        };
+    pub fn clock_hz(&self, rcc: &pac::RCC) -> Option<u32> {
+        use pac::{adc::cfgr2::CKMODE_A, rcc::ccipr::ADCSEL_A};
 
         let source_freq: Ratio<u32> = match self.adc.cfgr2.read().ckmode().variant() {
             CKMODE_A::Adclk => {
                 let src: Ratio<u32> = match rcc.ccipr.read().adcsel().variant() {
-                    ADCSEL_A::NoClock => Ratio::new_raw(0, 1),
+                    ADCSEL_A::NoClock => return None,
                     ADCSEL_A::Hsi16 => Ratio::new_raw(16_000_000, 1),
                     ADCSEL_A::Pllp => crate::rcc::pllpclk(rcc, &rcc.pllcfgr.read()),
                     ADCSEL_A::Sysclk => crate::rcc::sysclk(rcc, &rcc.cfgr.read()),
@@ -667,20 +1922,7 @@ impl Adc {
                 // only the asynchronous clocks have the prescaler applied
                 let ccr = self.adc.ccr.read();
                 let prescaler: u32 = match ccr.presc().variant() {
-                    Some(p) => match p {
-                        PRESC_A::Div1 => 1,
-                        PRESC_A::Div2 => 2,
-                        PRESC_A::Div4 => 4,
-                        PRESC_A::Div6 => 6,
-                        PRESC_A::Div8 => 8,
-                        PRESC_A::Div10 => 10,
-                        PRESC_A::Div12 => 12,
-                        PRESC_A::Div16 => 16,
-                        PRESC_A::Div32 => 32,
-                        PRESC_A::Div64 => 64,
-                        PRESC_A::Div128 => 128,
-                        PRESC_A::Div256 => 256,
-                    },
+                    Some(p) => presc_div(p),
                     None => {
                         error!("Reserved ADC prescaler value {:#X}", ccr.presc().bits());
                         1
@@ -694,7 +1936,7 @@ impl Adc {
             CKMODE_A::Pclk => crate::rcc::pclk2(rcc, &rcc.cfgr.read()),
         };
 
-        source_freq.to_integer()
+        Some(source_freq.to_integer())
     }
 
     /// Unmask the ADC IRQ in the NVIC.
@@ -773,7 +2015,153 @@ impl Adc {
         debug_assert!(self.adc.cr.read().adstart().is_not_active());
         self.adc.smpr.write(|w| unsafe {
             w.bits((mask & CH_MASK) << 8 | u32::from(sel1) << 4 | u32::from(sel0))
-        })
+        });
+    }
+
+    /// Set sample times from `(channel, sample time)` pairs.
+    ///
+    /// This builds the mask for [`set_sample_times`](Adc::set_sample_times)
+    /// from the pairs.
+    /// The hardware supports two distinct sample times, the first sample
+    /// time in `pairs` also applies to all channels not in `pairs`.
+    /// If a channel appears more than once the last pair takes precedence.
+    ///
+    /// Returns [`Error::SampleTimes`] if more than two distinct sample times
+    /// are requested, the sample times are not modified.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) An ADC conversion is in-progress
+    ///
+    /// # Example
+    ///
+    /// Set ADC channel [`In0`] (pin [`B13`]) to a sample time of
+    /// 39.5 ADC clock cycles, and set the internal V<sub>BAT</sub> and all
+    /// other channels to a sample time of 160.5 clock cycles.
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     self as hal,
+    ///     adc::{self, Adc, Ts},
+    ///     gpio::pins::B13,
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.configure_samples(&[(adc::Ch::Vbat, Ts::Cyc160), (B13::ADC_CH, Ts::Cyc39)])?;
+    /// # Ok::<(), stm32wlxx_hal::adc::Error>(())
+    /// ```
+    ///
+    /// [`In0`]: crate::adc::Ch::In0
+    /// [`B13`]: crate::gpio::pins::B13
+    pub fn configure_samples(&mut self, pairs: &[(Ch, Ts)]) -> Result<(), Error> {
+        let (mask, sel0, sel1) = sample_buckets(pairs)?;
+        self.set_sample_times(mask, sel0, sel1);
+        Ok(())
+    }
+
+    /// Sample time configured for a channel.
+    fn ch_sample_time(&self, ch: Ch) -> Ts {
+        let smpr: u32 = self.adc.smpr.read().bits();
+        if smpr >> 8 & ch.mask() != 0 {
+            Ts::from_bits((smpr >> 4) as u8)
+        } else {
+            Ts::from_bits(smpr as u8)
+        }
+    }
+
+    /// Returns `false` if the sample time configured for an internal channel
+    /// is shorter than the datasheet minimum, see [`Ch::min_sample_time`].
+    ///
+    /// Returns `true` for channels without a documented minimum, and if the
+    /// ADC clock frequency is unknown.
+    ///
+    /// # Example
+    ///
+    /// [`Ts::Cyc160`] is 10 µs with the 16 MHz HSI16, which is less than
+    /// [`VBAT_MIN_SAMPLE`].
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut adc: Adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.set_max_sample_time();
+    /// assert!(adc.is_sample_time_ok(adc::Ch::Vref, &dp.RCC));
+    /// assert!(!adc.is_sample_time_ok(adc::Ch::Vbat, &dp.RCC));
+    /// ```
+    pub fn is_sample_time_ok(&self, ch: Ch, rcc: &pac::RCC) -> bool {
+        match self.clock_hz(rcc) {
+            Some(hz) => !sample_time_short(ch, self.ch_sample_time(ch), hz),
+            None => true,
+        }
+    }
+
+    /// Total time from the start of a conversion to the result for a
+    /// channel, with the current configuration.
+    ///
+    /// This is the sample time of `ch` plus the successive approximation
+    /// time of the current resolution, times the oversampling ratio if
+    /// oversampling is enabled:
+    ///
+    /// | Resolution | Conversion cycles |
+    /// |------------|-------------------|
+    /// | 12-bit     | t<sub>SMPL</sub> + 12.5 |
+    /// | 10-bit     | t<sub>SMPL</sub> + 10.5 |
+    /// | 8-bit      | t<sub>SMPL</sub> + 8.5  |
+    /// | 6-bit      | t<sub>SMPL</sub> + 6.5  |
+    ///
+    /// Fractional nano-seconds are rounded towards zero.
+    /// Trigger latency and the time to read the result are not included.
+    ///
+    /// A conversion never completes without a clock, if there is no ADC
+    /// clock (see [`clock_hz`](Self::clock_hz)) this returns
+    /// [`Duration::MAX`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use core::time::Duration;
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc, Ts},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut adc: Adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.set_sample_times(0, Ts::Cyc1, Ts::Cyc1);
+    ///
+    /// // (1.5 + 12.5) cycles at 16 MHz
+    /// assert_eq!(
+    ///     adc.total_conversion_time(adc::Ch::In0, &dp.RCC),
+    ///     Duration::from_nanos(875)
+    /// );
+    /// ```
+    #[cfg(not(feature = "stm32wl5x_cm0p"))]
+    pub fn total_conversion_time(&self, ch: Ch, rcc: &pac::RCC) -> Duration {
+        let hz: u32 = self.clock_hz(rcc).unwrap_or(0);
+        let res: Resolution = self.adc.cfgr1.read().res().variant();
+        let cfgr2 = self.adc.cfgr2.read();
+        let oversamples: u32 = if cfgr2.ovse().is_enabled() {
+            2 << u8::from(cfgr2.ovsr().variant())
+        } else {
+            1
+        };
+        conversion_time(self.ch_sample_time(ch), res, oversamples, hz)
     }
 
     /// Sets all channels to the maximum sample time.
@@ -807,6 +2195,239 @@ impl Adc {
         self.set_sample_times(0, Ts::Cyc160, Ts::Cyc160);
     }
 
+    /// Set the data alignment.
+    ///
+    /// The reset value is [`Alignment::Right`].
+    ///
+    /// With [`Alignment::Left`] the sample is shifted so that the most
+    /// significant bit is bit 15 of [`data`](Self::data), except for 6-bit
+    /// resolution where the most significant bit is bit 7.
+    /// The shift depends on the resolution:
+    ///
+    /// | Resolution | Left-aligned bits |
+    /// |------------|-------------------|
+    /// | 12-bit     | 15:4              |
+    /// | 10-bit     | 15:6              |
+    /// | 8-bit      | 15:8              |
+    /// | 6-bit      | 7:2               |
+    ///
+    /// The raw sample methods such as [`pin`](Self::pin) and
+    /// [`vref`](Self::vref) return aligned data.
+    /// The scaling helpers such as [`temperature`](Self::temperature) convert
+    /// the sample back to right-aligned data before scaling.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) An ADC conversion is in-progress
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc, Alignment},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_alignment(Alignment::Left);
+    /// assert_eq!(adc.alignment(), Alignment::Left);
+    /// ```
+    #[inline]
+    pub fn set_alignment(&mut self, align: Alignment) {
+        debug_assert!(self.adc.cr.read().adstart().is_not_active());
+        self.adc.cfgr1.modify(|_, w| w.align().variant(align))
+    }
+
+    /// Get the data alignment.
+    ///
+    /// # Example
+    ///
+    /// See [`set_alignment`](Self::set_alignment).
+    #[inline]
+    #[must_use]
+    pub fn alignment(&self) -> Alignment {
+        self.adc.cfgr1.read().align().variant()
+    }
+
+    /// Set the external conversion trigger.
+    ///
+    /// With an edge other than [`TrigEdge::Disabled`]
+    /// [`start_conversion`](Self::start_conversion) arms the ADC, and each
+    /// trigger edge starts a conversion without CPU involvement.
+    /// [`TrigEdge::Disabled`] returns to software triggered conversions.
+    ///
+    /// The trigger sources on this device are:
+    ///
+    /// | [`ExtTrig`]   | `EXTSEL` | Source           |
+    /// |---------------|----------|------------------|
+    /// | `Tim1Trgo`    | `0b000`  | TIM1 TRGO2       |
+    /// | `Tim1Cc4`     | `0b001`  | TIM1 CC4         |
+    /// | `Tim2Trgo`    | `0b010`  | TIM2 TRGO        |
+    /// | `Tim2Ch4`     | `0b011`  | TIM2 CH4         |
+    /// | `Tim2Ch3`     | `0b101`  | TIM2 CH3         |
+    /// | `ExtiLine11`  | `0b111`  | EXTI line 11     |
+    ///
+    /// The comparators and the DAC are not trigger sources of this ADC.
+    /// For a hardware timed control loop trigger the ADC and the DAC from the
+    /// same timer.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) An ADC conversion is in-progress
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc, ExtTrig, TrigEdge},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_trigger(TrigEdge::RisingEdge, ExtTrig::Tim2Trgo);
+    /// assert_eq!(
+    ///     adc.trigger(),
+    ///     (TrigEdge::RisingEdge, Some(ExtTrig::Tim2Trgo))
+    /// );
+    /// ```
+    #[inline]
+    pub fn set_trigger(&mut self, edge: TrigEdge, trig: ExtTrig) {
+        debug_assert!(self.adc.cr.read().adstart().is_not_active());
+        self.adc
+            .cfgr1
+            .modify(|_, w| w.exten().variant(edge).extsel().variant(trig))
+    }
+
+    /// Get the external conversion trigger.
+    ///
+    /// The trigger source is `None` for reserved `EXTSEL` values.
+    ///
+    /// # Example
+    ///
+    /// See [`set_trigger`](Self::set_trigger).
+    #[inline]
+    #[must_use]
+    pub fn trigger(&self) -> (TrigEdge, Option<ExtTrig>) {
+        let cfgr1 = self.adc.cfgr1.read();
+        (cfgr1.exten().variant(), cfgr1.extsel().variant())
+    }
+
+    /// Configure and enable analog watchdog 1.
+    ///
+    /// The watchdog flags [`irq::AWD1`] when a conversion of `ch` is outside
+    /// of `low..=high`, or a conversion of any channel if `ch` is `None`.
+    /// Use [`set_ier`](Self::set_ier) to enable the interrupt.
+    ///
+    /// The thresholds are counts of a conversion at the configured
+    /// resolution, use [`set_watchdog1_mv`](Self::set_watchdog1_mv) for
+    /// thresholds in millivolts.
+    /// Thresholds above full scale saturate.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC conversion is in-progress.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_watchdog1(Some(adc::Ch::In3), 100, 3000);
+    /// ```
+    pub fn set_watchdog1(&mut self, ch: Option<Ch>, low: u16, high: u16) {
+        debug_assert!(self.adc.cr.read().adstart().is_not_active());
+        let res: Resolution = self.adc.cfgr1.read().res().variant();
+        self.adc.awd1tr.write(|w| {
+            w.lt1().bits(awd_threshold(low, res));
+            w.ht1().bits(awd_threshold(high, res))
+        });
+        self.adc.cfgr1.modify(|_, w| match ch {
+            // safety: the channel is a valid AWD1CH value
+            Some(ch) => unsafe {
+                w.awd1en()
+                    .enabled()
+                    .awd1sgl()
+                    .single_channel()
+                    .awd1ch()
+                    .bits(ch as u8)
+            },
+            None => w.awd1en().enabled().awd1sgl().all_channels(),
+        });
+    }
+
+    /// Configure and enable analog watchdog 1 with thresholds in millivolts.
+    ///
+    /// This converts the thresholds with
+    /// [`mv_to_counts`](Self::mv_to_counts), then calls
+    /// [`set_watchdog1`](Self::set_watchdog1).
+    /// The thresholds are not updated if V<sub>DDA</sub> changes.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    /// * (debug) ADC conversion is in-progress.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_max_sample_time();
+    /// adc.enable();
+    /// adc.enable_vref();
+    ///
+    /// adc.set_watchdog1_mv(Some(adc::Ch::In3), 500, 2500);
+    /// ```
+    pub fn set_watchdog1_mv(&mut self, ch: Option<Ch>, low_mv: u16, high_mv: u16) {
+        let low: u16 = self.mv_to_counts(low_mv);
+        let high: u16 = self.mv_to_counts(high_mv);
+        self.set_watchdog1(ch, low, high)
+    }
+
+    /// Disable analog watchdog 1.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC conversion is in-progress.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_watchdog1(None, 0, 1000);
+    /// // ... use the watchdog
+    /// adc.disable_watchdog1();
+    /// ```
+    pub fn disable_watchdog1(&mut self) {
+        debug_assert!(self.adc.cr.read().adstart().is_not_active());
+        self.adc.cfgr1.modify(|_, w| w.awd1en().disabled());
+    }
+
+    /// Convert aligned data to right-aligned data.
+    fn right_aligned(&self, data: u16) -> u16 {
+        let cfgr1 = self.adc.cfgr1.read();
+        data >> align_shift(cfgr1.align().variant(), cfgr1.res().variant())
+    }
+
     /// Clear interrupts.
     ///
     /// # Example
@@ -885,6 +2506,13 @@ impl Adc {
     ///
     /// This will not poll for completion, when this method returns the channel
     /// configuration may not be ready.
+    /// Use [`wait_chsel_ready`](Self::wait_chsel_ready) before starting a
+    /// conversion.
+    ///
+    /// The sequencer is not fully configurable, the selected channels are
+    /// converted in channel number order, not in the order they were
+    /// selected.
+    /// Use [`sequence_order`](Self::sequence_order) to index the results.
     ///
     /// # Panics
     ///
@@ -903,7 +2531,7 @@ impl Adc {
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
     /// adc.start_chsel(adc::Ch::Vbat.mask());
-    /// while Adc::isr().ccrdy().is_not_complete() {}
+    /// adc.wait_chsel_ready();
     /// ```
     #[inline]
     pub fn start_chsel(&mut self, ch: u32) {
@@ -915,46 +2543,227 @@ impl Adc {
             .write(|w| unsafe { w.chsel().bits(ch & CH_MASK) });
     }
 
-    #[inline]
-    fn cfg_ch_seq(&mut self, ch: u32) {
-        self.start_chsel(ch);
-        while self.adc.isr.read().ccrdy().is_not_complete() {}
-    }
-
-    /// Start an ADC conversion.
+    /// Wait for the channel configuration to be ready.
     ///
-    /// This is advanced ADC usage, most of the time you will want to use a
-    /// one of the available sample methods that will configure this.
-    ///
-    /// * [`pin`](Self::pin)
-    /// * [`temperature`](Self::temperature)
-    /// * [`vbat`](Self::vbat)
+    /// This polls the channel configuration ready flag (`CCRDY`) and
+    /// clears it.
     ///
-    /// This will not poll for completion, when this method returns the AD
-    /// conversion may not be complete.
+    /// On this ADC a write to the channel selection register
+    /// (`ADC_CHSELR`) is applied asynchronously in the ADC clock domain,
+    /// the new channel selection must be acknowledged with `CCRDY` before
+    /// starting a conversion.
+    /// This is only needed when configuring the channels with
+    /// [`start_chsel`](Self::start_chsel), the sample methods such as
+    /// [`pin`](Self::pin) already wait.
     ///
-    /// # Panics
+    /// Clearing the flag ensures a later wait is not satisfied by a stale
+    /// flag from a previous configuration.
     ///
-    /// * (debug) ADC is not enabled
-    /// * (debug) ADC has a pending disable request
+    /// This will poll forever if the channel selection was not written since
+    /// the flag was last cleared.
     ///
     /// # Example
     ///
-    /// See [`data`](Self::data).
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// adc.start_chsel(adc::Ch::In0.mask() | adc::Ch::In1.mask());
+    /// adc.wait_chsel_ready();
+    /// adc.start_conversion();
+    /// ```
     #[inline]
-    pub fn start_conversion(&mut self) {
-        debug_assert!(self.is_enabled());
-        debug_assert!(self.adc.cr.read().addis().is_not_disabling());
-        self.adc.cr.write(|w| w.adstart().start_conversion());
+    pub fn wait_chsel_ready(&mut self) {
+        while self.adc.isr.read().ccrdy().is_not_complete() {}
+        self.adc.isr.write(|w| w.ccrdy().set_bit());
     }
 
-    /// Stop an ADC conversion if there is one in-progress.
-    pub fn stop_conversion(&mut self) {
-        if self.adc.cr.read().adstart().is_active() {
-            self.adc.cr.write(|w| w.adstp().stop_conversion());
-            while self.adc.cr.read().adstp().bit_is_set() {}
-        }
-    }
+    /// Wait for the channel configuration to be ready without blocking.
+    ///
+    /// This is the async equivalent of
+    /// [`wait_chsel_ready`](Self::wait_chsel_ready).
+    ///
+    /// The channel configuration is awaited with the `CCRDY` interrupt,
+    /// [`on_interrupt`] must be called from the `ADC` interrupt handler and
+    /// the `ADC` interrupt must be unmasked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use stm32wlxx_hal::util::block_on;
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// unsafe { pac::NVIC::unmask(pac::Interrupt::ADC) };
+    /// adc.enable();
+    /// adc.start_chsel(adc::Ch::In0.mask());
+    /// // block_on from your executor
+    /// block_on(adc.wait_chsel_ready_async());
+    /// adc.start_conversion();
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn wait_chsel_ready_async(&mut self) {
+        Ccrdy { adc: &self.adc }.await
+    }
+
+    #[inline]
+    fn cfg_ch_seq(&mut self, ch: u32) {
+        // a stale flag from a previous configuration would end the wait early
+        self.adc.isr.write(|w| w.ccrdy().set_bit());
+        self.start_chsel(ch);
+        self.wait_chsel_ready();
+    }
+
+    /// Start an ADC conversion.
+    ///
+    /// This is advanced ADC usage, most of the time you will want to use a
+    /// one of the available sample methods that will configure this.
+    ///
+    /// * [`pin`](Self::pin)
+    /// * [`temperature`](Self::temperature)
+    /// * [`vbat`](Self::vbat)
+    ///
+    /// This will not poll for completion, when this method returns the AD
+    /// conversion may not be complete.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    ///
+    /// # Example
+    ///
+    /// See [`data`](Self::data).
+    #[inline]
+    pub fn start_conversion(&mut self) {
+        debug_assert!(self.is_enabled());
+        debug_assert!(self.adc.cr.read().addis().is_not_disabling());
+        self.adc.cr.write(|w| w.adstart().start_conversion());
+    }
+
+    /// Wait for the end of the sampling phase.
+    ///
+    /// This polls the end of sampling flag (`EOSMP`) and clears it.
+    ///
+    /// The end of sampling flag is set when the sample-and-hold has captured
+    /// the input, before the conversion completes.
+    /// The analog input can change after this returns without affecting the
+    /// conversion in-progress, for example to switch an external analog
+    /// multiplexer for the next conversion.
+    ///
+    /// This will poll forever if no conversion is in-progress and the
+    /// flag is not set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// adc.start_chsel(adc::Ch::In0.mask());
+    /// adc.wait_chsel_ready();
+    ///
+    /// adc.start_conversion();
+    /// adc.wait_eosmp();
+    /// // ... switch the external multiplexer
+    /// while Adc::isr().eoc().is_not_complete() {}
+    /// let sample: u16 = adc.data();
+    /// ```
+    #[inline]
+    pub fn wait_eosmp(&mut self) {
+        while self.adc.isr.read().eosmp().is_not_at_end() {}
+        self.adc.isr.write(|w| w.eosmp().set_bit());
+    }
+
+    /// Wait for the end of the sampling phase without blocking.
+    ///
+    /// This is the async equivalent of [`wait_eosmp`](Self::wait_eosmp).
+    ///
+    /// The end of sampling is awaited with the `EOSMP` interrupt,
+    /// [`on_interrupt`] must be called from the `ADC` interrupt handler and
+    /// the `ADC` interrupt must be unmasked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use stm32wlxx_hal::util::block_on;
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// unsafe { pac::NVIC::unmask(pac::Interrupt::ADC) };
+    /// adc.enable();
+    /// adc.start_chsel(adc::Ch::In0.mask());
+    /// adc.wait_chsel_ready();
+    ///
+    /// adc.start_conversion();
+    /// // block_on from your executor
+    /// block_on(adc.wait_eosmp_async());
+    /// // ... switch the external multiplexer
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn wait_eosmp_async(&mut self) {
+        Eosmp { adc: &self.adc }.await
+    }
+
+    /// Returns `true` if an ADC conversion is in-progress.
+    ///
+    /// Configuration methods such as
+    /// [`set_sample_times`](Self::set_sample_times) require that no
+    /// conversion is in-progress.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc, Ts},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    ///
+    /// if adc.is_conversion_active() {
+    ///     adc.stop_conversion();
+    /// }
+    /// adc.set_sample_times(0, Ts::Cyc160, Ts::Cyc160);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_conversion_active(&self) -> bool {
+        self.adc.cr.read().adstart().is_active()
+    }
+
+    /// Stop an ADC conversion if there is one in-progress.
+    ///
+    /// The result of the stopped conversion is discarded.
+    ///
+    /// This will poll forever if the ADC clock is stopped during a
+    /// conversion, the ADC needs its clock to clear `ADSTART`.
+    pub fn stop_conversion(&mut self) {
+        let cr = &self.adc.cr;
+        stop_with(
+            || cr.read().adstart().is_active(),
+            || cr.write(|w| w.adstp().stop_conversion()),
+        );
+    }
 
     /// Read the ADC conversion data.
     ///
@@ -965,6 +2774,19 @@ impl Adc {
     /// * [`temperature`](Self::temperature)
     /// * [`vbat`](Self::vbat)
     ///
+    /// The data is aligned as configured with
+    /// [`set_alignment`](Self::set_alignment).
+    ///
+    /// This only reads the `ADC_DR` register, `ADC_ISR` is not written.
+    /// The hardware clears the end of conversion flag (`EOC`) when `ADC_DR`
+    /// is read, there is no way to read the data without clearing `EOC`.
+    ///
+    /// * For software triggered conversions poll `EOC` with [`Adc::isr`]
+    ///   and then call this method, as in the example below.
+    /// * For DMA transfers do not call this method, the DMA reads `ADC_DR`
+    ///   and clears `EOC`, reading the data from software takes a sample
+    ///   from the DMA.
+    ///
     /// # Example
     ///
     /// Read the ADC V<sub>BAT</sub> channel.
@@ -997,7 +2819,7 @@ impl Adc {
     ///
     /// // select the Vbat channel and poll for completion
     /// adc.start_chsel(adc::Ch::Vbat.mask());
-    /// while Adc::isr().ccrdy().is_not_complete() {}
+    /// adc.wait_chsel_ready();
     ///
     /// // start the conversion and poll for completion
     /// adc.start_conversion();
@@ -1064,6 +2886,82 @@ impl Adc {
         self.adc.ccr.read().tsen().is_enabled()
     }
 
+    /// Returns `true` if the temperature sensor is enabled and ready.
+    ///
+    /// The ADC has no way to track the time, `elapsed` is the time since
+    /// [`enable_tsen`](Self::enable_tsen) was called as measured by the
+    /// caller.
+    /// The sensor is ready after [`TS_START_MAX`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use core::time::Duration;
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable_tsen();
+    /// assert!(!adc.tsen_ready(Duration::from_micros(10)));
+    /// assert!(adc.tsen_ready(adc::TS_START_MAX));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn tsen_ready(&self, elapsed: Duration) -> bool {
+        self.adc.ccr.read().tsen().is_enabled() && ts_startup_elapsed(elapsed)
+    }
+
+    /// Get the junction temperature, checking the sensor is ready.
+    ///
+    /// This is [`temperature`](Self::temperature) with a readiness check,
+    /// `elapsed` is the time since [`enable_tsen`](Self::enable_tsen) was
+    /// called as measured by the caller, see
+    /// [`tsen_ready`](Self::tsen_ready).
+    ///
+    /// Returns [`Error::NotReady`] if the temperature sensor is disabled, or
+    /// `elapsed` is less than [`TS_START_MAX`], no conversion is started.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// adc.set_max_sample_time();
+    /// adc.enable_tsen();
+    /// assert_eq!(
+    ///     adc.temperature_checked(core::time::Duration::ZERO).err(),
+    ///     Some(adc::Error::NotReady)
+    /// );
+    ///
+    /// delay.delay_us(adc::TS_START_MAX.as_micros() as u32);
+    /// let tj: i16 = adc.temperature_checked(adc::TS_START_MAX)?.to_integer();
+    /// # Ok::<(), stm32wlxx_hal::adc::Error>(())
+    /// ```
+    pub fn temperature_checked(&mut self, elapsed: Duration) -> Result<Ratio<i16>, Error> {
+        if self.tsen_ready(elapsed) {
+            Ok(self.temperature())
+        } else {
+            Err(Error::NotReady)
+        }
+    }
+
     /// Get the junction temperature.
     ///
     /// # Panics
@@ -1076,7 +2974,9 @@ impl Adc {
     ///
     /// You must set a sampling time with
     /// [`set_sample_times`](Adc::set_sample_times) greater than or equal to
-    /// [`TS_MIN_SAMPLE`] before calling this method.
+    /// [`TS_MIN_SAMPLE`] before calling this method, see
+    /// [`Ch::min_sample_time`] and
+    /// [`is_sample_time_ok`](Self::is_sample_time_ok).
     /// When in doubt use the maximum sampling time, [`Ts::Cyc160`].
     ///
     /// # Calibration
@@ -1128,11 +3028,87 @@ impl Adc {
             Ratio::new_raw(TS_CAL_TEMP_DELTA, ts_cal2.wrapping_sub(ts_cal1) as i16);
 
         let calfact: u8 = self.adc.calfact.read().calfact().bits();
-        let ts_data: u16 = self.poll_data().saturating_add(u16::from(calfact));
+        let ts_data: u16 = self
+            .right_aligned(self.poll_data())
+            .saturating_add(u16::from(calfact));
 
         ret * (ts_data.wrapping_sub(ts_cal1) as i16) + TS_CAL1_TEMP
     }
 
+    /// Get the junction temperature, configuring the sample time
+    /// automatically.
+    ///
+    /// This is [`temperature`](Self::temperature) without the sample time
+    /// and sensor setup requirements:
+    ///
+    /// 1. The sample time for [`Ch::Vts`] is temporarily set to the
+    ///    shortest sample time greater than or equal to [`TS_MIN_SAMPLE`]
+    ///    for the current ADC clock, or [`Ts::MAX`] if the ADC clock
+    ///    frequency is unknown.
+    /// 2. If the temperature sensor is disabled it is enabled, and `delay`
+    ///    is used to wait [`TS_START_MAX`] for the sensor to startup.
+    /// 3. After sampling the previous sample time configuration is restored,
+    ///    and the temperature sensor is disabled if it was disabled.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    /// * (debug) An ADC conversion is in-progress
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    ///
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.enable();
+    ///
+    /// let tj: i16 = adc.temperature_auto(&dp.RCC, &mut delay).to_integer();
+    /// ```
+    pub fn temperature_auto<D: DelayUs<u8>>(
+        &mut self,
+        rcc: &pac::RCC,
+        delay: &mut D,
+    ) -> Ratio<i16> {
+        let ts: Ts = match self.clock_hz(rcc) {
+            Some(hz) => Ch::Vts.min_sample_time(hz).unwrap_or(Ts::MAX),
+            None => Ts::MAX,
+        };
+
+        let smpr: u32 = self.adc.smpr.read().bits();
+        self.set_sample_times(Ch::Vts.mask(), Ts::from_bits(smpr as u8), ts);
+
+        let tsen: bool = self.is_tsen_enabled();
+        if !tsen {
+            self.enable_tsen();
+            delay.delay_us(TS_START_MAX.as_micros() as u8);
+        }
+
+        let ret: Ratio<i16> = self.temperature();
+
+        if !tsen {
+            self.disable_tsen();
+        }
+        // safety: restoring a previously valid configuration
+        self.adc.smpr.write(|w| unsafe { w.bits(smpr) });
+
+        ret
+    }
+
     /// Enable the internal voltage reference.
     #[inline]
     pub fn enable_vref(&mut self) {
@@ -1189,6 +3165,58 @@ impl Adc {
         self.adc.cfgr2.read().ovse().is_enabled()
     }
 
+    /// Sample a channel with hardware oversampling, averaging `ratio`
+    /// conversions.
+    ///
+    /// This configures the oversampler to accumulate `ratio` conversions and
+    /// shift the sum right by log<sub>2</sub>(`ratio`), the result is the
+    /// average at the configured resolution, right-aligned.
+    /// The conversions run back to back from a single software trigger.
+    ///
+    /// The oversampling configuration can only be written while the ADC is
+    /// disabled, this disables the ADC, and restores the prior oversampling
+    /// configuration and enable state before returning.
+    ///
+    /// # Example
+    ///
+    /// Average 256 samples of the internal voltage reference.
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc, OversampleRatio},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_max_sample_time();
+    /// adc.enable_vref();
+    ///
+    /// let sample: u16 = adc.read_oversampled(adc::Ch::Vref, OversampleRatio::Mul256);
+    /// ```
+    #[cfg(not(feature = "stm32wl5x_cm0p"))]
+    pub fn read_oversampled(&mut self, ch: Ch, ratio: OversampleRatio) -> u16 {
+        let enabled: bool = self.is_enabled();
+        self.disable();
+
+        let cfgr2: u32 = self.adc.cfgr2.read().bits();
+        self.enable_oversampling(ratio, avg_shift(ratio));
+        self.enable();
+
+        self.cfg_ch_seq(ch.mask());
+        self.start_conversion();
+        let data: u16 = self.poll_data();
+
+        self.disable();
+        // safety: restoring a previously valid configuration
+        self.adc.cfgr2.write(|w| unsafe { w.bits(cfgr2) });
+        if enabled {
+            self.enable();
+        }
+
+        data
+    }
+
     /// Read the internal voltage reference.
     ///
     /// # Panics
@@ -1197,6 +3225,11 @@ impl Adc {
     /// * (debug) ADC has a pending disable request
     /// * (debug) Voltage reference is not enabled
     ///
+    /// # Sample Time
+    ///
+    /// The sample time must be greater than or equal to [`VREF_MIN_SAMPLE`],
+    /// see [`is_sample_time_ok`](Self::is_sample_time_ok).
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -1288,6 +3321,8 @@ impl Adc {
     ///
     /// * (debug) ADC is not enabled
     /// * (debug) ADC has a pending disable request
+    /// * (debug) GPIO is not in analog mode, for example if it was
+    ///   reconfigured after creating the [`Analog`](gpio::Analog) wrapper
     ///
     /// # Example
     ///
@@ -1318,55 +3353,88 @@ impl Adc {
     ///
     /// let sample: u16 = adc.pin(&b4);
     /// ```
-    #[allow(unused_variables)]
-    pub fn pin<P: gpio::sealed::AdcCh>(&mut self, pin: &gpio::Analog<P>) -> u16 {
-        debug_assert!(self.is_enabled());
-        self.cfg_ch_seq(P::ADC_CH.mask());
-        self.start_conversion();
-        self.poll_data()
+    pub fn pin<P>(&mut self, pin: &gpio::Analog<P>) -> u16
+    where
+        P: gpio::sealed::PinOps + gpio::sealed::AdcCh,
+    {
+        if cfg!(debug_assertions) {
+            assert_analog(pin.is_analog(), P::ADC_CH);
+        }
+        self.read_channel(P::ADC_CH)
     }
 
-    /// Enable V<sub>BAT</sub>.
+    /// Sample a channel without a pin wrapper.
     ///
-    /// To prevent any unwanted consumption on the battery, it is recommended to
-    /// enable the bridge divider only when needed for ADC conversion.
-    #[inline]
-    pub fn enable_vbat(&mut self) {
-        self.adc.ccr.modify(|_, w| w.vbaten().enabled())
-    }
-
-    /// Disable V<sub>BAT</sub>.
-    #[inline]
-    pub fn disable_vbat(&mut self) {
-        self.adc.ccr.modify(|_, w| w.vbaten().disabled());
-    }
-
-    /// Returns `true` if V<sub>BAT</sub> is enabled.
-    #[inline]
-    #[must_use]
-    pub fn is_vbat_enabled(&self) -> bool {
-        self.adc.ccr.read().vbaten().is_enabled()
+    /// This is [`pin`](Self::pin) for a raw channel, for prototyping and
+    /// for code where the pin type is erased.
+    /// Prefer [`pin`](Self::pin), which guarantees the pin is in analog mode
+    /// through the [`Analog`](gpio::Analog) wrapper.
+    ///
+    /// **Note:** The caller is responsible for putting the GPIO connected to
+    /// `ch` in analog mode.
+    /// There is no check, a GPIO in another mode is not disconnected from
+    /// the digital circuitry and the sample is meaningless.
+    /// The internal channels do not use a GPIO.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     gpio::{pins::B4, Analog, PortB},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // B4 is ADC input 3
+    /// let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+    /// let b4: Analog<B4> = cortex_m::interrupt::free(|cs| Analog::new(gpiob.b4, cs));
+    ///
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_max_sample_time();
+    /// adc.enable();
+    ///
+    /// // B4 is in analog mode
+    /// let sample: u16 = adc.read_channel(adc::Ch::In3);
+    /// ```
+    pub fn read_channel(&mut self, ch: Ch) -> u16 {
+        debug_assert!(self.is_enabled());
+        self.cfg_ch_seq(ch.mask());
+        self.start_conversion();
+        self.poll_data()
     }
 
-    /// Sample the V<sub>BAT</sub> pin.
+    /// Sample a channel and convert the sample with a [`SampleFormat`].
     ///
-    /// This is internally connected to a bridge divider, the converted digital
-    /// value is a third the V<sub>BAT</sub> voltage.
+    /// The sample is right-aligned before conversion, and the full scale
+    /// accounts for the resolution and oversampling.
+    /// For formats that use V<sub>DDA</sub> (such as [`Millivolts`]) the
+    /// internal voltage reference is sampled first.
+    ///
+    /// `adc.read_as::<Raw>(ch)` is the right-aligned sample of `ch`, the
+    /// channel specific methods such as [`vref`](Self::vref) and
+    /// [`pin`](Self::pin) return the register data.
     ///
     /// # Panics
     ///
     /// * (debug) ADC is not enabled
     /// * (debug) ADC has a pending disable request
-    /// * (debug) V<sub>BAT</sub> is not enabled
+    /// * (debug) Voltage reference is not enabled, and the format uses
+    ///   V<sub>DDA</sub>
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
-    ///     self as hal,
-    ///     adc::{self, Adc},
-    ///     dac::{Dac, ModeChip},
-    ///     pac, rcc,
+    ///     adc::{self, Adc, Millivolts, Raw},
+    ///     gpio::{pins::B4, Analog},
+    ///     pac,
     ///     util::new_delay,
     /// };
     ///
@@ -1381,26 +3449,56 @@ impl Adc {
     ///
     /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
     /// adc.calibrate(&mut delay);
-    ///
-    /// adc.enable();
-    /// adc.enable_vbat();
     /// adc.set_max_sample_time();
-    /// let sample: u16 = adc.vbat();
+    /// adc.enable();
+    /// adc.enable_vref();
+    ///
+    /// let counts: u16 = adc.read_as::<Raw>(B4::ADC_CH);
+    /// let mv: u16 = adc.read_as::<Millivolts>(B4::ADC_CH);
     /// ```
-    pub fn vbat(&mut self) -> u16 {
+    pub fn read_as<F: SampleFormat>(&mut self, ch: Ch) -> F::Output {
         debug_assert!(self.is_enabled());
-        debug_assert!(self.is_vbat_enabled());
-        self.cfg_ch_seq(Ch::Vbat.mask());
-        self.start_conversion();
-        self.poll_data()
+
+        let ovs: Option<(OversampleRatio, OversampleShift)> = self.oversampling();
+        let max: u16 = full_scale(self.adc.cfgr1.read().res().variant(), ovs);
+
+        let vdda_mv: u16 = if F::VDDA {
+            let vref: u16 = self.read_right_aligned(Ch::Vref, ovs.is_some());
+            vdda_mv(vref, vref_cal(), max)
+        } else {
+            0
+        };
+
+        let sample: u16 = self.read_right_aligned(ch, ovs.is_some());
+        F::convert(sample, max, vdda_mv)
     }
-}
 
-// on-off control
-// see RM0453 rev 1 section 18.3.4 page 537
-#[cfg(not(feature = "stm32wl5x_cm0p"))]
-impl Adc {
-    /// Returns `true` if the ADC is enabled.
+    fn oversampling(&self) -> Option<(OversampleRatio, OversampleShift)> {
+        let cfgr2 = self.adc.cfgr2.read();
+        if cfgr2.ovse().is_enabled() {
+            Some((
+                cfgr2.ovsr().variant(),
+                cfgr2.ovss().variant().unwrap_or(OversampleShift::Shift8),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Convert millivolts to the counts of a conversion at the configured
+    /// resolution.
+    ///
+    /// This samples the internal voltage reference to measure
+    /// V<sub>DDA</sub>, the counts are relative to V<sub>DDA</sub>.
+    /// The result is the counts of a single conversion without
+    /// oversampling, this is the scale of the analog watchdog thresholds,
+    /// see [`set_watchdog1`](Self::set_watchdog1).
+    /// Millivolts above V<sub>DDA</sub> saturate at full scale.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
     ///
     /// # Example
     ///
@@ -1411,127 +3509,245 @@ impl Adc {
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_max_sample_time();
+    /// adc.enable();
+    /// adc.enable_vref();
     ///
-    /// // enable the HSI16 source clock
-    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
-    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
-    ///
-    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
-    /// assert_eq!(adc.is_enabled(), false);
+    /// let counts: u16 = adc.mv_to_counts(1650);
     /// ```
-    #[inline]
-    #[must_use]
-    pub fn is_enabled(&self) -> bool {
-        self.adc.cr.read().aden().bit_is_set()
+    pub fn mv_to_counts(&mut self, mv: u16) -> u16 {
+        debug_assert!(self.is_enabled());
+
+        let res: Resolution = self.adc.cfgr1.read().res().variant();
+        let ovs: Option<(OversampleRatio, OversampleShift)> = self.oversampling();
+        let vref: u16 = self.read_right_aligned(Ch::Vref, ovs.is_some());
+        let vdda_mv: u16 = vdda_mv(vref, vref_cal(), full_scale(res, ovs));
+        mv_counts(mv, vdda_mv, full_scale(res, None))
     }
 
-    /// Returns `true` if an ADC disable command is in-progress.
-    #[inline]
-    #[must_use]
-    pub fn disable_in_progress(&self) -> bool {
-        self.adc.cr.read().addis().bit_is_set()
+    fn read_right_aligned(&mut self, ch: Ch, oversampling: bool) -> u16 {
+        self.cfg_ch_seq(ch.mask());
+        self.start_conversion();
+        let data: u16 = self.poll_data();
+        // the oversampler output is always right-aligned
+        if oversampling {
+            data
+        } else {
+            self.right_aligned(data)
+        }
     }
 
-    /// Returns `true` if the ADC is disabled, and there is no disable command
-    /// in-progress.
+    /// Estimate the effective number of bits (ENOB) on a channel.
+    ///
+    /// This takes `n` samples of `ch`, computes the standard deviation in
+    /// LSBs, and returns the ENOB relative to the configured resolution.
+    ///
+    /// The channel **must** be driven by a quiet DC source; signal variation
+    /// is indistinguishable from noise and lowers the result.
+    /// The result saturates at the configured resolution when the noise is
+    /// below the ideal quantization noise, and is the resolution when `n` is
+    /// less than two.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
     ///     adc::{self, Adc},
-    ///     pac,
+    ///     pac, rcc,
+    ///     util::new_delay,
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
     ///
     /// // enable the HSI16 source clock
     /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
     /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
     ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    ///
     /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
-    /// assert_eq!(adc.is_disabled(), true);
+    /// adc.calibrate(&mut delay);
+    /// adc.set_max_sample_time();
+    /// adc.enable();
+    /// adc.enable_vref();
+    ///
+    /// let enob: f32 = adc.enob(adc::Ch::Vref, 1024);
     /// ```
+    #[cfg(feature = "float")]
+    pub fn enob(&mut self, ch: Ch, n: usize) -> f32 {
+        use pac::adc::cfgr1::RES_A;
+
+        debug_assert!(self.is_enabled());
+        debug_assert!(!self.disable_in_progress());
+
+        let bits: u8 = match self.adc.cfgr1.read().res().variant() {
+            RES_A::Bits12 => 12,
+            RES_A::Bits10 => 10,
+            RES_A::Bits8 => 8,
+            RES_A::Bits6 => 6,
+        };
+
+        self.cfg_ch_seq(ch.mask());
+        let variance: f32 = sample_variance((0..n).map(|_| {
+            self.start_conversion();
+            let data: u16 = self.poll_data();
+            self.right_aligned(data)
+        }));
+
+        enob_from_variance(bits, variance)
+    }
+
+    /// Enable V<sub>BAT</sub>.
+    ///
+    /// To prevent any unwanted consumption on the battery, it is recommended to
+    /// enable the bridge divider only when needed for ADC conversion.
+    #[inline]
+    pub fn enable_vbat(&mut self) {
+        self.adc.ccr.modify(|_, w| w.vbaten().enabled())
+    }
+
+    /// Disable V<sub>BAT</sub>.
+    #[inline]
+    pub fn disable_vbat(&mut self) {
+        self.adc.ccr.modify(|_, w| w.vbaten().disabled());
+    }
+
+    /// Returns `true` if V<sub>BAT</sub> is enabled.
     #[inline]
     #[must_use]
-    pub fn is_disabled(&self) -> bool {
-        let cr = self.adc.cr.read();
-        cr.aden().bit_is_clear() && cr.addis().bit_is_clear()
+    pub fn is_vbat_enabled(&self) -> bool {
+        self.adc.ccr.read().vbaten().is_enabled()
     }
 
-    /// Start the ADC enable procedure.
+    /// Sample the V<sub>BAT</sub> pin.
     ///
-    /// This is advanced ADC usage, most of the time you will want to use
-    /// [`enable`](Self::enable).
+    /// This is internally connected to a bridge divider, the converted digital
+    /// value is a third the V<sub>BAT</sub> voltage.
     ///
-    /// This will not poll for completion, when this method returns the ADC
-    /// may not be enabled.
+    /// # Panics
     ///
-    /// The method returns `true` if the caller function should poll for
-    /// completion (the ADC was not already enabled),
-    /// if the ADC was already enabled and the ADRDY interrupt was cleared then
-    /// the ADRDY bit will **not** be set again after calling this method
-    /// which can lead to polling loops that will never terminate.
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    /// * (debug) V<sub>BAT</sub> is not enabled
+    ///
+    /// # Sample Time
+    ///
+    /// The sample time must be greater than or equal to [`VBAT_MIN_SAMPLE`],
+    /// see [`is_sample_time_ok`](Self::is_sample_time_ok).
     ///
     /// # Example
     ///
+    /// [`Ts::Cyc160`] is 10 µs with the 16 MHz HSI16, which is less than
+    /// [`VBAT_MIN_SAMPLE`], a slower ADC clock is required.
+    ///
     /// ```no_run
     /// use stm32wlxx_hal::{
+    ///     self as hal,
     ///     adc::{self, Adc},
-    ///     pac,
+    ///     dac::{Dac, ModeChip},
+    ///     pac, rcc,
+    ///     util::new_delay,
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    ///
+    /// // 4 MHz MSI / 4 is 1 MHz
     /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
-    /// if adc.start_enable() {
-    ///     while Adc::isr().adrdy().is_not_ready() {}
-    /// }
+    /// adc.calibrate(&mut delay);
+    ///
+    /// adc.enable();
+    /// adc.enable_vbat();
+    /// adc.set_max_sample_time();
+    /// let sample: u16 = adc.vbat();
     /// ```
-    #[inline]
-    #[must_use = "the return value indicates if you should wait for completion"]
-    pub fn start_enable(&mut self) -> bool {
-        if self.adc.cr.read().aden().is_disabled() {
-            self.adc.isr.write(|w| w.adrdy().set_bit());
-            self.adc.cr.write(|w| w.aden().set_bit());
-            true
-        } else {
-            false
-        }
+    pub fn vbat(&mut self) -> u16 {
+        debug_assert!(self.is_enabled());
+        debug_assert!(self.is_vbat_enabled());
+        self.cfg_ch_seq(Ch::Vbat.mask());
+        self.start_conversion();
+        self.poll_data()
     }
 
-    /// Enable the ADC and poll for completion.
+    /// Estimate the battery state of charge in percent.
+    ///
+    /// This samples V<sub>BAT</sub> and the internal voltage reference to
+    /// scale the V<sub>BAT</sub> sample by the measured V<sub>DDA</sub>,
+    /// then interpolates the voltage on the discharge `curve`.
+    ///
+    /// This assumes a 12-bit resolution without oversampling.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    /// * (debug) V<sub>BAT</sub> is not enabled
+    /// * (debug) Voltage reference is not enabled
+    ///
+    /// # Sample Time
+    ///
+    /// The sample times must be greater than or equal to
+    /// [`VBAT_MIN_SAMPLE`] and [`VREF_MIN_SAMPLE`], see
+    /// [`is_sample_time_ok`](Self::is_sample_time_ok).
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
-    ///     adc::{self, Adc},
+    ///     adc::{self, Adc, BatteryCurve},
     ///     pac,
+    ///     util::new_delay,
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    ///
+    /// // 4 MHz MSI / 4 is 1 MHz
     /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.calibrate(&mut delay);
+    ///
     /// adc.enable();
+    /// adc.enable_vbat();
+    /// adc.enable_vref();
+    /// adc.set_max_sample_time();
+    /// let percent: u8 = adc.battery_percent(&BatteryCurve::CR2032);
     /// ```
-    #[inline]
-    pub fn enable(&mut self) {
-        if self.start_enable() {
-            while self.adc.isr.read().adrdy().is_not_ready() {}
-        }
+    pub fn battery_percent(&mut self, curve: &BatteryCurve) -> u8 {
+        let vref: u16 = self.vref();
+        let vbat: u16 = self.vbat();
+        curve.percent(vbat_mv(vbat, vref, vref_cal()))
     }
 
-    /// Start the ADC disable procedure.
+    /// Sample two channels in one sequence and return the difference
+    /// `pos - neg`.
     ///
-    /// This is advanced ADC usage, most of the time you will want to use
-    /// [`disable`](Self::disable).
+    /// This provides pseudo-differential measurements, for example for
+    /// ratiometric sensors, without external hardware.
+    /// The difference saturates at the bounds of `i16`, which is only
+    /// possible with oversampling enabled.
     ///
-    /// This will not poll for completion, when this function returns the ADC
-    /// may not be disabled.
+    /// **Note:** Both channels must have compatible sample times.
+    /// The channels are converted back-to-back, a channel with a much shorter
+    /// sample time than its source requires will not settle, skewing the
+    /// difference.
+    /// See [`set_sample_times`](Self::set_sample_times).
     ///
-    /// This will stop any conversions in-progress.
+    /// # Panics
     ///
-    /// The ADC takes about 20 CPU cycles to disable with a 48MHz sysclk and
-    /// the ADC on the 16MHz HSI clock.
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    /// * (debug) `pos` and `neg` are the same channel
     ///
     /// # Example
     ///
@@ -1544,32 +3760,80 @@ impl Adc {
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
     /// adc.enable();
-    /// // ... use ADC
-    /// adc.start_disable();
-    /// while !adc.is_disabled() {}
+    /// adc.set_max_sample_time();
+    ///
+    /// let diff: i16 = adc.read_differential(adc::Ch::In3, adc::Ch::In4);
     /// ```
-    pub fn start_disable(&mut self) {
-        // RM0453 rev 1 section 18.3.4 page 537 ADC on-off control
-        // 1. Check that ADSTART = 0 in the ADC_CR register to ensure that no
-        //    conversion is ongoing.
-        //    If required, stop any ongoing conversion by writing 1 to the
-        //    ADSTP bit in the ADC_CR register and waiting until this bit is
-        //    read at 0.
-        // 2. Set ADDIS = 1 in the ADC_CR register.
-        // 3. If required by the application, wait until ADEN = 0 in the ADC_CR
-        //    register, indicating that the ADC is fully disabled
-        //    (ADDIS is automatically reset once ADEN = 0).
-        // 4. Clear the ADRDY bit in ADC_ISR register by programming this bit to 1
-        //    (optional).
-        self.stop_conversion();
-        // Setting ADDIS to `1` is only effective when ADEN = 1 and ADSTART = 0
-        // (which ensures that no conversion is ongoing)
-        if self.adc.cr.read().aden().bit_is_set() {
-            self.adc.cr.write(|w| w.addis().set_bit());
+    pub fn read_differential(&mut self, pos: Ch, neg: Ch) -> i16 {
+        debug_assert!(self.is_enabled());
+        debug_assert!(pos != neg);
+
+        self.cfg_ch_seq(pos.mask() | neg.mask());
+        self.start_conversion();
+        let first: u16 = self.poll_data();
+        let second: u16 = self.poll_data();
+        self.adc.isr.write(|w| w.eos().set_bit());
+
+        // See section 18.3.8 page 542 "Channel selection"
+        // channels are converted in ascending order unless SCANDIR is set
+        let pos_first: bool =
+            ((pos as u8) < (neg as u8)) != self.adc.cfgr1.read().scandir().is_backward();
+        if pos_first {
+            saturating_diff(first, second)
+        } else {
+            saturating_diff(second, first)
         }
     }
 
-    /// Disable the ADC and poll for completion.
+    /// Order in which the channels in `mask` are converted.
+    ///
+    /// The ADC sequencer is not fully configurable, channels are scanned in
+    /// ascending channel number order regardless of the order the mask was
+    /// built in.
+    /// Use this to index the results of a sequence, for example samples
+    /// transferred with DMA or read with
+    /// [`read_raw_sequence`](Self::read_raw_sequence).
+    /// Invalid channels in the mask are ignored.
+    ///
+    /// If the scan direction is backward the channels are converted in
+    /// descending order, reverse the iterator with [`Iterator::rev`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::adc::{Adc, Ch};
+    ///
+    /// let mask: u32 = Ch::Vbat.mask() | Ch::In3.mask() | Ch::Vref.mask();
+    /// let mut order = Adc::sequence_order(mask);
+    /// assert_eq!(order.next(), Some(Ch::In3));
+    /// assert_eq!(order.next(), Some(Ch::Vref));
+    /// assert_eq!(order.next(), Some(Ch::Vbat));
+    /// assert_eq!(order.next(), None);
+    /// ```
+    pub fn sequence_order(mask: u32) -> impl DoubleEndedIterator<Item = Ch> {
+        let mask: u32 = mask & CH_MASK;
+        (0..u32::BITS as u8)
+            .filter(move |idx| mask & (1 << idx) != 0)
+            .filter_map(Ch::from_index)
+    }
+
+    /// Sample a sequence of channels.
+    ///
+    /// This starts a conversion of every channel in `mask`, and returns an
+    /// iterator that yields each channel with its sample as the conversions
+    /// complete.
+    /// Invalid channels in the mask are ignored.
+    ///
+    /// Channels are converted in ascending order, or descending order if the
+    /// scan direction is backward.
+    ///
+    /// If the iterator is dropped before all samples have been read the
+    /// conversion is stopped.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
     ///
     /// # Example
     ///
@@ -1582,241 +3846,2059 @@ impl Adc {
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
     /// adc.enable();
-    /// // ... use ADC
-    /// adc.disable();
+    /// adc.enable_vref();
+    /// adc.enable_vbat();
+    /// adc.set_max_sample_time();
+    ///
+    /// for (ch, sample) in adc.sequence(adc::Ch::Vref.mask() | adc::Ch::Vbat.mask()) {
+    ///     // ... use sample
+    /// }
     /// ```
-    pub fn disable(&mut self) {
-        self.start_disable();
-        while !self.is_disabled() {}
+    pub fn sequence(&mut self, mask: u32) -> impl Iterator<Item = (Ch, u16)> + '_ {
+        debug_assert!(self.is_enabled());
+
+        let remaining: u32 = mask & CH_MASK;
+        let backward: bool = self.adc.cfgr1.read().scandir().is_backward();
+        if remaining != 0 {
+            self.cfg_ch_seq(remaining);
+            self.start_conversion();
+        }
+
+        Sequence {
+            adc: self,
+            remaining,
+            backward,
+        }
     }
-}
 
-// calibration related methods
-// see RM0453 rev 1 section 18.3.3 page 536
-#[cfg(not(feature = "stm32wl5x_cm0p"))]
-impl Adc {
-    /// Calibrate the ADC for additional accuracy.
+    /// Sample a sequence of channels without any post-processing.
     ///
-    /// Calibration should be performed before starting A/D conversion.
-    /// It removes the offset error which may vary from chip to chip due to
-    /// process variation.
+    /// This starts a conversion of every channel in `mask`, and writes the
+    /// raw data register value of each conversion to `out` in conversion
+    /// order.
+    /// Invalid channels in the mask are ignored.
     ///
-    /// The calibration factor is lost in the following cases:
-    /// * The power supply is removed from the ADC
-    ///   (for example when entering STANDBY or VBAT mode)
-    /// * The ADC peripheral is reset.
+    /// Channels are converted in ascending order, or descending order if the
+    /// scan direction is backward.
     ///
-    /// This will disable the ADC if it is not already disabled.
+    /// Returns `Ok(true)` if an overrun occurred.
+    /// With an overrun the conversions that completed while the data register
+    /// was unread are lost, and the following conversions are written to the
+    /// elements of the lost conversions.
+    /// The samples after the first lost conversion no longer belong to the
+    /// channel of their element, and the trailing elements are not written.
+    /// Discard the samples if an overrun occurred.
     ///
-    /// This function is the simple way to calibrate the ADC, you can use
-    /// these methods to achieve the same results if you desire finer controls:
+    /// Returns [`Error::Length`] if `out` is shorter than the number of
+    /// channels in `mask`, no conversion is started.
     ///
-    /// * [`enable_vreg`](Self::enable_vreg)
-    /// * A delay function
-    /// * [`start_calibrate`](Self::start_calibrate)
-    /// * [`set_ier`](Self::set_ier) (optional)
-    /// * [`Adc::isr`]
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
     ///     adc::{self, Adc},
-    ///     pac, rcc,
-    ///     util::new_delay,
+    ///     pac,
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
-    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
-    ///
-    /// // enable the HSI16 source clock
-    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
-    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// adc.enable_vref();
+    /// adc.enable_vbat();
+    /// adc.set_max_sample_time();
     ///
-    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
-    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
-    /// adc.calibrate(&mut delay);
+    /// let mut samples: [u16; 2] = [0; 2];
+    /// let overran: bool =
+    ///     adc.read_raw_sequence(adc::Ch::Vref.mask() | adc::Ch::Vbat.mask(), &mut samples)?;
+    /// # Ok::<(), stm32wlxx_hal::adc::Error>(())
     /// ```
-    pub fn calibrate<D: DelayUs<u8>>(&mut self, delay: &mut D) {
-        self.enable_vreg();
+    pub fn read_raw_sequence(&mut self, mask: u32, out: &mut [u16]) -> Result<bool, Error> {
+        debug_assert!(self.is_enabled());
 
-        // voltage regulator output is available after T_ADCVREG_SETUP
-        delay.delay_us(T_ADCVREG_SETUP_MICROS);
+        let mask: u32 = mask & CH_MASK;
+        let len: usize = mask.count_ones() as usize;
+        let out: &mut [u16] = out.get_mut(..len).ok_or(Error::Length)?;
+        if len == 0 {
+            return Ok(false);
+        }
 
-        self.start_calibrate();
+        self.adc
+            .isr
+            .write(|w| w.eoc().set_bit().eos().set_bit().ovr().set_bit());
+        self.cfg_ch_seq(mask);
+        self.start_conversion();
 
-        // takes 401 cycles at 48MHz sysclk, ADC at 16MHz with HSI
-        while self.adc.cr.read().adcal().is_calibrating() {}
-        self.adc.isr.write(|w| w.eocal().set_bit());
+        'samples: for sample in out.iter_mut() {
+            loop {
+                let isr = self.adc.isr.read();
+                if isr.eoc().is_complete() {
+                    *sample = self.data();
+                    self.adc.isr.write(|w| w.eoc().set_bit());
+                    break;
+                }
+                // the remaining conversions were lost to an overrun
+                if isr.eos().is_complete() {
+                    break 'samples;
+                }
+            }
+        }
+
+        while self.adc.isr.read().eos().is_not_complete() {}
+        let overran: bool = self.adc.isr.read().ovr().is_overrun();
+        self.adc.isr.write(|w| w.eos().set_bit().ovr().set_bit());
+        if overran {
+            warn!("ADC overrun, conversions were lost");
+        }
+
+        Ok(overran)
     }
 
-    /// Enable the ADC voltage regulator for calibration.
+    /// Start an interrupt driven scan of a sequence of channels.
     ///
-    /// This is advanced ADC usage, most of the time you will want to use
-    /// [`calibrate`](Self::calibrate).
+    /// This enables the end of conversion (EOC) and end of sequence (EOS)
+    /// interrupts and starts a conversion of every channel in `mask`.
+    /// Invalid channels in the mask are ignored.
     ///
-    /// This will disable the ADC and DMA request generation if not already
-    /// disabled.
+    /// Call [`on_eoc`](Self::on_eoc) from the `ADC` interrupt handler to
+    /// read each sample, the scan restarts after every sequence until it is
+    /// stopped with [`stop_irq_scan`](Self::stop_irq_scan).
     ///
-    /// You **MUST** wait [`T_ADCVREG_SETUP`] before the voltage regulator
-    /// output is available.  This delay is not performed for you.
+    /// This provides multi-channel sampling without a DMA channel, at the
+    /// cost of one interrupt per sample.
+    /// The interrupt handler must read each sample before the next
+    /// conversion completes, otherwise samples are lost to an overrun.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC has a pending disable request
+    /// * (debug) An ADC conversion is in-progress
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
     ///     adc::{self, Adc},
-    ///     pac, rcc,
-    ///     util::new_delay,
+    ///     pac,
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
-    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// adc.enable_vref();
+    /// adc.enable_vbat();
+    /// adc.set_max_sample_time();
     ///
-    /// // enable the HSI16 source clock
-    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
-    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    /// adc.start_irq_scan(adc::Ch::Vref.mask() | adc::Ch::Vbat.mask());
+    /// # #[cfg(all(not(feature = "stm32wl5x_cm0p"), feature = "rt"))]
+    /// unsafe { Adc::unmask_irq() };
     ///
-    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
-    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
-    /// adc.enable_vreg();
-    /// delay.delay_us(u32::from(adc::T_ADCVREG_SETUP_MICROS));
+    /// // in the ADC interrupt handler
+    /// // the ADC is moved into a static shared with the handler
+    /// adc.on_eoc(|sample| {
+    ///     // ... use sample
+    /// });
     /// ```
-    pub fn enable_vreg(&mut self) {
-        // RM0453 rev 1 section 18.3.3 page 536 Software calibration procedure
-        // 1. Ensure that ADEN = 0, ADVREGEN = 1 and DMAEN = 0.
-        // 2. Set ADCAL = 1.
-        // 3. Wait until ADCAL = 0 (or until EOCAL = 1).
-        //    This can be handled by interrupt if the interrupt is enabled by
-        //    setting the EOCALIE bit in the ADC_IER register
-        // 4. The calibration factor can be read from bits 6:0 of ADC_DR or
-        //    ADC_CALFACT registers.
-
-        // takes appx 55 cycles when already disabled
-        self.disable();
+    pub fn start_irq_scan(&mut self, mask: u32) {
+        debug_assert!(self.is_enabled());
 
-        // enable the voltage regulator as soon as possible to start the
-        // countdown on the regulator setup time
-        // this is a write because all other fields must be zero
-        self.adc.cr.write(|w| w.advregen().enabled());
-        // disable DMA per the calibration procedure
-        self.adc.cfgr1.modify(|_, w| w.dmaen().clear_bit());
+        self.adc
+            .isr
+            .write(|w| w.eoc().set_bit().eos().set_bit().ovr().set_bit());
+        self.cfg_ch_seq(mask & CH_MASK);
+        self.adc
+            .ier
+            .modify(|_, w| w.eocie().enabled().eosie().enabled());
+        self.start_conversion();
     }
 
-    /// Disable the ADC voltage regulator.
+    /// Handle the end of conversion interrupt for
+    /// [`start_irq_scan`](Self::start_irq_scan).
     ///
-    /// # Panics
+    /// This should be called from the `ADC` interrupt handler.
     ///
-    /// * (debug) ADC is enabled
-    #[inline]
-    pub fn disable_vreg(&mut self) {
-        debug_assert!(self.is_disabled());
-        self.adc.cr.write(|w| w.advregen().disabled());
-    }
-
-    /// Start the ADC calibration.
+    /// 1. If a conversion completed the data register is read, the EOC flag
+    ///    is cleared, and `f` is called with the sample.
+    /// 2. If the sequence completed the EOS flag is cleared and the scan is
+    ///    restarted.
     ///
-    /// This is advanced ADC usage, most of the time you will want to use
-    /// [`calibrate`](Self::calibrate).
+    /// Samples are passed to `f` in conversion order.
+    /// Channels are converted in ascending order, or descending order if the
+    /// scan direction is backward.
     ///
-    /// When this function returns the ADC calibration has started, but
-    /// may not have finished.
-    /// Check if the ADC calibration has finished with [`Adc::isr`].
+    /// # Example
     ///
-    /// # Panics
+    /// See [`start_irq_scan`](Self::start_irq_scan).
+    pub fn on_eoc<F: FnMut(u16)>(&mut self, mut f: F) {
+        let isr = self.adc.isr.read();
+        if isr.eoc().is_complete() {
+            let data: u16 = self.data();
+            self.adc.isr.write(|w| w.eoc().set_bit());
+            f(data);
+        }
+        if isr.eos().is_complete() {
+            self.adc.isr.write(|w| w.eos().set_bit());
+            self.start_conversion();
+        }
+    }
+
+    /// Stop an interrupt driven scan started with
+    /// [`start_irq_scan`](Self::start_irq_scan).
     ///
-    /// * (debug) ADC is enabled.
-    /// * (debug) ADC voltage regulator is not enabled.
+    /// This disables the EOC and EOS interrupts, stops the conversion
+    /// in-progress, and clears the EOC, EOS, and overrun flags.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
     ///     adc::{self, Adc},
-    ///     pac, rcc,
-    ///     util::new_delay,
+    ///     pac,
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
-    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
-    ///
-    /// // enable the HSI16 source clock
-    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
-    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
-    ///
-    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
-    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// adc.enable_vref();
+    /// adc.set_max_sample_time();
     ///
-    /// adc.enable_vreg();
-    /// delay.delay_us(u32::from(adc::T_ADCVREG_SETUP_MICROS));
-    /// adc.start_calibrate();
-    /// // datasheet says this takes 82 ADC clock cycles
-    /// // my measurements are closer to 120 ADC clock cycles
-    /// while Adc::isr().eocal().is_not_complete() {}
+    /// adc.start_irq_scan(adc::Ch::Vref.mask());
+    /// // ... sample for a while
+    /// adc.stop_irq_scan();
     /// ```
-    #[inline]
-    pub fn start_calibrate(&mut self) {
-        debug_assert!(self.adc.cr.read().advregen().is_enabled());
-        debug_assert!(self.is_disabled());
+    pub fn stop_irq_scan(&mut self) {
         self.adc
-            .cr
-            .write(|w| w.adcal().start_calibration().advregen().enabled());
+            .ier
+            .modify(|_, w| w.eocie().disabled().eosie().disabled());
+        self.stop_conversion();
+        self.adc
+            .isr
+            .write(|w| w.eoc().set_bit().eos().set_bit().ovr().set_bit());
     }
 
-    /// Get the ADC calibration factor.
+    /// Sample a channel immediately, preempting any conversion in-progress.
     ///
-    /// # Example
+    /// This is intended for urgent reads while a continuous background scan
+    /// is running.
     ///
-    /// See [`force_cal`](Self::force_cal).
-    #[inline]
-    pub fn calfact(&self) -> u8 {
-        self.adc.calfact.read().calfact().bits()
-    }
-
-    /// Force the ADC calibration.
+    /// 1. The conversion in-progress (if any) is stopped with `ADSTP`.
+    /// 2. `ch` is sampled with a single conversion.
+    /// 3. The previous channel selection and `CFGR1` configuration are
+    ///    restored.
+    /// 4. If a conversion was in-progress it is restarted.
     ///
-    /// The calibration factor is lost each time power is removed from the ADC
-    /// (for example when entering standby or V<sub>BAT</sub> mode)
-    /// It is possible to save and restore the calibration factor with firmware
-    /// to save time when re-starting the ADC (as long as temperature and
-    /// voltage are stable during the ADC power-down).
+    /// **Note:** This disturbs the scan timing.
+    /// The interrupted conversion is discarded, and the restarted scan begins
+    /// again from the start of the sequence.
     ///
     /// # Panics
     ///
     /// * (debug) ADC is not enabled
-    /// * (debug) ADC conversion is in-progress
+    /// * (debug) ADC has a pending disable request
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
     ///     adc::{self, Adc},
-    ///     pac, rcc,
-    ///     util::new_delay,
+    ///     pac,
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
-    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
-    ///
-    /// // enable the HSI16 source clock
-    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
-    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
-    ///
-    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
-    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
-    /// adc.calibrate(&mut delay);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// adc.enable_vref();
+    /// adc.set_max_sample_time();
     ///
-    /// // save the calibration factor
-    /// let calfact: u8 = adc.calfact();
+    /// // ... start a background scan
     ///
-    /// // restore the calibration
-    /// adc.enable(); // ADC must be enabled to restore the calibration
-    /// adc.force_cal(calfact);
+    /// let vref: u16 = adc.read_now(adc::Ch::Vref);
     /// ```
-    #[inline]
-    pub fn force_cal(&mut self, calfact: u8) {
+    pub fn read_now(&mut self, ch: Ch) -> u16 {
         debug_assert!(self.is_enabled());
-        debug_assert!(self.adc.cr.read().adstart().bit_is_clear());
+
+        let was_active: bool = self.adc.cr.read().adstart().is_active();
+        self.stop_conversion();
+
+        let cfgr1: u32 = self.adc.cfgr1.read().bits();
+        let chselr: u32 = self.adc.chselr0().read().bits();
+
+        // CONT and CHSELRMOD can only be written when ADSTART = 0
+        self.adc
+            .cfgr1
+            .modify(|_, w| w.cont().single().chselrmod().bit_per_input());
+        // discard any result from the interrupted conversion
+        self.adc
+            .isr
+            .write(|w| w.eoc().set_bit().eos().set_bit().ccrdy().set_bit());
+        self.cfg_ch_seq(ch.mask());
+        self.start_conversion();
+        let data: u16 = self.poll_data();
+
+        // restore the previous configuration
+        self.adc.isr.write(|w| w.ccrdy().set_bit().eos().set_bit());
+        // safety: value was read from the register
+        self.adc.cfgr1.write(|w| unsafe { w.bits(cfgr1) });
+        self.adc.chselr0().write(|w| unsafe { w.bits(chselr) });
+        self.wait_chsel_ready();
+
+        if was_active {
+            self.start_conversion();
+        }
+
+        data
+    }
+}
+
+// on-off control
+// see RM0453 rev 1 section 18.3.4 page 537
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl Adc {
+    /// Returns `true` if the ADC is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// assert_eq!(adc.is_enabled(), false);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.adc.cr.read().aden().bit_is_set()
+    }
+
+    /// Returns `true` if an ADC disable command is in-progress.
+    #[inline]
+    #[must_use]
+    pub fn disable_in_progress(&self) -> bool {
+        self.adc.cr.read().addis().bit_is_set()
+    }
+
+    /// Returns `true` if the ADC is disabled, and there is no disable command
+    /// in-progress.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// assert_eq!(adc.is_disabled(), true);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_disabled(&self) -> bool {
+        let cr = self.adc.cr.read();
+        cr.aden().bit_is_clear() && cr.addis().bit_is_clear()
+    }
+
+    /// Start the ADC enable procedure.
+    ///
+    /// This is advanced ADC usage, most of the time you will want to use
+    /// [`enable`](Self::enable).
+    ///
+    /// This will not poll for completion, when this method returns the ADC
+    /// may not be enabled.
+    ///
+    /// The method returns `true` if the caller function should poll for
+    /// completion (the ADC was not already enabled),
+    /// if the ADC was already enabled and the ADRDY interrupt was cleared then
+    /// the ADRDY bit will **not** be set again after calling this method
+    /// which can lead to polling loops that will never terminate.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// if adc.start_enable() {
+    ///     while Adc::isr().adrdy().is_not_ready() {}
+    /// }
+    /// ```
+    #[inline]
+    #[must_use = "the return value indicates if you should wait for completion"]
+    pub fn start_enable(&mut self) -> bool {
+        let cr = self.adc.cr.read();
+        if cr.aden().is_disabled() {
+            self.adc.isr.write(|w| w.adrdy().set_bit());
+            // preserve the voltage regulator state, ADVREGEN is writeable
+            // when the ADC is disabled
+            let advregen: bool = cr.advregen().bit();
+            self.adc
+                .cr
+                .write(|w| w.aden().set_bit().advregen().bit(advregen));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Enable the ADC and poll for completion.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// ```
+    #[inline]
+    pub fn enable(&mut self) {
+        if self.start_enable() {
+            while self.adc.isr.read().adrdy().is_not_ready() {}
+        }
+    }
+
+    /// Start the ADC disable procedure.
+    ///
+    /// This is advanced ADC usage, most of the time you will want to use
+    /// [`disable`](Self::disable).
+    ///
+    /// This will not poll for completion, when this function returns the ADC
+    /// may not be disabled.
+    ///
+    /// This will stop any conversions in-progress.
+    ///
+    /// The ADC takes about 20 CPU cycles to disable with a 48MHz sysclk and
+    /// the ADC on the 16MHz HSI clock.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// // ... use ADC
+    /// adc.start_disable();
+    /// while !adc.is_disabled() {}
+    /// ```
+    pub fn start_disable(&mut self) {
+        // RM0453 rev 1 section 18.3.4 page 537 ADC on-off control
+        // 1. Check that ADSTART = 0 in the ADC_CR register to ensure that no
+        //    conversion is ongoing.
+        //    If required, stop any ongoing conversion by writing 1 to the
+        //    ADSTP bit in the ADC_CR register and waiting until this bit is
+        //    read at 0.
+        // 2. Set ADDIS = 1 in the ADC_CR register.
+        // 3. If required by the application, wait until ADEN = 0 in the ADC_CR
+        //    register, indicating that the ADC is fully disabled
+        //    (ADDIS is automatically reset once ADEN = 0).
+        // 4. Clear the ADRDY bit in ADC_ISR register by programming this bit to 1
+        //    (optional).
+        self.stop_conversion();
+        // Setting ADDIS to `1` is only effective when ADEN = 1 and ADSTART = 0
+        // (which ensures that no conversion is ongoing)
+        if self.adc.cr.read().aden().bit_is_set() {
+            self.adc.cr.write(|w| w.addis().set_bit());
+        }
+    }
+
+    /// Disable the ADC and poll for completion.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.enable();
+    /// // ... use ADC
+    /// adc.disable();
+    /// ```
+    pub fn disable(&mut self) {
+        self.start_disable();
+        while !self.is_disabled() {}
+    }
+}
+
+// calibration related methods
+// see RM0453 rev 1 section 18.3.3 page 536
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl Adc {
+    /// Calibrate the ADC for additional accuracy.
+    ///
+    /// Calibration should be performed before starting A/D conversion.
+    /// It removes the offset error which may vary from chip to chip due to
+    /// process variation.
+    ///
+    /// The calibration factor is lost in the following cases:
+    /// * The power supply is removed from the ADC
+    ///   (for example when entering STANDBY or VBAT mode)
+    /// * The ADC peripheral is reset.
+    ///
+    /// This will disable the ADC if it is not already disabled.
+    /// A conversion in-progress is stopped (`ADSTP`) before the ADC is
+    /// disabled, the result of that conversion, and any result that was not
+    /// read, is discarded.
+    ///
+    /// The voltage regulator setup delay is skipped if the voltage regulator
+    /// is already enabled, for example within [`with_power`](Self::with_power).
+    ///
+    /// This function is the simple way to calibrate the ADC, you can use
+    /// these methods to achieve the same results if you desire finer controls:
+    ///
+    /// * [`enable_vreg`](Self::enable_vreg)
+    /// * A delay function
+    /// * [`start_calibrate`](Self::start_calibrate)
+    /// * [`set_ier`](Self::set_ier) (optional)
+    /// * [`Adc::isr`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac, rcc,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.calibrate(&mut delay);
+    /// ```
+    pub fn calibrate<D: DelayUs<u8>>(&mut self, delay: &mut D) {
+        let vreg_enabled: bool = self.is_vreg_enabled();
+        self.enable_vreg();
+        self.discard_data();
+
+        // voltage regulator output is available after T_ADCVREG_SETUP
+        if !vreg_enabled {
+            delay.delay_us(T_ADCVREG_SETUP_MICROS);
+        }
+
+        self.start_calibrate();
+
+        // takes 401 cycles at 48MHz sysclk, ADC at 16MHz with HSI
+        while self.adc.cr.read().adcal().is_calibrating() {}
+        self.adc.isr.write(|w| w.eocal().set_bit());
+    }
+
+    /// Calibrate the ADC, returning the change in the calibration factor.
+    ///
+    /// This reads the current calibration factor with
+    /// [`calfact`](Self::calfact), runs [`calibrate`](Self::calibrate), and
+    /// returns the new factor minus the previous factor.
+    ///
+    /// Periodic recalibration with this method can be used to track the
+    /// offset drift over temperature and supply voltage, and flag excessive
+    /// drift.
+    ///
+    /// The calibration factor is 7 bits, the delta is in the range
+    /// `-127..=127`.
+    /// If the previous calibration factor was lost (see
+    /// [`calibrate`](Self::calibrate)) the previous factor is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.calibrate(&mut delay);
+    ///
+    /// // ... some time later
+    /// adc.disable();
+    /// let drift: i8 = adc.recalibrate_and_delta(&mut delay);
+    /// if drift.abs() > 4 {
+    ///     // ... flag excessive drift
+    /// }
+    /// ```
+    pub fn recalibrate_and_delta<D: DelayUs<u8>>(&mut self, delay: &mut D) -> i8 {
+        let prev: u8 = self.calfact();
+        self.calibrate(delay);
+        calfact_delta(prev, self.calfact())
+    }
+
+    /// Run a function with the ADC voltage regulator and the ADC enabled.
+    ///
+    /// Calibrations and samples each power up the ADC, this batches the
+    /// power transitions for a sequence of operations.
+    ///
+    /// If the ADC is disabled and the voltage regulator is disabled this
+    /// enables the voltage regulator, waits [`T_ADCVREG_SETUP`] with `delay`,
+    /// and enables the ADC before calling `f`.
+    /// The ADC and voltage regulator are returned to their prior state after
+    /// `f` returns, an ADC that was enabled before the call is enabled again
+    /// if `f` disabled it.
+    ///
+    /// `f` is passed the delay for methods that require one.
+    /// [`calibrate`](Self::calibrate) within `f` skips the voltage regulator
+    /// setup delay, calibration disables the ADC,
+    /// [`enable`](Self::enable) must be called before sampling.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.set_max_sample_time();
+    ///
+    /// let (vref, vbat): (u16, u16) = adc.with_power(&mut delay, |adc, delay| {
+    ///     adc.calibrate(delay);
+    ///     adc.enable();
+    ///     adc.enable_vref();
+    ///     adc.enable_vbat();
+    ///     (adc.vref(), adc.vbat())
+    /// });
+    /// ```
+    pub fn with_power<D, R, F>(&mut self, delay: &mut D, f: F) -> R
+    where
+        D: DelayUs<u8>,
+        F: FnOnce(&mut Adc, &mut D) -> R,
+    {
+        let was_enabled: bool = self.is_enabled();
+        let power_up: bool = !was_enabled && !self.is_vreg_enabled();
+
+        if power_up {
+            self.enable_vreg();
+            // voltage regulator output is available after T_ADCVREG_SETUP
+            delay.delay_us(T_ADCVREG_SETUP_MICROS);
+        }
+        self.enable();
+
+        let ret: R = f(self, delay);
+
+        if was_enabled {
+            if !self.is_vreg_enabled() {
+                self.enable_vreg();
+                delay.delay_us(T_ADCVREG_SETUP_MICROS);
+            }
+            self.enable();
+        } else {
+            self.disable();
+            if power_up {
+                self.disable_vreg();
+            }
+        }
+        ret
+    }
+
+    /// Calibrate the ADC without blocking.
+    ///
+    /// This is the async equivalent of [`calibrate`](Self::calibrate).
+    ///
+    /// `vreg_setup` is a future that completes after at least
+    /// [`T_ADCVREG_SETUP`], typically a timer-backed delay from your
+    /// executor.
+    /// The end of calibration is awaited with the `EOCAL` interrupt,
+    /// [`on_interrupt`] must be called from the `ADC` interrupt handler and
+    /// the `ADC` interrupt must be unmasked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn vreg_delay() {}
+    /// # use stm32wlxx_hal::util::block_on;
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// unsafe { pac::NVIC::unmask(pac::Interrupt::ADC) };
+    ///
+    /// // block_on from your executor
+    /// // vreg_delay is a timer-backed delay of at least adc::T_ADCVREG_SETUP
+    /// block_on(adc.calibrate_async(vreg_delay()));
+    /// let calfact: u8 = adc.calfact();
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn calibrate_async<F: Future<Output = ()>>(&mut self, vreg_setup: F) {
+        self.enable_vreg();
+        self.discard_data();
+
+        // voltage regulator output is available after T_ADCVREG_SETUP
+        vreg_setup.await;
+
+        self.start_calibrate();
+
+        Eocal { adc: &self.adc }.await
+    }
+
+    /// Clear the flags of conversion results that were not read.
+    #[inline]
+    fn discard_data(&mut self) {
+        self.adc.isr.write(|w| {
+            w.eoc()
+                .set_bit()
+                .eos()
+                .set_bit()
+                .eosmp()
+                .set_bit()
+                .ovr()
+                .set_bit()
+        });
+    }
+
+    /// Enable the ADC voltage regulator for calibration.
+    ///
+    /// This is advanced ADC usage, most of the time you will want to use
+    /// [`calibrate`](Self::calibrate).
+    ///
+    /// This will disable the ADC and DMA request generation if not already
+    /// disabled.
+    ///
+    /// You **MUST** wait [`T_ADCVREG_SETUP`] before the voltage regulator
+    /// output is available.  This delay is not performed for you.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac, rcc,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.enable_vreg();
+    /// delay.delay_us(u32::from(adc::T_ADCVREG_SETUP_MICROS));
+    /// ```
+    pub fn enable_vreg(&mut self) {
+        // RM0453 rev 1 section 18.3.3 page 536 Software calibration procedure
+        // 1. Ensure that ADEN = 0, ADVREGEN = 1 and DMAEN = 0.
+        // 2. Set ADCAL = 1.
+        // 3. Wait until ADCAL = 0 (or until EOCAL = 1).
+        //    This can be handled by interrupt if the interrupt is enabled by
+        //    setting the EOCALIE bit in the ADC_IER register
+        // 4. The calibration factor can be read from bits 6:0 of ADC_DR or
+        //    ADC_CALFACT registers.
+
+        // takes appx 55 cycles when already disabled
+        self.disable();
+
+        // enable the voltage regulator as soon as possible to start the
+        // countdown on the regulator setup time
+        // this is a write because all other fields must be zero
+        self.adc.cr.write(|w| w.advregen().enabled());
+        // disable DMA per the calibration procedure
+        self.adc.cfgr1.modify(|_, w| w.dmaen().clear_bit());
+    }
+
+    /// Disable the ADC voltage regulator.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is enabled
+    #[inline]
+    pub fn disable_vreg(&mut self) {
+        debug_assert!(self.is_disabled());
+        self.adc.cr.write(|w| w.advregen().disabled());
+    }
+
+    /// Returns `true` if the ADC voltage regulator is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// assert!(!adc.is_vreg_enabled());
+    /// adc.enable_vreg();
+    /// assert!(adc.is_vreg_enabled());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_vreg_enabled(&self) -> bool {
+        self.adc.cr.read().advregen().is_enabled()
+    }
+
+    /// Start the ADC calibration.
+    ///
+    /// This is advanced ADC usage, most of the time you will want to use
+    /// [`calibrate`](Self::calibrate).
+    ///
+    /// When this function returns the ADC calibration has started, but
+    /// may not have finished.
+    /// Check if the ADC calibration has finished with [`Adc::isr`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is enabled.
+    /// * (debug) ADC voltage regulator is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac, rcc,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    ///
+    /// adc.enable_vreg();
+    /// delay.delay_us(u32::from(adc::T_ADCVREG_SETUP_MICROS));
+    /// adc.start_calibrate();
+    /// // datasheet says this takes 82 ADC clock cycles
+    /// // my measurements are closer to 120 ADC clock cycles
+    /// while Adc::isr().eocal().is_not_complete() {}
+    /// ```
+    #[inline]
+    pub fn start_calibrate(&mut self) {
+        debug_assert!(self.adc.cr.read().advregen().is_enabled());
+        debug_assert!(self.is_disabled());
+        self.adc
+            .cr
+            .write(|w| w.adcal().start_calibration().advregen().enabled());
+    }
+
+    /// Get the ADC calibration factor.
+    ///
+    /// # Example
+    ///
+    /// See [`force_cal`](Self::force_cal).
+    #[inline]
+    pub fn calfact(&self) -> u8 {
+        self.adc.calfact.read().calfact().bits()
+    }
+
+    /// Force the ADC calibration.
+    ///
+    /// The calibration factor is lost each time power is removed from the ADC
+    /// (for example when entering standby or V<sub>BAT</sub> mode)
+    /// It is possible to save and restore the calibration factor with firmware
+    /// to save time when re-starting the ADC (as long as temperature and
+    /// voltage are stable during the ADC power-down).
+    ///
+    /// # Panics
+    ///
+    /// * (debug) ADC is not enabled
+    /// * (debug) ADC conversion is in-progress
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac, rcc,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc = Adc::new(dp.ADC, adc::Clk::RccHsi, &mut dp.RCC);
+    /// adc.calibrate(&mut delay);
+    ///
+    /// // save the calibration factor
+    /// let calfact: u8 = adc.calfact();
+    ///
+    /// // restore the calibration
+    /// adc.enable(); // ADC must be enabled to restore the calibration
+    /// adc.force_cal(calfact);
+    /// ```
+    #[inline]
+    pub fn force_cal(&mut self, calfact: u8) {
+        debug_assert!(self.is_enabled());
+        debug_assert!(self.adc.cr.read().adstart().bit_is_clear());
         self.adc.calfact.write(|w| w.calfact().bits(calfact))
     }
 }
+
+// type-state wrappers
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl Adc {
+    /// Disable the ADC and convert it to the [`Disabled`] type-state.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let adc: adc::Disabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_disabled();
+    /// ```
+    #[inline]
+    pub fn into_disabled(mut self) -> Disabled {
+        self.disable();
+        Disabled { adc: self }
+    }
+
+    /// Enable the ADC and convert it to the [`Enabled`] type-state.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let adc: adc::Enabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_enabled();
+    /// ```
+    #[inline]
+    pub fn into_enabled(mut self) -> Enabled {
+        self.enable();
+        Enabled { adc: self }
+    }
+}
+
+/// Disabled ADC type-state.
+///
+/// This wraps [`Adc`] to check at compile time that methods requiring a
+/// disabled ADC, such as calibration, are not called on an enabled ADC.
+/// Sampling is only available in the [`Enabled`] type-state.
+///
+/// Methods that do not modify the ADC are available with
+/// [`Deref`](core::ops::Deref).
+///
+/// # Example
+///
+/// Sampling a disabled ADC does not compile.
+///
+/// ```compile_fail
+/// use stm32wlxx_hal::{
+///     adc::{self, Adc},
+///     pac,
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+/// let mut adc: adc::Disabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_disabled();
+/// let sample: u16 = adc.vbat();
+/// ```
+#[derive(Debug)]
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub struct Disabled {
+    adc: Adc,
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl Disabled {
+    /// Steal the ADC peripheral from whatever is currently using it.
+    ///
+    /// This will **not** initialize the ADC.
+    ///
+    /// # Safety
+    ///
+    /// 1. Ensure that the code stealing the ADC has exclusive access to the
+    ///    peripheral. Singleton checks are bypassed with this method.
+    /// 2. The ADC must be disabled, see [`Adc::is_disabled`].
+    /// 3. Enable the ADC peripheral clock before using the ADC.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::adc;
+    ///
+    /// let adc: adc::Disabled = unsafe { adc::Disabled::steal() };
+    /// ```
+    #[inline]
+    pub unsafe fn steal() -> Disabled {
+        Disabled { adc: Adc::steal() }
+    }
+
+    /// Calibrate the ADC for additional accuracy.
+    ///
+    /// See [`Adc::calibrate`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    ///     util::new_delay,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+    ///
+    /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+    /// let mut adc: adc::Disabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_disabled();
+    /// adc.calibrate(&mut delay);
+    /// ```
+    #[inline]
+    pub fn calibrate<D: DelayUs<u8>>(&mut self, delay: &mut D) {
+        self.adc.calibrate(delay)
+    }
+
+    /// Calibrate the ADC, returning the change in the calibration factor.
+    ///
+    /// See [`Adc::recalibrate_and_delta`].
+    #[inline]
+    pub fn recalibrate_and_delta<D: DelayUs<u8>>(&mut self, delay: &mut D) -> i8 {
+        self.adc.recalibrate_and_delta(delay)
+    }
+
+    /// Set the ADC clock source.
+    ///
+    /// See [`Adc::set_clock_source`].
+    #[inline]
+    pub fn set_clock_source(&mut self, clk: Clk, rcc: &mut pac::RCC) {
+        self.adc.set_clock_source(clk, rcc)
+    }
+
+    /// Apply a configuration from [`plan`].
+    ///
+    /// See [`Adc::apply_plan`].
+    #[inline]
+    pub fn apply_plan(&mut self, plan: &AdcPlan, rcc: &mut pac::RCC) {
+        self.adc.apply_plan(plan, rcc)
+    }
+
+    /// Set the sample times.
+    ///
+    /// See [`Adc::set_sample_times`].
+    #[inline]
+    pub fn set_sample_times(&mut self, mask: u32, sel0: Ts, sel1: Ts) {
+        self.adc.set_sample_times(mask, sel0, sel1)
+    }
+
+    /// Set sample times from `(channel, sample time)` pairs.
+    ///
+    /// See [`Adc::configure_samples`].
+    #[inline]
+    pub fn configure_samples(&mut self, pairs: &[(Ch, Ts)]) -> Result<(), Error> {
+        self.adc.configure_samples(pairs)
+    }
+
+    /// Sets all channels to the maximum sample time.
+    ///
+    /// See [`Adc::set_max_sample_time`].
+    #[inline]
+    pub fn set_max_sample_time(&mut self) {
+        self.adc.set_max_sample_time()
+    }
+
+    /// Enable the ADC and poll for completion.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let adc: adc::Disabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_disabled();
+    /// let adc: adc::Enabled = adc.enable();
+    /// ```
+    #[inline]
+    pub fn enable(self) -> Enabled {
+        self.adc.into_enabled()
+    }
+
+    /// Convert to the runtime checked [`Adc`].
+    #[inline]
+    pub fn into_adc(self) -> Adc {
+        self.adc
+    }
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl core::ops::Deref for Disabled {
+    type Target = Adc;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.adc
+    }
+}
+
+/// Enabled ADC type-state.
+///
+/// This wraps [`Adc`] to check at compile time that the ADC is enabled
+/// before sampling.
+///
+/// Methods that do not modify the ADC are available with
+/// [`Deref`](core::ops::Deref).
+///
+/// # Example
+///
+/// Calibrating an enabled ADC does not compile.
+///
+/// ```compile_fail
+/// use stm32wlxx_hal::{
+///     adc::{self, Adc},
+///     pac,
+///     util::new_delay,
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+/// let cp: pac::CorePeripherals = pac::CorePeripherals::take().unwrap();
+///
+/// let mut delay = new_delay(cp.SYST, &dp.RCC);
+/// let mut adc: adc::Enabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_enabled();
+/// adc.calibrate(&mut delay);
+/// ```
+#[derive(Debug)]
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+pub struct Enabled {
+    adc: Adc,
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl Enabled {
+    /// Steal the ADC peripheral from whatever is currently using it.
+    ///
+    /// This will **not** initialize the ADC.
+    ///
+    /// # Safety
+    ///
+    /// 1. Ensure that the code stealing the ADC has exclusive access to the
+    ///    peripheral. Singleton checks are bypassed with this method.
+    /// 2. The ADC must be enabled, see [`Adc::is_enabled`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::adc;
+    ///
+    /// let adc: adc::Enabled = unsafe { adc::Enabled::steal() };
+    /// ```
+    #[inline]
+    pub unsafe fn steal() -> Enabled {
+        Enabled { adc: Adc::steal() }
+    }
+
+    /// Disable the ADC and poll for completion.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let adc: adc::Enabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_enabled();
+    /// let adc: adc::Disabled = adc.disable();
+    /// ```
+    #[inline]
+    pub fn disable(self) -> Disabled {
+        self.adc.into_disabled()
+    }
+
+    /// Convert to the runtime checked [`Adc`].
+    #[inline]
+    pub fn into_adc(self) -> Adc {
+        self.adc
+    }
+
+    /// Set the sample times.
+    ///
+    /// See [`Adc::set_sample_times`].
+    #[inline]
+    pub fn set_sample_times(&mut self, mask: u32, sel0: Ts, sel1: Ts) {
+        self.adc.set_sample_times(mask, sel0, sel1)
+    }
+
+    /// Set sample times from `(channel, sample time)` pairs.
+    ///
+    /// See [`Adc::configure_samples`].
+    #[inline]
+    pub fn configure_samples(&mut self, pairs: &[(Ch, Ts)]) -> Result<(), Error> {
+        self.adc.configure_samples(pairs)
+    }
+
+    /// Sets all channels to the maximum sample time.
+    ///
+    /// See [`Adc::set_max_sample_time`].
+    #[inline]
+    pub fn set_max_sample_time(&mut self) {
+        self.adc.set_max_sample_time()
+    }
+
+    /// Enable the temperature sensor.
+    #[inline]
+    pub fn enable_tsen(&mut self) {
+        self.adc.enable_tsen()
+    }
+
+    /// Enable the internal voltage reference.
+    #[inline]
+    pub fn enable_vref(&mut self) {
+        self.adc.enable_vref()
+    }
+
+    /// Enable V<sub>BAT</sub>.
+    #[inline]
+    pub fn enable_vbat(&mut self) {
+        self.adc.enable_vbat()
+    }
+
+    /// Sample the junction temperature.
+    ///
+    /// See [`Adc::temperature`].
+    #[inline]
+    pub fn temperature(&mut self) -> Ratio<i16> {
+        self.adc.temperature()
+    }
+
+    /// Get the junction temperature, checking the sensor is ready.
+    ///
+    /// See [`Adc::temperature_checked`].
+    #[inline]
+    pub fn temperature_checked(&mut self, elapsed: Duration) -> Result<Ratio<i16>, Error> {
+        self.adc.temperature_checked(elapsed)
+    }
+
+    /// Sample the junction temperature, configuring the sample time
+    /// automatically.
+    ///
+    /// See [`Adc::temperature_auto`].
+    #[inline]
+    pub fn temperature_auto<D: DelayUs<u8>>(
+        &mut self,
+        rcc: &pac::RCC,
+        delay: &mut D,
+    ) -> Ratio<i16> {
+        self.adc.temperature_auto(rcc, delay)
+    }
+
+    /// Read the internal voltage reference.
+    ///
+    /// See [`Adc::vref`].
+    #[inline]
+    pub fn vref(&mut self) -> u16 {
+        self.adc.vref()
+    }
+
+    /// Sample the V<sub>BAT</sub> pin.
+    ///
+    /// See [`Adc::vbat`].
+    #[inline]
+    pub fn vbat(&mut self) -> u16 {
+        self.adc.vbat()
+    }
+
+    /// Estimate the battery state of charge in percent.
+    ///
+    /// See [`Adc::battery_percent`].
+    #[inline]
+    pub fn battery_percent(&mut self, curve: &BatteryCurve) -> u8 {
+        self.adc.battery_percent(curve)
+    }
+
+    /// Sample the DAC output.
+    ///
+    /// See [`Adc::dac`].
+    #[inline]
+    pub fn dac(&mut self) -> u16 {
+        self.adc.dac()
+    }
+
+    /// Sample a GPIO pin.
+    ///
+    /// See [`Adc::pin`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     gpio::{pins::B4, Analog, PortB},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+    /// let b4: Analog<B4> = cortex_m::interrupt::free(|cs| Analog::new(gpiob.b4, cs));
+    ///
+    /// let mut adc: adc::Enabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_enabled();
+    /// adc.set_max_sample_time();
+    /// let sample: u16 = adc.pin(&b4);
+    /// ```
+    #[inline]
+    pub fn pin<P>(&mut self, pin: &gpio::Analog<P>) -> u16
+    where
+        P: gpio::sealed::PinOps + gpio::sealed::AdcCh,
+    {
+        self.adc.pin(pin)
+    }
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl core::ops::Deref for Enabled {
+    type Target = Adc;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.adc
+    }
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl<P: gpio::sealed::AdcCh> embedded_hal::adc::Channel<Adc> for gpio::Analog<P> {
+    type ID = Ch;
+
+    #[inline]
+    fn channel() -> Self::ID {
+        P::ADC_CH
+    }
+}
+
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl<P: gpio::sealed::AdcCh> embedded_hal::adc::Channel<Enabled> for gpio::Analog<P> {
+    type ID = Ch;
+
+    #[inline]
+    fn channel() -> Self::ID {
+        P::ADC_CH
+    }
+}
+
+/// Sample a GPIO pin with the embedded-hal ADC trait.
+///
+/// The ADC is enabled if it is not already, and the sample is returned by
+/// [`Adc::pin`].
+/// This blocks until the conversion completes, it never returns
+/// [`nb::Error::WouldBlock`].
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{
+///     adc::{self, Adc},
+///     embedded_hal::adc::OneShot,
+///     gpio::{pins::B4, Analog, PortB},
+///     pac,
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+///
+/// let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+/// let mut b4: Analog<B4> = cortex_m::interrupt::free(|cs| Analog::new(gpiob.b4, cs));
+///
+/// let mut adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+/// adc.set_max_sample_time();
+/// let sample: u16 = nb::block!(adc.read(&mut b4)).unwrap();
+/// ```
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl<P> embedded_hal::adc::OneShot<Adc, u16, gpio::Analog<P>> for Adc
+where
+    P: gpio::sealed::PinOps + gpio::sealed::AdcCh,
+{
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn read(&mut self, pin: &mut gpio::Analog<P>) -> nb::Result<u16, Self::Error> {
+        self.enable();
+        Ok(self.pin(pin))
+    }
+}
+
+/// Sample a GPIO pin with the embedded-hal ADC trait.
+///
+/// The sample is returned by [`Enabled::pin`].
+/// This blocks until the conversion completes, it never returns
+/// [`nb::Error::WouldBlock`].
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{
+///     adc::{self, Adc},
+///     embedded_hal::adc::OneShot,
+///     gpio::{pins::B4, Analog, PortB},
+///     pac,
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+///
+/// let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+/// let mut b4: Analog<B4> = cortex_m::interrupt::free(|cs| Analog::new(gpiob.b4, cs));
+///
+/// let mut adc: adc::Enabled = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC).into_enabled();
+/// adc.set_max_sample_time();
+/// let sample: u16 = nb::block!(adc.read(&mut b4)).unwrap();
+/// ```
+#[cfg(not(feature = "stm32wl5x_cm0p"))]
+impl<P> embedded_hal::adc::OneShot<Enabled, u16, gpio::Analog<P>> for Enabled
+where
+    P: gpio::sealed::PinOps + gpio::sealed::AdcCh,
+{
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn read(&mut self, pin: &mut gpio::Analog<P>) -> nb::Result<u16, Self::Error> {
+        Ok(self.pin(pin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        align_shift, assert_analog, avg_shift, awd_threshold, calfact_delta, clock_too_fast,
+        conversion_time, full_scale, internal_sample_time_short, mv_counts, plan_from_clocks,
+        sample_buckets, sample_time_short, saturating_diff, stop_with, ts_startup_elapsed, vbat_mv,
+        vdda_mv, Adc, Alignment, BatteryCurve, Ch, Clk, Error, ExtTrig, Millivolts,
+        OversampleRatio, OversampleShift, Prescaler, Raw, Resolution, SampleFormat, TrigEdge, Ts,
+        TS_START_MAX, TS_START_TYP,
+    };
+    use crate::{
+        gpio::{moder_mode, sealed::Mode},
+        pac::adc::cfgr1::RES_A,
+    };
+    use core::time::Duration;
+
+    #[test]
+    fn stop_idle() {
+        let mut stopped: bool = false;
+        assert!(!stop_with(|| false, || stopped = true));
+        assert!(!stopped);
+    }
+
+    #[test]
+    fn stop_in_progress() {
+        use core::cell::Cell;
+
+        // ADSTART stays set for a few polls after the stop request
+        let polls: Cell<u32> = Cell::new(0);
+        let stop_requests: Cell<u32> = Cell::new(0);
+        let adstart = || {
+            polls.set(polls.get() + 1);
+            stop_requests.get() == 0 || polls.get() < 5
+        };
+        assert!(stop_with(adstart, || stop_requests.set(stop_requests.get() + 1)));
+        assert_eq!(stop_requests.get(), 1);
+        assert_eq!(polls.get(), 5);
+    }
+
+    #[test]
+    fn stop_conversion_ending() {
+        use core::cell::Cell;
+
+        // the conversion ends after the check, the stop request is ignored by
+        // hardware and ADSTART is already clear
+        let polls: Cell<u32> = Cell::new(0);
+        let adstart = || {
+            polls.set(polls.get() + 1);
+            polls.get() == 1
+        };
+        assert!(stop_with(adstart, || ()));
+        assert_eq!(polls.get(), 2);
+    }
+
+    #[test]
+    fn trigger_encoding() {
+        // EXTSEL and EXTEN codes from RM0453
+        assert_eq!(u8::from(ExtTrig::Tim1Trgo), 0b000);
+        assert_eq!(u8::from(ExtTrig::Tim1Cc4), 0b001);
+        assert_eq!(u8::from(ExtTrig::Tim2Trgo), 0b010);
+        assert_eq!(u8::from(ExtTrig::Tim2Ch4), 0b011);
+        assert_eq!(u8::from(ExtTrig::Tim2Ch3), 0b101);
+        assert_eq!(u8::from(ExtTrig::ExtiLine11), 0b111);
+
+        assert_eq!(u8::from(TrigEdge::Disabled), 0b00);
+        assert_eq!(u8::from(TrigEdge::RisingEdge), 0b01);
+        assert_eq!(u8::from(TrigEdge::FallingEdge), 0b10);
+        assert_eq!(u8::from(TrigEdge::BothEdges), 0b11);
+    }
+
+    #[test]
+    fn ts_startup() {
+        assert!(!ts_startup_elapsed(Duration::ZERO));
+        assert!(!ts_startup_elapsed(TS_START_TYP));
+        assert!(!ts_startup_elapsed(TS_START_MAX - Duration::from_nanos(1)));
+        assert!(ts_startup_elapsed(TS_START_MAX));
+        assert!(ts_startup_elapsed(Duration::MAX));
+    }
+
+    #[test]
+    fn sample_buckets_two() {
+        assert_eq!(sample_buckets(&[]), Ok((0, Ts::Cyc1, Ts::Cyc1)));
+        assert_eq!(
+            sample_buckets(&[(Ch::In0, Ts::Cyc39)]),
+            Ok((0, Ts::Cyc39, Ts::Cyc39))
+        );
+        assert_eq!(
+            sample_buckets(&[
+                (Ch::Vbat, Ts::Cyc160),
+                (Ch::In0, Ts::Cyc39),
+                (Ch::In3, Ts::Cyc160),
+                (Ch::In8, Ts::Cyc39),
+            ]),
+            Ok((Ch::In0.mask() | Ch::In8.mask(), Ts::Cyc160, Ts::Cyc39))
+        );
+        // last pair for a channel wins
+        assert_eq!(
+            sample_buckets(&[
+                (Ch::In1, Ts::Cyc12),
+                (Ch::In2, Ts::Cyc79),
+                (Ch::In2, Ts::Cyc12),
+            ]),
+            Ok((0, Ts::Cyc12, Ts::Cyc79))
+        );
+    }
+
+    #[test]
+    fn sample_buckets_too_many() {
+        assert_eq!(
+            sample_buckets(&[
+                (Ch::In0, Ts::Cyc1),
+                (Ch::In1, Ts::Cyc3),
+                (Ch::In2, Ts::Cyc7),
+            ]),
+            Err(Error::SampleTimes)
+        );
+    }
+
+    #[test]
+    fn vbat_scaled() {
+        // V_DDA of 3.3 V, V_BAT of 3 V
+        assert_eq!(vbat_mv(1241, 1500, 1500), 3000);
+        // V_DDA of 3 V, V_BAT of 3 V is full scale
+        assert_eq!(vbat_mv(1365, 1650, 1500), 3000);
+        assert_eq!(vbat_mv(0, 1500, 1500), 0);
+        assert_eq!(vbat_mv(1000, 0, 1500), u16::MAX);
+        assert_eq!(vbat_mv(u16::MAX, 1, u16::MAX), u16::MAX);
+    }
+
+    #[test]
+    fn battery_curve_interpolate() {
+        const CURVE: BatteryCurve = BatteryCurve::new(&[(2000, 0), (2400, 10), (3000, 100)]);
+        assert_eq!(CURVE.percent(0), 0);
+        assert_eq!(CURVE.percent(2000), 0);
+        assert_eq!(CURVE.percent(2200), 5);
+        assert_eq!(CURVE.percent(2400), 10);
+        assert_eq!(CURVE.percent(2700), 55);
+        assert_eq!(CURVE.percent(2999), 99);
+        assert_eq!(CURVE.percent(3000), 100);
+        assert_eq!(CURVE.percent(u16::MAX), 100);
+
+        // monotonic over the built-in curves
+        for curve in [BatteryCurve::CR2032, BatteryCurve::LIPO] {
+            let mut prev: u8 = 0;
+            for mv in 0..=5000 {
+                let pct: u8 = curve.percent(mv);
+                assert!(pct >= prev);
+                assert!(pct <= 100);
+                prev = pct;
+            }
+        }
+    }
+
+    #[test]
+    fn battery_curve_single_point() {
+        const CURVE: BatteryCurve = BatteryCurve::new(&[(3000, 42)]);
+        assert_eq!(CURVE.percent(0), 42);
+        assert_eq!(CURVE.percent(3000), 42);
+        assert_eq!(CURVE.percent(4000), 42);
+    }
+
+    #[test]
+    #[should_panic]
+    fn battery_curve_unsorted() {
+        let _ = BatteryCurve::new(&[(3000, 100), (2000, 0)]);
+    }
+
+    #[test]
+    fn calfact_delta_range() {
+        assert_eq!(calfact_delta(0x40, 0x40), 0);
+        assert_eq!(calfact_delta(0x40, 0x43), 3);
+        assert_eq!(calfact_delta(0x43, 0x40), -3);
+        assert_eq!(calfact_delta(0, 0x7F), 127);
+        assert_eq!(calfact_delta(0x7F, 0), -127);
+        // only the lower 7 bits are the calibration factor
+        assert_eq!(calfact_delta(0x80, 0x81), 1);
+    }
+
+    #[test]
+    fn conversion_time_datasheet() {
+        const HZ: u32 = 16_000_000;
+
+        // RM0453 tCONV = tSMPL + tSAR, 62.5 ns per cycle
+        assert_eq!(
+            conversion_time(Ts::Cyc1, Resolution::Bits12, 1, HZ),
+            Duration::from_nanos(875)
+        );
+        assert_eq!(
+            conversion_time(Ts::Cyc1, Resolution::Bits8, 1, HZ),
+            Duration::from_nanos(625)
+        );
+        assert_eq!(
+            conversion_time(Ts::Cyc160, Resolution::Bits12, 1, HZ),
+            Duration::from_nanos(10_812)
+        );
+        assert_eq!(
+            conversion_time(Ts::Cyc160, Resolution::Bits8, 1, HZ),
+            Duration::from_nanos(10_562)
+        );
+
+        // every oversample is a full conversion
+        assert_eq!(
+            conversion_time(Ts::Cyc1, Resolution::Bits12, 16, HZ),
+            Duration::from_micros(14)
+        );
+        assert_eq!(
+            conversion_time(Ts::Cyc1, Resolution::Bits12, 1, 0),
+            Duration::MAX
+        );
+    }
+
+    #[test]
+    fn sample_formats() {
+        assert_eq!(full_scale(Resolution::Bits12, None), 4095);
+        assert_eq!(full_scale(Resolution::Bits8, None), 255);
+        // 16x oversampling with no shift is 16 bits
+        assert_eq!(
+            full_scale(
+                Resolution::Bits12,
+                Some((OversampleRatio::Mul16, OversampleShift::NoShift))
+            ),
+            u16::MAX
+        );
+        // 256x oversampling shifted by 8 is the original resolution
+        assert_eq!(
+            full_scale(
+                Resolution::Bits12,
+                Some((OversampleRatio::Mul256, OversampleShift::Shift8))
+            ),
+            4095
+        );
+        assert_eq!(
+            full_scale(
+                Resolution::Bits6,
+                Some((OversampleRatio::Mul2, OversampleShift::Shift8))
+            ),
+            0
+        );
+
+        // measured at the calibration voltage
+        assert_eq!(vdda_mv(1500, 1500, 4095), 3300);
+        // a lower reference sample is a higher VDDA
+        assert_eq!(vdda_mv(1650, 1800, 4095), 3600);
+        // 8-bit reference sample
+        assert_eq!(vdda_mv(1500 >> 4, 1500, 255), 3314);
+        assert_eq!(vdda_mv(0, 1500, 4095), u16::MAX);
+
+        assert_eq!(Raw::convert(1234, 4095, 0), 1234);
+        assert_eq!(Millivolts::convert(2048, 4095, 3300), 1650);
+        assert_eq!(Millivolts::convert(128, 255, 3300), 1656);
+        assert_eq!(Millivolts::convert(0, 4095, 3300), 0);
+        assert_eq!(Millivolts::convert(u16::MAX, 1, u16::MAX), u16::MAX);
+        assert!(!Raw::VDDA);
+        assert!(Millivolts::VDDA);
+
+        #[cfg(feature = "float")]
+        {
+            use super::Normalized;
+            assert_eq!(Normalized::convert(0, 255, 0), 0.0);
+            assert_eq!(Normalized::convert(255, 255, 0), 1.0);
+            assert!((Normalized::convert(1024, 4095, 0) - 0.25).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn align_shift_right() {
+        for res in [RES_A::Bits12, RES_A::Bits10, RES_A::Bits8, RES_A::Bits6] {
+            assert_eq!(align_shift(Alignment::Right, res), 0);
+        }
+    }
+
+    #[test]
+    fn align_shift_left() {
+        // maximum sample at each resolution, left-aligned
+        assert_eq!(0xFFF0 >> align_shift(Alignment::Left, RES_A::Bits12), 0xFFF);
+        assert_eq!(0xFFC0 >> align_shift(Alignment::Left, RES_A::Bits10), 0x3FF);
+        assert_eq!(0xFF00 >> align_shift(Alignment::Left, RES_A::Bits8), 0xFF);
+        assert_eq!(0x00FC >> align_shift(Alignment::Left, RES_A::Bits6), 0x3F);
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn log2_approx() {
+        for (x, expected) in [
+            (1.0, 0.0),
+            (2.0, 1.0),
+            (16.0, 4.0),
+            (0.5, -1.0),
+            (3.0, 1.584_962_5),
+        ] {
+            assert!((super::log2(x) - expected).abs() < 1e-5, "log2({x})");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn enob_synthetic() {
+        use super::{enob_from_variance, sample_variance};
+
+        // sample variance 4/3 LSB², 12 * 4/3 = 16, ENOB = 12 - log2(16) / 2
+        let samples: [u16; 4] = [100, 102, 100, 102];
+        let variance: f32 = sample_variance(samples.iter().copied());
+        assert!((variance - 4.0 / 3.0).abs() < 1e-5);
+        assert!((enob_from_variance(12, variance) - 10.0).abs() < 1e-4);
+
+        // noiseless input saturates at the resolution
+        assert_eq!(sample_variance([2048; 16].iter().copied()), 0.0);
+        assert_eq!(enob_from_variance(12, 0.0), 12.0);
+        assert_eq!(enob_from_variance(10, 1.0 / 12.0), 10.0);
+        assert_eq!(sample_variance([7].iter().copied()), 0.0);
+    }
+
+    #[test]
+    fn saturating_diff_subtracts() {
+        assert_eq!(saturating_diff(1000, 250), 750);
+        assert_eq!(saturating_diff(250, 1000), -750);
+        assert_eq!(saturating_diff(4095, 4095), 0);
+        assert_eq!(saturating_diff(4095, 0), 4095);
+        assert_eq!(saturating_diff(0, 4095), -4095);
+    }
+
+    #[test]
+    fn saturating_diff_saturates() {
+        assert_eq!(saturating_diff(u16::MAX, 0), i16::MAX);
+        assert_eq!(saturating_diff(0, u16::MAX), i16::MIN);
+        assert_eq!(saturating_diff(40_000, 7_233), 32_767);
+        assert_eq!(saturating_diff(40_000, 7_232), i16::MAX);
+        assert_eq!(saturating_diff(7_232, 40_000), -32_768);
+        assert_eq!(saturating_diff(7_231, 40_000), i16::MIN);
+    }
+
+    const CLOCKS: [(Clk, u32); 5] = [
+        (Clk::PClkDiv2, 24_000_000),
+        (Clk::PClkDiv4, 12_000_000),
+        (Clk::RccSysClk, 48_000_000),
+        (Clk::RccPllP, 0),
+        (Clk::RccHsi, 16_000_000),
+    ];
+
+    #[test]
+    fn plan_slow() {
+        let plan = plan_from_clocks(1_000, &CLOCKS).unwrap();
+        assert_eq!(plan.clk, Clk::PClkDiv2);
+        assert!(plan.prescaler == Prescaler::Div1);
+        assert_eq!(plan.ts, Ts::Cyc160);
+        assert!(plan.resolution == Resolution::Bits12);
+        assert_eq!(plan.clock_hz, 24_000_000);
+        // 24 MHz / (160.5 + 12.5)
+        assert_eq!(plan.sps, 138_728);
+    }
+
+    #[test]
+    fn plan_fast() {
+        // 12 bits at 1.5 cycles is 14 cycles, 24 MHz / 14 = 1.71 MSPS
+        let plan = plan_from_clocks(1_700_000, &CLOCKS).unwrap();
+        assert_eq!(plan.ts, Ts::Cyc1);
+        assert!(plan.resolution == Resolution::Bits12);
+        assert_eq!(plan.sps, 1_714_285);
+
+        // 10 bits at 1.5 cycles is 12 cycles
+        let plan = plan_from_clocks(2_000_000, &CLOCKS).unwrap();
+        assert_eq!(plan.ts, Ts::Cyc1);
+        assert!(plan.resolution == Resolution::Bits10);
+        assert_eq!(plan.sps, 2_000_000);
+
+        let plan = plan_from_clocks(500_000, &CLOCKS).unwrap();
+        assert_eq!(plan.ts, Ts::Cyc19);
+        assert!(plan.resolution == Resolution::Bits12);
+        assert!(plan.sps >= 500_000);
+    }
+
+    #[test]
+    fn plan_prescaler() {
+        // 64 MHz is too fast for the ADC, divided by 2 is 32 MHz
+        let plan = plan_from_clocks(1_000, &[(Clk::RccSysClk, 64_000_000)]).unwrap();
+        assert_eq!(plan.clk, Clk::RccSysClk);
+        assert!(plan.prescaler == Prescaler::Div2);
+        assert_eq!(plan.clock_hz, 32_000_000);
+
+        // synchronous clocks do not use the prescaler
+        assert_eq!(
+            plan_from_clocks(1_000, &[(Clk::PClkDiv2, 48_000_000)]),
+            Err(Error::Rate)
+        );
+    }
+
+    #[test]
+    fn plan_unachievable() {
+        // 6 bits at 1.5 cycles is 8 cycles, 24 MHz / 8 = 3 MSPS
+        assert_eq!(plan_from_clocks(3_000_001, &CLOCKS), Err(Error::Rate));
+        assert_eq!(plan_from_clocks(1, &[]), Err(Error::Rate));
+        assert_eq!(plan_from_clocks(1, &[(Clk::RccHsi, 0)]), Err(Error::Rate));
+    }
+
+    #[test]
+    fn sequence_order_ascending() {
+        let order: Vec<Ch> = Adc::sequence_order(u32::MAX).collect();
+        assert_eq!(order.len(), 16);
+        assert!(order.windows(2).all(|w| (w[0] as u8) < (w[1] as u8)));
+        assert_eq!(order.first(), Some(&Ch::In0));
+        assert_eq!(order.last(), Some(&Ch::Dac));
+
+        let mask: u32 = Ch::Dac.mask() | Ch::In0.mask() | Ch::Vts.mask() | Ch::In7.mask();
+        assert!(Adc::sequence_order(mask).eq([Ch::In0, Ch::In7, Ch::Vts, Ch::Dac]));
+        assert!(Adc::sequence_order(mask)
+            .rev()
+            .eq([Ch::Dac, Ch::Vts, Ch::In7, Ch::In0]));
+
+        // invalid channels are ignored
+        assert_eq!(
+            Adc::sequence_order(1 << 15 | 1 << 16 | 1 << 31).next(),
+            None
+        );
+        assert_eq!(Adc::sequence_order(0).next(), None);
+    }
+
+    // GPIOB_MODER reset value, B3 and B4 are JTAG pins in alternate mode
+    const GPIOB_MODER_RESET: u32 = 0xFFFF_FEBF;
+
+    // Analog::is_analog for B4 (ADC channel 3) with a GPIOB_MODER value
+    fn b4_is_analog(moder: u32) -> bool {
+        moder_mode(moder, 4) == Mode::Analog
+    }
+
+    #[test]
+    fn assert_analog_mode() {
+        assert_analog(b4_is_analog(0xFFFF_FFFF), Ch::In3);
+        assert_analog(b4_is_analog(GPIOB_MODER_RESET | 0b11 << 8), Ch::In3)
+    }
+
+    #[test]
+    #[should_panic(expected = "GPIO for ADC channel 3 is not in analog mode")]
+    fn assert_analog_other_mode() {
+        assert_analog(b4_is_analog(GPIOB_MODER_RESET), Ch::In3)
+    }
+
+    #[test]
+    fn mv_to_counts_12bit() {
+        const MAX: u16 = 4095;
+        assert_eq!(mv_counts(0, 3300, MAX), 0);
+        assert_eq!(mv_counts(1650, 3300, MAX), 2048);
+        assert_eq!(mv_counts(3300, 3300, MAX), MAX);
+        assert_eq!(mv_counts(1000, 3000, MAX), 1365);
+        // saturates above VDDA
+        assert_eq!(mv_counts(u16::MAX, 3300, MAX), MAX);
+        assert_eq!(mv_counts(1000, 0, MAX), MAX);
+        // round trip with the millivolts format
+        (0..=3300).step_by(50).for_each(|mv| {
+            let counts: u16 = mv_counts(mv, 3300, MAX);
+            assert!(Millivolts::convert(counts, MAX, 3300).abs_diff(mv) <= 1);
+        });
+    }
+
+    #[test]
+    fn mv_to_counts_8bit() {
+        const MAX: u16 = 255;
+        assert_eq!(mv_counts(0, 3300, MAX), 0);
+        assert_eq!(mv_counts(1650, 3300, MAX), 128);
+        assert_eq!(mv_counts(3300, 3300, MAX), MAX);
+        assert_eq!(mv_counts(3301, 3300, MAX), MAX);
+        assert_eq!(mv_counts(1200, 3600, MAX), 85);
+    }
+
+    #[test]
+    fn awd_thresholds() {
+        assert_eq!(awd_threshold(2048, Resolution::Bits12), 2048);
+        assert_eq!(awd_threshold(u16::MAX, Resolution::Bits12), 4095);
+        assert_eq!(awd_threshold(128, Resolution::Bits8), 128 << 4);
+        assert_eq!(awd_threshold(255, Resolution::Bits8), 0xFF0);
+        assert_eq!(awd_threshold(1000, Resolution::Bits8), 0xFF0);
+        assert_eq!(awd_threshold(63, Resolution::Bits6), 0xFC0);
+    }
+
+    #[test]
+    fn oversample_average() {
+        const RATIOS: [OversampleRatio; 8] = [
+            OversampleRatio::Mul2,
+            OversampleRatio::Mul4,
+            OversampleRatio::Mul8,
+            OversampleRatio::Mul16,
+            OversampleRatio::Mul32,
+            OversampleRatio::Mul64,
+            OversampleRatio::Mul128,
+            OversampleRatio::Mul256,
+        ];
+        for ratio in RATIOS {
+            let shift: OversampleShift = avg_shift(ratio);
+            // the average is at the native resolution
+            assert_eq!(
+                full_scale(Resolution::Bits12, Some((ratio, shift))),
+                full_scale(Resolution::Bits12, None)
+            );
+            // accumulating a constant then shifting returns the constant
+            let n: u32 = 2 << (ratio as u8);
+            assert_eq!((0..n).map(|_| 1234_u32).sum::<u32>() >> (shift as u8), 1234);
+        }
+    }
+
+    #[test]
+    fn clock_anomaly() {
+        assert!(!clock_too_fast(35_000_000));
+        assert!(clock_too_fast(35_000_001));
+        assert!(clock_too_fast(48_000_000));
+        assert!(!clock_too_fast(0));
+    }
+
+    #[test]
+    fn sample_time_anomaly() {
+        // 4 µs minimum, 79.5 cycles at 16 MHz is 4.97 µs
+        assert!(!sample_time_short(Ch::Vref, Ts::Cyc79, 16_000_000));
+        // 39.5 cycles at 16 MHz is 2.47 µs
+        assert!(sample_time_short(Ch::Vref, Ts::Cyc39, 16_000_000));
+        // 12 µs minimum, 160.5 cycles at 16 MHz is 10.03 µs
+        assert!(sample_time_short(Ch::Vbat, Ts::Cyc160, 16_000_000));
+        assert!(!sample_time_short(Ch::Vbat, Ts::Cyc160, 12_000_000));
+        assert!(sample_time_short(Ch::Vts, Ts::Cyc1, 16_000_000));
+        // no documented minimum
+        assert!(!sample_time_short(Ch::In0, Ts::Cyc1, 35_000_000));
+    }
+
+    #[test]
+    fn internal_sample_time_in_use() {
+        // 160.5 cycles at 16 MHz is below the 12 µs V_BAT minimum
+        assert!(internal_sample_time_short(
+            Ch::Vbat,
+            true,
+            0,
+            Ts::Cyc160,
+            16_000_000
+        ));
+        assert!(internal_sample_time_short(
+            Ch::Vbat,
+            false,
+            Ch::Vbat.mask(),
+            Ts::Cyc160,
+            16_000_000
+        ));
+        assert!(!internal_sample_time_short(
+            Ch::Vbat,
+            true,
+            0,
+            Ts::Cyc160,
+            12_000_000
+        ));
+    }
+
+    #[test]
+    fn internal_sample_time_unused() {
+        // the reset state, internal channels disabled and no channels selected
+        [Ch::Vts, Ch::Vref, Ch::Vbat].into_iter().for_each(|ch| {
+            assert!(!internal_sample_time_short(
+                ch,
+                false,
+                0,
+                Ts::Cyc1,
+                16_000_000
+            ));
+            // other channels selected
+            assert!(!internal_sample_time_short(
+                ch,
+                false,
+                Ch::In0.mask() | Ch::In3.mask(),
+                Ts::Cyc1,
+                16_000_000
+            ));
+        });
+    }
+}
@@ -1,13 +1,212 @@
 //! Advanced encryption standard
+//!
+//! # Byte order
+//!
+//! Keys, initialization vectors, and data blocks are arrays of `u32` words.
+//! With the default [`SwapMode::None`] the words are the big-endian
+//! representation of the byte strings in FIPS-197 and NIST SP 800-38A:
+//!
+//! * `words[0]` holds bytes 0 to 3, byte 0 in the most significant byte
+//! * `words[1]` holds bytes 4 to 7, byte 4 in the most significant byte
+//! * and so on for the remaining words
+//!
+//! A hex string from the standards splits into words in order, the FIPS-197
+//! appendix C.1 plaintext `00112233445566778899aabbccddeeff` is
+//! `[0x00112233, 0x44556677, 0x8899aabb, 0xccddeeff]`.
+//! [`block_from_bytes`] and [`block_to_bytes`] convert between the two,
+//! [`key_256_from_bytes`] converts a 256-bit key.
+//!
+//! [`Aes::set_dataswap`] changes the byte order of the input and output data
+//! words, the key and initialization vector words are never swapped.
+//! With [`SwapMode::Byte`] each data word is the little-endian
+//! representation of 4 bytes, which is a byte buffer reinterpreted as words
+//! on the little-endian Cortex-M cores.
+//! [`swap`] is the software equivalent of the swap modes.
 
-use crate::pac;
+use crate::{
+    pac,
+    rcc::{self, Peripheral},
+};
+use core::sync::atomic::{AtomicBool, Ordering};
 pub use pac::aes::cr::DATATYPE_A as SwapMode;
-use pac::aes::cr::KEYSIZE_A as KeySize;
+use pac::aes::cr::{GCMPH_A as GcmPhase, KEYSIZE_A as KeySize};
+
+/// Common AES types, and the [byte order](super#byte-order) conversions.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{aes::prelude::*, pac};
+///
+/// const KEY: [u32; 4] = [0; 4];
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+///
+/// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+/// aes.set_dataswap(SwapMode::None);
+/// let plaintext: Plaintext = Plaintext(block_from_bytes(&[0; 16]));
+/// let ciphertext: Ciphertext = aes.encrypt_block(&KEY, &plaintext)?;
+/// let bytes: [u8; 16] = block_to_bytes(&ciphertext.0);
+/// # Ok::<(), Error>(())
+/// ```
+pub mod prelude {
+    pub use super::{
+        block_from_bytes, block_to_bytes, key_256_from_bytes, Aes, AesWrapClk, Algorithm,
+        Ciphertext, CtrIv, Error, KeyedAes, Mode, Plaintext, SwapMode, TagLen,
+    };
+}
+
+/// Convert a 16 byte block to words in the [byte order](self#byte-order)
+/// of [`SwapMode::None`].
+///
+/// This also converts 128-bit keys.
+///
+/// # Example
+///
+/// FIPS-197 appendix C.1 plaintext.
+///
+/// ```
+/// use stm32wlxx_hal::aes::block_from_bytes;
+///
+/// let bytes: [u8; 16] = [
+///     0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+///     0xff,
+/// ];
+/// assert_eq!(
+///     block_from_bytes(&bytes),
+///     [0x00112233, 0x44556677, 0x8899aabb, 0xccddeeff]
+/// );
+/// ```
+pub const fn block_from_bytes(bytes: &[u8; 16]) -> [u32; 4] {
+    let mut block: [u32; 4] = [0; 4];
+    let mut i: usize = 0;
+    while i < 4 {
+        block[i] = u32::from_be_bytes([
+            bytes[i * 4],
+            bytes[i * 4 + 1],
+            bytes[i * 4 + 2],
+            bytes[i * 4 + 3],
+        ]);
+        i += 1;
+    }
+    block
+}
+
+/// Convert words in the [byte order](self#byte-order) of
+/// [`SwapMode::None`] to a 16 byte block.
+///
+/// This is the inverse of [`block_from_bytes`].
+///
+/// # Example
+///
+/// FIPS-197 appendix C.1 ciphertext.
+///
+/// ```
+/// use stm32wlxx_hal::aes::block_to_bytes;
+///
+/// assert_eq!(
+///     block_to_bytes(&[0x69c4e0d8, 0x6a7b0430, 0xd8cdb780, 0x70b4c55a]),
+///     [
+///         0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4,
+///         0xc5, 0x5a
+///     ]
+/// );
+/// ```
+pub const fn block_to_bytes(block: &[u32; 4]) -> [u8; 16] {
+    let mut bytes: [u8; 16] = [0; 16];
+    let mut i: usize = 0;
+    while i < 16 {
+        bytes[i] = block[i / 4].to_be_bytes()[i % 4];
+        i += 1;
+    }
+    bytes
+}
+
+/// Convert a 32 byte key to words in the [byte order](self#byte-order)
+/// of [`SwapMode::None`].
+///
+/// Byte 0 is the most significant byte of `key[0]`, byte 31 is the least
+/// significant byte of `key[7]`.
+/// 128-bit keys convert with [`block_from_bytes`].
+///
+/// # Example
+///
+/// FIPS-197 appendix C.3 key.
+///
+/// ```
+/// use stm32wlxx_hal::aes::key_256_from_bytes;
+///
+/// let mut bytes: [u8; 32] = [0; 32];
+/// (0..32).for_each(|n| bytes[n] = n as u8);
+///
+/// let key: [u32; 8] = key_256_from_bytes(&bytes);
+/// assert_eq!(key[0], 0x00010203);
+/// assert_eq!(key[7], 0x1c1d1e1f);
+/// bytes
+///     .chunks_exact(4)
+///     .zip(key.iter())
+///     .for_each(|(chunk, word)| {
+///         assert_eq!(
+///             *word,
+///             u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
+///         )
+///     });
+/// ```
+pub const fn key_256_from_bytes(bytes: &[u8; 32]) -> [u32; 8] {
+    let mut key: [u32; 8] = [0; 8];
+    let mut i: usize = 0;
+    while i < 8 {
+        key[i] = u32::from_be_bytes([
+            bytes[i * 4],
+            bytes[i * 4 + 1],
+            bytes[i * 4 + 2],
+            bytes[i * 4 + 3],
+        ]);
+        i += 1;
+    }
+    key
+}
+
+/// Swap a data word the same way the peripheral does for a swap mode.
+///
+/// Swapping a word with the swap mode set by [`Aes::set_dataswap`] gives the
+/// word in the [byte order](self#byte-order) of [`SwapMode::None`].
+/// Every swap mode is its own inverse.
+///
+/// See RM0453 rev 2 section 23.4.13 "AES data registers and data swapping".
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::aes::{swap, SwapMode};
+///
+/// assert_eq!(swap(0x00112233, SwapMode::None), 0x00112233);
+/// assert_eq!(swap(0x00112233, SwapMode::HalfWord), 0x22330011);
+/// assert_eq!(swap(0x00112233, SwapMode::Byte), 0x33221100);
+/// assert_eq!(swap(0x00112233, SwapMode::Bit), 0xCC448800);
+///
+/// // a byte buffer reinterpreted as a word on a little-endian core
+/// let bytes: [u8; 4] = [0x00, 0x11, 0x22, 0x33];
+/// assert_eq!(
+///     swap(u32::from_le_bytes(bytes), SwapMode::Byte),
+///     u32::from_be_bytes(bytes)
+/// );
+/// ```
+pub const fn swap(word: u32, mode: SwapMode) -> u32 {
+    match mode {
+        SwapMode::None => word,
+        SwapMode::HalfWord => word.rotate_left(16),
+        SwapMode::Byte => word.swap_bytes(),
+        SwapMode::Bit => word.reverse_bits(),
+    }
+}
 
 /// Algorithm modes.
+///
+/// This is used for low-level configuration with [`Aes::configure`].
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-#[allow(dead_code)]
-enum Algorithm {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Algorithm {
     /// Electronic codebook chaining algorithm
     Ecb,
     /// Cipher block chaining algorithm
@@ -21,13 +220,34 @@ enum Algorithm {
 }
 
 impl Algorithm {
-    /// Bit 16
-    pub(crate) const fn chmod2(&self) -> bool {
+    /// Value of the `CHMOD[2]` field (bit 16) in the `AES_CR` register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::aes::Algorithm;
+    ///
+    /// assert!(Algorithm::Ccm.chmod2());
+    /// assert!(!Algorithm::Gcm.chmod2());
+    /// ```
+    pub const fn chmod2(&self) -> bool {
         matches!(self, Algorithm::Ccm)
     }
 
-    /// Bits 6:5
-    pub(crate) const fn chmod10(&self) -> u8 {
+    /// Value of the `CHMOD[1:0]` field (bits 6:5) in the `AES_CR` register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::aes::Algorithm;
+    ///
+    /// assert_eq!(Algorithm::Ecb.chmod10(), 0b00);
+    /// assert_eq!(Algorithm::Cbc.chmod10(), 0b01);
+    /// assert_eq!(Algorithm::Ctr.chmod10(), 0b10);
+    /// assert_eq!(Algorithm::Gcm.chmod10(), 0b11);
+    /// assert_eq!(Algorithm::Ccm.chmod10(), 0b00);
+    /// ```
+    pub const fn chmod10(&self) -> u8 {
         match self {
             Algorithm::Ecb => 0b00,
             Algorithm::Cbc => 0b01,
@@ -36,20 +256,74 @@ impl Algorithm {
             Algorithm::Ccm => 0b00,
         }
     }
+
+    /// Returns `true` if the hardware supports the operating mode with this
+    /// algorithm.
+    ///
+    /// Key derivation prepares the decryption key schedule for ECB and CBC
+    /// decryption, it is not supported by the counter based algorithms (CTR,
+    /// GCM, CCM), which decrypt with the encryption key schedule.
+    ///
+    /// See RM0453 rev 2 section 23.4.2 "AES encryption and decryption
+    /// operations".
+    ///
+    /// This is a `const fn`, invalid combinations can be rejected at compile
+    /// time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::aes::{Algorithm, Mode};
+    ///
+    /// assert!(Algorithm::Cbc.supports(Mode::KeyDerivation));
+    /// assert!(Algorithm::Gcm.supports(Mode::Decryption));
+    /// assert!(!Algorithm::Gcm.supports(Mode::KeyDerivation));
+    ///
+    /// const _: () = assert!(Algorithm::Ctr.supports(Mode::Encryption));
+    /// ```
+    pub const fn supports(&self, mode: Mode) -> bool {
+        match mode {
+            Mode::Encryption | Mode::Decryption => true,
+            Mode::KeyDerivation | Mode::KeyDerivationDecryption => {
+                matches!(self, Algorithm::Ecb | Algorithm::Cbc)
+            }
+        }
+    }
 }
 
+/// Operating modes.
+///
+/// This is used for low-level configuration with [`Aes::configure`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
-#[allow(dead_code)]
-enum Mode {
+pub enum Mode {
+    /// Encryption
     Encryption = 0b00,
+    /// Key derivation (key preparation for ECB/CBC decryption)
+    ///
+    /// The derived key is stored in the write-only `AES_KEYRx` registers,
+    /// and cannot be read back by software.
     KeyDerivation = 0b01,
+    /// Decryption
     Decryption = 0b10,
+    /// Key derivation then decryption.
+    ///
     /// ST does not document this!
     /// ST uses this in their HAL implementation and it passes NIST tests...
     KeyDerivationDecryption = 0b11,
 }
 
 impl Mode {
+    /// Value of the `MODE` field (bits 4:3) in the `AES_CR` register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::aes::Mode;
+    ///
+    /// assert_eq!(Mode::Decryption.bits(), 0b10);
+    /// ```
     pub const fn bits(self) -> u8 {
         self as u8
     }
@@ -61,6 +335,287 @@ impl From<Mode> for u8 {
     }
 }
 
+/// Compare two byte slices in constant time.
+///
+/// Use this to compare authentication tags, such as the tag from
+/// [`Aes::decrypt_gcm_inplace`] or [`Aes::cbc_mac`], with the tag sent
+/// from the peer.
+/// Comparing tags with `==` exits at the first differing byte, which leaks
+/// the length of the matching prefix through timing.
+///
+/// For slices of equal length the execution time does not depend on the
+/// contents, every byte is compared without branching on the result.
+///
+/// Slices of differing lengths return `false`, this does not exit early;
+/// the bytes up to the length of the shorter slice are still compared.
+/// The execution time depends on the length of the shorter slice, lengths
+/// are assumed to be public.
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::aes::constant_time_eq;
+///
+/// assert!(constant_time_eq(b"tag", b"tag"));
+/// assert!(!constant_time_eq(b"tag", b"tab"));
+/// assert!(!constant_time_eq(b"tag", b"tags"));
+/// ```
+#[inline(never)]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff: u8 = u8::from(a.len() != b.len());
+    a.iter().zip(b.iter()).for_each(|(x, y)| diff |= x ^ y);
+    // safety: reading a local variable
+    // the volatile read prevents the compiler from adding an early exit
+    let diff: u8 = unsafe { core::ptr::read_volatile(&diff) };
+    diff == 0
+}
+
+/// Compare two 16 byte blocks in constant time.
+///
+/// This is [`constant_time_eq`] for 16 byte tags, such as the tags from
+/// [`Aes::cbc_mac`].
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::aes::constant_time_eq_16;
+///
+/// let tag: [u8; 16] = [0x5A; 16];
+/// let mut other: [u8; 16] = tag;
+/// assert!(constant_time_eq_16(&tag, &other));
+/// other[15] ^= 1;
+/// assert!(!constant_time_eq_16(&tag, &other));
+/// ```
+#[inline]
+pub fn constant_time_eq_16(a: &[u8; 16], b: &[u8; 16]) -> bool {
+    constant_time_eq(a, b)
+}
+
+/// A block of plaintext.
+///
+/// Used with [`Aes::encrypt_block`] and [`Aes::decrypt_block`] to
+/// distinguish plaintext from ciphertext at compile time.
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::aes::Plaintext;
+///
+/// let plaintext: Plaintext = Plaintext::from([0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6]);
+/// let raw: [u32; 4] = plaintext.into();
+/// assert_eq!(raw[0], 0xf34481ec);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Plaintext(pub [u32; 4]);
+
+impl From<[u32; 4]> for Plaintext {
+    #[inline]
+    fn from(block: [u32; 4]) -> Self {
+        Plaintext(block)
+    }
+}
+
+impl From<Plaintext> for [u32; 4] {
+    #[inline]
+    fn from(block: Plaintext) -> Self {
+        block.0
+    }
+}
+
+/// A block of ciphertext.
+///
+/// Used with [`Aes::encrypt_block`] and [`Aes::decrypt_block`] to
+/// distinguish ciphertext from plaintext at compile time.
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::aes::Ciphertext;
+///
+/// let ciphertext: Ciphertext = Ciphertext::from([0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e]);
+/// let raw: [u32; 4] = ciphertext.into();
+/// assert_eq!(raw[0], 0x0336763e);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ciphertext(pub [u32; 4]);
+
+impl From<[u32; 4]> for Ciphertext {
+    #[inline]
+    fn from(block: [u32; 4]) -> Self {
+        Ciphertext(block)
+    }
+}
+
+impl From<Ciphertext> for [u32; 4] {
+    #[inline]
+    fn from(block: Ciphertext) -> Self {
+        block.0
+    }
+}
+
+/// Initial counter block for the counter (CTR) algorithm.
+///
+/// The 128-bit counter block is a 96-bit nonce followed by a 32-bit
+/// big-endian counter, this is the layout used by most protocols and by
+/// NIST SP 800-38A.
+///
+/// The AES peripheral increments the 32-bit counter after each block, the
+/// counter wraps around without carrying into the nonce.
+///
+/// Argument of [`Aes::encrypt_ctr_inplace`] and
+/// [`Aes::decrypt_ctr_inplace`].
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::aes::CtrIv;
+///
+/// const NONCE: [u8; 12] = [
+///     0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb,
+/// ];
+/// const IV: CtrIv = CtrIv::new(NONCE, 0xfcfdfeff);
+/// assert_eq!(IV.ivr(), [0xfcfdfeff, 0xf8f9fafb, 0xf4f5f6f7, 0xf0f1f2f3]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CtrIv {
+    ivr: [u32; 4],
+}
+
+impl CtrIv {
+    /// Create a new initial counter block from a nonce and a counter.
+    pub const fn new(nonce: [u8; 12], counter: u32) -> CtrIv {
+        CtrIv {
+            ivr: [
+                counter,
+                u32::from_be_bytes([nonce[8], nonce[9], nonce[10], nonce[11]]),
+                u32::from_be_bytes([nonce[4], nonce[5], nonce[6], nonce[7]]),
+                u32::from_be_bytes([nonce[0], nonce[1], nonce[2], nonce[3]]),
+            ],
+        }
+    }
+
+    /// Values of the `AES_IVR0` to `AES_IVR3` registers, in that order.
+    ///
+    /// `AES_IVR0` holds the counter, `AES_IVR3` holds the first 4 bytes of
+    /// the nonce.
+    pub const fn ivr(&self) -> [u32; 4] {
+        self.ivr
+    }
+}
+
+/// Length of a truncated authentication tag in bytes.
+///
+/// The tag computed by the AES peripheral is always 16 bytes, protocols
+/// send a truncated tag made of the first bytes of the computed tag.
+/// The GCM methods of [`Aes`] always write the full tag, `TagLen` is only
+/// used by [`TagLen::verify`] to compare the computed tag to a truncated
+/// tag from the peer.
+/// The driver does not implement CCM, [`TagLen::ccm`] only checks the
+/// length.
+///
+/// The allowed lengths depend on the mode:
+///
+/// * GCM: 4, 8, 12, 13, 14, 15, or 16 bytes, from NIST SP 800-38D section
+///   5.2.1.2. Tags shorter than 12 bytes are only suitable for short
+///   messages with a limited number of decryptions per key.
+/// * CCM: an even length from 4 to 16 bytes, from NIST SP 800-38C section
+///   A.1. The tag length is an input of the CCM computation, a tag
+///   computed for one length does not verify with another.
+///
+/// # Example
+///
+/// ```
+/// use stm32wlxx_hal::aes::TagLen;
+///
+/// assert_eq!(TagLen::gcm(12).map(TagLen::get), Some(12));
+/// assert_eq!(TagLen::gcm(10), None);
+/// assert_eq!(TagLen::ccm(10).map(TagLen::get), Some(10));
+/// assert_eq!(TagLen::ccm(13), None);
+/// assert_eq!(TagLen::FULL.get(), 16);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TagLen(u8);
+
+impl TagLen {
+    /// Full 16 byte tag, valid for every mode.
+    pub const FULL: TagLen = TagLen(16);
+
+    /// Create a tag length for the Galois counter mode (GCM) algorithm.
+    ///
+    /// Returns `None` if the length is not 4, 8, or 12 to 16 bytes.
+    pub const fn gcm(len: u8) -> Option<TagLen> {
+        match len {
+            4 | 8 | 12..=16 => Some(TagLen(len)),
+            _ => None,
+        }
+    }
+
+    /// Create a tag length for the counter with CBC-MAC (CCM) algorithm.
+    ///
+    /// Returns `None` if the length is not an even number from 4 to 16
+    /// bytes.
+    pub const fn ccm(len: u8) -> Option<TagLen> {
+        match len {
+            4 | 6 | 8 | 10 | 12 | 14 | 16 => Some(TagLen(len)),
+            _ => None,
+        }
+    }
+
+    /// Length in bytes.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Compare the truncated tag sent from the peer to the computed tag in
+    /// constant time.
+    ///
+    /// `tag` is the tag written by a function such as
+    /// [`Aes::decrypt_gcm_inplace`], `received` is the truncated tag from
+    /// the peer.
+    /// Returns `false` if the length of `received` does not match.
+    ///
+    /// The tag bytes are big-endian words, this matches the tag from the
+    /// driver with the default [`SwapMode::None`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::aes::TagLen;
+    ///
+    /// const TAG_LEN: TagLen = match TagLen::gcm(12) {
+    ///     Some(len) => len,
+    ///     None => panic!(),
+    /// };
+    ///
+    /// // tag from decrypt_gcm_inplace
+    /// let tag: [u32; 4] = [0x00112233, 0x44556677, 0x8899aabb, 0xccddeeff];
+    ///
+    /// let received: [u8; 12] = [
+    ///     0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb,
+    /// ];
+    /// assert!(TAG_LEN.verify(&tag, &received));
+    /// assert!(!TAG_LEN.verify(&tag, &received[..8]));
+    /// ```
+    pub fn verify(self, tag: &[u32; 4], received: &[u8]) -> bool {
+        let tag: [u8; 16] = block_to_bytes(tag);
+        // the received length is public, it is not a timing leak
+        if received.len() != usize::from(self.0) {
+            return false;
+        }
+        constant_time_eq(&tag[..usize::from(self.0)], received)
+    }
+}
+
+impl From<TagLen> for u8 {
+    fn from(len: TagLen) -> Self {
+        len.0
+    }
+}
+
 /// Wrapper around [`Aes`] for safely disabling the peripheral clock.
 #[derive(Debug)]
 pub struct AesWrapClk {
@@ -127,29 +682,62 @@ impl From<Aes> for AesWrapClk {
     }
 }
 
-/// AES errors.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub enum Error {
-    /// Unexpected read operation from the `AES_DOUTR` register
-    /// during computation or data input phase.
-    Read,
-    /// Unexpected write operation to the `AES_DINR` register
-    /// during computation or data output phase.
-    Write,
+/// AES driver handle that keeps a key loaded between operations.
+///
+/// Created with [`Aes::keyed`].
+///
+/// The key registers are only written when the key changes with
+/// [`set_key`](Self::set_key), operations only switch the mode in the
+/// control register.
+/// This saves reloading the key when alternating between encryption and
+/// decryption with the same key.
+///
+/// The handle mutably borrows the [`Aes`] driver, nothing else can write to
+/// the key registers while the handle exists.
+///
+/// With the `zeroize` feature the copy of the key is cleared on drop.
+///
+/// The [`Debug`](core::fmt::Debug) output redacts the key.
+pub struct KeyedAes<'a> {
+    aes: &'a mut Aes,
+    key: [u32; 8],
+    keysize: KeySize,
 }
 
-/// AES driver.
-#[derive(Debug)]
-pub struct Aes {
-    aes: pac::AES,
-    swap_mode: SwapMode,
+impl<'a> core::fmt::Debug for KeyedAes<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KeyedAes")
+            .field("aes", &self.aes)
+            .field("key", &format_args!("<redacted>"))
+            .field("keysize", &self.keysize)
+            .finish()
+    }
 }
 
-impl Aes {
-    /// Create a new AES driver from an AES peripheral.
+#[cfg(feature = "zeroize")]
+impl<'a> Drop for KeyedAes<'a> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.key)
+    }
+}
+
+impl<'a> KeyedAes<'a> {
+    fn key(&self) -> &[u32] {
+        match self.keysize {
+            KeySize::Bits128 => &self.key[..4],
+            KeySize::Bits256 => &self.key[..],
+        }
+    }
+
+    /// Set the key.
     ///
-    /// This will enable clocks and reset the AES peripheral.
+    /// The key registers are only written if the key differs from the
+    /// currently loaded key.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
@@ -158,19 +746,45 @@ impl Aes {
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY_A: [u32; 4] = [0; 4];
+    /// const KEY_B: [u32; 4] = [1; 4];
+    ///
+    /// let mut keyed = aes.keyed(&KEY_A);
+    /// keyed.set_key(&KEY_B);
     /// ```
-    #[inline]
-    pub fn new(aes: pac::AES, rcc: &mut pac::RCC) -> Aes {
-        Self::enable_clock(rcc);
-        unsafe { Self::pulse_reset(rcc) };
-
-        Aes {
-            aes,
-            swap_mode: SwapMode::None,
+    pub fn set_key(&mut self, key: &[u32]) {
+        if self.key() != key {
+            self.keysize = self.aes.set_key(key);
+            self.key[..key.len()].copy_from_slice(key);
         }
     }
 
-    /// Free the AES peripheral from the driver.
+    fn ecb<const MODE: u8>(&mut self, din: &[u32; 4], dout: &mut [u32; 4]) -> Result<(), Error> {
+        Aes::debug_assert_clock();
+        self.aes.check_idle()?;
+
+        // the mode can only be changed when the peripheral is disabled,
+        // the key registers are retained
+        self.aes
+            .aes
+            .cr
+            .write(|w| w.en().disabled().keysize().variant(self.keysize));
+        self.aes.start_ecb::<MODE>(self.keysize);
+
+        self.aes.set_din(din);
+        self.aes.poll_completion()?;
+        self.aes.dout(dout);
+        self.aes.finish_op();
+        Ok(())
+    }
+
+    /// Encrypt using the electronic codebook chaining (ECB) algorithm with
+    /// the loaded key.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
@@ -179,37 +793,29 @@ impl Aes {
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
-    /// // ... use AES
-    /// let aes: pac::AES = aes.free();
-    /// ```
-    #[inline]
-    pub fn free(self) -> pac::AES {
-        self.aes
-    }
-
-    /// Reset the AES peripheral.
     ///
-    /// [`new`](Self::new) will pulse reset for you.
-    ///
-    /// # Safety
-    ///
-    /// 1. Ensure nothing is using the AES peripheral before calling this function.
-    ///
-    /// # Example
+    /// const KEY: [u32; 4] = [0; 4];
     ///
-    /// See [`steal`](Self::steal).
-    #[inline]
-    pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.ahb3rstr.modify(|_, w| w.aesrst().set_bit());
-        rcc.ahb3rstr.modify(|_, w| w.aesrst().clear_bit());
+    /// let plaintext: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// let mut ciphertext: [u32; 4] = [0; 4];
+    /// aes.keyed(&KEY).encrypt_ecb(&plaintext, &mut ciphertext)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_ecb(
+        &mut self,
+        plaintext: &[u32; 4],
+        ciphertext: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Encryption.bits();
+        self.ecb::<MODE>(plaintext, ciphertext)
     }
 
-    /// Disable the AES peripheral clock.
+    /// Encrypt using the electronic codebook chaining (ECB) algorithm
+    /// in-place with the loaded key.
     ///
-    /// # Safety
+    /// # Panics
     ///
-    /// 1. Ensure nothing is using the AES peripheral before disabling the clock.
-    /// 2. You are responsible for re-enabling the clock before using the AES peripheral.
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
@@ -218,42 +824,58 @@ impl Aes {
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
-    /// // ... use AES
-    ///
-    /// // safety: AES is not in use
-    /// unsafe { Aes::disable_clock(&mut dp.RCC) };
     ///
-    /// // have a low power nap or something
+    /// const KEY: [u32; 4] = [0; 4];
     ///
-    /// Aes::enable_clock(&mut dp.RCC);
-    /// // ... use AES
+    /// let mut text: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// aes.keyed(&KEY).encrypt_ecb_inplace(&mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
     /// ```
-    #[inline]
-    pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb3enr.modify(|_, w| w.aesen().disabled());
+    pub fn encrypt_ecb_inplace(&mut self, plaintext: &mut [u32; 4]) -> Result<(), Error> {
+        const MODE: u8 = Mode::Encryption.bits();
+        let din: [u32; 4] = *plaintext;
+        self.ecb::<MODE>(&din, plaintext)
     }
 
-    /// Enable the AES peripheral clock.
+    /// Decrypt using the electronic codebook chaining (ECB) algorithm with
+    /// the loaded key.
     ///
-    /// [`new`](Self::new) will enable clocks for you.
+    /// # Panics
+    ///
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
-    /// See [`steal`](Self::steal).
-    #[inline]
-    pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb3enr.modify(|_, w| w.aesen().enabled());
-        rcc.ahb3enr.read(); // delay after an RCC peripheral clock enabling
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let ciphertext: [u32; 4] = [0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e];
+    /// let mut plaintext: [u32; 4] = [0; 4];
+    /// aes.keyed(&KEY).decrypt_ecb(&ciphertext, &mut plaintext)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_ecb(
+        &mut self,
+        ciphertext: &[u32; 4],
+        plaintext: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        // key derivation + decryption leaves the key registers unchanged,
+        // unlike key derivation alone
+        const MODE: u8 = Mode::KeyDerivationDecryption.bits();
+        self.ecb::<MODE>(ciphertext, plaintext)
     }
 
-    /// Create a new AES driver from an AES peripheral without initialization.
-    ///
-    /// This is a slightly safer version of [`steal`](Self::steal).
+    /// Decrypt using the electronic codebook chaining (ECB) algorithm
+    /// in-place with the loaded key.
     ///
-    /// # Safety
+    /// # Panics
     ///
-    /// 1. You are responsible for resetting the AES peripheral and enabling
-    ///    the AES peripheral clock before use.
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
@@ -261,20 +883,510 @@ impl Aes {
     /// use stm32wlxx_hal::{aes::Aes, pac};
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
     ///
-    /// // safety: nothing is using the peripheral
-    /// unsafe { Aes::pulse_reset(&mut dp.RCC) };
-    ///
-    /// Aes::enable_clock(&mut dp.RCC);
+    /// const KEY: [u32; 4] = [0; 4];
     ///
-    /// // safety: AES peripheral has been reset and clocks are enabled
-    /// let aes: Aes = unsafe { Aes::new_no_init(dp.AES) };
-    /// ```
+    /// let mut text: [u32; 4] = [0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e];
+    /// aes.keyed(&KEY).decrypt_ecb_inplace(&mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_ecb_inplace(&mut self, ciphertext: &mut [u32; 4]) -> Result<(), Error> {
+        const MODE: u8 = Mode::KeyDerivationDecryption.bits();
+        let din: [u32; 4] = *ciphertext;
+        self.ecb::<MODE>(&din, ciphertext)
+    }
+}
+
+/// AES errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Unexpected read operation from the `AES_DOUTR` register
+    /// during computation or data input phase.
+    Read,
+    /// Unexpected write operation to the `AES_DINR` register
+    /// during computation or data output phase.
+    Write,
+    /// The peripheral was not idle at the start of an operation.
+    ///
+    /// The computation complete flag (`CCF`) was set, indicating a result
+    /// that was never read, for example from low-level register access after
+    /// [`Aes::configure`].
+    ///
+    /// Use [`Aes::pulse_reset`] to return the peripheral to a clean state.
+    /// [`Aes::configure`] also clears `CCF`, unless
+    /// [auto-clear](Aes::set_auto_clear) is disabled, in that case clear the
+    /// flag by setting `AES_CR.CCFC`, for example with
+    /// `aes.cr.modify(|_, w| w.ccfc().clear())` on the [`pac::AES`]
+    /// registers.
+    Busy,
+    /// The input length is invalid for the operation.
+    ///
+    /// For example [`Aes::cbc_mac`] requires a non-empty message with a
+    /// length that is a multiple of 16 bytes.
+    Length,
+    /// The peripheral busy flag (`BUSY`) did not clear.
+    ///
+    /// Returned by [`Aes::wait_idle`], and by the GCM methods which wait for
+    /// `BUSY` to clear before entering each GCM phase.
+    Timeout,
+    /// Another AES operation holds the lock.
+    ///
+    /// Returned by [`Aes::try_lock`].
+    Contended,
+    /// The output of the peripheral did not match a known answer.
+    ///
+    /// Returned by [`Aes::self_test`].
+    SelfTest,
+}
+
+/// Plaintext of the FIPS-197 appendix C example vectors.
+const SELF_TEST_PT: [u32; 4] = [0x00112233, 0x44556677, 0x8899aabb, 0xccddeeff];
+/// FIPS-197 appendix C.1 key and ciphertext.
+const SELF_TEST_KEY_128: [u32; 4] = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+const SELF_TEST_CT_128: [u32; 4] = [0x69c4e0d8, 0x6a7b0430, 0xd8cdb780, 0x70b4c55a];
+/// FIPS-197 appendix C.3 key and ciphertext.
+const SELF_TEST_KEY_256: [u32; 8] = [
+    0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f, 0x10111213, 0x14151617, 0x18191a1b, 0x1c1d1e1f,
+];
+const SELF_TEST_CT_256: [u32; 4] = [0x8ea2b7ca, 0x516745bf, 0xeafc4990, 0x4b496089];
+
+/// Known answer test of ECB encryption with 128 and 256 bit keys.
+///
+/// `encrypt` encrypts a block with a key, both blocks are encrypted before
+/// the comparison.
+fn self_test_with<F>(mut encrypt: F) -> Result<(), Error>
+where
+    F: FnMut(&[u32], &[u32; 4]) -> Result<[u32; 4], Error>,
+{
+    let ct_128: [u32; 4] = encrypt(&SELF_TEST_KEY_128, &SELF_TEST_PT)?;
+    let ct_256: [u32; 4] = encrypt(&SELF_TEST_KEY_256, &SELF_TEST_PT)?;
+    if ct_128 == SELF_TEST_CT_128 && ct_256 == SELF_TEST_CT_256 {
+        Ok(())
+    } else {
+        Err(Error::SelfTest)
+    }
+}
+
+/// Maximum number of `AES_SR` polls in [`Aes::wait_idle`].
+///
+/// The longest busy operation is a key derivation, this is many times
+/// longer at any core clock frequency.
+const WAIT_IDLE_POLLS: u32 = 100_000;
+
+/// GHASH function of NIST SP 800-38D section 6.4.
+///
+/// Only used for initialization vectors that are not 96 bits, the peripheral
+/// computes GHASH for everything else.
+struct Ghash {
+    h: u128,
+    y: u128,
+}
+
+impl Ghash {
+    const fn new(h: u128) -> Ghash {
+        Ghash { h, y: 0 }
+    }
+
+    /// Multiplication in GF(2^128), NIST SP 800-38D section 6.3.
+    ///
+    /// Branchless, the operands are secret.
+    fn mul(x: u128, y: u128) -> u128 {
+        const R: u128 = 0xE1 << 120;
+        let mut z: u128 = 0;
+        let mut v: u128 = y;
+        for i in 0..128 {
+            z ^= v & ((x >> (127 - i)) & 1).wrapping_neg();
+            v = (v >> 1) ^ (R & (v & 1).wrapping_neg());
+        }
+        z
+    }
+
+    /// Absorb `data`, zero padded to a multiple of 16 bytes.
+    fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block: [u8; 16] = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.y = Self::mul(self.y ^ u128::from_be_bytes(block), self.h);
+        }
+    }
+
+    /// Absorb the block of the 64-bit bit lengths and return the hash.
+    fn finish(mut self, a_len: usize, c_len: usize) -> u128 {
+        let lengths: u128 = u128::from((a_len as u64) << 3) << 64 | u128::from((c_len as u64) << 3);
+        self.y = Self::mul(self.y ^ lengths, self.h);
+        self.y
+    }
+}
+
+/// Pre-counter block J0 for an initialization vector that is not 96 bits.
+///
+/// `J0 = GHASH(IV || 0^(s+64) || [len(IV)]64)`, NIST SP 800-38D section 7.1
+/// step 2.
+fn gcm_j0(h: u128, iv: &[u8]) -> [u8; 16] {
+    let mut ghash: Ghash = Ghash::new(h);
+    ghash.update_padded(iv);
+    ghash.finish(0, iv.len()).to_be_bytes()
+}
+
+/// Cursor over the concatenation of non-contiguous byte slices.
+#[derive(Debug, Default)]
+struct Cursor {
+    part: usize,
+    offset: usize,
+}
+
+impl Cursor {
+    /// Copy the next bytes into `block`, up to the length of `block`.
+    ///
+    /// Returns the number of bytes copied, `0` at the end of `parts`.
+    fn read<T: AsRef<[u8]>>(&mut self, parts: &[T], block: &mut [u8]) -> usize {
+        let mut n: usize = 0;
+        while let Some(part) = parts.get(self.part) {
+            let part: &[u8] = part.as_ref();
+            let len: usize = (part.len() - self.offset).min(block.len() - n);
+            block[n..n + len].copy_from_slice(&part[self.offset..self.offset + len]);
+            n += len;
+            self.offset += len;
+            if self.offset == part.len() {
+                self.part += 1;
+                self.offset = 0;
+            }
+            if n == block.len() {
+                break;
+            }
+        }
+        n
+    }
+
+    /// Copy all of `block` into the next bytes of `parts`.
+    ///
+    /// Bytes past the end of `parts` are dropped.
+    fn write(&mut self, parts: &mut [&mut [u8]], block: &[u8]) {
+        let mut n: usize = 0;
+        while let Some(part) = parts.get_mut(self.part) {
+            let len: usize = (part.len() - self.offset).min(block.len() - n);
+            part[self.offset..self.offset + len].copy_from_slice(&block[n..n + len]);
+            n += len;
+            self.offset += len;
+            if self.offset == part.len() {
+                self.part += 1;
+                self.offset = 0;
+            }
+            if n == block.len() {
+                break;
+            }
+        }
+    }
+}
+
+/// Poll `is_busy` until it returns `false`, up to `polls` times.
+fn poll_idle<F: FnMut() -> bool>(mut is_busy: F, polls: u32) -> Result<(), Error> {
+    for _ in 0..polls {
+        if !is_busy() {
+            return Ok(());
+        }
+    }
+    Err(Error::Timeout)
+}
+
+/// `AES_SR` computation complete flag.
+const SR_CCF: u32 = 1 << 0;
+/// `AES_SR` read error flag.
+const SR_RDERR: u32 = 1 << 1;
+/// `AES_SR` write error flag.
+const SR_WRERR: u32 = 1 << 2;
+
+/// Poll `read_sr` until the computation complete flag is set.
+///
+/// `read_sr` returns the bits of the `AES_SR` register.
+/// The error flags take priority over the computation complete flag.
+fn poll_ccf<F: FnMut() -> u32>(mut read_sr: F) -> Result<(), Error> {
+    loop {
+        let sr: u32 = read_sr();
+        if sr & SR_WRERR != 0 {
+            return Err(Error::Write);
+        }
+        if sr & SR_RDERR != 0 {
+            return Err(Error::Read);
+        }
+        if sr & SR_CCF != 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Write `AES_CR` for a GCM or CCM phase after `AES_SR.BUSY` clears.
+///
+/// Writing `CR.GCMPH` while the peripheral is busy with the previous phase
+/// (for example computing the hash subkey in the GCM init phase) corrupts
+/// the internal state, `is_busy` is polled up to [`WAIT_IDLE_POLLS`] times.
+fn advance_phase<B: FnMut() -> bool, W: FnOnce()>(is_busy: B, write_cr: W) -> Result<(), Error> {
+    poll_idle(is_busy, WAIT_IDLE_POLLS)?;
+    write_cr();
+    Ok(())
+}
+
+/// Key size and key register values for a key, indexed by register number
+/// (`keyr[0]` is `AES_KEYR0`).
+///
+/// The first key word is written to the highest key register, `AES_KEYR3`
+/// for a 128-bit key and `AES_KEYR7` for a 256-bit key.
+/// Registers not used by a 128-bit key are `0`.
+///
+/// Returns `None` if the key is not 128-bits or 256-bits long.
+const fn key_registers(key: &[u32]) -> Option<(KeySize, [u32; 8])> {
+    let keysize: KeySize = match key.len() {
+        4 => KeySize::Bits128,
+        8 => KeySize::Bits256,
+        _ => return None,
+    };
+    let mut keyr: [u32; 8] = [0; 8];
+    let mut i: usize = 0;
+    while i < key.len() {
+        keyr[key.len() - 1 - i] = key[i];
+        i += 1;
+    }
+    Some((keysize, keyr))
+}
+
+/// Initialization vector register values for a 128-bit block, indexed by
+/// register number (`ivr[0]` is `AES_IVR0`).
+///
+/// The first IV word is written to the highest IV register, `AES_IVR3`.
+const fn ivr_registers(iv: &[u32; 4]) -> [u32; 4] {
+    [iv[3], iv[2], iv[1], iv[0]]
+}
+
+/// Panic if the AES peripheral clock is not enabled.
+#[inline]
+#[track_caller]
+fn assert_clock(enabled: bool) {
+    assert!(
+        enabled,
+        "AES clock is not enabled, enable it with Aes::enable_clock"
+    );
+}
+
+/// Panic in debug builds if `is_enabled` returns `false`.
+///
+/// `is_enabled` is not called in release builds.
+#[inline]
+#[track_caller]
+fn debug_assert_clock_with<F: FnOnce() -> bool>(is_enabled: F) {
+    if cfg!(debug_assertions) {
+        assert_clock(is_enabled())
+    }
+}
+
+/// Set while a function runs in [`Aes::try_lock`].
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Take the lock if it is free.
+///
+/// The caller must prevent preemption between the load and the store.
+fn try_acquire(locked: &AtomicBool) -> bool {
+    if locked.load(Ordering::Acquire) {
+        false
+    } else {
+        locked.store(true, Ordering::Release);
+        true
+    }
+}
+
+/// Releases the lock when dropped, including when the locked function
+/// unwinds.
+struct LockGuard<'a>(&'a AtomicBool);
+
+impl Drop for LockGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// AES driver.
+#[derive(Debug)]
+pub struct Aes {
+    aes: pac::AES,
+    swap_mode: SwapMode,
+    auto_disable: bool,
+    auto_clear: bool,
+}
+
+impl Aes {
+    /// Create a new AES driver from an AES peripheral.
+    ///
+    /// This will enable clocks and reset the AES peripheral.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// ```
+    #[inline]
+    pub fn new(aes: pac::AES, rcc: &mut pac::RCC) -> Aes {
+        Self::enable_clock(rcc);
+        unsafe { Self::pulse_reset(rcc) };
+
+        Aes {
+            aes,
+            swap_mode: SwapMode::None,
+            auto_disable: false,
+            auto_clear: true,
+        }
+    }
+
+    /// Free the AES peripheral from the driver.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// // ... use AES
+    /// let aes: pac::AES = aes.free();
+    /// ```
+    #[inline]
+    pub fn free(self) -> pac::AES {
+        self.aes
+    }
+
+    /// Reset the AES peripheral.
+    ///
+    /// [`new`](Self::new) will pulse reset for you.
+    ///
+    /// # Safety
+    ///
+    /// 1. Ensure nothing is using the AES peripheral before calling this function.
+    ///
+    /// # Example
+    ///
+    /// See [`steal`](Self::steal).
+    #[inline]
+    pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
+        rcc::reset(rcc, Peripheral::Aes);
+    }
+
+    /// Disable the AES peripheral clock.
+    ///
+    /// # Safety
+    ///
+    /// 1. Ensure nothing is using the AES peripheral before disabling the clock.
+    /// 2. You are responsible for re-enabling the clock before using the AES peripheral.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// // ... use AES
+    ///
+    /// // safety: AES is not in use
+    /// unsafe { Aes::disable_clock(&mut dp.RCC) };
+    ///
+    /// // have a low power nap or something
+    ///
+    /// Aes::enable_clock(&mut dp.RCC);
+    /// // ... use AES
+    /// ```
+    #[inline]
+    pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
+        rcc::disable(rcc, Peripheral::Aes);
+    }
+
+    /// Enable the AES peripheral clock.
+    ///
+    /// [`new`](Self::new) will enable clocks for you.
+    ///
+    /// # Example
+    ///
+    /// See [`steal`](Self::steal).
+    #[inline]
+    pub fn enable_clock(rcc: &mut pac::RCC) {
+        rcc::enable(rcc, Peripheral::Aes);
+    }
+
+    /// Returns `true` if the AES peripheral clock is enabled.
+    ///
+    /// Without a clock the AES peripheral does not respond, every operation
+    /// hangs waiting for completion.
+    /// [`new`](Self::new) and [`enable_clock`](Self::enable_clock) enable the
+    /// clock, peripherals created with [`steal`](Self::steal) or
+    /// [`new_no_init`](Self::new_no_init) require the clock to be enabled
+    /// separately.
+    ///
+    /// Operations debug-assert this.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // safety: nothing is using the peripheral
+    /// unsafe { Aes::pulse_reset(&mut dp.RCC) };
+    ///
+    /// // bring-up check, the clock is not enabled by new_no_init
+    /// if !Aes::is_clock_enabled(&dp.RCC) {
+    ///     Aes::enable_clock(&mut dp.RCC);
+    /// }
+    /// assert!(Aes::is_clock_enabled(&dp.RCC));
+    ///
+    /// // safety: AES peripheral has been reset and clocks are enabled
+    /// let aes: Aes = unsafe { Aes::new_no_init(dp.AES) };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_clock_enabled(rcc: &pac::RCC) -> bool {
+        rcc.ahb3enr.read().aesen().is_enabled()
+    }
+
+    /// Create a new AES driver from an AES peripheral without initialization.
+    ///
+    /// This is a slightly safer version of [`steal`](Self::steal).
+    ///
+    /// # Safety
+    ///
+    /// 1. You are responsible for resetting the AES peripheral and enabling
+    ///    the AES peripheral clock before use.
+    ///    The clock is the `AESEN` bit in the AHB3 peripheral clock enable
+    ///    register (`RCC_AHB3ENR`), enable it with
+    ///    [`enable_clock`](Self::enable_clock) and check it with
+    ///    [`is_clock_enabled`](Self::is_clock_enabled).
+    ///    Without the clock operations hang waiting for completion, debug
+    ///    builds panic on the first operation instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // safety: nothing is using the peripheral
+    /// unsafe { Aes::pulse_reset(&mut dp.RCC) };
+    ///
+    /// Aes::enable_clock(&mut dp.RCC);
+    ///
+    /// // safety: AES peripheral has been reset and clocks are enabled
+    /// let aes: Aes = unsafe { Aes::new_no_init(dp.AES) };
+    /// ```
     #[inline]
     pub const unsafe fn new_no_init(aes: pac::AES) -> Aes {
         Aes {
             aes,
             swap_mode: SwapMode::None,
+            auto_disable: false,
+            auto_clear: true,
         }
     }
 
@@ -288,6 +1400,8 @@ impl Aes {
     ///    Singleton checks are bypassed with this method.
     /// 2. You are responsible for resetting the AES peripheral and enabling
     ///    the AES peripheral clock before use.
+    ///    The clock is the `AESEN` bit in the AHB3 peripheral clock enable
+    ///    register (`RCC_AHB3ENR`), see [`new_no_init`](Self::new_no_init).
     ///
     /// # Example
     ///
@@ -316,6 +1430,8 @@ impl Aes {
         Aes {
             aes: dp.AES,
             swap_mode: SwapMode::None,
+            auto_disable: false,
+            auto_clear: true,
         }
     }
 
@@ -337,55 +1453,180 @@ impl Aes {
         pac::NVIC::unmask(pac::Interrupt::AES)
     }
 
-    fn set_key(&mut self, key: &[u32]) -> KeySize {
-        match key.len() {
-            4 => {
-                self.aes.cr.write(|w| w.en().disabled().keysize().bits128());
-                self.aes.keyr3.write(|w| w.key().bits(key[0]));
-                self.aes.keyr2.write(|w| w.key().bits(key[1]));
-                self.aes.keyr1.write(|w| w.key().bits(key[2]));
-                self.aes.keyr0.write(|w| w.key().bits(key[3]));
-                KeySize::Bits128
-            }
-            8 => {
-                self.aes.cr.write(|w| w.en().disabled().keysize().bits256());
-                self.aes.keyr7.write(|w| w.key().bits(key[0]));
-                self.aes.keyr6.write(|w| w.key().bits(key[1]));
-                self.aes.keyr5.write(|w| w.key().bits(key[2]));
-                self.aes.keyr4.write(|w| w.key().bits(key[3]));
-                self.aes.keyr3.write(|w| w.key().bits(key[4]));
-                self.aes.keyr2.write(|w| w.key().bits(key[5]));
-                self.aes.keyr1.write(|w| w.key().bits(key[6]));
-                self.aes.keyr0.write(|w| w.key().bits(key[7]));
-                KeySize::Bits256
-            }
-            _ => panic!("Key must be 128-bit or 256-bit not {}-bit", key.len() * 32),
-        }
+    /// Panic in debug builds if the AES peripheral clock is not enabled.
+    ///
+    /// Without a clock the `CCF` poll never completes.
+    /// This catches drivers created with [`steal`](Self::steal) or
+    /// [`new_no_init`](Self::new_no_init) without enabling the clock, the
+    /// first operation panics instead of hanging.
+    #[inline]
+    #[track_caller]
+    fn debug_assert_clock() {
+        debug_assert_clock_with(|| {
+            // safety: is_clock_enabled only reads AHB3ENR, which has no side
+            // effects
+            let rcc: pac::RCC = unsafe { pac::Peripherals::steal() }.RCC;
+            Self::is_clock_enabled(&rcc)
+        })
     }
 
-    fn poll_completion(&self) -> Result<(), Error> {
-        loop {
-            let sr = self.aes.sr.read();
-            if sr.wrerr().bit_is_set() {
-                return Err(Error::Write);
-            }
-            if sr.rderr().bit_is_set() {
-                return Err(Error::Read);
-            }
-            if sr.ccf().bit_is_set() {
-                return Ok(());
-            }
+    fn set_key(&mut self, key: &[u32]) -> KeySize {
+        Self::debug_assert_clock();
+        let (keysize, keyr) = match key_registers(key) {
+            Some(regs) => regs,
+            None => panic!("Key must be 128-bit or 256-bit not {}-bit", key.len() * 32),
+        };
+        self.aes
+            .cr
+            .write(|w| w.en().disabled().keysize().variant(keysize));
+        if let KeySize::Bits256 = keysize {
+            self.aes.keyr7.write(|w| w.key().bits(keyr[7]));
+            self.aes.keyr6.write(|w| w.key().bits(keyr[6]));
+            self.aes.keyr5.write(|w| w.key().bits(keyr[5]));
+            self.aes.keyr4.write(|w| w.key().bits(keyr[4]));
         }
+        self.aes.keyr3.write(|w| w.key().bits(keyr[3]));
+        self.aes.keyr2.write(|w| w.key().bits(keyr[2]));
+        self.aes.keyr1.write(|w| w.key().bits(keyr[1]));
+        self.aes.keyr0.write(|w| w.key().bits(keyr[0]));
+        keysize
     }
 
-    fn set_din(&mut self, din: &[u32; 4]) {
-        din.iter()
-            .for_each(|dw| self.aes.dinr.write(|w| w.din().bits(*dw)))
-    }
+    fn start_ecb<const MODE: u8>(&mut self, keysize: KeySize) {
+        const ALGO: Algorithm = Algorithm::Ecb;
+        const CHMOD2: bool = ALGO.chmod2();
+        const CHMOD10: u8 = ALGO.chmod10();
+
+        self.aes.cr.write(|w| {
+            w.en().enabled();
+            w.datatype().variant(self.swap_mode);
+            w.mode().bits(MODE);
+            w.chmod2().bit(CHMOD2);
+            w.chmod().bits(CHMOD10);
+            w.ccfc().clear();
+            w.errc().clear();
+            w.ccfie().disabled();
+            w.errie().disabled();
+            w.dmainen().disabled();
+            w.dmaouten().disabled();
+            w.gcmph().bits(0); // do not care for ECB
+            w.keysize().variant(keysize);
+            w.npblb().bits(0) // no padding
+        });
+    }
+
+    /// Wait for the AES peripheral to be idle.
+    ///
+    /// This polls the `AES_SR.BUSY` flag until it clears.
+    /// The busy flag is set while the peripheral is processing internally
+    /// without a data transfer, for example during a key derivation, a GCM
+    /// or CCM phase transition, or after a DMA transfer is aborted.
+    ///
+    /// Use this before reconfiguring the peripheral with low-level register
+    /// access.
+    ///
+    /// Returns [`Error::Timeout`] if the busy flag does not clear after a
+    /// fixed number of polls.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// // ... abort a DMA transfer
+    ///
+    /// aes.wait_idle()?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn wait_idle(&mut self) -> Result<(), Error> {
+        poll_idle(|| self.aes.sr.read().busy().is_busy(), WAIT_IDLE_POLLS)
+    }
+
+    /// Run a function with exclusive access to the AES peripheral.
+    ///
+    /// [`steal`](Self::steal) bypasses the singleton checks.
+    /// If an interrupt handler and the main thread both use a stolen driver
+    /// the interrupt can overwrite the configuration of an operation
+    /// in-progress.
+    /// Running every AES operation inside `try_lock` turns this into an
+    /// error instead of corrupted output.
+    ///
+    /// The lock is shared by all AES drivers and is taken inside a critical
+    /// section.
+    /// It does not protect against the other core on dual-core devices.
+    ///
+    /// Returns [`Error::Contended`] without calling `f` if the lock is held,
+    /// for example when an interrupt preempts a `try_lock` call in the main
+    /// thread.
+    /// The lock is not re-entrant, a `try_lock` call nested inside `f` also
+    /// returns [`Error::Contended`].
+    ///
+    /// The lock is released when `f` returns or unwinds.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let mut text: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// aes.try_lock(|aes| aes.encrypt_ecb_inplace(&KEY, &mut text))?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn try_lock<F, T>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Aes) -> Result<T, Error>,
+    {
+        if !cortex_m::interrupt::free(|_| try_acquire(&LOCKED)) {
+            return Err(Error::Contended);
+        }
+        let _guard: LockGuard = LockGuard(&LOCKED);
+        f(self)
+    }
+
+    fn check_idle(&self) -> Result<(), Error> {
+        if self.aes.sr.read().ccf().bit_is_set() {
+            Err(Error::Busy)
+        } else {
+            Ok(())
+        }
+    }
+
+    // clear the computation complete flag after the output has been read,
+    // the next operation uses this to check the peripheral is idle
+    #[inline]
+    fn clear_ccf(&mut self) {
+        self.aes.cr.modify(|_, w| w.ccfc().clear())
+    }
+
+    // called after the output of an operation has been read
+    #[inline]
+    fn finish_op(&mut self) {
+        if self.auto_disable {
+            self.aes.cr.modify(|_, w| w.en().disabled())
+        }
+    }
+
+    fn poll_completion(&self) -> Result<(), Error> {
+        poll_ccf(|| self.aes.sr.read().bits())
+    }
+
+    fn set_din(&mut self, din: &[u32; 4]) {
+        din.iter()
+            .for_each(|dw| self.aes.dinr.write(|w| w.din().bits(*dw)))
+    }
 
     fn dout(&mut self, buf: &mut [u32; 4]) {
         buf.iter_mut()
             .for_each(|dw| *dw = self.aes.doutr.read().bits());
+        self.clear_ccf();
     }
 
     fn set_din_slice(&mut self, din: &[u32]) {
@@ -403,6 +1644,7 @@ impl Aes {
                 *dw = dout;
             }
         });
+        self.clear_ccf();
     }
 
     // expensive copy for the sake of allowing unaligned u8 data
@@ -445,6 +1687,7 @@ impl Aes {
         for _ in 0..remain_dw {
             let _: u32 = self.aes.doutr.read().bits();
         }
+        self.clear_ccf();
     }
 
     fn gcm_init_phase<const MODE: u8>(
@@ -452,9 +1695,7 @@ impl Aes {
         key: &[u32],
         iv: &[u32; 3],
     ) -> Result<KeySize, Error> {
-        const ALGO: Algorithm = Algorithm::Gcm;
-        const CHMOD2: bool = ALGO.chmod2();
-        const CHMOD10: u8 = ALGO.chmod10();
+        self.check_idle()?;
 
         let keysize: KeySize = self.set_key(key);
 
@@ -462,22 +1703,7 @@ impl Aes {
         self.aes.ivr1.write(|w| w.ivi().bits(iv[2]));
         self.aes.ivr2.write(|w| w.ivi().bits(iv[1]));
         self.aes.ivr3.write(|w| w.ivi().bits(iv[0]));
-        self.aes.cr.write(|w| {
-            w.en().enabled();
-            w.datatype().variant(self.swap_mode);
-            w.mode().bits(MODE);
-            w.chmod2().bit(CHMOD2);
-            w.chmod().bits(CHMOD10);
-            w.ccfc().clear();
-            w.errc().clear();
-            w.ccfie().disabled();
-            w.errie().disabled();
-            w.dmainen().disabled();
-            w.dmaouten().disabled();
-            w.gcmph().init();
-            w.keysize().variant(keysize);
-            w.npblb().bits(0)
-        });
+        self.write_gcm_cr::<MODE>(keysize, GcmPhase::Init, 0)?;
         self.poll_completion()?;
         Ok(keysize)
     }
@@ -489,26 +1715,7 @@ impl Aes {
         buf_len: usize,
         tag: &mut [u32; 4],
     ) -> Result<(), Error> {
-        const ALGO: Algorithm = Algorithm::Gcm;
-        const CHMOD2: bool = ALGO.chmod2();
-        const CHMOD10: u8 = ALGO.chmod10();
-
-        self.aes.cr.write(|w| {
-            w.en().enabled();
-            w.datatype().variant(self.swap_mode);
-            w.mode().bits(MODE);
-            w.chmod2().bit(CHMOD2);
-            w.chmod().bits(CHMOD10);
-            w.ccfc().clear();
-            w.errc().clear();
-            w.ccfie().disabled();
-            w.errie().disabled();
-            w.dmainen().disabled();
-            w.dmaouten().disabled();
-            w.gcmph().final_();
-            w.keysize().variant(keysize);
-            w.npblb().bits(0)
-        });
+        self.write_gcm_cr::<MODE>(keysize, GcmPhase::Final, 0)?;
 
         // byte length to bit lengths
         // impossible to overflow, not enough RAM for [u8; (u32::MAX >> 3) + 1]
@@ -522,6 +1729,7 @@ impl Aes {
 
         self.poll_completion()?;
         self.dout(tag);
+        self.finish_op();
         Ok(())
     }
 
@@ -533,53 +1741,19 @@ impl Aes {
         buf: &mut [u8],
         tag: &mut [u32; 4],
     ) -> Result<(), Error> {
-        const ALGO: Algorithm = Algorithm::Gcm;
-        const CHMOD2: bool = ALGO.chmod2();
-        const CHMOD10: u8 = ALGO.chmod10();
-
         // init phase
         let keysize: KeySize = self.gcm_init_phase::<MODE>(key, iv)?;
 
         // header phase
         for block in aad.chunks(16) {
-            self.aes.cr.write(|w| {
-                w.en().enabled();
-                w.datatype().variant(self.swap_mode);
-                w.mode().bits(MODE);
-                w.chmod2().bit(CHMOD2);
-                w.chmod().bits(CHMOD10);
-                w.ccfc().clear();
-                w.errc().clear();
-                w.ccfie().disabled();
-                w.errie().disabled();
-                w.dmainen().disabled();
-                w.dmaouten().disabled();
-                w.gcmph().header();
-                w.keysize().variant(keysize);
-                w.npblb().bits(0) // not used in header phase
-            });
+            self.write_gcm_cr::<MODE>(keysize, GcmPhase::Header, 0)?;
             self.set_din_block(block);
             self.poll_completion()?;
         }
 
         // payload phase
         for block in buf.chunks_mut(16) {
-            self.aes.cr.write(|w| {
-                w.en().enabled();
-                w.datatype().variant(self.swap_mode);
-                w.mode().bits(MODE);
-                w.chmod2().bit(CHMOD2);
-                w.chmod().bits(CHMOD10);
-                w.ccfc().clear();
-                w.errc().clear();
-                w.ccfie().disabled();
-                w.errie().disabled();
-                w.dmainen().disabled();
-                w.dmaouten().disabled();
-                w.gcmph().payload();
-                w.keysize().variant(keysize);
-                w.npblb().bits(16 - (block.len() as u8))
-            });
+            self.write_gcm_cr::<MODE>(keysize, GcmPhase::Payload, 16 - (block.len() as u8))?;
             self.set_din_block(block);
             self.poll_completion()?;
             self.dout_block(block);
@@ -596,53 +1770,23 @@ impl Aes {
         buf: &mut [u32],
         tag: &mut [u32; 4],
     ) -> Result<(), Error> {
-        const ALGO: Algorithm = Algorithm::Gcm;
-        const CHMOD2: bool = ALGO.chmod2();
-        const CHMOD10: u8 = ALGO.chmod10();
-
         // init phase
         let keysize: KeySize = self.gcm_init_phase::<MODE>(key, iv)?;
 
         // header phase
         for block in aad.chunks(4) {
-            self.aes.cr.write(|w| {
-                w.en().enabled();
-                w.datatype().variant(self.swap_mode);
-                w.mode().bits(MODE);
-                w.chmod2().bit(CHMOD2);
-                w.chmod().bits(CHMOD10);
-                w.ccfc().clear();
-                w.errc().clear();
-                w.ccfie().disabled();
-                w.errie().disabled();
-                w.dmainen().disabled();
-                w.dmaouten().disabled();
-                w.gcmph().header();
-                w.keysize().variant(keysize);
-                w.npblb().bits(0) // not used in header phase
-            });
+            self.write_gcm_cr::<MODE>(keysize, GcmPhase::Header, 0)?;
             self.set_din_slice(block);
             self.poll_completion()?;
         }
 
         // payload phase
         for block in buf.chunks_mut(4) {
-            self.aes.cr.write(|w| {
-                w.en().enabled();
-                w.datatype().variant(self.swap_mode);
-                w.mode().bits(MODE);
-                w.chmod2().bit(CHMOD2);
-                w.chmod().bits(CHMOD10);
-                w.ccfc().clear();
-                w.errc().clear();
-                w.ccfie().disabled();
-                w.errie().disabled();
-                w.dmainen().disabled();
-                w.dmaouten().disabled();
-                w.gcmph().payload();
-                w.keysize().variant(keysize);
-                w.npblb().bits(16 - (core::mem::size_of_val(block) as u8))
-            });
+            self.write_gcm_cr::<MODE>(
+                keysize,
+                GcmPhase::Payload,
+                16 - (core::mem::size_of_val(block) as u8),
+            )?;
             self.set_din_slice(block);
             self.poll_completion()?;
             self.dout_slice(block);
@@ -657,219 +1801,456 @@ impl Aes {
         )
     }
 
-    /// Set the way data is read from input and output registers according to section
-    /// 23.4.13 (AES Data register and data swapping) of Reference Manual
-    pub fn set_dataswap(&mut self, mode: SwapMode) {
-        self.swap_mode = mode;
+    fn gcm_inplace_iv<const MODE: u8>(
+        &mut self,
+        key: &[u32],
+        iv: &[u8],
+        aad: &[u8],
+        buf: &mut [u8],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        if iv.len() == 12 {
+            let iv: [u32; 3] = [
+                u32::from_be_bytes([iv[0], iv[1], iv[2], iv[3]]),
+                u32::from_be_bytes([iv[4], iv[5], iv[6], iv[7]]),
+                u32::from_be_bytes([iv[8], iv[9], iv[10], iv[11]]),
+            ];
+            return self.gcm_inplace::<MODE>(key, &iv, aad, buf, tag);
+        }
+        if iv.is_empty() {
+            return Err(Error::Length);
+        }
+
+        // the software GHASH operates on the byte strings
+        self.with_dataswap(SwapMode::None, |aes| {
+            // the peripheral init phase only accepts a 96-bit IV,
+            // the rest of GCM is built from ECB, CTR, and a software GHASH
+            let mut h: [u32; 4] = [0; 4];
+            aes.encrypt_ecb(key, &[0; 4], &mut h)?;
+            let h: u128 = u128::from_be_bytes(block_to_bytes(&h));
+
+            let j0: [u8; 16] = gcm_j0(h, iv);
+            let mut ek_j0: [u32; 4] = [0; 4];
+            aes.encrypt_ecb(key, &block_from_bytes(&j0), &mut ek_j0)?;
+
+            // the payload starts at inc32(J0)
+            let mut nonce: [u8; 12] = [0; 12];
+            nonce.copy_from_slice(&j0[..12]);
+            let counter: u32 = u32::from_be_bytes([j0[12], j0[13], j0[14], j0[15]]);
+            let ctr: CtrIv = CtrIv::new(nonce, counter.wrapping_add(1));
+
+            let mut ghash: Ghash = Ghash::new(h);
+            ghash.update_padded(aad);
+            if MODE == Mode::Decryption.bits() {
+                ghash.update_padded(buf);
+                aes.ctr_inplace(key, &ctr, buf)?;
+            } else {
+                aes.ctr_inplace(key, &ctr, buf)?;
+                ghash.update_padded(buf);
+            }
+
+            let s: u128 = ghash.finish(aad.len(), buf.len());
+            let ek_j0: u128 = u128::from_be_bytes(block_to_bytes(&ek_j0));
+            *tag = block_from_bytes(&(s ^ ek_j0).to_be_bytes());
+            Ok(())
+        })
     }
 
-    /// Encrypt using the electronic codebook chaining (ECB) algorithm.
-    ///
-    /// # Panics
-    ///
-    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
-    ///
-    /// # Example
-    ///
-    /// ```no_run
-    /// use stm32wlxx_hal::{aes::Aes, pac};
-    ///
-    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
-    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
-    ///
-    /// const KEY: [u32; 4] = [0; 4];
-    ///
-    /// let plaintext: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
-    /// let mut ciphertext: [u32; 4] = [0; 4];
-    /// aes.encrypt_ecb(&KEY, &plaintext, &mut ciphertext)?;
-    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
-    /// ```
-    pub fn encrypt_ecb(
+    fn gcm_vectored<const MODE: u8>(
         &mut self,
         key: &[u32],
-        plaintext: &[u32; 4],
-        ciphertext: &mut [u32; 4],
+        iv: &[u32; 3],
+        aad: &[&[u8]],
+        buf: &mut [&mut [u8]],
+        tag: &mut [u32; 4],
     ) -> Result<(), Error> {
-        const ALGO: Algorithm = Algorithm::Ecb;
-        const CHMOD2: bool = ALGO.chmod2();
-        const CHMOD10: u8 = ALGO.chmod10();
-        const MODE: u8 = Mode::Encryption.bits();
+        // init phase
+        let keysize: KeySize = self.gcm_init_phase::<MODE>(key, iv)?;
 
-        let keysize: KeySize = self.set_key(key);
+        // header phase, blocks are gathered across the slices
+        let mut aad_len: usize = 0;
+        let mut rd: Cursor = Cursor::default();
+        loop {
+            let mut block: [u8; 16] = [0; 16];
+            let n: usize = rd.read(aad, &mut block);
+            if n == 0 {
+                break;
+            }
+            aad_len += n;
 
-        self.aes.cr.write(|w| {
-            w.en().enabled();
-            w.datatype().variant(self.swap_mode);
-            w.mode().bits(MODE);
-            w.chmod2().bit(CHMOD2);
-            w.chmod().bits(CHMOD10);
-            w.ccfc().clear();
-            w.errc().clear();
-            w.ccfie().disabled();
-            w.errie().disabled();
-            w.dmainen().disabled();
-            w.dmaouten().disabled();
-            w.gcmph().bits(0); // do not care for ECB
-            w.keysize().variant(keysize);
-            w.npblb().bits(0) // no padding
-        });
+            self.write_gcm_cr::<MODE>(keysize, GcmPhase::Header, 0)?;
+            self.set_din_block(&block[..n]);
+            self.poll_completion()?;
+        }
 
-        self.set_din(plaintext);
-        self.poll_completion()?;
-        self.dout(ciphertext);
-        Ok(())
+        // payload phase, output blocks are scattered back to the slices
+        let mut buf_len: usize = 0;
+        let mut rd: Cursor = Cursor::default();
+        let mut wr: Cursor = Cursor::default();
+        loop {
+            let mut block: [u8; 16] = [0; 16];
+            let n: usize = rd.read(buf, &mut block);
+            if n == 0 {
+                break;
+            }
+            buf_len += n;
+
+            self.write_gcm_cr::<MODE>(keysize, GcmPhase::Payload, 16 - (n as u8))?;
+            self.set_din_block(&block[..n]);
+            self.poll_completion()?;
+            self.dout_block(&mut block[..n]);
+            wr.write(buf, &block[..n]);
+        }
+
+        self.gcm_final_phase::<MODE>(keysize, aad_len, buf_len, tag)
     }
 
-    /// Encrypt using the electronic codebook chaining (ECB) algorithm in-place.
+    /// Configure and enable the AES peripheral for an algorithm and mode.
+    ///
+    /// This is advanced AES usage for configurations not covered by the
+    /// high-level functions such as [`encrypt_ecb`](Self::encrypt_ecb).
+    /// After this returns the peripheral is enabled, and data is exchanged
+    /// with the `AES_DINR` and `AES_DOUTR` registers directly through the
+    /// PAC.
+    ///
+    /// The driver does not track low-level configuration, you are responsible
+    /// for the following:
+    ///
+    /// 1. Load the initialization vector registers (`AES_IVRx`) before calling
+    ///    this method for algorithms that use one (CBC, CTR, GCM, CCM).
+    /// 2. Drive the GCM/CCM phases with `AES_CR.GCMPH`, this method starts in
+    ///    the init phase.
+    /// 3. Poll or handle `AES_SR.CCF` for each block.
+    ///
+    /// This clears the computation complete and error flags with
+    /// `AES_CR.CCFC` and `AES_CR.ERRC`, unless
+    /// [auto-clear](Self::set_auto_clear) is disabled.
+    ///
+    /// The high-level functions reconfigure the peripheral from scratch, they
+    /// are safe to use after this method.
     ///
     /// # Panics
     ///
     /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * The algorithm does not support the mode, see [`Algorithm::supports`].
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use stm32wlxx_hal::{aes::Aes, pac};
+    /// use stm32wlxx_hal::{
+    ///     aes::{Aes, Algorithm, Mode},
+    ///     pac,
+    /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
     ///
     /// const KEY: [u32; 4] = [0; 4];
     ///
-    /// let mut text: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
-    /// aes.encrypt_ecb_inplace(&KEY, &mut text)?;
-    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// aes.configure(Algorithm::Ctr, Mode::Encryption, &KEY);
     /// ```
-    pub fn encrypt_ecb_inplace(
-        &mut self,
-        key: &[u32],
-        plaintext: &mut [u32; 4],
-    ) -> Result<(), Error> {
-        const ALGO: Algorithm = Algorithm::Ecb;
-        const CHMOD2: bool = ALGO.chmod2();
-        const CHMOD10: u8 = ALGO.chmod10();
-        const MODE: u8 = Mode::Encryption.bits();
-
+    pub fn configure(&mut self, algorithm: Algorithm, mode: Mode, key: &[u32]) {
+        assert!(
+            algorithm.supports(mode),
+            "{:?} does not support {:?}",
+            algorithm,
+            mode
+        );
         let keysize: KeySize = self.set_key(key);
+        self.write_cr(
+            algorithm,
+            mode.bits(),
+            keysize,
+            GcmPhase::Init,
+            0, // no padding
+            self.auto_clear,
+        );
+    }
 
+    /// Write the control register to enable the peripheral without
+    /// interrupts or DMA.
+    ///
+    /// `clear` sets `CCFC` and `ERRC` to clear the flags.
+    fn write_cr(
+        &self,
+        algorithm: Algorithm,
+        mode: u8,
+        keysize: KeySize,
+        phase: GcmPhase,
+        npblb: u8,
+        clear: bool,
+    ) {
         self.aes.cr.write(|w| {
             w.en().enabled();
             w.datatype().variant(self.swap_mode);
-            w.mode().bits(MODE);
-            w.chmod2().bit(CHMOD2);
-            w.chmod().bits(CHMOD10);
-            w.ccfc().clear();
-            w.errc().clear();
+            w.mode().bits(mode);
+            w.chmod2().bit(algorithm.chmod2());
+            w.chmod().bits(algorithm.chmod10());
+            w.ccfc().bit(clear);
+            w.errc().bit(clear);
             w.ccfie().disabled();
             w.errie().disabled();
             w.dmainen().disabled();
             w.dmaouten().disabled();
-            w.gcmph().bits(0); // do not care for ECB
+            w.gcmph().variant(phase);
             w.keysize().variant(keysize);
-            w.npblb().bits(0) // no padding
+            w.npblb().bits(npblb)
         });
+    }
 
-        self.set_din(plaintext);
-        self.poll_completion()?;
-        self.dout(plaintext);
-        Ok(())
+    /// Advance a GCM operation to `phase`.
+    ///
+    /// This waits for `AES_SR.BUSY` to clear with [`advance_phase`], then
+    /// writes the control register.
+    /// `npblb` is the number of padding bytes in the last payload block, it
+    /// is not used in the other phases.
+    fn write_gcm_cr<const MODE: u8>(
+        &mut self,
+        keysize: KeySize,
+        phase: GcmPhase,
+        npblb: u8,
+    ) -> Result<(), Error> {
+        advance_phase(
+            || self.aes.sr.read().busy().is_busy(),
+            || self.write_cr(Algorithm::Gcm, MODE, keysize, phase, npblb, true),
+        )
     }
 
-    /// Encrypt using the Galois counter mode (GCM) algorithm in-place.
+    /// Write a block to the input data register (`AES_DINR`).
+    ///
+    /// This is advanced AES usage to implement algorithms not provided by
+    /// the driver, see [`configure`](Self::configure).
+    /// This does not touch `AES_CR` or the key registers, the data is
+    /// swapped according to the swap mode in `AES_CR.DATATYPE`.
+    ///
+    /// Follow this with [`read_block`](Self::read_block) to read the output.
+    ///
+    /// # Safety
+    ///
+    /// The peripheral must be in the following state, otherwise the output
+    /// is undefined:
+    ///
+    /// 1. The peripheral is enabled (`AES_CR.EN`) and configured for the
+    ///    algorithm and mode, for example with [`configure`](Self::configure).
+    /// 2. The key and initialization vector registers are loaded.
+    /// 3. The peripheral is in the data input phase, the computation
+    ///    complete flag (`AES_SR.CCF`) is clear, and the output of the
+    ///    previous block has been read with [`read_block`](Self::read_block).
+    /// 4. For GCM and CCM the phase (`AES_CR.GCMPH`) is set for the block.
     ///
     /// # Panics
     ///
-    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral is not enabled.
+    /// * (debug) AES computation complete flag is set.
     ///
     /// # Example
     ///
+    /// ECB encryption through the low-level primitives.
+    ///
     /// ```no_run
     /// use stm32wlxx_hal::{
-    ///     aes::Aes,
+    ///     aes::{Aes, Algorithm, Mode},
     ///     pac,
-    ///     rng::{self, Rng},
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
-    /// let mut rng = Rng::new(dp.RNG, rng::Clk::Msi, &mut dp.RCC);
     ///
     /// const KEY: [u32; 4] = [0; 4];
     ///
-    /// let mut iv: [u32; 3] = [0; 3];
-    /// rng.try_fill_u32(&mut iv)
-    ///     .expect("failed to generate entropy");
+    /// aes.configure(Algorithm::Ecb, Mode::Encryption, &KEY);
     ///
-    /// let mut associated_data: [u8; 0] = [];
-    /// let mut plaintext: [u8; 13] = b"Hello, World!".clone();
-    /// let mut tag: [u32; 4] = [0; 4];
-    /// aes.encrypt_gcm_inplace(&KEY, &iv, &associated_data, &mut plaintext, &mut tag)?;
+    /// // safety: the peripheral is configured for ECB encryption
+    /// unsafe { aes.feed_block(&[0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6]) };
+    /// let ciphertext: [u32; 4] = unsafe { aes.read_block() }?;
+    /// assert_eq!(ciphertext, [0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e]);
     /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
     /// ```
-    pub fn encrypt_gcm_inplace(
-        &mut self,
-        key: &[u32],
-        iv: &[u32; 3],
-        aad: &[u8],
-        plaintext: &mut [u8],
-        tag: &mut [u32; 4],
-    ) -> Result<(), Error> {
-        const MODE: u8 = Mode::Encryption.bits();
-        self.gcm_inplace::<MODE>(key, iv, aad, plaintext, tag)
+    pub unsafe fn feed_block(&mut self, block: &[u32; 4]) {
+        debug_assert!(self.aes.cr.read().en().is_enabled());
+        debug_assert!(self.aes.sr.read().ccf().bit_is_clear());
+        self.set_din(block)
     }
 
-    /// Encrypt using the Galois counter mode (GCM) algorithm in-place.
+    /// Wait for the computation to complete and read a block from the output
+    /// data register (`AES_DOUTR`).
     ///
-    /// `u32` is the native AES peripheral data size.
-    /// This method skips byte packing / unpacking that occurs in
-    /// [`encrypt_gcm_inplace`](Self::encrypt_gcm_inplace).
+    /// This clears the computation complete flag with `AES_CR.CCFC`,
+    /// no other `AES_CR` bits are modified.
+    /// The flag is left set if [auto-clear](Self::set_auto_clear) is
+    /// disabled.
+    ///
+    /// Returns [`Error::Write`] or [`Error::Read`] if the peripheral flagged
+    /// an unexpected access to the data registers, the error flags are left
+    /// set.
+    /// Reconfigure the peripheral with [`configure`](Self::configure) to
+    /// clear the error flags.
+    ///
+    /// # Safety
+    ///
+    /// 1. A block was written with [`feed_block`](Self::feed_block), or the
+    ///    peripheral is otherwise computing an output, in any other state
+    ///    this will poll forever.
+    /// 2. The same peripheral state as [`feed_block`](Self::feed_block).
+    ///
+    /// # Example
+    ///
+    /// See [`feed_block`](Self::feed_block).
+    pub unsafe fn read_block(&mut self) -> Result<[u32; 4], Error> {
+        self.poll_completion()?;
+        let mut block: [u32; 4] = [0; 4];
+        block
+            .iter_mut()
+            .for_each(|dw| *dw = self.aes.doutr.read().bits());
+        if self.auto_clear {
+            self.clear_ccf();
+        }
+        Ok(block)
+    }
+
+    /// Load a key, returning a handle that keeps the key loaded between
+    /// operations.
+    ///
+    /// See [`KeyedAes`] for details.
     ///
     /// # Panics
     ///
     /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let mut keyed = aes.keyed(&KEY);
+    /// let mut text: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// keyed.encrypt_ecb_inplace(&mut text)?;
+    /// keyed.decrypt_ecb_inplace(&mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn keyed(&mut self, key: &[u32]) -> KeyedAes<'_> {
+        let keysize: KeySize = self.set_key(key);
+        let mut buf: [u32; 8] = [0; 8];
+        buf[..key.len()].copy_from_slice(key);
+        KeyedAes {
+            aes: self,
+            key: buf,
+            keysize,
+        }
+    }
+
+    /// Set the way data is read from input and output registers according to section
+    /// 23.4.13 (AES Data register and data swapping) of Reference Manual
+    ///
+    /// The default is [`SwapMode::None`], see the module level
+    /// [byte order](self#byte-order) documentation.
+    /// The swap mode applies to the input and output data words only, not
+    /// to the key or initialization vector.
+    ///
+    /// The swap mode is stored in the driver and applied to every following
+    /// operation, including the byte slice methods such as
+    /// [`encrypt_ctr_inplace`](Self::encrypt_ctr_inplace).
+    /// Set it once for a project that standardizes on one word order, and use
+    /// [`with_dataswap`](Self::with_dataswap) to override it for a single
+    /// operation.
+    ///
+    /// The byte slice methods pack bytes into big-endian words, they only
+    /// match the byte order of the NIST test vectors with
+    /// [`SwapMode::None`].
     ///
     /// # Example
     ///
     /// ```no_run
     /// use stm32wlxx_hal::{
-    ///     aes::Aes,
+    ///     aes::{Aes, SwapMode},
     ///     pac,
-    ///     rng::{self, Rng},
     /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
-    /// let mut rng = Rng::new(dp.RNG, rng::Clk::Msi, &mut dp.RCC);
+    /// assert_eq!(aes.dataswap(), SwapMode::None);
     ///
-    /// const KEY: [u32; 4] = [0; 4];
+    /// // words are byte buffers reinterpreted on the little-endian core
+    /// aes.set_dataswap(SwapMode::Byte);
+    /// assert_eq!(aes.dataswap(), SwapMode::Byte);
+    /// ```
+    pub fn set_dataswap(&mut self, mode: SwapMode) {
+        self.swap_mode = mode;
+    }
+
+    /// Get the data swap mode set by [`set_dataswap`](Self::set_dataswap).
     ///
-    /// let mut iv: [u32; 3] = [0; 3];
-    /// rng.try_fill_u32(&mut iv)
-    ///     .expect("failed to generate entropy");
+    /// # Example
     ///
-    /// let mut associated_data: [u32; 0] = [];
-    /// let mut plaintext: [u32; 1] = [0x12345678];
-    /// let mut tag: [u32; 4] = [0; 4];
-    /// aes.encrypt_gcm_inplace_u32(&KEY, &iv, &associated_data, &mut plaintext, &mut tag)?;
+    /// See [`set_dataswap`](Self::set_dataswap).
+    #[inline]
+    #[must_use]
+    pub fn dataswap(&self) -> SwapMode {
+        self.swap_mode
+    }
+
+    /// Run a function with a different data swap mode.
+    ///
+    /// The swap mode set by [`set_dataswap`](Self::set_dataswap) is
+    /// restored after the function returns.
+    ///
+    /// # Example
+    ///
+    /// FIPS-197 appendix C.1 with the default swap mode set to
+    /// [`SwapMode::Byte`].
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     aes::{Aes, SwapMode},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// aes.set_dataswap(SwapMode::Byte);
+    ///
+    /// const KEY: [u32; 4] = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+    /// let mut text: [u32; 4] = [0x00112233, 0x44556677, 0x8899aabb, 0xccddeeff];
+    /// aes.with_dataswap(SwapMode::None, |aes| {
+    ///     aes.encrypt_ecb_inplace(&KEY, &mut text)
+    /// })?;
+    /// assert_eq!(text, [0x69c4e0d8, 0x6a7b0430, 0xd8cdb780, 0x70b4c55a]);
+    /// assert_eq!(aes.dataswap(), SwapMode::Byte);
     /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
     /// ```
-    pub fn encrypt_gcm_inplace_u32(
-        &mut self,
-        key: &[u32],
-        iv: &[u32; 3],
-        aad: &[u32],
-        plaintext: &mut [u32],
-        tag: &mut [u32; 4],
-    ) -> Result<(), Error> {
-        const MODE: u8 = Mode::Encryption.bits();
-        self.gcm_inplace_u32::<MODE>(key, iv, aad, plaintext, tag)
+    pub fn with_dataswap<R, F: FnOnce(&mut Aes) -> R>(&mut self, mode: SwapMode, f: F) -> R {
+        let prev: SwapMode = core::mem::replace(&mut self.swap_mode, mode);
+        let ret: R = f(self);
+        self.swap_mode = prev;
+        ret
     }
 
-    /// Decrypt using the electronic codebook chaining (ECB) algorithm.
+    /// Disable the AES peripheral (`AES_CR.EN`) after each operation.
     ///
-    /// # Panics
+    /// By default the peripheral is left enabled after an operation
+    /// completes.
+    /// With auto-disable the peripheral is disabled after the output of each
+    /// successful operation has been read, the next operation re-enables the
+    /// peripheral.
+    /// The key registers are retained while the peripheral is disabled.
     ///
-    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// This does not apply to [`configure`](Self::configure), and the
+    /// peripheral is left as-is when an operation returns an error.
+    ///
+    /// # Power Savings
+    ///
+    /// The datasheet does not specify the consumption of an enabled but idle
+    /// AES peripheral, and the idle current with and without auto-disable
+    /// has not been measured.
+    /// Expect the savings to be small compared to gating the peripheral
+    /// clock with [`AesWrapClk`].
     ///
     /// # Example
     ///
@@ -878,55 +2259,128 @@ impl Aes {
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// aes.set_auto_disable(true);
     ///
     /// const KEY: [u32; 4] = [0; 4];
     ///
-    /// let ciphertext: [u32; 4] = [0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e];
-    /// let mut plaintext: [u32; 4] = [0; 4];
-    /// aes.decrypt_ecb(&KEY, &ciphertext, &mut plaintext)?;
+    /// let mut text: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// aes.encrypt_ecb_inplace(&KEY, &mut text)?;
     /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
     /// ```
-    pub fn decrypt_ecb(
-        &mut self,
-        key: &[u32],
-        ciphertext: &[u32; 4],
-        plaintext: &mut [u32; 4],
-    ) -> Result<(), Error> {
-        const ALGO: Algorithm = Algorithm::Ecb;
-        const CHMOD2: bool = ALGO.chmod2();
-        const CHMOD10: u8 = ALGO.chmod10();
-        const MODE: u8 = Mode::KeyDerivationDecryption.bits();
+    #[inline]
+    pub fn set_auto_disable(&mut self, auto_disable: bool) {
+        self.auto_disable = auto_disable;
+    }
 
-        let keysize: KeySize = self.set_key(key);
+    /// Returns `true` if the AES peripheral is disabled after each operation.
+    ///
+    /// See [`set_auto_disable`](Self::set_auto_disable).
+    #[inline]
+    #[must_use]
+    pub const fn auto_disable(&self) -> bool {
+        self.auto_disable
+    }
 
-        self.aes.cr.write(|w| {
-            w.en().enabled();
-            w.datatype().variant(self.swap_mode);
-            w.mode().bits(MODE);
-            w.chmod2().bit(CHMOD2);
-            w.chmod().bits(CHMOD10);
-            w.ccfc().clear();
-            w.errc().clear();
-            w.ccfie().disabled();
-            w.errie().disabled();
-            w.dmainen().disabled();
-            w.dmaouten().disabled();
-            w.gcmph().bits(0); // do not care for ECB
-            w.keysize().variant(keysize);
-            w.npblb().bits(0) // no padding
-        });
+    /// Clear the computation complete and error flags in the low-level
+    /// functions.
+    ///
+    /// There are two usage models for the `AES_SR.CCF`, `AES_SR.RDERR`, and
+    /// `AES_SR.WRERR` flags:
+    ///
+    /// * Polling (default): [`configure`](Self::configure) clears the flags,
+    ///   and [`read_block`](Self::read_block) clears `CCF` after reading the
+    ///   output.
+    /// * Interrupt driven (auto-clear disabled): the driver never writes
+    ///   `AES_CR.CCFC` or `AES_CR.ERRC` from the low-level functions, the
+    ///   interrupt handler owns clearing the flags.
+    ///   `CCF` must be cleared before the next block is fed.
+    ///
+    /// The high-level functions such as [`encrypt_ecb`](Self::encrypt_ecb)
+    /// always poll with the interrupts disabled, and clear the flags for the
+    /// blocks they process.
+    /// They do not discard a flag set by a previous operation, they return
+    /// [`Error::Busy`] if `CCF` is set when they start.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     aes::{Aes, Algorithm, Mode},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// aes.set_auto_clear(false);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// aes.configure(Algorithm::Ecb, Mode::Encryption, &KEY);
+    /// // ... enable AES_CR.CCFIE and clear AES_SR.CCF in the interrupt handler
+    /// ```
+    #[inline]
+    pub fn set_auto_clear(&mut self, auto_clear: bool) {
+        self.auto_clear = auto_clear;
+    }
 
-        self.set_din(ciphertext);
-        self.poll_completion()?;
-        self.dout(plaintext);
-        Ok(())
+    /// Returns `true` if the low-level functions clear the computation
+    /// complete and error flags.
+    ///
+    /// See [`set_auto_clear`](Self::set_auto_clear).
+    #[inline]
+    #[must_use]
+    pub const fn auto_clear(&self) -> bool {
+        self.auto_clear
     }
 
-    /// Decrypt using the electronic codebook chaining (ECB) algorithm in-place.
+    /// Run a known answer test of the peripheral.
+    ///
+    /// This encrypts the FIPS-197 appendix C example vectors with ECB, with
+    /// a 128-bit and a 256-bit key, and compares the output to the expected
+    /// ciphertext.
+    /// A mismatch indicates a faulty peripheral or a wrong key register
+    /// order, 256-bit keys span `AES_KEYR0` to `AES_KEYR7`.
+    /// Use this at boot before encrypting with the peripheral.
+    ///
+    /// The test runs with [`SwapMode::None`], the swap mode is restored
+    /// before returning.
+    /// The test keys replace the key loaded in the peripheral.
+    ///
+    /// Returns [`Error::SelfTest`] if either ciphertext does not match.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// aes.self_test()?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn self_test(&mut self) -> Result<(), Error> {
+        self.with_dataswap(SwapMode::None, |aes| {
+            self_test_with(|key, plaintext| {
+                let mut ciphertext: [u32; 4] = [0; 4];
+                aes.encrypt_ecb(key, plaintext, &mut ciphertext)?;
+                Ok(ciphertext)
+            })
+        })
+    }
+
+    /// Encrypt using the electronic codebook chaining (ECB) algorithm.
+    ///
+    /// The ciphertext is written directly into the caller-provided
+    /// `ciphertext` buffer, there is no intermediate copy.
     ///
     /// # Panics
     ///
     /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
@@ -938,19 +2392,23 @@ impl Aes {
     ///
     /// const KEY: [u32; 4] = [0; 4];
     ///
-    /// let mut text: [u32; 4] = [0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e];
-    /// aes.decrypt_ecb_inplace(&KEY, &mut text)?;
+    /// let plaintext: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// let mut ciphertext: [u32; 4] = [0; 4];
+    /// aes.encrypt_ecb(&KEY, &plaintext, &mut ciphertext)?;
     /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
     /// ```
-    pub fn decrypt_ecb_inplace(
+    pub fn encrypt_ecb(
         &mut self,
         key: &[u32],
+        plaintext: &[u32; 4],
         ciphertext: &mut [u32; 4],
     ) -> Result<(), Error> {
         const ALGO: Algorithm = Algorithm::Ecb;
         const CHMOD2: bool = ALGO.chmod2();
         const CHMOD10: u8 = ALGO.chmod10();
-        const MODE: u8 = Mode::KeyDerivationDecryption.bits();
+        const MODE: u8 = Mode::Encryption.bits();
+
+        self.check_idle()?;
         let keysize: KeySize = self.set_key(key);
 
         self.aes.cr.write(|w| {
@@ -970,20 +2428,25 @@ impl Aes {
             w.npblb().bits(0) // no padding
         });
 
-        self.set_din(ciphertext);
+        self.set_din(plaintext);
         self.poll_completion()?;
         self.dout(ciphertext);
+        self.finish_op();
         Ok(())
     }
 
-    /// Decrypt using the Galois counter mode (GCM) algorithm in-place.
+    /// Encrypt using the electronic codebook chaining (ECB) algorithm,
+    /// returning the ciphertext as bytes.
     ///
-    /// The resulting tag should be compared to the tag sent from the peer
-    /// to verify the authenticity of the message.
+    /// This is [`encrypt_ecb`](Self::encrypt_ecb) with the ciphertext words
+    /// serialized big-endian with [`block_to_bytes`], ready to transmit.
+    /// With [`SwapMode::None`] the bytes are in the byte order of the NIST
+    /// test vectors.
     ///
     /// # Panics
     ///
     /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
@@ -994,65 +2457,2155 @@ impl Aes {
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
     ///
     /// const KEY: [u32; 4] = [0; 4];
-    /// const IV: [u32; 3] = [0; 3];
     ///
-    /// let mut associated_data: [u8; 0] = [];
-    /// let mut ciphertext: [u8; 5] = [0xf3, 0x44, 0x81, 0xec, 0x3c];
-    /// let mut tag: [u32; 4] = [0; 4];
-    /// aes.decrypt_gcm_inplace(&KEY, &IV, &associated_data, &mut ciphertext, &mut tag)?;
+    /// let plaintext: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// let bytes: [u8; 16] = aes.encrypt_ecb_to_bytes(&KEY, &plaintext)?;
+    ///
+    /// let mut ciphertext: [u32; 4] = [0; 4];
+    /// aes.encrypt_ecb(&KEY, &plaintext, &mut ciphertext)?;
+    /// let mut expected: [u8; 16] = [0; 16];
+    /// expected
+    ///     .chunks_exact_mut(4)
+    ///     .zip(ciphertext)
+    ///     .for_each(|(chunk, word)| chunk.copy_from_slice(&word.to_be_bytes()));
+    /// assert_eq!(bytes, expected);
     /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
     /// ```
-    pub fn decrypt_gcm_inplace(
+    pub fn encrypt_ecb_to_bytes(
         &mut self,
         key: &[u32],
-        iv: &[u32; 3],
-        aad: &[u8],
-        ciphertext: &mut [u8],
-        tag: &mut [u32; 4],
-    ) -> Result<(), Error> {
-        const MODE: u8 = Mode::Decryption.bits();
-        self.gcm_inplace::<MODE>(key, iv, aad, ciphertext, tag)
+        plaintext: &[u32; 4],
+    ) -> Result<[u8; 16], Error> {
+        let mut ciphertext: [u32; 4] = [0; 4];
+        self.encrypt_ecb(key, plaintext, &mut ciphertext)?;
+        Ok(block_to_bytes(&ciphertext))
     }
 
-    /// Decrypt using the Galois counter mode (GCM) algorithm in-place.
-    ///
-    /// `u32` is the native AES peripheral data size.
-    /// This method skips byte packing / unpacking that occurs in
-    /// [`decrypt_gcm_inplace`](Self::decrypt_gcm_inplace).
+    /// Encrypt a [`Plaintext`] block using the electronic codebook chaining
+    /// (ECB) algorithm.
     ///
-    /// The resulting tag should be compared to the tag sent from the peer
-    /// to verify the authenticity of the message.
+    /// This is [`encrypt_ecb`](Self::encrypt_ecb) with types that prevent
+    /// encrypting ciphertext by mistake.
     ///
     /// # Panics
     ///
     /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use stm32wlxx_hal::{aes::Aes, pac};
+    /// use stm32wlxx_hal::{
+    ///     aes::{Aes, Ciphertext, Plaintext},
+    ///     pac,
+    /// };
     ///
     /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
     /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
     ///
     /// const KEY: [u32; 4] = [0; 4];
-    /// const IV: [u32; 3] = [0; 3];
     ///
-    /// let mut associated_data: [u32; 0] = [];
-    /// let mut ciphertext: [u32; 1] = [0xf34481ec];
-    /// let mut tag: [u32; 4] = [0; 4];
-    /// aes.decrypt_gcm_inplace_u32(&KEY, &IV, &associated_data, &mut ciphertext, &mut tag)?;
+    /// let plaintext = Plaintext([0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6]);
+    /// let ciphertext: Ciphertext = aes.encrypt_block(&KEY, &plaintext)?;
     /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
     /// ```
-    pub fn decrypt_gcm_inplace_u32(
+    ///
+    /// Ciphertext cannot be encrypted:
+    ///
+    /// ```compile_fail
+    /// # let mut aes = unsafe { stm32wlxx_hal::aes::Aes::steal() };
+    /// use stm32wlxx_hal::aes::Ciphertext;
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let ciphertext = Ciphertext([0; 4]);
+    /// let _ = aes.encrypt_block(&KEY, &ciphertext);
+    /// ```
+    pub fn encrypt_block(
         &mut self,
         key: &[u32],
-        iv: &[u32; 3],
-        aad: &[u32],
-        ciphertext: &mut [u32],
-        tag: &mut [u32; 4],
-    ) -> Result<(), Error> {
-        const MODE: u8 = Mode::Decryption.bits();
-        self.gcm_inplace_u32::<MODE>(key, iv, aad, ciphertext, tag)
+        plaintext: &Plaintext,
+    ) -> Result<Ciphertext, Error> {
+        let mut ciphertext: Ciphertext = Ciphertext::default();
+        self.encrypt_ecb(key, &plaintext.0, &mut ciphertext.0)?;
+        Ok(ciphertext)
+    }
+
+    /// Encrypt using the electronic codebook chaining (ECB) algorithm in-place.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let mut text: [u32; 4] = [0xf34481ec, 0x3cc627ba, 0xcd5dc3fb, 0x08f273e6];
+    /// aes.encrypt_ecb_inplace(&KEY, &mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_ecb_inplace(
+        &mut self,
+        key: &[u32],
+        plaintext: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const ALGO: Algorithm = Algorithm::Ecb;
+        const CHMOD2: bool = ALGO.chmod2();
+        const CHMOD10: u8 = ALGO.chmod10();
+        const MODE: u8 = Mode::Encryption.bits();
+
+        self.check_idle()?;
+
+        let keysize: KeySize = self.set_key(key);
+
+        self.aes.cr.write(|w| {
+            w.en().enabled();
+            w.datatype().variant(self.swap_mode);
+            w.mode().bits(MODE);
+            w.chmod2().bit(CHMOD2);
+            w.chmod().bits(CHMOD10);
+            w.ccfc().clear();
+            w.errc().clear();
+            w.ccfie().disabled();
+            w.errie().disabled();
+            w.dmainen().disabled();
+            w.dmaouten().disabled();
+            w.gcmph().bits(0); // do not care for ECB
+            w.keysize().variant(keysize);
+            w.npblb().bits(0) // no padding
+        });
+
+        self.set_din(plaintext);
+        self.poll_completion()?;
+        self.dout(plaintext);
+        self.finish_op();
+        Ok(())
+    }
+
+    /// Encrypt a stream of blocks using the electronic codebook chaining (ECB)
+    /// algorithm.
+    ///
+    /// The key is loaded once, then each block yielded by `blocks` is
+    /// encrypted and the ciphertext is passed to `sink`.
+    /// This avoids materializing the full plaintext or ciphertext, for
+    /// example when the plaintext comes from a parser.
+    ///
+    /// The first [`Error::Read`] or [`Error::Write`] aborts the operation,
+    /// `sink` has been called for every block before the failing block, and
+    /// the remaining blocks are not consumed.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let blocks = (0..3).map(|n| [n; 4]);
+    /// let mut ciphertext: [[u32; 4]; 3] = [[0; 4]; 3];
+    /// let mut idx: usize = 0;
+    /// aes.encrypt_ecb_iter(&KEY, blocks, |block| {
+    ///     ciphertext[idx] = block;
+    ///     idx += 1;
+    /// })?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_ecb_iter<I, F>(
+        &mut self,
+        key: &[u32],
+        blocks: I,
+        mut sink: F,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = [u32; 4]>,
+        F: FnMut([u32; 4]),
+    {
+        const MODE: u8 = Mode::Encryption.bits();
+
+        self.check_idle()?;
+        let keysize: KeySize = self.set_key(key);
+        self.start_ecb::<MODE>(keysize);
+
+        for plaintext in blocks {
+            let mut ciphertext: [u32; 4] = [0; 4];
+            self.set_din(&plaintext);
+            self.poll_completion()?;
+            self.dout(&mut ciphertext);
+            sink(ciphertext);
+        }
+
+        self.finish_op();
+        Ok(())
+    }
+
+    /// Encrypt using the Galois counter mode (GCM) algorithm in-place.
+    ///
+    /// The peripheral stays busy after some phases, for example while
+    /// computing the hash subkey in the init phase.
+    /// Every phase transition waits for `AES_SR.BUSY` to clear before
+    /// writing `AES_CR.GCMPH`, and returns [`Error::Timeout`] if it does not
+    /// clear.
+    /// This applies to all GCM methods.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     aes::Aes,
+    ///     pac,
+    ///     rng::{self, Rng},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// let mut rng = Rng::new(dp.RNG, rng::Clk::Msi, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let mut iv: [u32; 3] = [0; 3];
+    /// rng.try_fill_u32(&mut iv)
+    ///     .expect("failed to generate entropy");
+    ///
+    /// let mut associated_data: [u8; 0] = [];
+    /// let mut plaintext: [u8; 13] = b"Hello, World!".clone();
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.encrypt_gcm_inplace(&KEY, &iv, &associated_data, &mut plaintext, &mut tag)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_gcm_inplace(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 3],
+        aad: &[u8],
+        plaintext: &mut [u8],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Encryption.bits();
+        self.gcm_inplace::<MODE>(key, iv, aad, plaintext, tag)
+    }
+
+    /// Encrypt using the Galois counter mode (GCM) algorithm in-place.
+    ///
+    /// `u32` is the native AES peripheral data size.
+    /// This method skips byte packing / unpacking that occurs in
+    /// [`encrypt_gcm_inplace`](Self::encrypt_gcm_inplace).
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     aes::Aes,
+    ///     pac,
+    ///     rng::{self, Rng},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// let mut rng = Rng::new(dp.RNG, rng::Clk::Msi, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let mut iv: [u32; 3] = [0; 3];
+    /// rng.try_fill_u32(&mut iv)
+    ///     .expect("failed to generate entropy");
+    ///
+    /// let mut associated_data: [u32; 0] = [];
+    /// let mut plaintext: [u32; 1] = [0x12345678];
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.encrypt_gcm_inplace_u32(&KEY, &iv, &associated_data, &mut plaintext, &mut tag)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_gcm_inplace_u32(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 3],
+        aad: &[u32],
+        plaintext: &mut [u32],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Encryption.bits();
+        self.gcm_inplace_u32::<MODE>(key, iv, aad, plaintext, tag)
+    }
+
+    /// Encrypt fixed size buffers using the Galois counter mode (GCM)
+    /// algorithm.
+    ///
+    /// This is [`encrypt_gcm_inplace`](Self::encrypt_gcm_inplace) with the
+    /// associated data and plaintext lengths fixed at compile time,
+    /// allocation free, without runtime length checks in the caller.
+    ///
+    /// Returns the ciphertext and the tag as bytes.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// // never reuse an IV with the same key, this is only an example
+    /// const IV: [u32; 3] = [0; 3];
+    ///
+    /// let (ciphertext, tag): ([u8; 32], [u8; 16]) =
+    ///     aes.encrypt_gcm_fixed(&KEY, &IV, b"header", b"0123456789ABCDEF0123456789ABCDEF")?;
+    /// assert_eq!(
+    ///     ciphertext,
+    ///     [
+    ///         0x33, 0xB9, 0xE8, 0xFD, 0x54, 0x83, 0x95, 0xA5, 0xCB, 0x11, 0x83, 0xFB, 0x32, 0xF6,
+    ///         0xBB, 0x3E, 0xC7, 0xA4, 0x98, 0x98, 0x7D, 0x7E, 0x6F, 0x14, 0xCF, 0xC4, 0xC8, 0xBD,
+    ///         0xD7, 0xCF, 0x84, 0xA6
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     tag,
+    ///     [
+    ///         0x1A, 0x05, 0x87, 0x3F, 0x67, 0xA4, 0xAC, 0x89, 0xEA, 0x81, 0x46, 0x48, 0x06, 0x76,
+    ///         0x05, 0xD3
+    ///     ]
+    /// );
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_gcm_fixed<const A: usize, const N: usize>(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 3],
+        aad: &[u8; A],
+        plaintext: &[u8; N],
+    ) -> Result<([u8; N], [u8; 16]), Error> {
+        let mut ciphertext: [u8; N] = *plaintext;
+        let mut tag: [u32; 4] = [0; 4];
+        self.encrypt_gcm_inplace(key, iv, aad, &mut ciphertext, &mut tag)?;
+
+        let mut tag_bytes: [u8; 16] = [0; 16];
+        tag_bytes
+            .chunks_exact_mut(4)
+            .zip(tag.iter())
+            .for_each(|(chunk, word)| chunk.copy_from_slice(&word.to_be_bytes()));
+        Ok((ciphertext, tag_bytes))
+    }
+
+    /// Decrypt using the electronic codebook chaining (ECB) algorithm.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let ciphertext: [u32; 4] = [0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e];
+    /// let mut plaintext: [u32; 4] = [0; 4];
+    /// aes.decrypt_ecb(&KEY, &ciphertext, &mut plaintext)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_ecb(
+        &mut self,
+        key: &[u32],
+        ciphertext: &[u32; 4],
+        plaintext: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const ALGO: Algorithm = Algorithm::Ecb;
+        const CHMOD2: bool = ALGO.chmod2();
+        const CHMOD10: u8 = ALGO.chmod10();
+        const MODE: u8 = Mode::KeyDerivationDecryption.bits();
+
+        self.check_idle()?;
+
+        let keysize: KeySize = self.set_key(key);
+
+        self.aes.cr.write(|w| {
+            w.en().enabled();
+            w.datatype().variant(self.swap_mode);
+            w.mode().bits(MODE);
+            w.chmod2().bit(CHMOD2);
+            w.chmod().bits(CHMOD10);
+            w.ccfc().clear();
+            w.errc().clear();
+            w.ccfie().disabled();
+            w.errie().disabled();
+            w.dmainen().disabled();
+            w.dmaouten().disabled();
+            w.gcmph().bits(0); // do not care for ECB
+            w.keysize().variant(keysize);
+            w.npblb().bits(0) // no padding
+        });
+
+        self.set_din(ciphertext);
+        self.poll_completion()?;
+        self.dout(plaintext);
+        self.finish_op();
+        Ok(())
+    }
+
+    /// Decrypt a [`Ciphertext`] block using the electronic codebook chaining
+    /// (ECB) algorithm.
+    ///
+    /// This is [`decrypt_ecb`](Self::decrypt_ecb) with types that prevent
+    /// decrypting plaintext by mistake.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     aes::{Aes, Ciphertext, Plaintext},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let ciphertext = Ciphertext([0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e]);
+    /// let plaintext: Plaintext = aes.decrypt_block(&KEY, &ciphertext)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    ///
+    /// Plaintext cannot be decrypted:
+    ///
+    /// ```compile_fail
+    /// # let mut aes = unsafe { stm32wlxx_hal::aes::Aes::steal() };
+    /// use stm32wlxx_hal::aes::Plaintext;
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let plaintext = Plaintext([0; 4]);
+    /// let _ = aes.decrypt_block(&KEY, &plaintext);
+    /// ```
+    pub fn decrypt_block(
+        &mut self,
+        key: &[u32],
+        ciphertext: &Ciphertext,
+    ) -> Result<Plaintext, Error> {
+        let mut plaintext: Plaintext = Plaintext::default();
+        self.decrypt_ecb(key, &ciphertext.0, &mut plaintext.0)?;
+        Ok(plaintext)
+    }
+
+    /// Decrypt using the electronic codebook chaining (ECB) algorithm in-place.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let mut text: [u32; 4] = [0x0336763e, 0x966d9259, 0x5a567cc9, 0xce537f5e];
+    /// aes.decrypt_ecb_inplace(&KEY, &mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_ecb_inplace(
+        &mut self,
+        key: &[u32],
+        ciphertext: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const ALGO: Algorithm = Algorithm::Ecb;
+        const CHMOD2: bool = ALGO.chmod2();
+        const CHMOD10: u8 = ALGO.chmod10();
+        const MODE: u8 = Mode::KeyDerivationDecryption.bits();
+        self.check_idle()?;
+        let keysize: KeySize = self.set_key(key);
+
+        self.aes.cr.write(|w| {
+            w.en().enabled();
+            w.datatype().variant(self.swap_mode);
+            w.mode().bits(MODE);
+            w.chmod2().bit(CHMOD2);
+            w.chmod().bits(CHMOD10);
+            w.ccfc().clear();
+            w.errc().clear();
+            w.ccfie().disabled();
+            w.errie().disabled();
+            w.dmainen().disabled();
+            w.dmaouten().disabled();
+            w.gcmph().bits(0); // do not care for ECB
+            w.keysize().variant(keysize);
+            w.npblb().bits(0) // no padding
+        });
+
+        self.set_din(ciphertext);
+        self.poll_completion()?;
+        self.dout(ciphertext);
+        self.finish_op();
+        Ok(())
+    }
+
+    /// Decrypt using the Galois counter mode (GCM) algorithm in-place.
+    ///
+    /// The resulting tag should be compared to the tag sent from the peer
+    /// to verify the authenticity of the message.
+    /// Use [`TagLen::verify`] to compare a truncated tag.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: [u32; 3] = [0; 3];
+    ///
+    /// let mut associated_data: [u8; 0] = [];
+    /// let mut ciphertext: [u8; 5] = [0xf3, 0x44, 0x81, 0xec, 0x3c];
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.decrypt_gcm_inplace(&KEY, &IV, &associated_data, &mut ciphertext, &mut tag)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_gcm_inplace(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 3],
+        aad: &[u8],
+        ciphertext: &mut [u8],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Decryption.bits();
+        self.gcm_inplace::<MODE>(key, iv, aad, ciphertext, tag)
+    }
+
+    /// Decrypt using the Galois counter mode (GCM) algorithm in-place.
+    ///
+    /// `u32` is the native AES peripheral data size.
+    /// This method skips byte packing / unpacking that occurs in
+    /// [`decrypt_gcm_inplace`](Self::decrypt_gcm_inplace).
+    ///
+    /// The resulting tag should be compared to the tag sent from the peer
+    /// to verify the authenticity of the message.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: [u32; 3] = [0; 3];
+    ///
+    /// let mut associated_data: [u32; 0] = [];
+    /// let mut ciphertext: [u32; 1] = [0xf34481ec];
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.decrypt_gcm_inplace_u32(&KEY, &IV, &associated_data, &mut ciphertext, &mut tag)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_gcm_inplace_u32(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 3],
+        aad: &[u32],
+        ciphertext: &mut [u32],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Decryption.bits();
+        self.gcm_inplace_u32::<MODE>(key, iv, aad, ciphertext, tag)
+    }
+
+    /// Encrypt using the Galois counter mode (GCM) algorithm in-place with an
+    /// initialization vector of any length.
+    ///
+    /// A 12 byte (96-bit) IV takes the same path as
+    /// [`encrypt_gcm_inplace`](Self::encrypt_gcm_inplace), including the
+    /// configured [`dataswap`](Self::dataswap) mode.
+    /// Any other length is processed with GHASH into the pre-counter block
+    /// (NIST SP 800-38D section 7.1), the peripheral only supports a 96-bit
+    /// IV in the GCM init phase, so this is done with ECB and CTR operations
+    /// and a software GHASH, which is much slower.
+    /// Prefer a 96-bit IV unless a protocol requires another length.
+    ///
+    /// The IV is a byte string in the order of NIST SP 800-38D.
+    /// For an IV that is not 96-bits the swap mode is overridden: the data
+    /// are byte strings in the order of NIST SP 800-38D, and the tag is in
+    /// the [byte order](self#byte-order) of [`SwapMode::None`], regardless of
+    /// [`set_dataswap`](Self::set_dataswap).
+    /// The configured swap mode is restored afterwards.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Length`] the IV is empty.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308];
+    /// const IV: [u8; 8] = [0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad];
+    ///
+    /// let mut plaintext: [u8; 13] = *b"Hello, World!";
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.encrypt_gcm_inplace_iv(&KEY, &IV, b"header", &mut plaintext, &mut tag)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_gcm_inplace_iv(
+        &mut self,
+        key: &[u32],
+        iv: &[u8],
+        aad: &[u8],
+        plaintext: &mut [u8],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Encryption.bits();
+        self.gcm_inplace_iv::<MODE>(key, iv, aad, plaintext, tag)
+    }
+
+    /// Decrypt using the Galois counter mode (GCM) algorithm in-place with an
+    /// initialization vector of any length.
+    ///
+    /// This is the inverse of
+    /// [`encrypt_gcm_inplace_iv`](Self::encrypt_gcm_inplace_iv), see its
+    /// documentation for the IV processing.
+    ///
+    /// A 12 byte (96-bit) IV uses the configured
+    /// [`dataswap`](Self::dataswap) mode, like
+    /// [`decrypt_gcm_inplace`](Self::decrypt_gcm_inplace).
+    /// For any other IV length the swap mode is overridden with
+    /// [`SwapMode::None`], and restored afterwards.
+    ///
+    /// The resulting tag should be compared to the tag sent from the peer
+    /// to verify the authenticity of the message.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Length`] the IV is empty.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308];
+    /// const IV: [u8; 8] = [0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad];
+    ///
+    /// let mut ciphertext: [u8; 5] = [0xf3, 0x44, 0x81, 0xec, 0x3c];
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.decrypt_gcm_inplace_iv(&KEY, &IV, b"header", &mut ciphertext, &mut tag)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_gcm_inplace_iv(
+        &mut self,
+        key: &[u32],
+        iv: &[u8],
+        aad: &[u8],
+        ciphertext: &mut [u8],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Decryption.bits();
+        self.gcm_inplace_iv::<MODE>(key, iv, aad, ciphertext, tag)
+    }
+
+    /// Encrypt using the Galois counter mode (GCM) algorithm in-place, with
+    /// the associated data and plaintext split across multiple slices.
+    ///
+    /// This is [`encrypt_gcm_inplace`](Self::encrypt_gcm_inplace) on the
+    /// concatenation of the slices in `aad` and the concatenation of the
+    /// slices in `plaintext`, without copying them into one buffer first.
+    /// The slices can be any length, including empty, blocks that span two
+    /// slices are gathered and scattered by the driver.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: [u32; 3] = [0; 3];
+    ///
+    /// let protocol_header: [u8; 3] = [0x01, 0x02, 0x03];
+    /// let device_address: [u8; 4] = [0xDE, 0xAD, 0xBE, 0xEF];
+    /// let mut sensor: [u8; 5] = *b"Hello";
+    /// let mut counter: [u8; 2] = [0x00, 0x2A];
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.encrypt_gcm_vectored(
+    ///     &KEY,
+    ///     &IV,
+    ///     &[&protocol_header, &device_address],
+    ///     &mut [&mut sensor, &mut counter],
+    ///     &mut tag,
+    /// )?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_gcm_vectored(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 3],
+        aad: &[&[u8]],
+        plaintext: &mut [&mut [u8]],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Encryption.bits();
+        self.gcm_vectored::<MODE>(key, iv, aad, plaintext, tag)
+    }
+
+    /// Decrypt using the Galois counter mode (GCM) algorithm in-place, with
+    /// the associated data and ciphertext split across multiple slices.
+    ///
+    /// This is [`decrypt_gcm_inplace`](Self::decrypt_gcm_inplace) on the
+    /// concatenation of the slices in `aad` and the concatenation of the
+    /// slices in `ciphertext`, see
+    /// [`encrypt_gcm_vectored`](Self::encrypt_gcm_vectored).
+    ///
+    /// The resulting tag should be compared to the tag sent from the peer
+    /// to verify the authenticity of the message.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: [u32; 3] = [0; 3];
+    ///
+    /// let protocol_header: [u8; 3] = [0x01, 0x02, 0x03];
+    /// let device_address: [u8; 4] = [0xDE, 0xAD, 0xBE, 0xEF];
+    /// let mut sensor: [u8; 5] = [0xf3, 0x44, 0x81, 0xec, 0x3c];
+    /// let mut counter: [u8; 2] = [0xc6, 0x27];
+    /// let mut tag: [u32; 4] = [0; 4];
+    /// aes.decrypt_gcm_vectored(
+    ///     &KEY,
+    ///     &IV,
+    ///     &[&protocol_header, &device_address],
+    ///     &mut [&mut sensor, &mut counter],
+    ///     &mut tag,
+    /// )?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_gcm_vectored(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 3],
+        aad: &[&[u8]],
+        ciphertext: &mut [&mut [u8]],
+        tag: &mut [u32; 4],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Decryption.bits();
+        self.gcm_vectored::<MODE>(key, iv, aad, ciphertext, tag)
+    }
+
+    /// Compute a CBC-MAC of a message.
+    ///
+    /// The message is encrypted with the cipher block chaining (CBC)
+    /// algorithm and a zero IV, the final block of ciphertext is the MAC.
+    ///
+    /// Returns [`Error::Length`] if the message is empty or the message
+    /// length is not a multiple of 16 bytes.
+    /// No padding is applied.
+    ///
+    /// # Security
+    ///
+    /// CBC-MAC is only secure for messages of a fixed length.
+    /// If an attacker can obtain MACs for messages of different lengths they
+    /// can forge a MAC for the concatenation of two messages.
+    /// Using the same key for CBC-MAC and any other purpose, such as CBC
+    /// encryption, is also insecure.
+    ///
+    /// Prefer CMAC or GCM for new protocols, this exists for compatibility
+    /// with legacy protocols that require CBC-MAC.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    ///
+    /// let msg: [u8; 32] = [0; 32];
+    /// let mac: [u8; 16] = aes.cbc_mac(&KEY, &msg)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn cbc_mac(&mut self, key: &[u32], msg: &[u8]) -> Result<[u8; 16], Error> {
+        const ALGO: Algorithm = Algorithm::Cbc;
+        const CHMOD2: bool = ALGO.chmod2();
+        const CHMOD10: u8 = ALGO.chmod10();
+        const MODE: u8 = Mode::Encryption.bits();
+
+        if msg.is_empty() || msg.len() % 16 != 0 {
+            return Err(Error::Length);
+        }
+
+        self.check_idle()?;
+
+        let keysize: KeySize = self.set_key(key);
+
+        self.aes.ivr0.write(|w| w.ivi().bits(0));
+        self.aes.ivr1.write(|w| w.ivi().bits(0));
+        self.aes.ivr2.write(|w| w.ivi().bits(0));
+        self.aes.ivr3.write(|w| w.ivi().bits(0));
+        self.aes.cr.write(|w| {
+            w.en().enabled();
+            w.datatype().variant(self.swap_mode);
+            w.mode().bits(MODE);
+            w.chmod2().bit(CHMOD2);
+            w.chmod().bits(CHMOD10);
+            w.ccfc().clear();
+            w.errc().clear();
+            w.ccfie().disabled();
+            w.errie().disabled();
+            w.dmainen().disabled();
+            w.dmaouten().disabled();
+            w.gcmph().bits(0); // do not care for CBC
+            w.keysize().variant(keysize);
+            w.npblb().bits(0) // no padding
+        });
+
+        let mut mac: [u8; 16] = [0; 16];
+        for block in msg.chunks_exact(16) {
+            self.set_din_block(block);
+            self.poll_completion()?;
+            // the output must be read for every block, only the last is kept
+            self.dout_block(&mut mac);
+        }
+
+        self.finish_op();
+        Ok(mac)
+    }
+
+    fn ctr_inplace(&mut self, key: &[u32], iv: &CtrIv, buf: &mut [u8]) -> Result<(), Error> {
+        const ALGO: Algorithm = Algorithm::Ctr;
+        const CHMOD2: bool = ALGO.chmod2();
+        const CHMOD10: u8 = ALGO.chmod10();
+        // CTR decryption is the same as encryption
+        const MODE: u8 = Mode::Encryption.bits();
+
+        self.check_idle()?;
+
+        let keysize: KeySize = self.set_key(key);
+
+        let ivr: [u32; 4] = iv.ivr();
+        self.aes.ivr0.write(|w| w.ivi().bits(ivr[0]));
+        self.aes.ivr1.write(|w| w.ivi().bits(ivr[1]));
+        self.aes.ivr2.write(|w| w.ivi().bits(ivr[2]));
+        self.aes.ivr3.write(|w| w.ivi().bits(ivr[3]));
+        self.aes.cr.write(|w| {
+            w.en().enabled();
+            w.datatype().variant(self.swap_mode);
+            w.mode().bits(MODE);
+            w.chmod2().bit(CHMOD2);
+            w.chmod().bits(CHMOD10);
+            w.ccfc().clear();
+            w.errc().clear();
+            w.ccfie().disabled();
+            w.errie().disabled();
+            w.dmainen().disabled();
+            w.dmaouten().disabled();
+            w.gcmph().bits(0); // do not care for CTR
+            w.keysize().variant(keysize);
+            w.npblb().bits(0) // no padding
+        });
+
+        // the final partial block is zero padded, the padding output is
+        // discarded
+        for block in buf.chunks_mut(16) {
+            self.set_din_block(block);
+            self.poll_completion()?;
+            self.dout_block(block);
+        }
+
+        self.finish_op();
+        Ok(())
+    }
+
+    /// Encrypt using the counter (CTR) algorithm in-place.
+    ///
+    /// The buffer can be any length, no padding is required.
+    ///
+    /// **Note:** A nonce must never be reused with the same key, and the
+    /// counter wraps around after 2<sup>32</sup> blocks.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     aes::{Aes, CtrIv},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: CtrIv = CtrIv::new([0; 12], 1);
+    ///
+    /// let mut text: [u8; 5] = *b"Hello";
+    /// aes.encrypt_ctr_inplace(&KEY, &IV, &mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_ctr_inplace(
+        &mut self,
+        key: &[u32],
+        iv: &CtrIv,
+        plaintext: &mut [u8],
+    ) -> Result<(), Error> {
+        self.ctr_inplace(key, iv, plaintext)
+    }
+
+    /// Decrypt using the counter (CTR) algorithm in-place.
+    ///
+    /// The buffer can be any length, no padding is required.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     aes::{Aes, CtrIv},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: CtrIv = CtrIv::new([0; 12], 1);
+    ///
+    /// let mut text: [u8; 5] = [0; 5]; // ciphertext from the peer
+    /// aes.decrypt_ctr_inplace(&KEY, &IV, &mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_ctr_inplace(
+        &mut self,
+        key: &[u32],
+        iv: &CtrIv,
+        ciphertext: &mut [u8],
+    ) -> Result<(), Error> {
+        self.ctr_inplace(key, iv, ciphertext)
+    }
+
+    fn cbc_inplace<const MODE: u8>(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 4],
+        buf: &mut [[u32; 4]],
+    ) -> Result<(), Error> {
+        const ALGO: Algorithm = Algorithm::Cbc;
+        const CHMOD2: bool = ALGO.chmod2();
+        const CHMOD10: u8 = ALGO.chmod10();
+
+        self.check_idle()?;
+
+        let keysize: KeySize = self.set_key(key);
+
+        let ivr: [u32; 4] = ivr_registers(iv);
+        self.aes.ivr0.write(|w| w.ivi().bits(ivr[0]));
+        self.aes.ivr1.write(|w| w.ivi().bits(ivr[1]));
+        self.aes.ivr2.write(|w| w.ivi().bits(ivr[2]));
+        self.aes.ivr3.write(|w| w.ivi().bits(ivr[3]));
+        self.aes.cr.write(|w| {
+            w.en().enabled();
+            w.datatype().variant(self.swap_mode);
+            w.mode().bits(MODE);
+            w.chmod2().bit(CHMOD2);
+            w.chmod().bits(CHMOD10);
+            w.ccfc().clear();
+            w.errc().clear();
+            w.ccfie().disabled();
+            w.errie().disabled();
+            w.dmainen().disabled();
+            w.dmaouten().disabled();
+            w.gcmph().bits(0); // do not care for CBC
+            w.keysize().variant(keysize);
+            w.npblb().bits(0) // no padding
+        });
+
+        // The peripheral chains blocks through the IVR registers, when
+        // decrypting it keeps a copy of the ciphertext block written to DINR.
+        // The output can overwrite the input without buffering in software.
+        for block in buf.iter_mut() {
+            self.set_din(block);
+            self.poll_completion()?;
+            self.dout(block);
+        }
+
+        self.finish_op();
+        Ok(())
+    }
+
+    /// Encrypt using the cipher block chaining (CBC) algorithm in-place.
+    ///
+    /// The IV words are written from most significant (`AES_IVR3`) to least
+    /// significant (`AES_IVR0`), the same order as the key.
+    ///
+    /// **Note:** The IV must be unpredictable, never reuse an IV with the
+    /// same key.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: [u32; 4] = [0; 4];
+    ///
+    /// let mut text: [[u32; 4]; 2] = [[0; 4]; 2];
+    /// aes.encrypt_cbc_inplace(&KEY, &IV, &mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_cbc_inplace(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 4],
+        plaintext: &mut [[u32; 4]],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::Encryption.bits();
+        self.cbc_inplace::<MODE>(key, iv, plaintext)
+    }
+
+    /// Decrypt using the cipher block chaining (CBC) algorithm in-place.
+    ///
+    /// The IV words are written from most significant (`AES_IVR3`) to least
+    /// significant (`AES_IVR0`), the same order as the key.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{aes::Aes, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: [u32; 4] = [0; 4];
+    ///
+    /// let mut text: [[u32; 4]; 2] = [[0; 4]; 2]; // ciphertext from the peer
+    /// aes.decrypt_cbc_inplace(&KEY, &IV, &mut text)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn decrypt_cbc_inplace(
+        &mut self,
+        key: &[u32],
+        iv: &[u32; 4],
+        ciphertext: &mut [[u32; 4]],
+    ) -> Result<(), Error> {
+        const MODE: u8 = Mode::KeyDerivationDecryption.bits();
+        self.cbc_inplace::<MODE>(key, iv, ciphertext)
+    }
+
+    /// Pack samples into bytes and encrypt them using the counter (CTR)
+    /// algorithm.
+    ///
+    /// This is a convenience for encrypting telemetry such as ADC samples.
+    /// Each sample is packed into 2 little-endian bytes in `out`, and `out`
+    /// is encrypted in-place with
+    /// [`encrypt_ctr_inplace`](Self::encrypt_ctr_inplace).
+    /// The receiver decrypts with the same key and IV and unpacks the
+    /// samples with [`u16::from_le_bytes`].
+    ///
+    /// **Note:** A nonce must never be reused with the same key.
+    ///
+    /// Returns [`Error::Length`] if `out` is not exactly twice the length of
+    /// `samples`.
+    ///
+    /// # Panics
+    ///
+    /// * Key is not 128-bits long `[u32; 4]` or 256-bits long `[u32; 8]`.
+    /// * (debug) AES peripheral clock is not enabled.
+    ///
+    /// # Example
+    ///
+    /// Encrypt V<sub>BAT</sub> samples.
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     adc::{self, Adc},
+    ///     aes::{Aes, CtrIv},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut aes: Aes = Aes::new(dp.AES, &mut dp.RCC);
+    /// let mut adc: Adc = Adc::new(dp.ADC, adc::Clk::PClkDiv4, &mut dp.RCC);
+    /// adc.set_max_sample_time();
+    /// adc.enable();
+    /// adc.enable_vbat();
+    ///
+    /// const KEY: [u32; 4] = [0; 4];
+    /// const IV: CtrIv = CtrIv::new([0; 12], 1);
+    ///
+    /// let mut samples: [u16; 8] = [0; 8];
+    /// samples.iter_mut().for_each(|s| *s = adc.vbat());
+    ///
+    /// let mut out: [u8; 16] = [0; 16];
+    /// aes.encrypt_samples(&KEY, &IV, &samples, &mut out)?;
+    /// # Ok::<(), stm32wlxx_hal::aes::Error>(())
+    /// ```
+    pub fn encrypt_samples(
+        &mut self,
+        key: &[u32],
+        iv: &CtrIv,
+        samples: &[u16],
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        pack_samples(samples, out)?;
+        self.ctr_inplace(key, iv, out)
+    }
+}
+
+/// Pack samples into little-endian bytes.
+fn pack_samples(samples: &[u16], out: &mut [u8]) -> Result<(), Error> {
+    if out.len() != samples.len().saturating_mul(2) {
+        return Err(Error::Length);
+    }
+    samples
+        .iter()
+        .zip(out.chunks_exact_mut(2))
+        .for_each(|(sample, bytes)| bytes.copy_from_slice(&sample.to_le_bytes()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        advance_phase, assert_clock, block_from_bytes, block_to_bytes, constant_time_eq,
+        constant_time_eq_16, debug_assert_clock_with, ivr_registers, key_256_from_bytes,
+        key_registers, pack_samples, poll_ccf, poll_idle, self_test_with, swap, try_acquire, Aes,
+        Algorithm, CtrIv, Cursor, Error, Ghash, KeySize, KeyedAes, LockGuard, Mode, SwapMode,
+        TagLen, SR_CCF, SR_RDERR, SR_WRERR,
+    };
+
+    #[test]
+    fn block_bytes_round_trip() {
+        let mut bytes: [u8; 16] = [0; 16];
+        bytes
+            .iter_mut()
+            .enumerate()
+            .for_each(|(n, byte)| *byte = n as u8);
+        let block: [u32; 4] = block_from_bytes(&bytes);
+        assert_eq!(block, [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f]);
+        assert_eq!(block_to_bytes(&block), bytes);
+    }
+
+    #[test]
+    fn key_registers_128() {
+        // FIPS-197 appendix C.1 key
+        let (keysize, keyr) =
+            key_registers(&[0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f]).unwrap();
+        assert_eq!(keysize, KeySize::Bits128);
+        assert_eq!(
+            keyr,
+            [0x0c0d0e0f, 0x08090a0b, 0x04050607, 0x00010203, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn key_registers_256() {
+        // FIPS-197 appendix C.3 key
+        let key: [u32; 8] = [
+            0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f, 0x10111213, 0x14151617, 0x18191a1b,
+            0x1c1d1e1f,
+        ];
+        let (keysize, keyr) = key_registers(&key).unwrap();
+        assert_eq!(keysize, KeySize::Bits256);
+        assert_eq!(
+            keyr,
+            [
+                0x1c1d1e1f, 0x18191a1b, 0x14151617, 0x10111213, 0x0c0d0e0f, 0x08090a0b, 0x04050607,
+                0x00010203
+            ]
+        );
+    }
+
+    #[test]
+    fn key_registers_invalid() {
+        assert!(key_registers(&[]).is_none());
+        assert!(key_registers(&[0; 6]).is_none());
+        assert!(key_registers(&[0; 16]).is_none());
+    }
+
+    #[test]
+    fn ivr_registers_order() {
+        assert_eq!(
+            ivr_registers(&[0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f]),
+            [0x0c0d0e0f, 0x08090a0b, 0x04050607, 0x00010203]
+        );
+    }
+
+    #[test]
+    fn key_256_bytes() {
+        let mut bytes: [u8; 32] = [0; 32];
+        bytes
+            .iter_mut()
+            .enumerate()
+            .for_each(|(n, byte)| *byte = n as u8);
+        let key: [u32; 8] = key_256_from_bytes(&bytes);
+        assert_eq!(
+            key[..4],
+            block_from_bytes(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15])
+        );
+        assert_eq!(key[4..], [0x10111213, 0x14151617, 0x18191a1b, 0x1c1d1e1f]);
+    }
+
+    #[test]
+    fn swap_modes() {
+        const WORD: u32 = 0x0123_4567;
+        for mode in [
+            SwapMode::None,
+            SwapMode::HalfWord,
+            SwapMode::Byte,
+            SwapMode::Bit,
+        ] {
+            // every swap mode is its own inverse
+            assert_eq!(swap(swap(WORD, mode), mode), WORD);
+        }
+        assert_eq!(swap(WORD, SwapMode::HalfWord), 0x4567_0123);
+        assert_eq!(swap(WORD, SwapMode::Byte), 0x6745_2301);
+        assert_eq!(swap(WORD, SwapMode::Bit), 0xE6A2_C480);
+        assert_eq!(swap(1, SwapMode::Bit), 0x8000_0000);
+    }
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn try_acquire_reentry() {
+        let locked: AtomicBool = AtomicBool::new(false);
+        assert!(try_acquire(&locked));
+        // re-entry while held, for example from an interrupt
+        assert!(!try_acquire(&locked));
+        assert!(!try_acquire(&locked));
+        locked.store(false, Ordering::Release);
+        assert!(try_acquire(&locked));
+    }
+
+    #[test]
+    fn lock_guard_unwind() {
+        let locked: AtomicBool = AtomicBool::new(false);
+        let ret = std::panic::catch_unwind(|| {
+            assert!(try_acquire(&locked));
+            let _guard: LockGuard = LockGuard(&locked);
+            panic!("unwind while locked");
+        });
+        assert!(ret.is_err());
+        assert!(try_acquire(&locked));
+    }
+
+    #[test]
+    fn poll_idle_busy_then_idle() {
+        let mut busy_polls: u32 = 5;
+        let mut polls: u32 = 0;
+        let is_busy = || {
+            polls += 1;
+            if busy_polls == 0 {
+                false
+            } else {
+                busy_polls -= 1;
+                true
+            }
+        };
+        assert_eq!(poll_idle(is_busy, 10), Ok(()));
+        assert_eq!(polls, 6);
+    }
+
+    #[test]
+    fn poll_idle_timeout() {
+        let mut polls: u32 = 0;
+        let is_busy = || {
+            polls += 1;
+            true
+        };
+        assert_eq!(poll_idle(is_busy, 10), Err(Error::Timeout));
+        assert_eq!(polls, 10);
+        assert_eq!(poll_idle(|| false, 1), Ok(()));
+        assert_eq!(poll_idle(|| false, 0), Err(Error::Timeout));
+    }
+
+    #[test]
+    fn pack_samples_le() {
+        let mut out: [u8; 6] = [0; 6];
+        assert_eq!(pack_samples(&[0x0123, 0x0FFF, 0x0000], &mut out), Ok(()));
+        assert_eq!(out, [0x23, 0x01, 0xFF, 0x0F, 0x00, 0x00]);
+        assert_eq!(pack_samples(&[], &mut []), Ok(()));
+        assert_eq!(pack_samples(&[1, 2], &mut [0; 3]), Err(Error::Length));
+        assert_eq!(pack_samples(&[1, 2], &mut [0; 5]), Err(Error::Length));
+    }
+
+    #[test]
+    fn poll_ccf_complete() {
+        let mut polls: u32 = 0;
+        let read_sr = || {
+            polls += 1;
+            // busy for 3 polls
+            if polls > 3 {
+                SR_CCF
+            } else {
+                1 << 3
+            }
+        };
+        assert_eq!(poll_ccf(read_sr), Ok(()));
+        assert_eq!(polls, 4);
+    }
+
+    #[test]
+    fn poll_ccf_errors() {
+        assert_eq!(poll_ccf(|| SR_RDERR), Err(Error::Read));
+        assert_eq!(poll_ccf(|| SR_WRERR), Err(Error::Write));
+        // errors take priority over completion
+        assert_eq!(poll_ccf(|| SR_CCF | SR_RDERR), Err(Error::Read));
+        assert_eq!(poll_ccf(|| SR_CCF | SR_WRERR), Err(Error::Write));
+        assert_eq!(poll_ccf(|| SR_CCF | SR_RDERR | SR_WRERR), Err(Error::Write));
+
+        let mut polls: u32 = 0;
+        let read_sr = || {
+            polls += 1;
+            if polls > 2 {
+                SR_WRERR
+            } else {
+                0
+            }
+        };
+        assert_eq!(poll_ccf(read_sr), Err(Error::Write));
+        assert_eq!(polls, 3);
+    }
+
+    #[test]
+    fn ctr_iv_words() {
+        const NONCE: [u8; 12] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B,
+        ];
+        assert_eq!(
+            CtrIv::new(NONCE, 0xDEADBEEF).ivr(),
+            [0xDEADBEEF, 0x08090A0B, 0x04050607, 0x00010203]
+        );
+        assert_eq!(CtrIv::new([0; 12], 0).ivr(), [0; 4]);
+        assert_eq!(
+            CtrIv::new([0xFF; 12], 1).ivr(),
+            [1, u32::MAX, u32::MAX, u32::MAX]
+        );
+    }
+
+    #[test]
+    fn constant_time_eq_slices() {
+        assert!(constant_time_eq(&[], &[]));
+        assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!constant_time_eq(&[0, 2, 3], &[1, 2, 3]));
+        // differing lengths with a matching prefix
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+        assert!(!constant_time_eq(&[1, 2], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[], &[0]));
+    }
+
+    #[test]
+    fn constant_time_eq_blocks() {
+        let a: [u8; 16] = [0xA5; 16];
+        assert!(constant_time_eq_16(&a, &a));
+        for idx in 0..16 {
+            for bit in 0..8 {
+                let mut b: [u8; 16] = a;
+                b[idx] ^= 1 << bit;
+                assert!(!constant_time_eq_16(&a, &b), "{} {}", idx, bit);
+            }
+        }
+    }
+
+    #[test]
+    fn supports() {
+        const ALGORITHMS: [Algorithm; 5] = [
+            Algorithm::Ecb,
+            Algorithm::Cbc,
+            Algorithm::Ctr,
+            Algorithm::Gcm,
+            Algorithm::Ccm,
+        ];
+
+        for algorithm in ALGORITHMS {
+            assert!(algorithm.supports(Mode::Encryption), "{:?}", algorithm);
+            assert!(algorithm.supports(Mode::Decryption), "{:?}", algorithm);
+
+            let key_derivation: bool = matches!(algorithm, Algorithm::Ecb | Algorithm::Cbc);
+            assert_eq!(
+                algorithm.supports(Mode::KeyDerivation),
+                key_derivation,
+                "{:?}",
+                algorithm
+            );
+            assert_eq!(
+                algorithm.supports(Mode::KeyDerivationDecryption),
+                key_derivation,
+                "{:?}",
+                algorithm
+            );
+        }
+    }
+
+    /// Software AES reference, only used to check the word order contract
+    /// of the driver against the byte order of FIPS-197.
+    mod reference {
+        fn xtime(b: u8) -> u8 {
+            (b << 1) ^ if b & 0x80 != 0 { 0x1B } else { 0 }
+        }
+
+        fn mul(mut a: u8, mut b: u8) -> u8 {
+            let mut p: u8 = 0;
+            while b != 0 {
+                if b & 1 != 0 {
+                    p ^= a;
+                }
+                a = xtime(a);
+                b >>= 1;
+            }
+            p
+        }
+
+        fn sbox(b: u8) -> u8 {
+            // multiplicative inverse in GF(2^8) is b^254, 0 maps to 0
+            let mut inv: u8 = 1;
+            for _ in 0..254 {
+                inv = mul(inv, b);
+            }
+            let inv: u8 = if b == 0 { 0 } else { inv };
+            inv ^ inv.rotate_left(1)
+                ^ inv.rotate_left(2)
+                ^ inv.rotate_left(3)
+                ^ inv.rotate_left(4)
+                ^ 0x63
+        }
+
+        fn expand_key(key: &[u8]) -> Vec<[u8; 16]> {
+            let nk: usize = key.len() / 4;
+            let rounds: usize = nk + 6;
+            let mut w: Vec<[u8; 4]> = key.chunks(4).map(|c| [c[0], c[1], c[2], c[3]]).collect();
+            let mut rcon: u8 = 1;
+            for i in nk..4 * (rounds + 1) {
+                let mut t: [u8; 4] = w[i - 1];
+                if i % nk == 0 {
+                    t = [sbox(t[1]) ^ rcon, sbox(t[2]), sbox(t[3]), sbox(t[0])];
+                    rcon = xtime(rcon);
+                } else if nk > 6 && i % nk == 4 {
+                    t = t.map(sbox);
+                }
+                let prev: [u8; 4] = w[i - nk];
+                w.push([
+                    prev[0] ^ t[0],
+                    prev[1] ^ t[1],
+                    prev[2] ^ t[2],
+                    prev[3] ^ t[3],
+                ]);
+            }
+            w.chunks(4)
+                .map(|c| {
+                    let mut rk: [u8; 16] = [0; 16];
+                    rk.iter_mut()
+                        .enumerate()
+                        .for_each(|(n, b)| *b = c[n / 4][n % 4]);
+                    rk
+                })
+                .collect()
+        }
+
+        /// Encrypt one block with a 128 or 256 bit key.
+        pub fn encrypt(key: &[u8], block: &[u8; 16]) -> [u8; 16] {
+            let round_keys: Vec<[u8; 16]> = expand_key(key);
+            let rounds: usize = round_keys.len() - 1;
+            let mut s: [u8; 16] = *block;
+            s.iter_mut().zip(round_keys[0]).for_each(|(b, k)| *b ^= k);
+            for (round, rk) in round_keys.iter().enumerate().skip(1) {
+                s = s.map(sbox);
+                // shift rows, the state is column major
+                let t: [u8; 16] = s;
+                for c in 0..4 {
+                    for r in 0..4 {
+                        s[c * 4 + r] = t[((c + r) % 4) * 4 + r];
+                    }
+                }
+                if round != rounds {
+                    for c in 0..4 {
+                        let col: [u8; 4] = [s[c * 4], s[c * 4 + 1], s[c * 4 + 2], s[c * 4 + 3]];
+                        for r in 0..4 {
+                            s[c * 4 + r] = mul(col[r], 2)
+                                ^ mul(col[(r + 1) % 4], 3)
+                                ^ col[(r + 2) % 4]
+                                ^ col[(r + 3) % 4];
+                        }
+                    }
+                }
+                s.iter_mut().zip(rk).for_each(|(b, k)| *b ^= k);
+            }
+            s
+        }
+    }
+
+    /// Seeded xorshift32 generator for repeatable random cases.
+    struct XorShift(u32);
+
+    impl XorShift {
+        fn next_u32(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+
+        fn fill(&mut self, buf: &mut [u8]) {
+            buf.iter_mut().for_each(|b| *b = self.next_u32() as u8);
+        }
+    }
+
+    const SEEDS: [u32; 5] = [1, 42, 0x1234_5678, 0xDEAD_BEEF, 0xFFFF_FFFF];
+    const SWAP_MODES: [SwapMode; 4] = [
+        SwapMode::None,
+        SwapMode::HalfWord,
+        SwapMode::Byte,
+        SwapMode::Bit,
+    ];
+
+    /// Register stub of the peripheral.
+    ///
+    /// The core sees `KEYR(n-1)` to `KEYR0`, `IVR3` to `IVR0`, and the data
+    /// words after the data swap, as big-endian FIPS-197 byte strings.
+    struct Stub {
+        keyr: [u32; 8],
+        keysize: usize,
+        ivr: [u32; 4],
+        swap: SwapMode,
+    }
+
+    impl Stub {
+        fn new(swap: SwapMode) -> Stub {
+            Stub {
+                keyr: [0; 8],
+                keysize: 0,
+                ivr: [0; 4],
+                swap,
+            }
+        }
+
+        /// Same register order as the `set_key` driver method.
+        fn set_key(&mut self, key: &[u32]) {
+            self.keysize = key.len();
+            self.keyr = key_registers(key).unwrap().1;
+        }
+
+        fn key_bytes(&self) -> Vec<u8> {
+            self.keyr[..self.keysize]
+                .iter()
+                .rev()
+                .flat_map(|word| word.to_be_bytes())
+                .collect()
+        }
+
+        fn iv_block(&self) -> [u32; 4] {
+            [self.ivr[3], self.ivr[2], self.ivr[1], self.ivr[0]]
+        }
+
+        fn core(&self, block: [u32; 4]) -> [u32; 4] {
+            block_from_bytes(&reference::encrypt(
+                &self.key_bytes(),
+                &block_to_bytes(&block),
+            ))
+        }
+
+        fn ecb(&mut self, din: [u32; 4]) -> [u32; 4] {
+            self.core(din.map(|w| swap(w, self.swap)))
+                .map(|w| swap(w, self.swap))
+        }
+
+        fn cbc(&mut self, din: [u32; 4]) -> [u32; 4] {
+            let iv: [u32; 4] = self.iv_block();
+            let mut block: [u32; 4] = din.map(|w| swap(w, self.swap));
+            block.iter_mut().zip(iv).for_each(|(w, iv)| *w ^= iv);
+            let out: [u32; 4] = self.core(block);
+            self.ivr = [out[3], out[2], out[1], out[0]];
+            out.map(|w| swap(w, self.swap))
+        }
+
+        fn ctr(&mut self, din: [u32; 4]) -> [u32; 4] {
+            let keystream: [u32; 4] = self.core(self.iv_block());
+            self.ivr[0] = self.ivr[0].wrapping_add(1);
+            let mut out: [u32; 4] = din.map(|w| swap(w, self.swap));
+            out.iter_mut().zip(keystream).for_each(|(w, k)| *w ^= k);
+            out.map(|w| swap(w, self.swap))
+        }
+    }
+
+    /// Words written by software for a byte string with a swap mode.
+    fn words(bytes: &[u8; 16], mode: SwapMode) -> [u32; 4] {
+        block_from_bytes(bytes).map(|w| swap(w, mode))
+    }
+
+    fn bytes(words: [u32; 4], mode: SwapMode) -> [u8; 16] {
+        block_to_bytes(&words.map(|w| swap(w, mode)))
+    }
+
+    /// Parse a test vector hex string, whitespace is ignored.
+    fn hex(s: &str) -> Vec<u8> {
+        let digits: Vec<u8> = s
+            .bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .map(|b| match (b as char).to_digit(16) {
+                Some(digit) => digit as u8,
+                None => panic!("invalid hex digit {:?} in {s:?}", b as char),
+            })
+            .collect();
+        assert!(digits.len() % 2 == 0, "odd number of hex digits in {s:?}");
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect()
+    }
+
+    /// Parse a 16 byte block from a test vector hex string.
+    fn hex16(s: &str) -> [u8; 16] {
+        let bytes: Vec<u8> = hex(s);
+        bytes
+            .try_into()
+            .unwrap_or_else(|bytes: Vec<u8>| panic!("{} bytes in {s:?}, not 16", bytes.len()))
+    }
+
+    /// Parse words in the [byte order](super#byte-order) of
+    /// [`SwapMode::None`] from a test vector hex string.
+    fn hex_to_words(s: &str) -> Vec<u32> {
+        let bytes: Vec<u8> = hex(s);
+        assert!(bytes.len() % 4 == 0, "partial word in {s:?}");
+        bytes
+            .chunks(4)
+            .map(|word| u32::from_be_bytes([word[0], word[1], word[2], word[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn hex_parser() {
+        assert_eq!(hex(""), Vec::<u8>::new());
+        assert_eq!(hex("00ff7A"), [0x00, 0xFF, 0x7A]);
+        assert_eq!(
+            hex("0011 2233\n\t4455"),
+            [0x00, 0x11, 0x22, 0x33, 0x44, 0x55]
+        );
+        assert_eq!(
+            hex16("00112233445566778899aabbccddeeff"),
+            [
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff
+            ]
+        );
+        assert_eq!(
+            hex_to_words("000102030405060708090a0b0c0d0e0f"),
+            block_from_bytes(&hex16("000102030405060708090a0b0c0d0e0f"))
+        );
+        assert_eq!(hex_to_words("feffe992 8665731c"), [0xfeffe992, 0x8665731c]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn hex_parser_odd() {
+        hex("abc");
+    }
+
+    #[test]
+    #[should_panic]
+    fn hex_parser_invalid() {
+        hex("0g");
+    }
+
+    #[test]
+    #[should_panic]
+    fn hex16_length() {
+        hex16("0011");
+    }
+
+    #[test]
+    #[should_panic]
+    fn hex_to_words_partial() {
+        hex_to_words("001122");
+    }
+
+    #[test]
+    fn reference_fips197() {
+        let plaintext: [u8; 16] = hex16("00112233445566778899aabbccddeeff");
+        // FIPS-197 appendix C.1
+        assert_eq!(
+            reference::encrypt(&hex("000102030405060708090a0b0c0d0e0f"), &plaintext),
+            hex16("69c4e0d86a7b0430d8cdb78070b4c55a")
+        );
+        // FIPS-197 appendix C.3
+        assert_eq!(
+            reference::encrypt(
+                &hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+                &plaintext
+            ),
+            hex16("8ea2b7ca516745bfeafc49904b496089")
+        );
+    }
+
+    #[test]
+    fn stub_fips197_words() {
+        // FIPS-197 appendix C.1 and C.3 in the word order of the driver
+        for (key, ct) in [
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ] {
+            let mut stub: Stub = Stub::new(SwapMode::None);
+            stub.set_key(&hex_to_words(key));
+            let plaintext: [u32; 4] = block_from_bytes(&hex16("00112233445566778899aabbccddeeff"));
+            assert_eq!(stub.ecb(plaintext).to_vec(), hex_to_words(ct), "key={key}");
+        }
+    }
+
+    #[test]
+    fn reference_ecb() {
+        for seed in SEEDS {
+            for key_len in [16, 32] {
+                for mode in SWAP_MODES {
+                    let mut rng: XorShift = XorShift(seed);
+                    let mut key: [u8; 32] = [0; 32];
+                    rng.fill(&mut key[..key_len]);
+                    let key_words: Vec<u32> = key[..key_len]
+                        .chunks(4)
+                        .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                        .collect();
+
+                    let mut stub: Stub = Stub::new(mode);
+                    stub.set_key(&key_words);
+                    for _ in 0..4 {
+                        let mut plaintext: [u8; 16] = [0; 16];
+                        rng.fill(&mut plaintext);
+                        let ciphertext: [u32; 4] = stub.ecb(words(&plaintext, mode));
+                        assert_eq!(
+                            bytes(ciphertext, mode),
+                            reference::encrypt(&key[..key_len], &plaintext),
+                            "seed={seed:#x} key_len={key_len} mode={mode:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reference_cbc() {
+        for seed in SEEDS {
+            for mode in SWAP_MODES {
+                let mut rng: XorShift = XorShift(seed);
+                let mut key: [u8; 16] = [0; 16];
+                rng.fill(&mut key);
+                let mut iv: [u8; 16] = [0; 16];
+                rng.fill(&mut iv);
+
+                let mut stub: Stub = Stub::new(mode);
+                stub.set_key(&block_from_bytes(&key));
+                stub.ivr = ivr_registers(&block_from_bytes(&iv));
+
+                let mut chain: [u8; 16] = iv;
+                for _ in 0..4 {
+                    let mut plaintext: [u8; 16] = [0; 16];
+                    rng.fill(&mut plaintext);
+                    let mut block: [u8; 16] = plaintext;
+                    block.iter_mut().zip(chain).for_each(|(b, c)| *b ^= c);
+                    chain = reference::encrypt(&key, &block);
+
+                    let ciphertext: [u32; 4] = stub.cbc(words(&plaintext, mode));
+                    assert_eq!(
+                        bytes(ciphertext, mode),
+                        chain,
+                        "seed={seed:#x} mode={mode:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// NIST SP 800-38A F.2.1 CBC-AES128.Encrypt, first two blocks.
+    #[test]
+    fn reference_cbc_known_answer() {
+        const KEY: [u32; 4] = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+        const IV: [u32; 4] = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+        const PLAINTEXT: [[u32; 4]; 2] = [
+            [0x6bc1bee2, 0x2e409f96, 0xe93d7e11, 0x7393172a],
+            [0xae2d8a57, 0x1e03ac9c, 0x9eb76fac, 0x45af8e51],
+        ];
+        const CIPHERTEXT: [[u32; 4]; 2] = [
+            [0x7649abac, 0x8119b246, 0xcee98e9b, 0x12e9197d],
+            [0x5086cb9b, 0x507219ee, 0x95db113a, 0x917678b2],
+        ];
+
+        let mut stub: Stub = Stub::new(SwapMode::None);
+        stub.set_key(&KEY);
+        stub.ivr = ivr_registers(&IV);
+        for (plaintext, ciphertext) in PLAINTEXT.iter().zip(CIPHERTEXT) {
+            assert_eq!(stub.cbc(*plaintext), ciphertext);
+        }
+    }
+
+    #[test]
+    fn reference_ctr() {
+        for seed in SEEDS {
+            for mode in SWAP_MODES {
+                let mut rng: XorShift = XorShift(seed);
+                let mut key: [u8; 16] = [0; 16];
+                rng.fill(&mut key);
+                let mut nonce: [u8; 12] = [0; 12];
+                rng.fill(&mut nonce);
+                // start near the end to cover the 32-bit counter wrapping
+                let counter: u32 = u32::MAX - 1;
+
+                let mut stub: Stub = Stub::new(mode);
+                stub.set_key(&block_from_bytes(&key));
+                stub.ivr = CtrIv::new(nonce, counter).ivr();
+
+                for n in 0..4 {
+                    let mut plaintext: [u8; 16] = [0; 16];
+                    rng.fill(&mut plaintext);
+
+                    let mut counter_block: [u8; 16] = [0; 16];
+                    counter_block[..12].copy_from_slice(&nonce);
+                    counter_block[12..].copy_from_slice(&counter.wrapping_add(n).to_be_bytes());
+                    let mut expected: [u8; 16] = reference::encrypt(&key, &counter_block);
+                    expected
+                        .iter_mut()
+                        .zip(plaintext)
+                        .for_each(|(b, p)| *b ^= p);
+
+                    let ciphertext: [u32; 4] = stub.ctr(words(&plaintext, mode));
+                    assert_eq!(
+                        bytes(ciphertext, mode),
+                        expected,
+                        "seed={seed:#x} mode={mode:?} block={n}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ghash_mul_identity() {
+        // the element 1 is the most significant bit in the GCM bit order
+        const ONE: u128 = 1 << 127;
+        const X: u128 = 0x66e94bd4ef8a2c3b884cfa59ca342b2e;
+        assert_eq!(Ghash::mul(X, ONE), X);
+        assert_eq!(Ghash::mul(ONE, X), X);
+        assert_eq!(Ghash::mul(X, 0), 0);
+    }
+
+    #[test]
+    fn cursor_gather_scatter() {
+        let parts: [&[u8]; 4] = [&[0, 1, 2], &[], &[3, 4, 5, 6, 7], &[8]];
+
+        let mut rd: Cursor = Cursor::default();
+        let mut block: [u8; 4] = [0xFF; 4];
+        assert_eq!(rd.read(&parts, &mut block), 4);
+        assert_eq!(block, [0, 1, 2, 3]);
+        assert_eq!(rd.read(&parts, &mut block), 4);
+        assert_eq!(block, [4, 5, 6, 7]);
+        assert_eq!(rd.read(&parts, &mut block), 1);
+        assert_eq!(block[0], 8);
+        assert_eq!(rd.read(&parts, &mut block), 0);
+
+        let mut a: [u8; 1] = [0; 1];
+        let mut b: [u8; 0] = [];
+        let mut c: [u8; 6] = [0; 6];
+        let mut out: [&mut [u8]; 3] = [&mut a, &mut b, &mut c];
+        let mut wr: Cursor = Cursor::default();
+        wr.write(&mut out, &[1, 2, 3]);
+        wr.write(&mut out, &[4, 5, 6, 7, 8]);
+        assert_eq!(a, [1]);
+        assert_eq!(c, [2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn advance_phase_waits_for_busy() {
+        use core::cell::Cell;
+
+        let busy_polls: Cell<u32> = Cell::new(3);
+        let polls: Cell<u32> = Cell::new(0);
+        let written: Cell<bool> = Cell::new(false);
+        let is_busy = || {
+            // CR must not be written while busy
+            assert!(!written.get());
+            polls.set(polls.get() + 1);
+            let busy: bool = busy_polls.get() != 0;
+            busy_polls.set(busy_polls.get().saturating_sub(1));
+            busy
+        };
+        assert_eq!(advance_phase(is_busy, || written.set(true)), Ok(()));
+        assert!(written.get());
+        assert_eq!(polls.get(), 4);
+
+        assert_eq!(
+            advance_phase(|| true, || panic!("CR written while busy")),
+            Err(Error::Timeout)
+        );
+    }
+
+    #[test]
+    fn keyed_debug_redacts_key() {
+        const KEY: [u32; 8] = [
+            0x603D_EB10,
+            0x15CA_71BE,
+            0x2B73_AEF0,
+            0x857D_7781,
+            0x1F35_2C07,
+            0x3B61_08D7,
+            0x2D98_10A3,
+            0x0914_DFF4,
+        ];
+        // safety: the peripheral registers are never accessed
+        let mut aes: Aes = unsafe { Aes::new_no_init(crate::pac::Peripherals::steal().AES) };
+        let keyed: KeyedAes = KeyedAes {
+            aes: &mut aes,
+            key: KEY,
+            keysize: KeySize::Bits256,
+        };
+
+        let debug: String = format!("{keyed:?}");
+        assert!(debug.contains("key: <redacted>"), "{debug}");
+        assert!(debug.contains("keysize: Bits256"), "{debug}");
+        KEY.iter().for_each(|word| {
+            assert!(!debug.contains(&format!("{word}")), "{debug}");
+            assert!(!debug.contains(&format!("{word:x}")), "{debug}");
+            assert!(!debug.contains(&format!("{word:X}")), "{debug}");
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn keyed_zeroize_on_drop() {
+        // safety: the peripheral registers are never accessed
+        let mut aes: Aes = unsafe { Aes::new_no_init(crate::pac::Peripherals::steal().AES) };
+        let mut keyed: core::mem::ManuallyDrop<KeyedAes> = core::mem::ManuallyDrop::new(KeyedAes {
+            aes: &mut aes,
+            key: [0xFFFF_FFFF; 8],
+            keysize: KeySize::Bits256,
+        });
+
+        // safety: the fields have no drop glue and remain in place after the
+        // drop, the key is only read afterwards
+        unsafe { core::mem::ManuallyDrop::drop(&mut keyed) };
+        assert_eq!(keyed.key, [0; 8]);
+    }
+
+    #[test]
+    fn assert_clock_enabled() {
+        assert_clock(true)
+    }
+
+    #[test]
+    #[should_panic(expected = "AES clock is not enabled")]
+    fn assert_clock_disabled() {
+        assert_clock(false)
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "AES clock is not enabled")]
+    fn debug_assert_clock_disabled() {
+        debug_assert_clock_with(|| false)
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn debug_assert_clock_release() {
+        debug_assert_clock_with(|| unreachable!())
+    }
+
+    #[test]
+    fn tag_len_gcm() {
+        let valid: Vec<u8> = (0..=u8::MAX)
+            .filter(|&n| TagLen::gcm(n).is_some())
+            .collect();
+        assert_eq!(valid, [4, 8, 12, 13, 14, 15, 16]);
+        valid
+            .iter()
+            .for_each(|&n| assert_eq!(TagLen::gcm(n).map(u8::from), Some(n)));
+    }
+
+    #[test]
+    fn tag_len_ccm() {
+        let valid: Vec<u8> = (0..=u8::MAX)
+            .filter(|&n| TagLen::ccm(n).is_some())
+            .collect();
+        assert_eq!(valid, [4, 6, 8, 10, 12, 14, 16]);
+        valid
+            .iter()
+            .for_each(|&n| assert_eq!(TagLen::ccm(n).map(u8::from), Some(n)));
+    }
+
+    #[test]
+    fn tag_len_verify() {
+        const TAG: [u32; 4] = [0x00112233, 0x44556677, 0x8899aabb, 0xccddeeff];
+        let bytes: [u8; 16] = block_to_bytes(&TAG);
+
+        assert!(TagLen::FULL.verify(&TAG, &bytes));
+        let len: TagLen = TagLen::gcm(12).unwrap();
+        assert!(len.verify(&TAG, &bytes[..12]));
+        assert!(!len.verify(&TAG, &bytes));
+        assert!(!len.verify(&TAG, &bytes[..8]));
+        assert!(!len.verify(&TAG, &bytes[4..]));
+
+        let mut tampered: [u8; 16] = bytes;
+        tampered[11] ^= 1;
+        assert!(!len.verify(&TAG, &tampered[..12]));
+        // bytes past the truncated length are not compared
+        let len: TagLen = TagLen::ccm(6).unwrap();
+        assert!(len.verify(&TAG, &tampered[..6]));
+    }
+
+    #[test]
+    fn self_test_stub() {
+        assert_eq!(
+            self_test_with(|key, pt| {
+                let mut stub: Stub = Stub::new(SwapMode::None);
+                stub.set_key(key);
+                Ok(stub.ecb(*pt))
+            }),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn self_test_key_order() {
+        // the 128-bit key is loaded correctly, the 256-bit key is loaded
+        // in the reverse register order
+        assert_eq!(
+            self_test_with(|key, pt| {
+                let mut stub: Stub = Stub::new(SwapMode::None);
+                if key.len() == 8 {
+                    let mut reversed: [u32; 8] = [0; 8];
+                    reversed.copy_from_slice(key);
+                    reversed.reverse();
+                    stub.set_key(&reversed);
+                } else {
+                    stub.set_key(key);
+                }
+                Ok(stub.ecb(*pt))
+            }),
+            Err(Error::SelfTest)
+        );
+    }
+
+    #[test]
+    fn self_test_error() {
+        assert_eq!(self_test_with(|_, _| Err(Error::Busy)), Err(Error::Busy));
     }
 }
@@ -0,0 +1,401 @@
+//! Cyclic redundancy check calculation unit
+//!
+//! The CRC peripheral computes a CRC with a programmable polynomial, initial
+//! value, and input/output reflection.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use stm32wlxx_hal::{crc::Crc, pac};
+//!
+//! let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+//!
+//! let mut crc: Crc = Crc::new_crc32(dp.CRC, &mut dp.RCC);
+//! assert_eq!(crc.feed(b"123456789"), 0xCBF4_3926);
+//! ```
+
+use crate::{
+    pac,
+    rcc::{self, Peripheral},
+};
+
+pub use pac::crc::cr::POLYSIZE_A as PolySize;
+
+/// Number of bits in a CRC of the given polynomial size.
+const fn width(size: PolySize) -> u8 {
+    match size {
+        PolySize::Polysize32 => 32,
+        PolySize::Polysize16 => 16,
+        PolySize::Polysize8 => 8,
+        PolySize::Polysize7 => 7,
+    }
+}
+
+/// Mask of the valid bits for a CRC of the given polynomial size.
+const fn mask(size: PolySize) -> u32 {
+    u32::MAX >> (32 - width(size))
+}
+
+/// Reflect the lower `width` bits of `val`.
+const fn reflect(val: u32, width: u8) -> u32 {
+    val.reverse_bits() >> (32 - width)
+}
+
+/// CRC polynomial.
+///
+/// The polynomial is in normal (MSB-first) representation, with the implicit
+/// highest order coefficient omitted.
+/// For example the CRC-32 polynomial is `0x04C1_1DB7`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Poly {
+    size: PolySize,
+    poly: u32,
+}
+
+impl Poly {
+    /// CRC-32 polynomial, `0x04C1_1DB7`.
+    pub const CRC32: Poly = Poly {
+        size: PolySize::Polysize32,
+        poly: 0x04C1_1DB7,
+    };
+
+    /// CRC-16/CCITT polynomial, `0x1021`.
+    pub const CRC16_CCITT: Poly = Poly {
+        size: PolySize::Polysize16,
+        poly: 0x1021,
+    };
+
+    /// Create a new polynomial.
+    ///
+    /// Returns `None` if the polynomial is even, or has bits set outside of
+    /// the polynomial size.
+    /// Even polynomials are not supported by the hardware.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::crc::{Poly, PolySize};
+    ///
+    /// assert_eq!(
+    ///     Poly::new(PolySize::Polysize32, 0x04C1_1DB7),
+    ///     Some(Poly::CRC32)
+    /// );
+    /// assert_eq!(
+    ///     Poly::new(PolySize::Polysize16, 0x1021),
+    ///     Some(Poly::CRC16_CCITT)
+    /// );
+    /// assert!(Poly::new(PolySize::Polysize8, 0x07).is_some());
+    /// assert_eq!(Poly::new(PolySize::Polysize8, 0x06), None);
+    /// assert_eq!(Poly::new(PolySize::Polysize7, 0x89), None);
+    /// ```
+    pub const fn new(size: PolySize, poly: u32) -> Option<Poly> {
+        if poly & 1 == 0 || poly & !mask(size) != 0 {
+            None
+        } else {
+            Some(Poly { size, poly })
+        }
+    }
+
+    /// Get the polynomial size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::crc::{Poly, PolySize};
+    ///
+    /// assert_eq!(Poly::CRC32.size(), PolySize::Polysize32);
+    /// assert_eq!(Poly::CRC16_CCITT.size(), PolySize::Polysize16);
+    /// ```
+    #[inline]
+    pub const fn size(&self) -> PolySize {
+        self.size
+    }
+
+    /// Get the polynomial value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::crc::Poly;
+    ///
+    /// assert_eq!(Poly::CRC32.poly(), 0x04C1_1DB7);
+    /// assert_eq!(Poly::CRC16_CCITT.poly(), 0x1021);
+    /// ```
+    #[inline]
+    pub const fn poly(&self) -> u32 {
+        self.poly
+    }
+}
+
+/// CRC driver.
+#[derive(Debug)]
+pub struct Crc {
+    crc: pac::CRC,
+    size: PolySize,
+    refout: bool,
+    xorout: u32,
+}
+
+impl Crc {
+    /// Create a new CRC driver from a CRC peripheral.
+    ///
+    /// This will enable clocks, reset the CRC peripheral, and configure the
+    /// CRC with the [Rocksoft model] parameters:
+    ///
+    /// * `poly`: Polynomial.
+    /// * `init`: Initial value of the CRC register.
+    /// * `refin`: Reflect each input byte.
+    /// * `refout`: Reflect the result before the final XOR.
+    /// * `xorout`: Value XORed with the result.
+    ///
+    /// [Rocksoft model]: https://reveng.sourceforge.io/crc-catalogue/
+    ///
+    /// # Panics
+    ///
+    /// * (debug) `init` or `xorout` have bits set outside of the polynomial
+    ///   size.
+    ///
+    /// # Example
+    ///
+    /// CRC-16/KERMIT
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     crc::{Crc, Poly},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let mut crc: Crc = Crc::new(dp.CRC, Poly::CRC16_CCITT, 0, true, true, 0, &mut dp.RCC);
+    /// assert_eq!(crc.feed(b"123456789"), 0x2189);
+    /// ```
+    pub fn new(
+        crc: pac::CRC,
+        poly: Poly,
+        init: u32,
+        refin: bool,
+        refout: bool,
+        xorout: u32,
+        rcc: &mut pac::RCC,
+    ) -> Crc {
+        debug_assert_eq!(init & !mask(poly.size), 0);
+        debug_assert_eq!(xorout & !mask(poly.size), 0);
+
+        Self::enable_clock(rcc);
+        // safety: ownership of the peripheral was moved in, nothing else is using it
+        unsafe { rcc::reset(rcc, Peripheral::Crc) };
+
+        crc.pol.write(|w| w.pol().bits(poly.poly));
+        crc.init.write(|w| w.init().bits(init));
+
+        // Input reflection is done per byte by the hardware, independent of
+        // the access width.
+        // Output reflection is done in software because the hardware always
+        // reflects the full 32-bit word.
+        crc.cr.write(|w| {
+            if refin {
+                w.rev_in().byte();
+            } else {
+                w.rev_in().normal();
+            }
+            w.rev_out().normal();
+            w.polysize().variant(poly.size);
+            w.reset().reset()
+        });
+
+        Crc {
+            crc,
+            size: poly.size,
+            refout,
+            xorout,
+        }
+    }
+
+    /// Create a new CRC driver configured for CRC-32.
+    ///
+    /// This is the CRC used by ethernet, zip, and many others, also known as
+    /// CRC-32/ISO-HDLC.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{crc::Crc, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let mut crc: Crc = Crc::new_crc32(dp.CRC, &mut dp.RCC);
+    /// assert_eq!(crc.feed(b"123456789"), 0xCBF4_3926);
+    /// ```
+    #[inline]
+    pub fn new_crc32(crc: pac::CRC, rcc: &mut pac::RCC) -> Crc {
+        Self::new(crc, Poly::CRC32, u32::MAX, true, true, u32::MAX, rcc)
+    }
+
+    /// Create a new CRC driver configured for CRC-16/CCITT.
+    ///
+    /// This is the non-reflected variant with an initial value of `0xFFFF`,
+    /// also known as CRC-16/CCITT-FALSE or CRC-16/IBM-3740.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{crc::Crc, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let mut crc: Crc = Crc::new_crc16_ccitt(dp.CRC, &mut dp.RCC);
+    /// assert_eq!(crc.feed(b"123456789"), 0x29B1);
+    /// ```
+    #[inline]
+    pub fn new_crc16_ccitt(crc: pac::CRC, rcc: &mut pac::RCC) -> Crc {
+        Self::new(crc, Poly::CRC16_CCITT, 0xFFFF, false, false, 0, rcc)
+    }
+
+    /// Free the CRC peripheral from the driver.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{crc::Crc, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let crc: Crc = Crc::new_crc32(dp.CRC, &mut dp.RCC);
+    /// // ... use crc
+    /// let crc: pac::CRC = crc.free();
+    /// ```
+    #[inline]
+    pub fn free(self) -> pac::CRC {
+        self.crc
+    }
+
+    /// Disable the CRC clock.
+    ///
+    /// # Safety
+    ///
+    /// 1. You cannot use the CRC bus while the clock is disabled.
+    /// 2. You are responsible for re-enabling the clock before resuming use
+    ///    of the CRC.
+    /// 3. You are responsible for setting up anything that may have lost state
+    ///    while the clock was disabled.
+    #[inline]
+    pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
+        rcc::disable(rcc, Peripheral::Crc);
+    }
+
+    /// Enable the CRC clock.
+    ///
+    /// [`new`](Self::new) will enable clocks for you.
+    #[inline]
+    pub fn enable_clock(rcc: &mut pac::RCC) {
+        rcc::enable(rcc, Peripheral::Crc);
+    }
+
+    /// Reset the CRC calculation to the initial value.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{crc::Crc, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let mut crc: Crc = Crc::new_crc32(dp.CRC, &mut dp.RCC);
+    /// crc.feed(b"some data");
+    /// crc.reset();
+    /// assert_eq!(crc.feed(b"123456789"), 0xCBF4_3926);
+    /// ```
+    #[inline]
+    pub fn reset(&mut self) {
+        self.crc.cr.modify(|_, w| w.reset().reset())
+    }
+
+    /// Feed data into the CRC calculation, returning the result.
+    ///
+    /// Multiple calls to `feed` continue the same calculation, use
+    /// [`reset`](Self::reset) to start a new calculation.
+    ///
+    /// Data is written to the peripheral a word at a time, with the remaining
+    /// bytes written as a half-word and a byte.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{crc::Crc, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let mut crc: Crc = Crc::new_crc32(dp.CRC, &mut dp.RCC);
+    /// crc.feed(b"1234");
+    /// assert_eq!(crc.feed(b"56789"), 0xCBF4_3926);
+    /// ```
+    pub fn feed(&mut self, data: &[u8]) -> u32 {
+        let mut chunks = data.chunks_exact(4);
+        for chunk in chunks.by_ref() {
+            let word: u32 = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            self.crc.dr().write(|w| w.dr().bits(word));
+        }
+
+        let mut rem = chunks.remainder();
+        if rem.len() >= 2 {
+            let half_word: u16 = u16::from_be_bytes([rem[0], rem[1]]);
+            self.crc.dr16().write(|w| w.dr16().bits(half_word));
+            rem = &rem[2..];
+        }
+        if let Some(byte) = rem.first() {
+            self.crc.dr8().write(|w| w.dr8().bits(*byte));
+        }
+
+        self.result()
+    }
+
+    /// Get the result of the CRC calculation.
+    ///
+    /// This does not modify the calculation, more data can be fed after
+    /// reading the result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{crc::Crc, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let mut crc: Crc = Crc::new_crc32(dp.CRC, &mut dp.RCC);
+    /// crc.feed(b"123456789");
+    /// assert_eq!(crc.result(), 0xCBF4_3926);
+    /// ```
+    #[must_use]
+    pub fn result(&self) -> u32 {
+        let raw: u32 = self.crc.dr().read().bits() & mask(self.size);
+        let crc: u32 = if self.refout {
+            reflect(raw, width(self.size))
+        } else {
+            raw
+        };
+        crc ^ self.xorout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mask, reflect, PolySize};
+
+    #[test]
+    fn reflect_width() {
+        assert_eq!(reflect(0x0000_0001, 32), 0x8000_0000);
+        assert_eq!(reflect(0x0000_0001, 16), 0x8000);
+        assert_eq!(reflect(0x0000_0001, 8), 0x80);
+        assert_eq!(reflect(0x0000_0001, 7), 0x40);
+        assert_eq!(reflect(0x1234, 16), 0x2C48);
+    }
+
+    #[test]
+    fn mask_size() {
+        assert_eq!(mask(PolySize::Polysize32), 0xFFFF_FFFF);
+        assert_eq!(mask(PolySize::Polysize16), 0xFFFF);
+        assert_eq!(mask(PolySize::Polysize8), 0xFF);
+        assert_eq!(mask(PolySize::Polysize7), 0x7F);
+    }
+}
@@ -3,8 +3,22 @@
 use super::pac;
 
 use crate::gpio::{pins::A10, Analog};
+use crate::rcc::{self, Peripheral};
 use pac::dac::mcr::MODE1_A;
 
+/// DAC errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// DMA underrun.
+    ///
+    /// A trigger arrived before the DMA acknowledged the previous request,
+    /// the DAC output no longer updates until the channel is restarted.
+    ///
+    /// See [`Dac::recover_underrun`].
+    Underrun,
+}
+
 /// ADC modes that use the A10 output pin
 ///
 /// The DAC is connected to the A10 output pin in all these modes.
@@ -66,11 +80,25 @@ impl From<ModeChip> for MODE1_A {
     }
 }
 
+/// Mode with the same sample and hold setting, disconnected from the A10 pin.
+const fn disconnected(mode: MODE1_A) -> MODE1_A {
+    match mode {
+        MODE1_A::NormalPinBuffer
+        | MODE1_A::NormalPinChipBuffer
+        | MODE1_A::NormalPinNoBuffer
+        | MODE1_A::NormalChipNoBuffer => MODE1_A::NormalChipNoBuffer,
+        MODE1_A::ShpinBuffer
+        | MODE1_A::ShpinChipBuffer
+        | MODE1_A::ShpinNoBuffer
+        | MODE1_A::ShchipNoBuffer => MODE1_A::ShchipNoBuffer,
+    }
+}
+
 /// Digital to analog converter driver
 #[derive(Debug)]
 pub struct Dac {
     dac: pac::DAC,
-    out: Option<Analog<A10>>,
+    out: Option<(Analog<A10>, ModePin)>,
 }
 
 impl Dac {
@@ -199,7 +227,7 @@ impl Dac {
     /// Dac::enable_clock(&mut dp.RCC);
     /// ```
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr1.modify(|_, w| w.dac1en().enabled());
+        rcc::enable(rcc, Peripheral::Dac);
     }
 
     /// Disable the DAC clock.
@@ -214,7 +242,7 @@ impl Dac {
     /// 4. You are responsible for setting up anything that may have lost state
     ///    while the clock was disabled.
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr1.modify(|_, w| w.dac1en().disabled());
+        rcc::disable(rcc, Peripheral::Dac);
     }
 
     /// Reset the DAC.
@@ -231,8 +259,7 @@ impl Dac {
     ///
     /// See [`steal`](Dac::steal)
     pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb1rstr1.modify(|_, w| w.dacrst().set_bit());
-        rcc.apb1rstr1.modify(|_, w| w.dacrst().clear_bit());
+        rcc::reset(rcc, Peripheral::Dac);
     }
 
     /// Set the DAC mode with the A10 output pin.
@@ -291,7 +318,7 @@ impl Dac {
         // "For ADC, DAC and COMP, configure the desired I/O in analog mode in
         // the GPIOx_MODER register and configure the required function in the
         // ADC, DAC and COMP registers."
-        self.out = Some(a10)
+        self.out = Some((a10, mode))
     }
 
     /// Set the DAC mode to output to on-chip peripherals.
@@ -361,7 +388,7 @@ impl Dac {
         debug_assert!(cr.cen1().bit_is_clear());
         debug_assert!(cr.en1().bit_is_clear());
         self.dac.mcr.write(|w| w.mode1().variant(mode.into()));
-        self.out.take()
+        self.out.take().map(|(a10, _)| a10)
     }
 
     /// Setup the DAC for use with a software trigger.
@@ -391,6 +418,77 @@ impl Dac {
         self.dac.cr.write(|w| w.en1().disabled());
     }
 
+    /// Enable the DAC channel output.
+    ///
+    /// This sets the channel enable (`DAC_CR.EN1`) without changing the
+    /// trigger configuration.
+    /// If the DAC owns the A10 pin (see [`set_mode_pin`](Self::set_mode_pin))
+    /// and the pin was disconnected by
+    /// [`disable_output`](Self::disable_output) the pin mode is restored
+    /// first.
+    ///
+    /// This does nothing if the channel is already enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{dac::Dac, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut dac: Dac = Dac::new(dp.DAC, &mut dp.RCC);
+    ///
+    /// dac.setup_soft_trigger();
+    /// dac.disable_output();
+    /// // ... another driver uses the pin
+    /// dac.enable_output();
+    /// ```
+    pub fn enable_output(&mut self) {
+        if self.dac.cr.read().en1().is_enabled() {
+            return;
+        }
+        if let Some((_, mode)) = self.out {
+            self.dac.mcr.modify(|_, w| w.mode1().variant(mode.into()));
+        }
+        self.dac.cr.modify(|_, w| w.en1().enabled());
+    }
+
+    /// Disable the DAC channel output and disconnect the A10 pin.
+    ///
+    /// Disabling the channel (`DAC_CR.EN1`) only stops the DAC, the channel
+    /// mode still connects the A10 pin to the DAC output stage.
+    /// This method also switches the channel to the on-chip peripheral mode
+    /// with the same sample and hold setting, this opens the switch to the
+    /// pin so that the pin is high-impedance and can be shared or parked.
+    /// [`disable`](Self::disable) only disables the channel.
+    ///
+    /// The trigger configuration is kept, and the DAC keeps ownership of A10,
+    /// [`enable_output`](Self::enable_output) reconnects the pin.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     dac::{Dac, ModePin},
+    ///     gpio::{Analog, PortA},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut dac: Dac = Dac::new(dp.DAC, &mut dp.RCC);
+    /// let mut gpioa: PortA = PortA::split(dp.GPIOA, &mut dp.RCC);
+    ///
+    /// cortex_m::interrupt::free(|cs| dac.set_mode_pin(Analog::new(gpioa.a10, cs), ModePin::NormBuf));
+    /// dac.setup_soft_trigger();
+    /// dac.disable_output(); // A10 is high-impedance
+    /// ```
+    pub fn disable_output(&mut self) {
+        self.dac.cr.modify(|_, w| w.en1().disabled());
+        let mode: MODE1_A = self.dac.mcr.read().mode1().variant();
+        self.dac
+            .mcr
+            .modify(|_, w| w.mode1().variant(disconnected(mode)));
+    }
+
     /// Set the value of the DAC output.
     ///
     /// The DAC should be setup for use with a software trigger with
@@ -421,6 +519,108 @@ impl Dac {
         self.dac.swtrgr.write(|w| w.swtrig1().trigger());
     }
 
+    /// Returns `true` if the DMA underrun flag is set.
+    ///
+    /// The DMA underrun flag is set when a trigger arrives before the DMA
+    /// acknowledged the previous request.
+    /// The DAC ignores further DMA requests until the channel is restarted,
+    /// see [`recover_underrun`](Self::recover_underrun).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{dac::Dac, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let dac: Dac = Dac::new(dp.DAC, &mut dp.RCC);
+    /// assert!(!dac.is_underrun());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_underrun(&self) -> bool {
+        self.dac.sr.read().dmaudr1().is_error()
+    }
+
+    /// Clear the DMA underrun flag.
+    ///
+    /// This only clears the flag, it does not restart the DMA requests.
+    /// Use [`recover_underrun`](Self::recover_underrun) to restart the
+    /// channel.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{dac::Dac, pac};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut dac: Dac = Dac::new(dp.DAC, &mut dp.RCC);
+    /// dac.clear_underrun();
+    /// assert!(!dac.is_underrun());
+    /// ```
+    #[inline]
+    pub fn clear_underrun(&mut self) {
+        // the other bits in this register are read-only
+        self.dac.sr.write(|w| w.dmaudr1().set_bit())
+    }
+
+    /// Check the DMA underrun flag.
+    ///
+    /// Returns [`Error::Underrun`] if the DMA underrun flag is set,
+    /// the flag is not cleared.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     dac::{Dac, Error},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut dac: Dac = Dac::new(dp.DAC, &mut dp.RCC);
+    ///
+    /// // ... setup a DMA transfer to the DAC
+    ///
+    /// if let Err(Error::Underrun) = dac.check_underrun() {
+    ///     // ... stop and reconfigure the DMA channel
+    ///     dac.recover_underrun();
+    /// }
+    /// ```
+    pub fn check_underrun(&self) -> Result<(), Error> {
+        if self.is_underrun() {
+            Err(Error::Underrun)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Recover from a DMA underrun.
+    ///
+    /// This follows the DMA underrun procedure in RM0453 rev 2 section 19
+    /// "Digital-to-analog converter (DAC)":
+    ///
+    /// 1. Disable the DAC channel and DAC DMA requests.
+    /// 2. Clear the DMA underrun flag.
+    /// 3. Restore the previous channel configuration, re-enabling the
+    ///    channel and DMA requests if they were enabled.
+    ///
+    /// The DMA channel feeding the DAC should be stopped and re-initialized
+    /// before calling this method, otherwise the restarted DAC may underrun
+    /// again immediately.
+    ///
+    /// # Example
+    ///
+    /// See [`check_underrun`](Self::check_underrun).
+    pub fn recover_underrun(&mut self) {
+        let cr: u32 = self.dac.cr.read().bits();
+        self.dac
+            .cr
+            .modify(|_, w| w.en1().disabled().dmaen1().disabled());
+        self.clear_underrun();
+        // safety: restoring a previously valid configuration
+        self.dac.cr.write(|w| unsafe { w.bits(cr) });
+    }
+
     /// Get the current DAC output.
     ///
     /// **Note:** Only the lower 12 bits of the return value are used.
@@ -501,3 +701,26 @@ impl Dac {
         self.dac.shrr.write(|w| w.trefresh1().bits(cycles))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{disconnected, ModeChip, ModePin, MODE1_A};
+
+    #[test]
+    fn disconnected_modes() {
+        for mode in [ModePin::NormBuf, ModePin::NormChipBuf, ModePin::NormNoBuf] {
+            assert_eq!(disconnected(mode.into()), ModeChip::Norm.into());
+        }
+        for mode in [
+            ModePin::SampleHoldBuf,
+            ModePin::SampleHoldChipBuf,
+            ModePin::SampleHoldNoBuf,
+        ] {
+            assert_eq!(disconnected(mode.into()), ModeChip::SampleHold.into());
+        }
+        for mode in [ModeChip::Norm, ModeChip::SampleHold] {
+            let mode: MODE1_A = mode.into();
+            assert_eq!(disconnected(mode), mode);
+        }
+    }
+}
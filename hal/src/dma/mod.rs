@@ -15,7 +15,10 @@
 
 mod cr;
 
-use core::ops::Mul;
+use core::{
+    ops::Mul,
+    sync::atomic::{compiler_fence, Ordering},
+};
 
 use super::pac;
 
@@ -48,6 +51,9 @@ const MUX_CCFR_ADDR: usize = MUX_BASE + 0x84;
 pub enum Error {
     /// Transfer error.
     Xfer,
+    /// The source and destination lengths differ, or the length exceeds the
+    /// maximum number of data items in a transfer, `u16::MAX`.
+    Length,
 }
 
 #[derive(Debug)]
@@ -222,6 +228,82 @@ pub trait DmaCh: sealed::DmaOps {
     fn mask_irq(&self) {
         pac::NVIC::mask(Self::IRQ)
     }
+
+    /// Copy `src` to `dst` with a memory-to-memory transfer.
+    ///
+    /// This blocks until the transfer is complete.
+    ///
+    /// The channel is configured for 32-bit transfers with incrementing source
+    /// and destination addresses, and is disabled after the transfer.
+    /// The DMA MUX request is set to `0` (no request), memory-to-memory
+    /// transfers are not triggered by a peripheral.
+    ///
+    /// Returns [`Error::Length`] if the lengths of `src` and `dst` differ,
+    /// or if the length is greater than `u16::MAX`.
+    /// Returns [`Error::Xfer`] on a transfer error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     dma::{AllDma, DmaCh},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut dma: AllDma = AllDma::split(dp.DMAMUX, dp.DMA1, dp.DMA2, &mut dp.RCC);
+    ///
+    /// let src: [u32; 64] = [0xDEADBEEF; 64];
+    /// let mut dst: [u32; 64] = [0; 64];
+    /// dma.d1.c1.mem_to_mem(&src, &mut dst)?;
+    /// assert_eq!(src, dst);
+    /// # Ok::<(), stm32wlxx_hal::dma::Error>(())
+    /// ```
+    fn mem_to_mem(&mut self, src: &[u32], dst: &mut [u32]) -> Result<(), Error> {
+        if src.len() != dst.len() || src.len() > usize::from(u16::MAX) {
+            return Err(Error::Length);
+        }
+        if src.is_empty() {
+            return Ok(());
+        }
+
+        // in memory-to-memory mode with DIR set the memory address is the
+        // source and the peripheral address is the destination
+        const CR: Cr = Cr::RESET
+            .set_mem2mem(true)
+            .set_dir_from_mem()
+            .set_mem_size(Size::Bits32)
+            .set_periph_size(Size::Bits32)
+            .set_mem_inc(true)
+            .set_periph_inc(true)
+            .set_enable(true);
+
+        self.set_cr(Cr::DISABLE);
+        self.clear_all_flags();
+        self.set_mux_cr_reqid(0);
+        self.set_mem_addr(src.as_ptr() as u32);
+        self.set_periph_addr(dst.as_mut_ptr() as u32);
+        self.set_num_data_xfer(src.len() as u32);
+
+        compiler_fence(Ordering::Release);
+        self.set_cr(CR);
+
+        let ret: Result<(), Error> = loop {
+            let flags: u8 = self.flags();
+            if flags & flags::XFER_ERR != 0 {
+                break Err(Error::Xfer);
+            }
+            if flags & flags::XFER_CPL != 0 {
+                break Ok(());
+            }
+        };
+
+        self.set_cr(Cr::DISABLE);
+        self.clear_all_flags();
+        compiler_fence(Ordering::Acquire);
+
+        ret
+    }
 }
 
 macro_rules! dma_ch {
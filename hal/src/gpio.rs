@@ -1,8 +1,20 @@
 //! General purpose input-output pins
 
-use crate::{adc, pac};
+use crate::{
+    adc, pac,
+    rcc::{self, Peripheral},
+};
 use cortex_m::interrupt::CriticalSection;
 
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+use core::{
+    cell::RefCell,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin as FuturePin,
+    task::{Context, Poll, Waker},
+};
+
 pub use embedded_hal::digital::v2::PinState;
 
 /// EXTI triggers.
@@ -63,6 +75,32 @@ pub enum Pull {
     Down = 0b10,
 }
 
+/// GPIO errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The pin is in analog mode.
+    ///
+    /// Analog mode disables the pull-up and pull-down resistors.
+    Analog,
+}
+
+/// Replace the 2-bit field for pin `n` in a `MODER`, `OSPEEDR`, or `PUPDR`
+/// register value.
+const fn set_field2(reg: u32, n: u8, bits: u8) -> u32 {
+    (reg & !(0b11 << (n * 2))) | ((bits as u32 & 0b11) << (n * 2))
+}
+
+/// Decode the mode of pin `n` from a `GPIOx_MODER` value.
+pub(crate) const fn moder_mode(moder: u32, n: u8) -> sealed::Mode {
+    match (moder >> (n * 2)) & 0b11 {
+        0b00 => sealed::Mode::Input,
+        0b01 => sealed::Mode::Output,
+        0b10 => sealed::Mode::Alternate,
+        _ => sealed::Mode::Analog,
+    }
+}
+
 const GPIOA_BASE: usize = 0x4800_0000;
 const GPIOB_BASE: usize = 0x4800_0400;
 const GPIOC_BASE: usize = 0x4800_0800;
@@ -103,10 +141,13 @@ impl<const BASE: usize, const N: u8> Pin<BASE, N> {
 
     #[inline(always)]
     pub(crate) fn set_mode(&mut self, _cs: &CriticalSection, mode: sealed::Mode) {
-        let mut val: u32 = unsafe { Self::MODER_R.read_volatile() };
-        val &= !(0b11 << (N * 2));
-        val |= (mode as u8 as u32) << (N * 2);
-        unsafe { Self::MODER_W.write_volatile(val) };
+        let val: u32 = unsafe { Self::MODER_R.read_volatile() };
+        unsafe { Self::MODER_W.write_volatile(set_field2(val, N, mode as u8)) };
+    }
+
+    #[inline(always)]
+    pub(crate) fn mode(&self) -> sealed::Mode {
+        moder_mode(unsafe { Self::MODER_R.read_volatile() }, N)
     }
 
     #[inline(always)]
@@ -121,18 +162,14 @@ impl<const BASE: usize, const N: u8> Pin<BASE, N> {
 
     #[inline(always)]
     pub(crate) fn set_speed(&mut self, _cs: &CriticalSection, speed: Speed) {
-        let mut val: u32 = unsafe { Self::OSPEEDR_R.read_volatile() };
-        val &= !(0b11 << (N * 2));
-        val |= (speed as u8 as u32) << (N * 2);
-        unsafe { Self::OSPEEDR_W.write_volatile(val) };
+        let val: u32 = unsafe { Self::OSPEEDR_R.read_volatile() };
+        unsafe { Self::OSPEEDR_W.write_volatile(set_field2(val, N, speed as u8)) };
     }
 
     #[inline(always)]
     pub(crate) fn set_pull(&mut self, _cs: &CriticalSection, pull: Pull) {
-        let mut val: u32 = unsafe { Self::PUPDR_R.read_volatile() };
-        val &= !(0b11 << (N * 2));
-        val |= (pull as u8 as u32) << (N * 2);
-        unsafe { Self::PUPDR_W.write_volatile(val) };
+        let val: u32 = unsafe { Self::PUPDR_R.read_volatile() };
+        unsafe { Self::PUPDR_W.write_volatile(set_field2(val, N, pull as u8)) };
     }
 
     #[inline(always)]
@@ -177,6 +214,7 @@ pub(crate) mod sealed {
 
     /// GPIO modes.
     #[repr(u8)]
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
     pub enum Mode {
         Input = 0b00,
         Output = 0b01,
@@ -188,8 +226,12 @@ pub(crate) mod sealed {
     /// Pin structures can implement it in a light wrapper without putting a ton
     /// of code into the macro which will result in longer compile times.
     pub trait PinOps {
+        /// Pin number, also the EXTI line number.
+        #[allow(dead_code)]
+        const N: u8;
         unsafe fn steal() -> Self;
         fn set_mode(&mut self, cs: &CriticalSection, mode: Mode);
+        fn mode(&self) -> Mode;
         fn set_output_type(&mut self, cs: &CriticalSection, ot: OutputType);
         fn set_speed(&mut self, cs: &CriticalSection, speed: Speed);
         fn set_pull(&mut self, cs: &CriticalSection, pull: Pull);
@@ -266,6 +308,10 @@ pub(crate) mod sealed {
     af_trait!(Tim17Ch1, set_tim17_ch1_af);
     af_trait!(Tim17Bkin, set_tim17_bkin_af);
     af_trait!(Tim17Ch1n, set_tim17_ch1n_af);
+    af_trait!(Tim2Ch1, set_tim2_ch1_af);
+    af_trait!(Tim2Ch2, set_tim2_ch2_af);
+    af_trait!(Tim2Ch3, set_tim2_ch3_af);
+    af_trait!(Tim2Ch4, set_tim2_ch4_af);
     af_trait!(Lsco, set_lsco_af);
 
     /// Indicate a GPIO pin can be sampled by the ADC.
@@ -464,6 +510,103 @@ pub trait Exti {
     }
 }
 
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+const NO_WAKER: Option<Waker> = None;
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+static EXTI_WAKERS: cortex_m::interrupt::Mutex<RefCell<[Option<Waker>; 16]>> =
+    cortex_m::interrupt::Mutex::new(RefCell::new([NO_WAKER; 16]));
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+fn set_exti_c1_mask(line: u8, unmask: bool) {
+    // safety: read-modify-write in a critical section
+    cortex_m::interrupt::free(|_| unsafe {
+        (*pac::EXTI::PTR).c1imr1.modify(|r, w| {
+            if unmask {
+                w.bits(r.bits() | 1 << line)
+            } else {
+                w.bits(r.bits() & !(1 << line))
+            }
+        })
+    })
+}
+
+/// EXTI interrupt handler for async operations.
+///
+/// This must be called from the EXTI interrupt handlers for
+/// [`Input::wait_for_rising_edge`] and [`Input::wait_for_falling_edge`] to
+/// make progress.
+///
+/// EXTI lines 5-9 and 10-15 share an interrupt, this handles every line with
+/// a waiting task, call it from every EXTI interrupt handler used for async
+/// operations.
+///
+/// This masks the lines that triggered and wakes the waiting tasks, the
+/// pending bits are left for the tasks to clear.
+///
+/// # Example
+///
+/// ```no_run
+/// // call this in the EXTI interrupt handlers
+/// stm32wlxx_hal::gpio::on_exti_interrupt();
+/// ```
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+pub fn on_exti_interrupt() {
+    // safety: atomic read with no side effects
+    let pr: u32 = unsafe { (*pac::EXTI::PTR).pr1.read().bits() };
+    cortex_m::interrupt::free(|cs| {
+        let mut wakers = EXTI_WAKERS.borrow(cs).borrow_mut();
+        for (line, waker) in wakers.iter_mut().enumerate() {
+            if pr & 1 << line != 0 {
+                if let Some(waker) = waker.take() {
+                    set_exti_c1_mask(line as u8, false);
+                    waker.wake()
+                }
+            }
+        }
+    })
+}
+
+/// Future that completes when an EXTI line triggers.
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+struct ExtiEdge<'a, P: sealed::PinOps + Exti> {
+    _pin: PhantomData<&'a mut P>,
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a, P: sealed::PinOps + Exti> Future for ExtiEdge<'a, P> {
+    type Output = ();
+
+    fn poll(self: FuturePin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        crate::util::poll_flag(
+            P::is_pending,
+            P::clear_exti,
+            || {
+                cortex_m::interrupt::free(|cs| {
+                    EXTI_WAKERS.borrow(cs).borrow_mut()[usize::from(P::N)] =
+                        Some(cx.waker().clone())
+                });
+                set_exti_c1_mask(P::N, true);
+            },
+            || set_exti_c1_mask(P::N, false),
+        )
+    }
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<'a, P: sealed::PinOps + Exti> Drop for ExtiEdge<'a, P> {
+    fn drop(&mut self) {
+        set_exti_c1_mask(P::N, false);
+        cortex_m::interrupt::free(|cs| {
+            // safety: read-modify-write in a critical section
+            let mut exti: pac::EXTI = unsafe { pac::Peripherals::steal() }.EXTI;
+            P::set_rising_trigger(&mut exti, false);
+            P::set_falling_trigger(&mut exti, false);
+            EXTI_WAKERS.borrow(cs).borrow_mut()[usize::from(P::N)].take()
+        });
+    }
+}
+
 /// GPIO pins
 pub mod pins {
     use super::{
@@ -487,6 +630,8 @@ pub mod pins {
             }
 
             impl super::sealed::PinOps for $name {
+                const N: u8 = $n;
+
                 #[inline(always)]
                 unsafe fn steal() -> Self {
                     Self::new()
@@ -497,6 +642,11 @@ pub mod pins {
                     self.pin.set_mode(cs, mode)
                 }
 
+                #[inline(always)]
+                fn mode(&self) -> super::sealed::Mode {
+                    self.pin.mode()
+                }
+
                 #[inline(always)]
                 fn set_output_type(&mut self, cs: &CriticalSection, ot: OutputType) {
                     self.pin.set_output_type(cs, ot)
@@ -608,6 +758,16 @@ pub mod pins {
     impl_af!(LpTim1In2, C2, set_lptim1_in2_af, 1);
     impl_af!(LpTim1Etr, C3, set_lptim1_etr_af, 1);
 
+    impl_af!(Tim2Ch1, A0, set_tim2_ch1_af, 1);
+    impl_af!(Tim2Ch2, A1, set_tim2_ch2_af, 1);
+    impl_af!(Tim2Ch3, A2, set_tim2_ch3_af, 1);
+    impl_af!(Tim2Ch4, A3, set_tim2_ch4_af, 1);
+    impl_af!(Tim2Ch1, A5, set_tim2_ch1_af, 1);
+    impl_af!(Tim2Ch1, A15, set_tim2_ch1_af, 1);
+    impl_af!(Tim2Ch2, B3, set_tim2_ch2_af, 1);
+    impl_af!(Tim2Ch3, B10, set_tim2_ch3_af, 1);
+    impl_af!(Tim2Ch4, B11, set_tim2_ch4_af, 1);
+
     impl_af!(LpTim3Out, A1, set_lptim3_out_af, 3);
     impl_af!(Spi2Miso, A5, set_spi2_miso_af, 3, SpiMiso);
     impl_af!(Spi2Nss, A9, set_spi2_nss_af, 3, SpiNss);
@@ -910,8 +1070,8 @@ impl PortA {
     #[inline]
     pub fn split(gpioa: pac::GPIOA, rcc: &mut pac::RCC) -> Self {
         Self::enable_clock(rcc);
-        rcc.ahb2rstr.modify(|_, w| w.gpioarst().set_bit());
-        rcc.ahb2rstr.modify(|_, w| w.gpioarst().clear_bit());
+        // safety: ownership of the peripheral was moved in, nothing else is using it
+        unsafe { rcc::reset(rcc, Peripheral::GpioA) };
 
         Self::GPIOS
     }
@@ -954,14 +1114,13 @@ impl PortA {
     ///    of any port A GPIO.
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb2enr.modify(|_, w| w.gpioaen().disabled());
+        rcc::disable(rcc, Peripheral::GpioA);
     }
 
     /// Enable the GPIOA clock.
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb2enr.modify(|_, w| w.gpioaen().enabled());
-        rcc.ahb2enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::GpioA);
     }
 
     /// Set the IO port clock enable during sleep mode.
@@ -1054,12 +1213,140 @@ impl PortB {
     #[inline]
     pub fn split(gpiob: pac::GPIOB, rcc: &mut pac::RCC) -> Self {
         Self::enable_clock(rcc);
-        rcc.ahb2rstr.modify(|_, w| w.gpiobrst().set_bit());
-        rcc.ahb2rstr.modify(|_, w| w.gpiobrst().clear_bit());
+        // safety: ownership of the peripheral was moved in, nothing else is using it
+        unsafe { rcc::reset(rcc, Peripheral::GpioB) };
 
         Self::GPIOS
     }
 
+    /// Reset GPIO port B and configure every pin in one step.
+    ///
+    /// This is [`split`] followed by [`PinCfg::configure`] for each field of
+    /// `cfg`.
+    /// The type of each field selects the type of the returned pin:
+    ///
+    /// | `cfg` field   | Returned pin    |
+    /// |---------------|-----------------|
+    /// | `()`          | `pins::Bn`      |
+    /// | [`Pull`]       | [`Input`]       |
+    /// | [`OutputArgs`] | [`Output`]      |
+    /// | [`AnalogArgs`] | [`Analog`]      |
+    ///
+    /// # Ownership
+    ///
+    /// The GPIOB peripheral is consumed, exactly like [`split`].
+    /// Each pin is moved into the field of the returned struct with the same
+    /// name; pins configured with `()` are returned unwrapped and can be
+    /// passed to other drivers or configured later.
+    /// A configured pin can be returned to its unwrapped type with the `free`
+    /// method of its wrapper.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     gpio::{pins, Analog, AnalogArgs, Input, Output, OutputArgs, PortB, PortBCfg, Pull},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpiob = cortex_m::interrupt::free(|cs| {
+    ///     PortB::configure(
+    ///         dp.GPIOB,
+    ///         &mut dp.RCC,
+    ///         PortBCfg {
+    ///             b0: (),
+    ///             b1: (),
+    ///             b2: (),
+    ///             b3: (),
+    ///             b4: (),
+    ///             b5: (),
+    ///             b6: (),
+    ///             b7: (),
+    ///             b8: (),
+    ///             b9: (),
+    ///             b10: (),
+    ///             b11: Pull::Up,
+    ///             b12: OutputArgs::new(),
+    ///             b13: AnalogArgs,
+    ///             b14: Pull::Down,
+    ///             b15: (),
+    ///         },
+    ///         cs,
+    ///     )
+    /// });
+    ///
+    /// let b11: Input<pins::B11> = gpiob.b11;
+    /// let b12: Output<pins::B12> = gpiob.b12;
+    /// let b13: Analog<pins::B13> = gpiob.b13;
+    /// let b15: pins::B15 = gpiob.b15;
+    /// ```
+    ///
+    /// [`split`]: crate::gpio::PortB::split
+    #[allow(clippy::type_complexity)]
+    pub fn configure<B0, B1, B2, B3, B4, B5, B6, B7, B8, B9, B10, B11, B12, B13, B14, B15>(
+        gpiob: pac::GPIOB,
+        rcc: &mut pac::RCC,
+        cfg: PortBCfg<B0, B1, B2, B3, B4, B5, B6, B7, B8, B9, B10, B11, B12, B13, B14, B15>,
+        cs: &CriticalSection,
+    ) -> PortBCfg<
+        B0::Pin,
+        B1::Pin,
+        B2::Pin,
+        B3::Pin,
+        B4::Pin,
+        B5::Pin,
+        B6::Pin,
+        B7::Pin,
+        B8::Pin,
+        B9::Pin,
+        B10::Pin,
+        B11::Pin,
+        B12::Pin,
+        B13::Pin,
+        B14::Pin,
+        B15::Pin,
+    >
+    where
+        B0: PinCfg<pins::B0>,
+        B1: PinCfg<pins::B1>,
+        B2: PinCfg<pins::B2>,
+        B3: PinCfg<pins::B3>,
+        B4: PinCfg<pins::B4>,
+        B5: PinCfg<pins::B5>,
+        B6: PinCfg<pins::B6>,
+        B7: PinCfg<pins::B7>,
+        B8: PinCfg<pins::B8>,
+        B9: PinCfg<pins::B9>,
+        B10: PinCfg<pins::B10>,
+        B11: PinCfg<pins::B11>,
+        B12: PinCfg<pins::B12>,
+        B13: PinCfg<pins::B13>,
+        B14: PinCfg<pins::B14>,
+        B15: PinCfg<pins::B15>,
+    {
+        let gpios: PortB = Self::split(gpiob, rcc);
+        PortBCfg {
+            b0: cfg.b0.configure(gpios.b0, cs),
+            b1: cfg.b1.configure(gpios.b1, cs),
+            b2: cfg.b2.configure(gpios.b2, cs),
+            b3: cfg.b3.configure(gpios.b3, cs),
+            b4: cfg.b4.configure(gpios.b4, cs),
+            b5: cfg.b5.configure(gpios.b5, cs),
+            b6: cfg.b6.configure(gpios.b6, cs),
+            b7: cfg.b7.configure(gpios.b7, cs),
+            b8: cfg.b8.configure(gpios.b8, cs),
+            b9: cfg.b9.configure(gpios.b9, cs),
+            b10: cfg.b10.configure(gpios.b10, cs),
+            b11: cfg.b11.configure(gpios.b11, cs),
+            b12: cfg.b12.configure(gpios.b12, cs),
+            b13: cfg.b13.configure(gpios.b13, cs),
+            b14: cfg.b14.configure(gpios.b14, cs),
+            b15: cfg.b15.configure(gpios.b15, cs),
+        }
+    }
+
     /// Steal the port B GPIOs from whatever is currently using them.
     ///
     /// This will **not** initialize the GPIOs (unlike [`split`]).
@@ -1098,14 +1385,13 @@ impl PortB {
     ///    of any port B GPIO.
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb2enr.modify(|_, w| w.gpioben().disabled());
+        rcc::disable(rcc, Peripheral::GpioB);
     }
 
     /// Enable the GPIOB clock.
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb2enr.modify(|_, w| w.gpioben().enabled());
-        rcc.ahb2enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::GpioB);
     }
 
     /// Set the IO port clock enable during sleep mode.
@@ -1132,6 +1418,31 @@ impl PortB {
     }
 }
 
+/// Per-pin configuration of port B, and the pins resulting from it.
+///
+/// Argument and return type of [`PortB::configure`].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(missing_docs)]
+pub struct PortBCfg<B0, B1, B2, B3, B4, B5, B6, B7, B8, B9, B10, B11, B12, B13, B14, B15> {
+    pub b0: B0,
+    pub b1: B1,
+    pub b2: B2,
+    pub b3: B3,
+    pub b4: B4,
+    pub b5: B5,
+    pub b6: B6,
+    pub b7: B7,
+    pub b8: B8,
+    pub b9: B9,
+    pub b10: B10,
+    pub b11: B11,
+    pub b12: B12,
+    pub b13: B13,
+    pub b14: B14,
+    pub b15: B15,
+}
+
 /// Port C GPIOs
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -1186,8 +1497,8 @@ impl PortC {
     #[inline]
     pub fn split(gpioc: pac::GPIOC, rcc: &mut pac::RCC) -> Self {
         Self::enable_clock(rcc);
-        rcc.ahb2rstr.modify(|_, w| w.gpiocrst().set_bit());
-        rcc.ahb2rstr.modify(|_, w| w.gpiocrst().clear_bit());
+        // safety: ownership of the peripheral was moved in, nothing else is using it
+        unsafe { rcc::reset(rcc, Peripheral::GpioC) };
 
         Self::GPIOS
     }
@@ -1230,14 +1541,13 @@ impl PortC {
     ///    of any port C GPIO.
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb2enr.modify(|_, w| w.gpiocen().disabled());
+        rcc::disable(rcc, Peripheral::GpioC);
     }
 
     /// Enable the GPIOC clock.
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb2enr.modify(|_, w| w.gpiocen().enabled());
-        rcc.ahb2enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::GpioC);
     }
 
     /// Set the IO port clock enable during sleep mode.
@@ -1310,6 +1620,65 @@ impl Default for OutputArgs {
     }
 }
 
+/// Analog pin arguments.
+///
+/// Selects [`Analog`] in [`PortB::configure`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AnalogArgs;
+
+/// Configuration of a single pin, used by [`PortB::configure`].
+///
+/// Implemented for:
+///
+/// * `()`: the pin is returned unchanged.
+/// * [`Pull`]: the pin is returned as an [`Input`].
+/// * [`OutputArgs`]: the pin is returned as an [`Output`].
+/// * [`AnalogArgs`]: the pin is returned as an [`Analog`].
+pub trait PinCfg<P> {
+    /// Pin type after configuration.
+    type Pin;
+
+    /// Configure `pin`.
+    fn configure(self, pin: P, cs: &CriticalSection) -> Self::Pin;
+}
+
+impl<P> PinCfg<P> for () {
+    type Pin = P;
+
+    #[inline]
+    fn configure(self, pin: P, _: &CriticalSection) -> P {
+        pin
+    }
+}
+
+impl<P: sealed::PinOps> PinCfg<P> for Pull {
+    type Pin = Input<P>;
+
+    #[inline]
+    fn configure(self, pin: P, cs: &CriticalSection) -> Input<P> {
+        Input::new(pin, self, cs)
+    }
+}
+
+impl<P: sealed::PinOps> PinCfg<P> for OutputArgs {
+    type Pin = Output<P>;
+
+    #[inline]
+    fn configure(self, pin: P, cs: &CriticalSection) -> Output<P> {
+        Output::new(pin, &self, cs)
+    }
+}
+
+impl<P: sealed::PinOps + sealed::AdcCh> PinCfg<P> for AnalogArgs {
+    type Pin = Analog<P>;
+
+    #[inline]
+    fn configure(self, pin: P, cs: &CriticalSection) -> Analog<P> {
+        Analog::new(pin, cs)
+    }
+}
+
 /// Output pin.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -1537,6 +1906,41 @@ where
     pub fn level(&self) -> PinState {
         self.pin.output_level()
     }
+
+    /// Toggle the GPIO output level.
+    ///
+    /// This reads the current output level from `ODR`, then writes the
+    /// opposite level to `BSRR`.
+    /// The read and the write are not a single atomic operation, a level set
+    /// by an interrupt between the two is overwritten.
+    ///
+    /// This is the infallible equivalent of
+    /// [`ToggleableOutputPin::toggle`](embedded_hal::digital::v2::ToggleableOutputPin::toggle).
+    ///
+    /// # Example
+    ///
+    /// Blink an LED.
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     gpio::{pins, Output, PortB},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+    /// let mut led: Output<pins::B11> = cortex_m::interrupt::free(|cs| Output::default(gpiob.b11, cs));
+    /// loop {
+    ///     led.toggle_level();
+    ///     // ... delay
+    /// }
+    /// ```
+    #[inline]
+    pub fn toggle_level(&mut self) {
+        let level: PinState = !self.pin.output_level();
+        self.pin.set_output_level(level)
+    }
 }
 
 impl<P> embedded_hal::digital::v2::OutputPin for Output<P>
@@ -1573,7 +1977,18 @@ where
     }
 }
 
-impl<P: sealed::PinOps> embedded_hal::digital::v2::toggleable::Default for Output<P> {}
+impl<P> embedded_hal::digital::v2::ToggleableOutputPin for Output<P>
+where
+    P: sealed::PinOps,
+{
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.toggle_level();
+        Ok(())
+    }
+}
 
 /// Input pin
 #[derive(Debug)]
@@ -1705,6 +2120,130 @@ where
     pub fn level(&self) -> PinState {
         self.pin.input_level()
     }
+
+    /// Set the pull-up and pull-down resistors.
+    ///
+    /// This changes the pull of a live pin without reconstructing it, for
+    /// example to reduce leakage before entering stop mode.
+    ///
+    /// Returns [`Error::Analog`] if the pin has been reconfigured to analog
+    /// mode, analog mode disables the pull-up and pull-down resistors.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     gpio::{pins, Input, PortC, Pull},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpioc: PortC = PortC::split(dp.GPIOC, &mut dp.RCC);
+    /// let mut c6: Input<pins::C6> =
+    ///     cortex_m::interrupt::free(|cs| Input::new(gpioc.c6, Pull::Up, cs));
+    ///
+    /// cortex_m::interrupt::free(|cs| c6.set_pull(Pull::Down, cs))?;
+    /// # Ok::<(), stm32wlxx_hal::gpio::Error>(())
+    /// ```
+    pub fn set_pull(&mut self, pull: Pull, cs: &CriticalSection) -> Result<(), Error> {
+        if self.pin.mode() == sealed::Mode::Analog {
+            Err(Error::Analog)
+        } else {
+            self.pin.set_pull(cs, pull);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+impl<P> Input<P>
+where
+    P: sealed::PinOps + Exti,
+{
+    async fn wait_for_edge(&mut self, trg: ExtiTrg) {
+        cortex_m::interrupt::free(|_| {
+            // safety: read-modify-write in a critical section
+            let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+            P::clear_exti();
+            P::set_port(&mut dp.SYSCFG);
+            P::set_rising_trigger(&mut dp.EXTI, trg == ExtiTrg::Rising);
+            P::set_falling_trigger(&mut dp.EXTI, trg == ExtiTrg::Falling);
+        });
+
+        ExtiEdge::<P> { _pin: PhantomData }.await
+    }
+
+    /// Wait for a rising edge on the pin.
+    ///
+    /// This arms the EXTI line of the pin, and resolves when the edge
+    /// occurs.
+    /// The EXTI line is disarmed when the future completes or is dropped.
+    ///
+    /// The edge is awaited with the EXTI interrupt,
+    /// [`on_exti_interrupt`] must be called from the EXTI interrupt handler
+    /// and the interrupt must be unmasked with [`Exti::unmask`].
+    ///
+    /// **Note:** EXTI lines are shared between ports, arming a line selects
+    /// the port of this pin for that line.
+    /// For example, arming C6 disables EXTI for A6 and B6.
+    /// Do not wait on pins with the same number at the same time.
+    ///
+    /// # Example
+    ///
+    /// Wait for button 3 on the NUCLEO-WL55JC2 to be released.
+    ///
+    /// ```no_run
+    /// # use stm32wlxx_hal::util::block_on;
+    /// use stm32wlxx_hal::{
+    ///     gpio::{pins, Exti, Input, PortC, Pull},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpioc: PortC = PortC::split(dp.GPIOC, &mut dp.RCC);
+    /// let mut c6: Input<pins::C6> =
+    ///     cortex_m::interrupt::free(|cs| Input::new(gpioc.c6, Pull::Up, cs));
+    /// unsafe { pins::C6::unmask() };
+    ///
+    /// // block_on from your executor
+    /// block_on(c6.wait_for_rising_edge());
+    /// ```
+    #[inline]
+    pub async fn wait_for_rising_edge(&mut self) {
+        self.wait_for_edge(ExtiTrg::Rising).await
+    }
+
+    /// Wait for a falling edge on the pin.
+    ///
+    /// See [`wait_for_rising_edge`](Self::wait_for_rising_edge) for details.
+    ///
+    /// # Example
+    ///
+    /// Wait for button 3 on the NUCLEO-WL55JC2 to be pressed.
+    ///
+    /// ```no_run
+    /// # use stm32wlxx_hal::util::block_on;
+    /// use stm32wlxx_hal::{
+    ///     gpio::{pins, Exti, Input, PortC, Pull},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpioc: PortC = PortC::split(dp.GPIOC, &mut dp.RCC);
+    /// let mut c6: Input<pins::C6> =
+    ///     cortex_m::interrupt::free(|cs| Input::new(gpioc.c6, Pull::Up, cs));
+    /// unsafe { pins::C6::unmask() };
+    ///
+    /// // block_on from your executor
+    /// block_on(c6.wait_for_falling_edge());
+    /// ```
+    #[inline]
+    pub async fn wait_for_falling_edge(&mut self) {
+        self.wait_for_edge(ExtiTrg::Falling).await
+    }
 }
 
 impl<P> embedded_hal::digital::v2::InputPin for Input<P>
@@ -1781,6 +2320,16 @@ where
     pub fn free(self) -> P {
         self.pin
     }
+
+    /// Returns `true` if the GPIO mode register (`GPIOx_MODER`) has the pin
+    /// in analog mode.
+    ///
+    /// This is `false` if the pin was reconfigured without the wrapper.
+    #[inline]
+    #[cfg(not(feature = "stm32wl5x_cm0p"))]
+    pub(crate) fn is_analog(&self) -> bool {
+        self.pin.mode() == sealed::Mode::Analog
+    }
 }
 
 /// RF Busy pin
@@ -2200,3 +2749,36 @@ impl SgMosiDbg {
         self.pin
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{moder_mode, sealed::Mode, set_field2, Pull};
+
+    #[test]
+    fn pupdr_encoding() {
+        assert_eq!(set_field2(0, 0, Pull::Up as u8), 0b01);
+        assert_eq!(set_field2(0, 0, Pull::Down as u8), 0b10);
+        assert_eq!(set_field2(0, 15, Pull::Up as u8), 0b01 << 30);
+        assert_eq!(set_field2(0, 15, Pull::Down as u8), 0b10 << 30);
+        assert_eq!(set_field2(u32::MAX, 3, Pull::None as u8), !(0b11 << 6));
+        assert_eq!(set_field2(0b11 << 8, 4, Pull::Up as u8), 0b01 << 8);
+        assert_eq!(set_field2(0b0101, 1, Pull::Down as u8), 0b1001);
+    }
+
+    #[test]
+    fn moder_decoding() {
+        assert_eq!(moder_mode(0, 0), Mode::Input);
+        assert_eq!(moder_mode(0b01 << 2, 1), Mode::Output);
+        assert_eq!(moder_mode(0b10 << 30, 15), Mode::Alternate);
+        assert_eq!(moder_mode(0b11 << 8, 4), Mode::Analog);
+        assert_eq!(moder_mode(0b11 << 8, 3), Mode::Input);
+        (0..16).for_each(|n| {
+            [Mode::Input, Mode::Output, Mode::Alternate, Mode::Analog]
+                .iter()
+                .for_each(|&mode| {
+                    assert_eq!(moder_mode(set_field2(u32::MAX, n, mode as u8), n), mode);
+                    assert_eq!(moder_mode(set_field2(0, n, mode as u8), n), mode);
+                })
+        });
+    }
+}
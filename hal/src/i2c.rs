@@ -1,10 +1,13 @@
 //! Inter-Integrated Circuit (I2C) bus
 
 use crate::{
-    embedded_hal::blocking::i2c::{Read, Write, WriteRead},
-    gpio::{OutputType, Pull},
+    embedded_hal::blocking::{
+        delay::DelayUs,
+        i2c::{Read, Write, WriteRead},
+    },
+    gpio::{OutputType, PinState, Pull},
     pac::{self, rcc::ccipr::I2C3SEL_A, I2C1, I2C2, I2C3, RCC},
-    rcc::{pclk1_hz, sysclk_hz},
+    rcc::{self, pclk1_hz, sysclk_hz, Peripheral},
 };
 
 use cortex_m::interrupt::CriticalSection;
@@ -28,6 +31,28 @@ pub enum Error {
      * Alert, // SMBUS mode only */
 }
 
+/// Slave mode event returned by the `slave_event` methods.
+///
+/// The peripheral stretches the clock (holds SCL low) until the event is
+/// handled, giving the application time to process each byte.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SlaveEvent {
+    /// The address set with `listen` was matched.
+    ///
+    /// The address match flag is cleared when this event is returned,
+    /// the transfer direction is indicated by the events that follow.
+    AddrMatch,
+    /// A byte was received from the master.
+    RxByte(u8),
+    /// The master is requesting a byte.
+    ///
+    /// The clock is stretched until a byte is provided with `slave_write`.
+    TxRequest,
+    /// A STOP condition was detected, the transfer is complete.
+    Stop,
+}
+
 /// I2C1 peripheral operating in master mode
 #[derive(Debug)]
 pub struct I2c1<PINS> {
@@ -49,6 +74,38 @@ pub struct I2c3<PINS> {
     pins: PINS,
 }
 
+/// Maximum number of SCL pulses generated by bus recovery.
+///
+/// A slave holding SDA low releases it after at most 8 data bits and an
+/// acknowledge bit.
+const RECOVERY_PULSES: u8 = 9;
+
+/// Half of the SCL period for bus recovery in microseconds, 100 kHz.
+const RECOVERY_HALF_PERIOD_MICROS: u8 = 5;
+
+/// Pulse SCL with `pulse_scl` until `sda_high` returns `true`, up to
+/// [`RECOVERY_PULSES`] times.
+///
+/// Returns the number of pulses generated, or [`Error::Busy`] if SDA is
+/// still low after the final pulse.
+fn clock_until_sda_released<S, P>(mut sda_high: S, mut pulse_scl: P) -> Result<u8, Error>
+where
+    S: FnMut() -> bool,
+    P: FnMut(),
+{
+    for pulses in 0..RECOVERY_PULSES {
+        if sda_high() {
+            return Ok(pulses);
+        }
+        pulse_scl();
+    }
+    if sda_high() {
+        Ok(RECOVERY_PULSES)
+    } else {
+        Err(Error::Busy)
+    }
+}
+
 macro_rules! busy_wait {
     ($self:ident, $flag:ident, $variant:ident) => {
         loop {
@@ -76,12 +133,10 @@ macro_rules! busy_wait {
 }
 
 trait I2cBase {
-    #[allow(dead_code)]
     fn cr1(&self) -> &pac::i2c1::CR1;
     fn cr2(&self) -> &pac::i2c1::CR2;
     fn icr(&self) -> &pac::i2c1::ICR;
     fn isr(&self) -> &pac::i2c1::ISR;
-    #[allow(dead_code)]
     fn oar1(&self) -> &pac::i2c1::OAR1;
     #[allow(dead_code)]
     fn oar2(&self) -> &pac::i2c1::OAR2;
@@ -310,6 +365,60 @@ trait I2cBase {
 
         Ok(())
     }
+
+    /// Listen for transfers addressed to the 7-bit `addr` with clock
+    /// stretching enabled.
+    fn listen(&mut self, addr: u8) {
+        // NOSTRETCH and SBC can only be modified when PE = 0
+        self.cr1().modify(|_, w| w.pe().disabled());
+
+        // OA1 can only be written when OA1EN = 0
+        self.oar1().write(|w| w.oa1en().disabled());
+        self.oar1().write(|w| {
+            w.oa1().bits((addr << 1) as u16);
+            w.oa1mode().bit7();
+            w.oa1en().enabled()
+        });
+
+        self.cr1().modify(|_, w| {
+            w.nostretch().enabled();
+            w.sbc().disabled();
+            w.pe().enabled()
+        });
+    }
+
+    /// Poll for a slave event, returns `None` if there is nothing to handle.
+    fn slave_event(&mut self) -> Option<SlaveEvent> {
+        let isr = self.isr().read();
+
+        // received data is handled before address matches and stops so the
+        // last byte of a write is not lost to a repeated START or STOP
+        if isr.rxne().is_not_empty() {
+            Some(SlaveEvent::RxByte(self.rxdr().read().rxdata().bits()))
+        } else if isr.addr().is_match() {
+            if isr.dir().is_read() {
+                // flush any byte left over from a previous read transfer
+                self.isr().write(|w| w.txe().set_bit());
+            }
+            // clearing ADDR releases SCL
+            self.icr().write(|w| w.addrcf().clear());
+            Some(SlaveEvent::AddrMatch)
+        } else if isr.txis().is_empty() {
+            Some(SlaveEvent::TxRequest)
+        } else if isr.stopf().is_stop() {
+            // master NACKs the last byte of a read transfer
+            self.icr().write(|w| w.stopcf().clear().nackcf().clear());
+            Some(SlaveEvent::Stop)
+        } else {
+            None
+        }
+    }
+
+    /// Respond to a [`SlaveEvent::TxRequest`], releasing SCL.
+    fn slave_write(&mut self, byte: u8) {
+        // NOTE(write): Writes all non-reserved bits.
+        self.txdr().write(|w| w.txdata().bits(byte));
+    }
 }
 
 #[rustfmt::skip]
@@ -334,18 +443,18 @@ macro_rules! impl_i2c_base_for {
 }
 
 macro_rules! impl_clocks_reset {
-    ($($I2cX:ident: ($i2cXen:ident, $i2cXrst:ident, $i2cXsel:ident),)+) => {
+    ($($I2cX:ident: ($i2cXsel:ident),)+) => {
         $(
             impl<SCL, SDA> $I2cX<(SCL, SDA)> {
                 /// Enables peripheral clock
                 fn enable_clock(rcc: &mut RCC) {
-                    rcc.apb1enr1.modify(|_, w| w.$i2cXen().enabled());
+                    rcc::enable(rcc, Peripheral::$I2cX);
                 }
 
                 /// Resets peripheral clock
                 fn pulse_reset(rcc: &mut RCC) {
-                    rcc.apb1rstr1.modify(|_, w| w.$i2cXrst().reset());
-                    rcc.apb1rstr1.modify(|_, w| w.$i2cXrst().clear_bit());
+                    // safety: called before the peripheral is configured
+                    unsafe { rcc::reset(rcc, Peripheral::$I2cX) };
                 }
 
                 /// Returns the frequency of the peripheral clock driver
@@ -420,6 +529,92 @@ macro_rules! impl_new_free {
                         Self { base: i2c, pins }
                     }
 
+                /// Recover the bus from a slave holding SDA low.
+                ///
+                /// A slave can hold SDA low if a transfer was interrupted,
+                /// for example by a reset of the master.
+                /// This disables the I2C peripheral, drives the SCL and SDA
+                /// pins as open-drain GPIO outputs, pulses SCL at 100 kHz up
+                /// to 9 times until SDA is released, and generates a STOP
+                /// condition.
+                /// The pins are returned to the I2C alternate function and
+                /// the peripheral is enabled afterwards.
+                ///
+                /// The bus must be idle from the point of view of the
+                /// master, no transfer may be in-progress.
+                /// The pins are owned by the driver, no other code may use
+                /// them during the recovery.
+                ///
+                /// Returns [`Error::Busy`] if SDA is still low after 9
+                /// pulses, the pins and peripheral are restored regardless.
+                ///
+                /// # Example
+                ///
+                /// ```no_run
+                /// use stm32wlxx_hal::{
+                ///     cortex_m,
+                ///     gpio::PortA,
+                ///     i2c::I2c2,
+                ///     pac,
+                ///     util::new_delay,
+                /// };
+                ///
+                /// let mut dp = pac::Peripherals::take().unwrap();
+                /// let cp = pac::CorePeripherals::take().unwrap();
+                ///
+                /// let mut delay = new_delay(cp.SYST, &dp.RCC);
+                /// let pa = PortA::split(dp.GPIOA, &mut dp.RCC);
+                /// let mut i2c = cortex_m::interrupt::free(|cs| {
+                ///     I2c2::new(dp.I2C2, (pa.a12, pa.a11), 100_000, &mut dp.RCC, false, cs)
+                /// });
+                /// cortex_m::interrupt::free(|cs| i2c.recover_bus(&mut delay, cs))?;
+                /// # Ok::<(), stm32wlxx_hal::i2c::Error>(())
+                /// ```
+                pub fn recover_bus<D>(&mut self, delay: &mut D, cs: &CriticalSection) -> Result<(), Error>
+                    where
+                    D: DelayUs<u8>,
+                    SCL: crate::gpio::sealed::$I2cXScl + crate::gpio::sealed::PinOps,
+                    SDA: crate::gpio::sealed::$I2cXSda + crate::gpio::sealed::PinOps,
+                    {
+                        use crate::gpio::sealed::Mode;
+
+                        // PE = 0 releases the SCL and SDA lines
+                        self.base.cr1.modify(|_, w| w.pe().clear_bit());
+
+                        let (scl, sda) = &mut self.pins;
+                        scl.set_output_level(PinState::High);
+                        sda.set_output_level(PinState::High);
+                        scl.set_mode(cs, Mode::Output);
+                        sda.set_mode(cs, Mode::Output);
+                        delay.delay_us(RECOVERY_HALF_PERIOD_MICROS);
+
+                        let ret: Result<u8, Error> = clock_until_sda_released(
+                            || sda.input_level() == PinState::High,
+                            || {
+                                scl.set_output_level(PinState::Low);
+                                delay.delay_us(RECOVERY_HALF_PERIOD_MICROS);
+                                scl.set_output_level(PinState::High);
+                                delay.delay_us(RECOVERY_HALF_PERIOD_MICROS);
+                            },
+                        );
+
+                        // STOP: SDA rises while SCL is high
+                        scl.set_output_level(PinState::Low);
+                        delay.delay_us(RECOVERY_HALF_PERIOD_MICROS);
+                        sda.set_output_level(PinState::Low);
+                        delay.delay_us(RECOVERY_HALF_PERIOD_MICROS);
+                        scl.set_output_level(PinState::High);
+                        delay.delay_us(RECOVERY_HALF_PERIOD_MICROS);
+                        sda.set_output_level(PinState::High);
+                        delay.delay_us(RECOVERY_HALF_PERIOD_MICROS);
+
+                        scl.$i2cXsclAf(cs);
+                        sda.$i2cXsdaAf(cs);
+                        self.base.cr1.modify(|_, w| w.pe().set_bit());
+
+                        ret.map(|_| ())
+                    }
+
                 /// Releases the I2C peripheral and associated pins
                 pub fn free(self) -> ($I2CX, (SCL, SDA)) {
                     (self.base, self.pins)
@@ -471,16 +666,52 @@ macro_rules! impl_write_read {
     }
 }
 
+macro_rules! impl_slave {
+    ($($I2cX:ident)+) => {
+        $(
+            impl<PINS> $I2cX<PINS> {
+                /// Listen for transfers addressed to the 7-bit `addr` as a slave.
+                ///
+                /// Clock stretching is enabled, SCL is held low until each
+                /// [`SlaveEvent`] returned by [`slave_event`](Self::slave_event)
+                /// is handled.
+                /// The peripheral can still be used as a master when the bus is idle.
+                pub fn listen(&mut self, addr: u8) {
+                    self.base.listen(addr)
+                }
+
+                /// Poll for a slave event.
+                ///
+                /// Returns `None` if there is no event to handle.
+                ///
+                /// [`SlaveEvent::TxRequest`] must be answered with
+                /// [`slave_write`](Self::slave_write), the clock is stretched
+                /// until then.
+                pub fn slave_event(&mut self) -> Option<SlaveEvent> {
+                    self.base.slave_event()
+                }
+
+                /// Provide a byte to the master in response to
+                /// [`SlaveEvent::TxRequest`].
+                pub fn slave_write(&mut self, byte: u8) {
+                    self.base.slave_write(byte)
+                }
+            }
+        )+
+    }
+}
+
 macro_rules! i2c {
     ([ $($X:literal),+ ]) => {
         paste::paste! {
             impl_i2c_base_for!($([<I2C $X>])+);
-            impl_clocks_reset!($([<I2c $X>]: ([<i2c $X en>], [<i2c $X rst>], [<i2c $X sel>]),)+);
+            impl_clocks_reset!($([<I2c $X>]: ([<i2c $X sel>]),)+);
             impl_new_free!($([<I2c $X>]: ([<I2C $X>], [<i2c $X en>], [<i2c $X rst>], [<i2c $X sel>], [<I2c $X Sda>],
                                     [<I2c $X Scl>], [<set_i2c $X _scl_af>], [<set_i2c $X _sda_af>]),)+);
             impl_read!($([<I2c $X>])+);
             impl_write!($([<I2c $X>])+);
             impl_write_read!($([<I2c $X>])+);
+            impl_slave!($([<I2c $X>])+);
         }
     };
 }
@@ -555,3 +786,36 @@ fn i2c_clocks(clock_hz: u32, freq_hz: u32) -> (u8, u8, u8, u8, u8) {
 
     (presc as u8, scll, sclh, sdadel as u8, scldel as u8)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{clock_until_sda_released, Error, RECOVERY_PULSES};
+    use core::cell::Cell;
+
+    /// Stub of a slave that releases SDA after `release` SCL pulses.
+    ///
+    /// Returns the recovery result and the number of SCL pulses.
+    fn recover(release: u8) -> (Result<u8, Error>, u8) {
+        let pulses: Cell<u8> = Cell::new(0);
+        let ret =
+            clock_until_sda_released(|| pulses.get() >= release, || pulses.set(pulses.get() + 1));
+        (ret, pulses.get())
+    }
+
+    #[test]
+    fn sda_released() {
+        assert!(matches!(recover(0), (Ok(0), 0)));
+        assert!(matches!(recover(1), (Ok(1), 1)));
+        assert!(matches!(recover(8), (Ok(8), 8)));
+        assert!(matches!(recover(RECOVERY_PULSES), (Ok(9), 9)));
+    }
+
+    #[test]
+    fn sda_stuck() {
+        assert!(matches!(
+            recover(RECOVERY_PULSES + 1),
+            (Err(Error::Busy), 9)
+        ));
+        assert!(matches!(recover(u8::MAX), (Err(Error::Busy), 9)));
+    }
+}
@@ -229,6 +229,83 @@ pub fn flash_size() -> u32 {
     u32::from(flash_size_kibibyte()) << 10
 }
 
+/// Flash memory density.
+///
+/// This is the flash size code in the part number, for example the `C` in
+/// STM32WL55JC.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u16)]
+pub enum FlashSize {
+    /// 64 KiB, part number code `8`.
+    Kib64 = 64,
+    /// 128 KiB, part number code `B`.
+    Kib128 = 128,
+    /// 256 KiB, part number code `C`.
+    Kib256 = 256,
+}
+
+impl TryFrom<u16> for FlashSize {
+    type Error = u16;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            64 => Ok(FlashSize::Kib64),
+            128 => Ok(FlashSize::Kib128),
+            256 => Ok(FlashSize::Kib256),
+            _ => Err(value),
+        }
+    }
+}
+
+impl From<FlashSize> for u16 {
+    fn from(f: FlashSize) -> Self {
+        f as u16
+    }
+}
+
+impl FlashSize {
+    /// Get the flash memory density from device memory.
+    ///
+    /// This decodes the flash size data register at `0x1FFF_75E0`, see
+    /// [`flash_size_kibibyte`].
+    ///
+    /// If the size is not a known density it will be returned in the `Err`
+    /// variant of the `Result`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::info;
+    ///
+    /// let flash: Result<info::FlashSize, u16> = info::FlashSize::from_device();
+    /// // valid for the NUCLEO-WL55JC2 dev board
+    /// assert_eq!(flash, Ok(info::FlashSize::Kib256));
+    /// ```
+    #[inline]
+    pub fn from_device() -> Result<Self, u16> {
+        flash_size_kibibyte().try_into()
+    }
+
+    /// Flash size code in the part number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::info::FlashSize;
+    ///
+    /// assert_eq!(FlashSize::Kib64.code(), '8');
+    /// assert_eq!(FlashSize::Kib256.code(), 'C');
+    /// ```
+    pub const fn code(&self) -> char {
+        match self {
+            FlashSize::Kib64 => '8',
+            FlashSize::Kib128 => 'B',
+            FlashSize::Kib256 => 'C',
+        }
+    }
+}
+
 /// Physical package type.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -264,6 +341,9 @@ impl From<Package> for u8 {
 impl Package {
     /// Get the package type from device memory.
     ///
+    /// This decodes `PKG[4:0]` of the package data register at
+    /// `0x1FFF_7500`.
+    ///
     /// If the value is reserved it will be returned in the `Err` variant of the
     /// `Result`.
     ///
@@ -278,7 +358,7 @@ impl Package {
     /// ```
     #[inline]
     pub fn from_device() -> Result<Self, u8> {
-        let raw: u8 = (unsafe { read(0x1FFF_7500 as *const u32) } & 0xF) as u8;
+        let raw: u8 = (unsafe { read(0x1FFF_7500 as *const u32) } & 0x1F) as u8;
         raw.try_into()
     }
 }
@@ -404,3 +484,28 @@ impl Display for Uid64 {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{FlashSize, Package};
+
+    #[test]
+    fn package_decode() {
+        assert_eq!(Package::try_from(0b00000), Ok(Package::UFBGA73));
+        assert_eq!(Package::try_from(0b00010), Ok(Package::WLCSP59));
+        assert_eq!(Package::try_from(0b01010), Ok(Package::UFQFPN48));
+        assert_eq!(Package::try_from(0b00001), Err(0b00001));
+        assert_eq!(Package::try_from(0b11010), Err(0b11010));
+        assert_eq!(u8::from(Package::UFQFPN48), 0b01010);
+    }
+
+    #[test]
+    fn flash_size_decode() {
+        assert_eq!(FlashSize::try_from(64), Ok(FlashSize::Kib64));
+        assert_eq!(FlashSize::try_from(128), Ok(FlashSize::Kib128));
+        assert_eq!(FlashSize::try_from(256), Ok(FlashSize::Kib256));
+        assert_eq!(FlashSize::try_from(0xFFFF), Err(0xFFFF));
+        assert_eq!(u16::from(FlashSize::Kib128), 128);
+        assert_eq!(FlashSize::Kib128.code(), 'B');
+    }
+}
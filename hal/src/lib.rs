@@ -48,6 +48,7 @@ pub(crate) mod macros;
 
 pub mod adc;
 pub mod aes;
+pub mod crc;
 pub mod dac;
 pub mod dma;
 pub mod flash;
@@ -63,8 +64,10 @@ pub mod rng;
 pub mod rtc;
 pub mod spi;
 pub mod subghz;
+pub mod timer;
 pub mod uart;
 pub mod util;
+pub mod wwdg;
 
 mod ratio;
 pub use ratio::Ratio;
@@ -105,6 +105,19 @@ pub enum Filter {
     Clk8 = 0b11,
 }
 
+/// Active edge of the external input for counting.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Edge {
+    /// Rising edge is the active edge.
+    Rising = 0b00,
+    /// Falling edge is the active edge.
+    Falling = 0b01,
+    /// Both edges are active edges.
+    Both = 0b10,
+}
+
 impl Prescaler {
     /// Get the prescaler divisor.
     ///
@@ -242,6 +255,72 @@ impl Cfgr {
         self
     }
 
+    /// Set the counter mode.
+    ///
+    /// * `false`: the counter is incremented by the internal clock.
+    /// * `true`: the counter is incremented by valid pulses on the external
+    ///   input 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::lptim::Cfgr;
+    ///
+    /// let cfgr: Cfgr = Cfgr::RESET;
+    /// assert_eq!(cfgr.countmode(), false);
+    ///
+    /// let cfgr: Cfgr = cfgr.set_countmode(true);
+    /// assert_eq!(cfgr.countmode(), true);
+    ///
+    /// let cfgr: Cfgr = cfgr.set_countmode(false);
+    /// assert_eq!(cfgr.countmode(), false);
+    /// ```
+    #[inline]
+    #[must_use = "set_countmode returns a modified Cfgr"]
+    pub const fn set_countmode(mut self, countmode: bool) -> Self {
+        if countmode {
+            self.val |= 1 << 23;
+        } else {
+            self.val &= !(1 << 23);
+        }
+        self
+    }
+
+    /// Get the counter mode.
+    #[inline]
+    #[must_use]
+    pub const fn countmode(&self) -> bool {
+        self.val & (1 << 23) != 0
+    }
+
+    /// Set the active edge of the external input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::lptim::{Cfgr, Edge};
+    ///
+    /// assert_eq!(Cfgr::RESET.set_ckpol(Edge::Both).raw(), 0b100);
+    /// assert_eq!(Cfgr::RESET.set_ckpol(Edge::Falling).raw(), 0b010);
+    /// assert_eq!(Cfgr::RESET.set_ckpol(Edge::Rising).raw(), 0b000);
+    /// ```
+    #[inline]
+    #[must_use = "set_ckpol returns a modified Cfgr"]
+    pub const fn set_ckpol(mut self, edge: Edge) -> Self {
+        self.val &= !(0b11 << 1);
+        self.val |= (edge as u32) << 1;
+        self
+    }
+
+    /// Set the external input filter.
+    #[inline]
+    #[must_use = "set_clk_filter returns a modified Cfgr"]
+    pub const fn set_clk_filter(mut self, filter: Filter) -> Self {
+        self.val &= !(0b11 << 3);
+        self.val |= (filter as u32) << 3;
+        self
+    }
+
     /// Get the prescaler value.
     ///
     /// # Example
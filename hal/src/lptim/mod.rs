@@ -30,18 +30,23 @@
 mod cfgr;
 mod cr;
 
-pub use cfgr::{Cfgr, Filter, Prescaler, TrgPol, TrgSel, TrgSel3};
+pub use cfgr::{Cfgr, Edge, Filter, Prescaler, TrgPol, TrgSel, TrgSel3};
 use cortex_m::interrupt::CriticalSection;
 pub use cr::Cr;
 
 use crate::{
     gpio::{
         pins,
-        sealed::{LpTim1Etr, LpTim1Out, LpTim2Etr, LpTim2Out, LpTim3Etr, LpTim3Out},
+        sealed::{
+            LpTim1Etr, LpTim1In1, LpTim1Out, LpTim2Etr, LpTim2In1, LpTim2Out, LpTim3Etr, LpTim3In1,
+            LpTim3Out,
+        },
     },
-    pac, Ratio,
+    pac,
+    rcc::{self, Peripheral},
+    Ratio,
 };
-use core::cmp::min;
+use core::{cmp::min, time::Duration};
 use paste::paste;
 use void::Void;
 
@@ -90,6 +95,14 @@ pub mod irq {
     pub const ALL: u32 = REPOK | UE | DOWN | UP | ARROK | CMPOK | EXTTRIG | ARRM | CMPM;
 }
 
+/// Low-power timer errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The period is too short or too long for the timer clock.
+    Period,
+}
+
 use sealed::{LpTim as SealedLpTim, LpTimBase};
 pub(crate) mod sealed {
     use super::{Cfgr, Cr, Ratio};
@@ -119,6 +132,7 @@ pub(crate) mod sealed {
         fn as_tim(&self) -> &Self::Pac;
         fn as_mut_tim(&mut self) -> &mut Self::Pac;
         fn _hz(&self) -> &Ratio<u32>;
+        fn _set_hz(&mut self, hz: Ratio<u32>);
     }
 }
 
@@ -277,6 +291,11 @@ macro_rules! paste_lptim {
                 fn _hz(&self) -> &Ratio<u32> {
                     &self.hz
                 }
+
+                #[inline(always)]
+                fn _set_hz(&mut self, hz: Ratio<u32>) {
+                    self.hz = hz;
+                }
             }
         }
     };
@@ -407,6 +426,168 @@ pub trait LpTim: sealed::LpTim {
         unsafe { Self::Pac::cnt() }
     }
 
+    /// Get the timer count.
+    ///
+    /// Unlike [`cnt`](Self::cnt) this reads the counter until two consecutive
+    /// reads return the same value.
+    /// RM0453 requires this when the counter is clocked asynchronously to
+    /// the APB clock, for example when counting external pulses with
+    /// [`count_external`](Self::count_external).
+    ///
+    /// # Example
+    ///
+    /// See [`count_external`](Self::count_external).
+    #[inline]
+    fn count() -> u16 {
+        consistent_read(Self::cnt)
+    }
+
+    /// Count pulses on the external input 1.
+    ///
+    /// The counter is incremented on each valid `edge` of input 1, using the
+    /// internal clock selected in [`new`](Self::new) to sample the input.
+    /// Configure the input 1 pin with `new_input_pin`, then start the timer
+    /// to begin counting.
+    ///
+    /// The input is sampled with the internal kernel clock, each high and low
+    /// phase of the input must last at least two kernel clock periods.
+    /// This limits the maximum counting frequency to a quarter of the kernel
+    /// clock frequency, for example 4 MHz with [`Clk::Hsi16`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) timer is enabled.
+    ///
+    /// # Example
+    ///
+    /// Count rising edges on pin [`A12`](crate::gpio::pins::A12).
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     embedded_hal::timer::CountDown,
+    ///     gpio::PortA,
+    ///     lptim::{self, Edge, LpTim, LpTim3, Prescaler::Div1},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // enable the HSI16 source clock
+    /// dp.RCC.cr.modify(|_, w| w.hsion().set_bit());
+    /// while dp.RCC.cr.read().hsirdy().is_not_ready() {}
+    ///
+    /// let pa: PortA = PortA::split(dp.GPIOA, &mut dp.RCC);
+    /// let mut lptim3: LpTim3 = LpTim3::new(dp.LPTIM3, lptim::Clk::Hsi16, Div1, &mut dp.RCC);
+    /// let _in1 = cortex_m::interrupt::free(|cs| lptim3.new_input_pin(pa.a12, cs));
+    /// lptim3.count_external(Edge::Rising);
+    /// lptim3.start(u16::MAX);
+    ///
+    /// // ... wait for pulses
+    ///
+    /// let pulses: u16 = LpTim3::count();
+    /// ```
+    fn count_external(&mut self, edge: Edge) {
+        debug_assert!(!self.is_enabled());
+        self.as_mut_tim()
+            .modify_cfgr(|w| w.set_countmode(true).set_ckpol(edge))
+    }
+
+    /// Start a periodic timer with the autoreload match interrupt.
+    ///
+    /// This selects the smallest prescaler that fits the period in the
+    /// 16-bit autoreload register, programs the autoreload register, enables
+    /// the [`ARRM`](irq::ARRM) interrupt, and starts the timer in continuous
+    /// mode.
+    /// The period is rounded to the nearest timer tick, the prescaler
+    /// selected here replaces the prescaler passed to [`new`](Self::new).
+    ///
+    /// The interrupt fires once per period, clear [`ARRM`](irq::ARRM) in the
+    /// interrupt handler with [`set_icr`](Self::set_icr).
+    /// Use [`unmask_irq`](Self::unmask_irq) to unmask the interrupt in the
+    /// NVIC.
+    ///
+    /// **Note:** Only the LSI and LSE clocks run in Stop mode.
+    /// To wake the CPU from Stop mode the timer must be created with
+    /// [`Clk::Lsi`] or [`Clk::Lse`], and that clock must be enabled.
+    /// With [`Clk::Pclk`] or [`Clk::Hsi16`] the timer only counts in Run
+    /// and Sleep modes.
+    ///
+    /// Returns [`Error::Period`] if the period is shorter than two timer
+    /// clock cycles, or longer than 65,536 timer clock cycles with the
+    /// largest prescaler, the timer is not modified.
+    ///
+    /// # Example
+    ///
+    /// Wake up every second from the LSE clock.
+    ///
+    /// ```no_run
+    /// use core::time::Duration;
+    /// use stm32wlxx_hal::{
+    ///     lptim::{self, LpTim, LpTim1, Prescaler::Div1},
+    ///     pac,
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // enable the LSE source clock
+    /// dp.PWR.cr1.modify(|_, w| w.dbp().enabled());
+    /// dp.RCC.bdcr.modify(|_, w| w.lseon().on());
+    /// while dp.RCC.bdcr.read().lserdy().is_not_ready() {}
+    ///
+    /// let mut lptim1: LpTim1 = LpTim1::new(dp.LPTIM1, lptim::Clk::Lse, Div1, &mut dp.RCC);
+    /// lptim1.start_periodic(Duration::from_secs(1))?;
+    /// # #[cfg(feature = "rt")]
+    /// unsafe { LpTim1::unmask_irq() };
+    /// # Ok::<(), stm32wlxx_hal::lptim::Error>(())
+    /// ```
+    fn start_periodic(&mut self, period: Duration) -> Result<(), Error> {
+        let pre: Prescaler = self.as_tim().cfgr().prescaler();
+        let src_hz: Ratio<u32> = *self.hz() * u32::from(pre.div());
+        let (pre, arr): (Prescaler, u16) =
+            periodic_config(src_hz.to_integer(), period).ok_or(Error::Period)?;
+
+        self.as_mut_tim().set_cr(Cr::DISABLE);
+        self.as_mut_tim().modify_cfgr(|w| w.set_prescaler(pre));
+        self._set_hz(src_hz / u32::from(pre.div()));
+        self.as_mut_tim().set_ier(irq::ARRM);
+
+        // enable timer
+        {
+            const CR: Cr = Cr::RESET.enable();
+            self.as_mut_tim().set_cr(CR);
+
+            // RM0461 Rev 4 "Timer enable":
+            // After setting the ENABLE bit, a delay of two counter
+            // clock is needed before the LPTIM is actually enabled.
+            const MAX_SYS_FREQ: u32 = 48_000_000;
+            let delay: u32 = (MAX_SYS_FREQ * 2) / self.hz().to_integer();
+            cortex_m::asm::delay(delay);
+        }
+
+        // can only be modified when enabled
+        self.as_mut_tim().set_autoreload(arr);
+        while Self::isr() & irq::ARROK == 0 {}
+        // ARROK is cleared before its interrupt is enabled, this matches the
+        // erratum workaround in set_icr
+        unsafe { self.set_icr(irq::ARROK) };
+
+        const CR: Cr = Cr::RESET.enable().set_continuous();
+        self.as_mut_tim().set_cr(CR);
+        Ok(())
+    }
+
+    /// Unmask the LPTIM IRQ in the NVIC.
+    ///
+    /// # Safety
+    ///
+    /// This can break mask-based critical sections.
+    ///
+    /// # Example
+    ///
+    /// See [`start_periodic`](Self::start_periodic).
+    #[cfg(feature = "rt")]
+    unsafe fn unmask_irq();
+
     /// Get the interrupt status.
     #[inline]
     fn isr() -> u32 {
@@ -597,25 +778,29 @@ impl LpTim for LpTim1 {
 
     #[inline]
     unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb1rstr1.modify(|_, w| w.lptim1rst().reset());
-        rcc.apb1rstr1.modify(|_, w| w.lptim1rst().no_reset());
+        rcc::reset(rcc, Peripheral::Lptim1);
     }
 
     #[inline]
     fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr1.modify(|_, w| w.lptim1en().enabled());
-        rcc.apb1enr1.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Lptim1);
     }
 
     #[inline]
     unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr1.modify(|_, w| w.lptim1en().disabled());
+        rcc::disable(rcc, Peripheral::Lptim1);
     }
 
     #[inline]
     fn clk(rcc: &pac::RCC) -> Clk {
         rcc.ccipr.read().lptim1sel().bits().into()
     }
+
+    #[cfg(feature = "rt")]
+    #[inline]
+    unsafe fn unmask_irq() {
+        pac::NVIC::unmask(pac::Interrupt::LPTIM1)
+    }
 }
 
 impl LpTim for LpTim2 {
@@ -645,25 +830,29 @@ impl LpTim for LpTim2 {
 
     #[inline]
     unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb1rstr2.modify(|_, w| w.lptim2rst().reset());
-        rcc.apb1rstr2.modify(|_, w| w.lptim2rst().no_reset());
+        rcc::reset(rcc, Peripheral::Lptim2);
     }
 
     #[inline]
     fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr2.modify(|_, w| w.lptim2en().enabled());
-        rcc.apb1enr2.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Lptim2);
     }
 
     #[inline]
     unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr2.modify(|_, w| w.lptim2en().disabled());
+        rcc::disable(rcc, Peripheral::Lptim2);
     }
 
     #[inline]
     fn clk(rcc: &pac::RCC) -> Clk {
         rcc.ccipr.read().lptim2sel().bits().into()
     }
+
+    #[cfg(feature = "rt")]
+    #[inline]
+    unsafe fn unmask_irq() {
+        pac::NVIC::unmask(pac::Interrupt::LPTIM2)
+    }
 }
 
 impl LpTim for LpTim3 {
@@ -693,25 +882,29 @@ impl LpTim for LpTim3 {
 
     #[inline]
     unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb1rstr2.modify(|_, w| w.lptim3rst().reset());
-        rcc.apb1rstr2.modify(|_, w| w.lptim3rst().no_reset());
+        rcc::reset(rcc, Peripheral::Lptim3);
     }
 
     #[inline]
     fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr2.modify(|_, w| w.lptim3en().enabled());
-        rcc.apb1enr2.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Lptim3);
     }
 
     #[inline]
     unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr2.modify(|_, w| w.lptim3en().disabled());
+        rcc::disable(rcc, Peripheral::Lptim3);
     }
 
     #[inline]
     fn clk(rcc: &pac::RCC) -> Clk {
         rcc.ccipr.read().lptim3sel().bits().into()
     }
+
+    #[cfg(feature = "rt")]
+    #[inline]
+    unsafe fn unmask_irq() {
+        pac::NVIC::unmask(pac::Interrupt::LPTIM3)
+    }
 }
 
 macro_rules! impl_eh_countdown_for {
@@ -1041,6 +1234,47 @@ impl LpTim3 {
     }
 }
 
+/// Prescaler and autoreload value for a periodic timer.
+///
+/// This selects the smallest prescaler for the best resolution, the period
+/// is rounded to the nearest timer tick.
+/// The autoreload value is one less than the number of ticks per period,
+/// and must be at least 1.
+fn periodic_config(src_hz: u32, period: Duration) -> Option<(Prescaler, u16)> {
+    const PRESCALERS: [Prescaler; 8] = [
+        Prescaler::Div1,
+        Prescaler::Div2,
+        Prescaler::Div4,
+        Prescaler::Div8,
+        Prescaler::Div16,
+        Prescaler::Div32,
+        Prescaler::Div64,
+        Prescaler::Div128,
+    ];
+
+    PRESCALERS.iter().find_map(|pre| {
+        let den: u128 = u128::from(pre.div()) * 1_000_000_000;
+        let ticks: u128 = (period.as_nanos() * u128::from(src_hz) + den / 2) / den;
+        if (2..=1 << 16).contains(&ticks) {
+            Some((*pre, (ticks - 1) as u16))
+        } else {
+            None
+        }
+    })
+}
+
+/// Read a register until two consecutive reads return the same value.
+fn consistent_read<F: FnMut() -> u16>(mut read: F) -> u16 {
+    let mut prev: u16 = read();
+    loop {
+        let next: u16 = read();
+        if next == prev {
+            return next;
+        }
+        prev = next;
+    }
+}
+
 /// Low-power timer 1 output pin.
 ///
 /// Constructed with [`new_output_pin`](LpTim1::new_output_pin).
@@ -1141,3 +1375,199 @@ impl LpTim3 {
         pin.free()
     }
 }
+
+/// Low-power timer 1 input 1 pin.
+///
+/// Constructed with [`new_input_pin`](LpTim1::new_input_pin).
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LpTim1In1Pin<P> {
+    pin: P,
+}
+
+impl<P> LpTim1In1Pin<P> {
+    fn free(self) -> P {
+        self.pin
+    }
+}
+
+impl LpTim1 {
+    /// Setup a new input 1 pin for [`count_external`](LpTim::count_external).
+    #[inline]
+    pub fn new_input_pin<P: LpTim1In1>(
+        &mut self,
+        mut pin: P,
+        cs: &CriticalSection,
+    ) -> LpTim1In1Pin<P> {
+        pin.set_lptim1_in1_af(cs);
+        LpTim1In1Pin { pin }
+    }
+
+    /// Free the input 1 pin previously created with
+    /// [`new_input_pin`](Self::new_input_pin).
+    #[inline]
+    pub fn free_input_pin<P: LpTim1In1>(&mut self, pin: LpTim1In1Pin<P>) -> P {
+        pin.free()
+    }
+}
+
+/// Low-power timer 2 input 1 pin.
+///
+/// Constructed with [`new_input_pin`](LpTim2::new_input_pin).
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LpTim2In1Pin<P> {
+    pin: P,
+}
+
+impl<P> LpTim2In1Pin<P> {
+    fn free(self) -> P {
+        self.pin
+    }
+}
+
+impl LpTim2 {
+    /// Setup a new input 1 pin for [`count_external`](LpTim::count_external).
+    #[inline]
+    pub fn new_input_pin<P: LpTim2In1>(
+        &mut self,
+        mut pin: P,
+        cs: &CriticalSection,
+    ) -> LpTim2In1Pin<P> {
+        pin.set_lptim2_in1_af(cs);
+        LpTim2In1Pin { pin }
+    }
+
+    /// Free the input 1 pin previously created with
+    /// [`new_input_pin`](Self::new_input_pin).
+    #[inline]
+    pub fn free_input_pin<P: LpTim2In1>(&mut self, pin: LpTim2In1Pin<P>) -> P {
+        pin.free()
+    }
+}
+
+/// Low-power timer 3 input 1 pin.
+///
+/// Constructed with [`new_input_pin`](LpTim3::new_input_pin).
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LpTim3In1Pin {
+    pin: pins::A12,
+}
+
+impl LpTim3In1Pin {
+    fn free(self) -> pins::A12 {
+        self.pin
+    }
+}
+
+impl LpTim3 {
+    /// Setup a new input 1 pin for [`count_external`](LpTim::count_external).
+    #[inline]
+    pub fn new_input_pin(&mut self, mut pin: pins::A12, cs: &CriticalSection) -> LpTim3In1Pin {
+        pin.set_lptim3_in1_af(cs);
+        LpTim3In1Pin { pin }
+    }
+
+    /// Free the input 1 pin previously created with
+    /// [`new_input_pin`](Self::new_input_pin).
+    #[inline]
+    pub fn free_input_pin(&mut self, pin: LpTim3In1Pin) -> pins::A12 {
+        pin.free()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{consistent_read, periodic_config, Prescaler};
+    use core::time::Duration;
+
+    #[test]
+    fn periodic_arr() {
+        // LSE
+        assert_eq!(
+            periodic_config(32_768, Duration::from_millis(1)),
+            Some((Prescaler::Div1, 32))
+        );
+        assert_eq!(
+            periodic_config(32_768, Duration::from_secs(1)),
+            Some((Prescaler::Div1, 32_767))
+        );
+        assert_eq!(
+            periodic_config(32_768, Duration::from_secs(2)),
+            Some((Prescaler::Div1, u16::MAX))
+        );
+        assert_eq!(
+            periodic_config(32_768, Duration::from_secs(10)),
+            Some((Prescaler::Div8, 40_959))
+        );
+        assert_eq!(
+            periodic_config(32_768, Duration::from_secs(256)),
+            Some((Prescaler::Div128, u16::MAX))
+        );
+        // LSI
+        assert_eq!(
+            periodic_config(32_000, Duration::from_secs(1)),
+            Some((Prescaler::Div1, 31_999))
+        );
+        assert_eq!(
+            periodic_config(32_000, Duration::from_secs(60)),
+            Some((Prescaler::Div32, 59_999))
+        );
+        // HSI16
+        assert_eq!(
+            periodic_config(16_000_000, Duration::from_micros(100)),
+            Some((Prescaler::Div1, 1_599))
+        );
+    }
+
+    #[test]
+    fn periodic_arr_range() {
+        // two ticks is the shortest period
+        assert_eq!(
+            periodic_config(32_768, Duration::from_nanos(61_036)),
+            Some((Prescaler::Div1, 1))
+        );
+        assert_eq!(periodic_config(32_768, Duration::from_micros(30)), None);
+        assert_eq!(periodic_config(32_768, Duration::ZERO), None);
+        assert_eq!(periodic_config(32_768, Duration::from_secs(257)), None);
+        assert_eq!(periodic_config(0, Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn consistent_read_stable() {
+        let mut reads: u32 = 0;
+        let count: u16 = consistent_read(|| {
+            reads += 1;
+            0x1234
+        });
+        assert_eq!(count, 0x1234);
+        assert_eq!(reads, 2);
+    }
+
+    #[test]
+    fn consistent_read_changing() {
+        // counter increments on every read until it settles at 5
+        let mut counter: u16 = 0;
+        let count: u16 = consistent_read(|| {
+            counter = core::cmp::min(counter + 1, 5);
+            counter
+        });
+        assert_eq!(count, 5);
+        assert_eq!(counter, 5);
+    }
+
+    #[test]
+    fn consistent_read_torn() {
+        // a torn read in the middle of a carry must not be returned
+        let values: [u16; 5] = [0x00FF, 0x01FF, 0x0100, 0x0100, 0x0101];
+        let mut idx: usize = 0;
+        let count: u16 = consistent_read(|| {
+            let val: u16 = values[idx];
+            idx += 1;
+            val
+        });
+        assert_eq!(count, 0x0100);
+        assert_eq!(idx, 4);
+    }
+}
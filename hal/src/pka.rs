@@ -4,6 +4,7 @@
 //!
 //! * [ECDSA signing](Pka::ecdsa_sign)
 //! * [ECDSA verify](Pka::ecdsa_verify)
+//! * [Modular exponentiation](Pka::mod_exp)
 //!
 //! # Alternatives
 //!
@@ -66,7 +67,10 @@
 //!
 //! [p256-cortex-m4]: https://crates.io/crates/p256-cortex-m4
 
-use crate::pac::{self, pka::cr::MODE_A};
+use crate::{
+    pac::{self, pka::cr::MODE_A},
+    rcc::{self, Peripheral},
+};
 use core::{
     mem::size_of,
     ptr::{read_volatile, write_volatile},
@@ -146,6 +150,30 @@ impl EcdsaVerifyError {
     }
 }
 
+/// Errors from a modular exponentiation operation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ModExpError {
+    /// Address access is out of range (unmapped address).
+    Address,
+    /// An AHB access to the PKA RAM occurred while the PKA core was computing
+    /// and using its internal RAM.
+    /// (AHB PKA_RAM access is not allowed while a PKA operation is in progress).
+    Ram,
+    /// An operand length is invalid.
+    ///
+    /// * The modulus is empty, even, or longer than [`MOD_EXP_MAX_LEN`].
+    /// * The exponent is empty or longer than [`MOD_EXP_MAX_LEN`].
+    /// * The base is longer than the modulus.
+    /// * The output length is not equal to the modulus length.
+    Length,
+}
+
+/// Maximum operand length in bytes for [`Pka::mod_exp`].
+///
+/// The PKA supports operands up to 3136 bits.
+pub const MOD_EXP_MAX_LEN: usize = 3136 / 8;
+
 /// PKA operation codes.
 #[derive(Debug)]
 #[repr(u8)]
@@ -236,6 +264,16 @@ const ECDSA_VERIFY_N: usize = BASE + 0xD5C;
 // ECDSA verify output addresses
 const ECDSA_VERIFY_OUT: usize = BASE + 0x5B0;
 
+// modular exponentiation inputs
+const MOD_EXP_EXP_LEN: usize = BASE + 0x400;
+const MOD_EXP_OP_LEN: usize = BASE + 0x404;
+const MOD_EXP_BASE: usize = BASE + 0xA44;
+const MOD_EXP_EXP: usize = BASE + 0xBF8;
+const MOD_EXP_MOD: usize = BASE + 0xD5C;
+
+// modular exponentiation outputs
+const MOD_EXP_OUT: usize = BASE + 0x724;
+
 /// PKA driver.
 #[derive(Debug)]
 pub struct Pka {
@@ -335,14 +373,13 @@ impl Pka {
     ///    while the clock was disabled.
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb3enr.modify(|_, w| w.pkaen().disabled());
+        rcc::disable(rcc, Peripheral::Pka);
     }
 
     /// Enable the PKA clock.
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb3enr.modify(|_, w| w.pkaen().enabled());
-        rcc.ahb3enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Pka);
     }
 
     /// Reset the PKA.
@@ -353,8 +390,7 @@ impl Pka {
     /// 2. You are responsible for setting up the PKA after a reset.
     #[inline]
     pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.ahb3rstr.modify(|_, w| w.pkarst().set_bit());
-        rcc.ahb3rstr.modify(|_, w| w.pkarst().clear_bit());
+        rcc::reset(rcc, Peripheral::Pka);
     }
 
     /// Unmask the PKA IRQ in the NVIC.
@@ -407,6 +443,34 @@ impl Pka {
         });
     }
 
+    // big-endian bytes to little-endian word order
+    unsafe fn write_ram_bytes(&mut self, offset: usize, buf: &[u8]) {
+        // asserts are for internal correctness, should not be accessible by users
+        debug_assert_eq!(offset % 4, 0);
+        debug_assert!(offset + buf.len() < 0x5800_33FF);
+        buf.rchunks(4).enumerate().for_each(|(idx, chunk)| {
+            let dw: u32 = chunk
+                .iter()
+                .fold(0, |dw, &byte| (dw << 8) | u32::from(byte));
+            write_volatile((offset + idx * size_of::<u32>()) as *mut u32, dw)
+        });
+    }
+
+    // little-endian word order to big-endian bytes
+    unsafe fn read_ram_bytes(&mut self, offset: usize, buf: &mut [u8]) {
+        // asserts are for internal correctness, should not be accessible by users
+        debug_assert_eq!(offset % 4, 0);
+        debug_assert!(offset + buf.len() < 0x5800_33FF);
+        buf.rchunks_mut(4).enumerate().for_each(|(idx, chunk)| {
+            let dw: u32 = read_volatile((offset + idx * size_of::<u32>()) as *const u32);
+            let len: usize = chunk.len();
+            chunk
+                .iter_mut()
+                .enumerate()
+                .for_each(|(byte_idx, byte)| *byte = (dw >> ((len - 1 - byte_idx) * 8)) as u8);
+        });
+    }
+
     #[inline]
     fn start_process(&mut self, mode: MODE_A) {
         self.pka.cr.write(|w| {
@@ -668,6 +732,90 @@ impl Pka {
             EcdsaVerifyError::from_raw(result)
         }
     }
+
+    /// Modular exponentiation.
+    ///
+    /// Computes `out = base ^ exp mod modulus`.
+    ///
+    /// This is the building block for RSA and classic Diffie-Hellman.
+    ///
+    /// All operands are big-endian byte arrays.
+    /// The operand size is the length of `modulus`, up to
+    /// [`MOD_EXP_MAX_LEN`] bytes.
+    /// `out` must be the same length as `modulus`.
+    /// The modulus must be odd.
+    ///
+    /// This is not a constant-time operation with respect to the exponent,
+    /// use RSA CRT or blinding if the exponent is secret.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut pka = unsafe { stm32wlxx_hal::pka::Pka::steal() };
+    /// // 4 ^ 13 mod 497 = 445
+    /// let mut out: [u8; 2] = [0; 2];
+    /// pka.mod_exp(&[4], &[13], &[0x01, 0xF1], &mut out)?;
+    /// assert_eq!(out, [0x01, 0xBD]);
+    /// # Ok::<(), stm32wlxx_hal::pka::ModExpError>(())
+    /// ```
+    pub fn mod_exp(
+        &mut self,
+        base: &[u8],
+        exp: &[u8],
+        modulus: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), ModExpError> {
+        let modulus_is_odd: bool = modulus.last().map_or(false, |byte| byte & 1 == 1);
+        if !modulus_is_odd
+            || modulus.len() > MOD_EXP_MAX_LEN
+            || exp.is_empty()
+            || exp.len() > MOD_EXP_MAX_LEN
+            || base.len() > modulus.len()
+            || out.len() != modulus.len()
+        {
+            return Err(ModExpError::Length);
+        }
+
+        self.zero_ram();
+        let exp_length: u32 = (exp.len() * 8) as u32;
+        let op_length: u32 = (modulus.len() * 8) as u32;
+
+        unsafe {
+            write_volatile(MOD_EXP_EXP_LEN as *mut u32, exp_length);
+            write_volatile(MOD_EXP_OP_LEN as *mut u32, op_length);
+            self.write_ram_bytes(MOD_EXP_BASE, base);
+            self.write_ram_bytes(MOD_EXP_EXP, exp);
+            self.write_ram_bytes(MOD_EXP_MOD, modulus);
+        }
+
+        let sr = self.pka.sr.read();
+        if sr.addrerrf().bit_is_set() {
+            self.clear_all_flags();
+            return Err(ModExpError::Address);
+        } else if sr.ramerrf().bit_is_set() {
+            self.clear_all_flags();
+            return Err(ModExpError::Ram);
+        }
+
+        self.start_process(MODE_A::MontgomeryCompExp);
+
+        loop {
+            let sr = self.pka.sr.read();
+            if sr.addrerrf().bit_is_set() {
+                self.clear_all_flags();
+                return Err(ModExpError::Address);
+            } else if sr.ramerrf().bit_is_set() {
+                self.clear_all_flags();
+                return Err(ModExpError::Ram);
+            } else if sr.procendf().is_completed() {
+                break;
+            }
+        }
+        self.clear_all_flags();
+
+        unsafe { self.read_ram_bytes(MOD_EXP_OUT, out) };
+        Ok(())
+    }
 }
 
 /// Sign bit for ECDSA coefficient signing and verification.
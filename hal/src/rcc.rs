@@ -933,6 +933,267 @@ pub fn enable_lsi(rcc: &mut pac::RCC) {
     while rcc.csr.read().lsirdy().is_not_ready() {}
 }
 
+/// RCC errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The LSE clock is not ready.
+    LseNotReady,
+    /// The MSI clock is off.
+    MsiOff,
+}
+
+/// Enable the MSI phase-locked loop (PLL-mode) and wait for the MSI to be
+/// ready.
+///
+/// In PLL-mode the MSI is automatically calibrated against the LSE, the
+/// MSI accuracy becomes the accuracy of the LSE crystal.
+/// This is useful for accurate timing without a HSE crystal.
+///
+/// The hardware clears the MSI PLL enable when a LSE failure is detected
+/// by the LSE clock security system.
+///
+/// See RM0453 rev 2 section 6.2.3 "MSI (multi-speed internal) RC oscillator
+/// clock".
+///
+/// The MSI must be on (`MSION`).
+/// The MSI is on after reset, and it is always on while it is the system
+/// clock.
+/// The MSI never becomes ready while it is off, this checks `MSION` instead
+/// of waiting forever.
+///
+/// # Errors
+///
+/// The MSI PLL is not enabled if an error is returned.
+///
+/// * [`Error::MsiOff`] the MSI is off.
+/// * [`Error::LseNotReady`] the LSE is not ready.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{pac, rcc::enable_msi_pll};
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+///
+/// // disable backup domain write protect
+/// dp.PWR.cr1.modify(|_, w| w.dbp().enabled());
+///
+/// // enable the LSE clock
+/// dp.RCC.bdcr.modify(|_, w| w.lseon().on());
+/// while dp.RCC.bdcr.read().lserdy().is_not_ready() {}
+///
+/// enable_msi_pll(&mut dp.RCC)?;
+/// # Ok::<(), stm32wlxx_hal::rcc::Error>(())
+/// ```
+#[inline]
+pub fn enable_msi_pll(rcc: &mut pac::RCC) -> Result<(), Error> {
+    if rcc.cr.read().msion().is_disabled() {
+        return Err(Error::MsiOff);
+    }
+    // MSIPLLEN must be set after LSERDY is set
+    if rcc.bdcr.read().lserdy().is_not_ready() {
+        return Err(Error::LseNotReady);
+    }
+    rcc.cr.modify(|_, w| w.msipllen().on());
+    while rcc.cr.read().msirdy().is_not_ready() {}
+    Ok(())
+}
+
+/// Disable the MSI phase-locked loop (PLL-mode).
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{pac, rcc::disable_msi_pll};
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+/// disable_msi_pll(&mut dp.RCC);
+/// ```
+#[inline]
+pub fn disable_msi_pll(rcc: &mut pac::RCC) {
+    rcc.cr.modify(|_, w| w.msipllen().off())
+}
+
+/// Returns `true` if the MSI phase-locked loop (PLL-mode) is enabled.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{pac, rcc::is_msi_pll_enabled};
+///
+/// let dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+///
+/// // MSI PLL-mode is disabled at power on
+/// assert!(!is_msi_pll_enabled(&dp.RCC));
+/// ```
+#[inline]
+#[must_use]
+pub fn is_msi_pll_enabled(rcc: &pac::RCC) -> bool {
+    rcc.cr.read().msipllen().is_on()
+}
+
+/// Clock tree specification violations.
+///
+/// Returned by [`validate`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockError {
+    /// SYSCLK exceeds the maximum frequency for the voltage range.
+    Sysclk,
+    /// An AHB clock (HCLK1, HCLK2, or HCLK3) exceeds the maximum frequency
+    /// for the voltage range.
+    Hclk,
+    /// An APB clock (PCLK1 or PCLK2) exceeds the maximum frequency for the
+    /// voltage range.
+    Pclk,
+    /// The flash has fewer wait states than required for HCLK3.
+    FlashLatency,
+    /// The ADC clock exceeds the maximum frequency, or the ADC uses the
+    /// undivided PCLK without a 50% duty cycle.
+    Adc,
+}
+
+/// Snapshot of the clock tree for [`validate`].
+#[derive(Debug, Clone, Copy)]
+struct ClockTree {
+    vos: Vos,
+    latency: u8,
+    sysclk: u32,
+    hclk1: u32,
+    hclk2: u32,
+    hclk3: u32,
+    pclk1: u32,
+    pclk2: u32,
+    adc: Option<u32>,
+    adc_pclk_undivided: bool,
+    pclk2_50_duty: bool,
+}
+
+impl Vos {
+    /// Maximum SYSCLK, HCLK, and PCLK frequency in the voltage range.
+    ///
+    /// See DS13293 rev 1 "General operating conditions".
+    const fn max_hz(self) -> u32 {
+        match self {
+            Vos::V1_2 => 48_000_000,
+            Vos::V1_0 => 16_000_000,
+        }
+    }
+
+    /// Minimum flash wait states for a HCLK3 frequency.
+    ///
+    /// See RM0453 rev 2 section 3 "Embedded flash memory (FLASH)".
+    const fn min_latency(self, hclk3: u32) -> u8 {
+        match self {
+            Vos::V1_2 => match hclk3 {
+                0..=18_000_000 => 0,
+                18_000_001..=36_000_000 => 1,
+                _ => 2,
+            },
+            Vos::V1_0 => match hclk3 {
+                0..=6_000_000 => 0,
+                6_000_001..=12_000_000 => 1,
+                _ => 2,
+            },
+        }
+    }
+}
+
+fn check_clock_tree(tree: &ClockTree) -> Result<(), ClockError> {
+    let max: u32 = tree.vos.max_hz();
+    if tree.sysclk > max {
+        Err(ClockError::Sysclk)
+    } else if tree.hclk1 > max || tree.hclk2 > max || tree.hclk3 > max {
+        Err(ClockError::Hclk)
+    } else if tree.pclk1 > max || tree.pclk2 > max {
+        Err(ClockError::Pclk)
+    } else if tree.latency < tree.vos.min_latency(tree.hclk3) {
+        Err(ClockError::FlashLatency)
+    } else if tree.adc.map_or(false, |hz| hz > crate::adc::ADC_MAX_HZ)
+        || (tree.adc_pclk_undivided && !tree.pclk2_50_duty)
+    {
+        Err(ClockError::Adc)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the current clock tree is within specification.
+///
+/// This reads the live registers and checks the following, returning the
+/// first violation:
+///
+/// 1. SYSCLK is within the maximum for the voltage range.
+/// 2. HCLK1, HCLK2, and HCLK3 are within the maximum for the voltage range.
+/// 3. PCLK1 and PCLK2 are within the maximum for the voltage range.
+/// 4. The flash has enough wait states for HCLK3,
+///    more wait states than required are allowed.
+/// 5. If the ADC clock is enabled the ADC clock is within the maximum,
+///    and the undivided PCLK is only used as the ADC clock if the AHB and
+///    APB2 prescalers are bypassed (50% duty cycle).
+///
+/// The maximum frequency is 48 MHz in range 1 ([`Vos::V1_2`]) and 16 MHz in
+/// range 2 ([`Vos::V1_0`]).
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{pac, rcc};
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+///
+/// cortex_m::interrupt::free(|cs| unsafe {
+///     rcc::set_sysclk_msi_max(&mut dp.FLASH, &mut dp.PWR, &mut dp.RCC, cs)
+/// });
+/// rcc::validate(&dp.RCC, &dp.FLASH, &dp.PWR)?;
+/// # Ok::<(), stm32wlxx_hal::rcc::ClockError>(())
+/// ```
+pub fn validate(rcc: &pac::RCC, flash: &pac::FLASH, pwr: &pac::PWR) -> Result<(), ClockError> {
+    let cfgr: pac::rcc::cfgr::R = rcc.cfgr.read();
+
+    let vos: Vos = match pwr.cr1.read().vos().bits() {
+        0b10 => Vos::V1_0,
+        _ => Vos::V1_2,
+    };
+
+    #[cfg(any(feature = "stm32wl5x_cm4", feature = "stm32wl5x_cm0p"))]
+    let hclk2: u32 = hclk2(rcc, &cfgr).to_integer();
+    #[cfg(not(any(feature = "stm32wl5x_cm4", feature = "stm32wl5x_cm0p")))]
+    let hclk2: u32 = 0;
+
+    // the PAC for the Cortex-M0+ does not have the ADC
+    #[cfg(not(feature = "stm32wl5x_cm0p"))]
+    let (adc, adc_pclk_undivided): (Option<u32>, bool) = if rcc.apb2enr.read().adcen().bit_is_set()
+    {
+        // safety: the stolen driver is only used to read the ADC clock
+        // configuration, no ADC registers are written
+        let adc: crate::adc::Adc = unsafe { crate::adc::Adc::steal() };
+        (
+            adc.clock_hz(rcc),
+            adc.clock_source(rcc) == Some(crate::adc::Clk::PClk),
+        )
+    } else {
+        (None, false)
+    };
+    #[cfg(feature = "stm32wl5x_cm0p")]
+    let (adc, adc_pclk_undivided): (Option<u32>, bool) = (None, false);
+
+    check_clock_tree(&ClockTree {
+        vos,
+        latency: flash.acr.read().latency().bits(),
+        sysclk: sysclk(rcc, &cfgr).to_integer(),
+        hclk1: hclk1(rcc, &cfgr).to_integer(),
+        hclk2,
+        hclk3: hclk3(rcc, &cfgr).to_integer(),
+        pclk1: pclk1(rcc, &cfgr).to_integer(),
+        pclk2: pclk2(rcc, &cfgr).to_integer(),
+        adc,
+        adc_pclk_undivided,
+        pclk2_50_duty: pre_div(cfgr.hpre().bits()) == 1 && ppre_div(cfgr.ppre2().bits()) == 1,
+    })
+}
+
 /// Reset the backup domain.
 ///
 /// # Safety
@@ -957,6 +1218,376 @@ pub unsafe fn pulse_reset_backup_domain(rcc: &mut pac::RCC, pwr: &mut pac::PWR)
     rcc.bdcr.modify(|_, w| w.bdrst().clear_bit());
 }
 
+/// Peripheral bus, selects the RCC enable and reset registers.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Bus {
+    /// `AHB1ENR` and `AHB1RSTR`.
+    Ahb1,
+    /// `AHB2ENR` and `AHB2RSTR`.
+    Ahb2,
+    /// `AHB3ENR` and `AHB3RSTR`.
+    Ahb3,
+    /// `APB1ENR1` and `APB1RSTR1`.
+    Apb1r1,
+    /// `APB1ENR2` and `APB1RSTR2`.
+    Apb1r2,
+    /// `APB2ENR` and `APB2RSTR`.
+    Apb2,
+    /// `APB3ENR` and `APB3RSTR`.
+    Apb3,
+}
+
+/// Peripherals with a clock enable and a reset bit in the RCC.
+///
+/// The window watchdog is the exception, it has a clock enable bit but no
+/// reset bit.
+///
+/// Used with [`enable`], [`disable`], [`is_enabled`], and [`reset`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Peripheral {
+    /// DMA controller 1.
+    Dma1,
+    /// DMA controller 2.
+    Dma2,
+    /// DMA request multiplexer.
+    Dmamux,
+    /// Cyclic redundancy check calculation unit.
+    Crc,
+    /// GPIO port A.
+    GpioA,
+    /// GPIO port B.
+    GpioB,
+    /// GPIO port C.
+    GpioC,
+    /// GPIO port H.
+    GpioH,
+    /// Public key accelerator.
+    Pka,
+    /// Advanced encryption standard hardware accelerator.
+    Aes,
+    /// True random number generator.
+    Rng,
+    /// Hardware semaphore.
+    Hsem,
+    /// Inter-processor communication controller.
+    #[cfg(not(feature = "stm32wle5"))]
+    Ipcc,
+    /// General purpose timer 2.
+    Tim2,
+    /// Window watchdog.
+    ///
+    /// This has no reset bit, [`reset`] has no effect.
+    Wwdg,
+    /// SPI 2.
+    Spi2,
+    /// USART 2.
+    Usart2,
+    /// I2C 1.
+    I2c1,
+    /// I2C 2.
+    I2c2,
+    /// I2C 3.
+    I2c3,
+    /// Digital to analog converter.
+    Dac,
+    /// Low-power timer 1.
+    Lptim1,
+    /// Low-power UART.
+    Lpuart,
+    /// Low-power timer 2.
+    Lptim2,
+    /// Low-power timer 3.
+    Lptim3,
+    /// Analog to digital converter.
+    Adc,
+    /// Advanced control timer 1.
+    Tim1,
+    /// SPI 1.
+    Spi1,
+    /// USART 1.
+    Usart1,
+    /// General purpose timer 16.
+    Tim16,
+    /// General purpose timer 17.
+    Tim17,
+    /// Sub-GHz radio SPI.
+    Spi3,
+}
+
+impl Peripheral {
+    /// Get the bus the peripheral is on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::rcc::{Bus, Peripheral};
+    ///
+    /// assert_eq!(Peripheral::Aes.bus(), Bus::Ahb3);
+    /// assert_eq!(Peripheral::Adc.bus(), Bus::Apb2);
+    /// ```
+    pub const fn bus(self) -> Bus {
+        match self {
+            Peripheral::Dma1 | Peripheral::Dma2 | Peripheral::Dmamux | Peripheral::Crc => Bus::Ahb1,
+            Peripheral::GpioA | Peripheral::GpioB | Peripheral::GpioC | Peripheral::GpioH => {
+                Bus::Ahb2
+            }
+            Peripheral::Pka | Peripheral::Aes | Peripheral::Rng | Peripheral::Hsem => Bus::Ahb3,
+            #[cfg(not(feature = "stm32wle5"))]
+            Peripheral::Ipcc => Bus::Ahb3,
+            Peripheral::Tim2
+            | Peripheral::Wwdg
+            | Peripheral::Spi2
+            | Peripheral::Usart2
+            | Peripheral::I2c1
+            | Peripheral::I2c2
+            | Peripheral::I2c3
+            | Peripheral::Dac
+            | Peripheral::Lptim1 => Bus::Apb1r1,
+            Peripheral::Lpuart | Peripheral::Lptim2 | Peripheral::Lptim3 => Bus::Apb1r2,
+            Peripheral::Adc
+            | Peripheral::Tim1
+            | Peripheral::Spi1
+            | Peripheral::Usart1
+            | Peripheral::Tim16
+            | Peripheral::Tim17 => Bus::Apb2,
+            Peripheral::Spi3 => Bus::Apb3,
+        }
+    }
+
+    /// Get the bit position of the peripheral in the enable and reset
+    /// registers of its [`bus`](Self::bus).
+    ///
+    /// The enable and reset bits share the same position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::rcc::Peripheral;
+    ///
+    /// assert_eq!(Peripheral::Aes.bit(), 17);
+    /// assert_eq!(Peripheral::Adc.bit(), 9);
+    /// ```
+    pub const fn bit(self) -> u8 {
+        match self {
+            Peripheral::Dma1 => 0,
+            Peripheral::Dma2 => 1,
+            Peripheral::Dmamux => 2,
+            Peripheral::Crc => 12,
+            Peripheral::GpioA => 0,
+            Peripheral::GpioB => 1,
+            Peripheral::GpioC => 2,
+            Peripheral::GpioH => 7,
+            Peripheral::Pka => 16,
+            Peripheral::Aes => 17,
+            Peripheral::Rng => 18,
+            Peripheral::Hsem => 19,
+            #[cfg(not(feature = "stm32wle5"))]
+            Peripheral::Ipcc => 20,
+            Peripheral::Tim2 => 0,
+            Peripheral::Wwdg => 11,
+            Peripheral::Spi2 => 14,
+            Peripheral::Usart2 => 17,
+            Peripheral::I2c1 => 21,
+            Peripheral::I2c2 => 22,
+            Peripheral::I2c3 => 23,
+            Peripheral::Dac => 29,
+            Peripheral::Lptim1 => 31,
+            Peripheral::Lpuart => 0,
+            Peripheral::Lptim2 => 5,
+            Peripheral::Lptim3 => 6,
+            Peripheral::Adc => 9,
+            Peripheral::Tim1 => 11,
+            Peripheral::Spi1 => 12,
+            Peripheral::Usart1 => 14,
+            Peripheral::Tim16 => 17,
+            Peripheral::Tim17 => 18,
+            Peripheral::Spi3 => 0,
+        }
+    }
+
+    const fn mask(self) -> u32 {
+        1 << self.bit()
+    }
+}
+
+// Expands `$body` with `$reg` bound to the register of `$bus`.
+macro_rules! on_bus {
+    (
+        $bus:expr,
+        [$ahb1:expr, $ahb2:expr, $ahb3:expr, $apb1r1:expr, $apb1r2:expr, $apb2:expr, $apb3:expr],
+        |$reg:ident| $body:expr
+    ) => {
+        match $bus {
+            Bus::Ahb1 => {
+                let $reg = &$ahb1;
+                $body
+            }
+            Bus::Ahb2 => {
+                let $reg = &$ahb2;
+                $body
+            }
+            Bus::Ahb3 => {
+                let $reg = &$ahb3;
+                $body
+            }
+            Bus::Apb1r1 => {
+                let $reg = &$apb1r1;
+                $body
+            }
+            Bus::Apb1r2 => {
+                let $reg = &$apb1r2;
+                $body
+            }
+            Bus::Apb2 => {
+                let $reg = &$apb2;
+                $body
+            }
+            Bus::Apb3 => {
+                let $reg = &$apb3;
+                $body
+            }
+        }
+    };
+}
+
+macro_rules! on_enr {
+    ($rcc:ident, $bus:expr, |$reg:ident| $body:expr) => {
+        on_bus!(
+            $bus,
+            [
+                $rcc.ahb1enr,
+                $rcc.ahb2enr,
+                $rcc.ahb3enr,
+                $rcc.apb1enr1,
+                $rcc.apb1enr2,
+                $rcc.apb2enr,
+                $rcc.apb3enr
+            ],
+            |$reg| $body
+        )
+    };
+}
+
+/// Enable the clock of a peripheral.
+///
+/// This reads back the enable register after setting the bit, which
+/// provides the delay required after enabling an RCC peripheral clock
+/// before the peripheral registers can be accessed.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{
+///     pac,
+///     rcc::{self, Peripheral},
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+/// rcc::enable(&mut dp.RCC, Peripheral::Aes);
+/// assert!(rcc::is_enabled(&dp.RCC, Peripheral::Aes));
+/// ```
+#[inline]
+pub fn enable(rcc: &mut pac::RCC, periph: Peripheral) {
+    let mask: u32 = periph.mask();
+    on_enr!(rcc, periph.bus(), |reg| {
+        reg.modify(|r, w| unsafe { w.bits(r.bits() | mask) });
+        reg.read(); // delay after an RCC peripheral clock enabling
+    })
+}
+
+/// Disable the clock of a peripheral.
+///
+/// # Safety
+///
+/// 1. Ensure nothing is using the peripheral before disabling the clock.
+/// 2. You are responsible for re-enabling the clock before using the peripheral.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{
+///     pac,
+///     rcc::{self, Peripheral},
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+/// // safety: AES is not in use
+/// unsafe { rcc::disable(&mut dp.RCC, Peripheral::Aes) };
+/// ```
+#[inline]
+pub unsafe fn disable(rcc: &mut pac::RCC, periph: Peripheral) {
+    let mask: u32 = periph.mask();
+    on_enr!(rcc, periph.bus(), |reg| reg
+        .modify(|r, w| w.bits(r.bits() & !mask)))
+}
+
+/// Returns `true` if the clock of a peripheral is enabled.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{
+///     pac,
+///     rcc::{self, Peripheral},
+/// };
+///
+/// let dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+/// let rng_enabled: bool = rcc::is_enabled(&dp.RCC, Peripheral::Rng);
+/// ```
+#[inline]
+pub fn is_enabled(rcc: &pac::RCC, periph: Peripheral) -> bool {
+    let mask: u32 = periph.mask();
+    on_enr!(rcc, periph.bus(), |reg| reg.read().bits() & mask != 0)
+}
+
+/// Pulse the reset of a peripheral.
+///
+/// This has no effect for [`Peripheral::Wwdg`], which does not have a reset
+/// bit.
+///
+/// # Safety
+///
+/// 1. Ensure nothing is using the peripheral before calling this function.
+/// 2. You are responsible for setting up the peripheral after a reset.
+///
+/// # Example
+///
+/// ```no_run
+/// use stm32wlxx_hal::{
+///     pac,
+///     rcc::{self, Peripheral},
+/// };
+///
+/// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+/// // safety: CRC is not in use
+/// unsafe { rcc::reset(&mut dp.RCC, Peripheral::Crc) };
+/// ```
+#[inline]
+pub unsafe fn reset(rcc: &mut pac::RCC, periph: Peripheral) {
+    if periph == Peripheral::Wwdg {
+        return;
+    }
+    let mask: u32 = periph.mask();
+    on_bus!(
+        periph.bus(),
+        [
+            rcc.ahb1rstr,
+            rcc.ahb2rstr,
+            rcc.ahb3rstr,
+            rcc.apb1rstr1,
+            rcc.apb1rstr2,
+            rcc.apb2rstr,
+            rcc.apb3rstr
+        ],
+        |reg| {
+            reg.modify(|r, w| w.bits(r.bits() | mask));
+            reg.modify(|r, w| w.bits(r.bits() & !mask));
+        }
+    )
+}
+
 /// Low-speed oscillator output pin.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -1054,3 +1685,178 @@ impl Lsco {
         self.pin
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_clock_tree, Bus, ClockError, ClockTree, Peripheral, Vos};
+
+    // 48 MHz MSI with 2 wait states
+    const VALID: ClockTree = ClockTree {
+        vos: Vos::V1_2,
+        latency: 2,
+        sysclk: 48_000_000,
+        hclk1: 48_000_000,
+        hclk2: 48_000_000,
+        hclk3: 48_000_000,
+        pclk1: 48_000_000,
+        pclk2: 48_000_000,
+        adc: Some(12_000_000),
+        adc_pclk_undivided: false,
+        pclk2_50_duty: true,
+    };
+
+    #[test]
+    fn valid() {
+        assert_eq!(check_clock_tree(&VALID), Ok(()));
+
+        // reset clock tree, 4 MHz MSI with 0 wait states
+        let reset: ClockTree = ClockTree {
+            latency: 0,
+            sysclk: 4_000_000,
+            hclk1: 4_000_000,
+            hclk2: 4_000_000,
+            hclk3: 4_000_000,
+            pclk1: 4_000_000,
+            pclk2: 4_000_000,
+            adc: None,
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&reset), Ok(()));
+
+        // more wait states than required are allowed
+        let slow_flash: ClockTree = ClockTree {
+            latency: 2,
+            ..reset
+        };
+        assert_eq!(check_clock_tree(&slow_flash), Ok(()));
+    }
+
+    #[test]
+    fn flash_latency() {
+        for latency in 0..2 {
+            let tree: ClockTree = ClockTree { latency, ..VALID };
+            assert_eq!(check_clock_tree(&tree), Err(ClockError::FlashLatency));
+        }
+
+        // 16 MHz in range 2 requires 2 wait states
+        let tree: ClockTree = ClockTree {
+            vos: Vos::V1_0,
+            latency: 1,
+            sysclk: 16_000_000,
+            hclk1: 16_000_000,
+            hclk2: 16_000_000,
+            hclk3: 16_000_000,
+            pclk1: 16_000_000,
+            pclk2: 16_000_000,
+            adc: None,
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&tree), Err(ClockError::FlashLatency));
+        assert_eq!(check_clock_tree(&ClockTree { latency: 2, ..tree }), Ok(()));
+    }
+
+    #[test]
+    fn frequency_limits() {
+        // 48 MHz is out of range 2
+        let tree: ClockTree = ClockTree {
+            vos: Vos::V1_0,
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&tree), Err(ClockError::Sysclk));
+
+        let tree: ClockTree = ClockTree {
+            sysclk: 64_000_000,
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&tree), Err(ClockError::Sysclk));
+
+        let tree: ClockTree = ClockTree {
+            hclk2: 48_000_001,
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&tree), Err(ClockError::Hclk));
+
+        let tree: ClockTree = ClockTree {
+            pclk1: 48_000_001,
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&tree), Err(ClockError::Pclk));
+    }
+
+    #[test]
+    fn adc() {
+        let tree: ClockTree = ClockTree {
+            adc: Some(48_000_000),
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&tree), Err(ClockError::Adc));
+
+        let tree: ClockTree = ClockTree {
+            adc: Some(24_000_000),
+            adc_pclk_undivided: true,
+            pclk2_50_duty: false,
+            ..VALID
+        };
+        assert_eq!(check_clock_tree(&tree), Err(ClockError::Adc));
+        assert_eq!(
+            check_clock_tree(&ClockTree {
+                pclk2_50_duty: true,
+                ..tree
+            }),
+            Ok(())
+        );
+    }
+
+    // RM0453 section 6.4, enable and reset register bit positions
+    const PERIPHERALS: &[(Peripheral, Bus, u8)] = &[
+        (Peripheral::Dma1, Bus::Ahb1, 0),
+        (Peripheral::Dma2, Bus::Ahb1, 1),
+        (Peripheral::Dmamux, Bus::Ahb1, 2),
+        (Peripheral::Crc, Bus::Ahb1, 12),
+        (Peripheral::GpioA, Bus::Ahb2, 0),
+        (Peripheral::GpioB, Bus::Ahb2, 1),
+        (Peripheral::GpioC, Bus::Ahb2, 2),
+        (Peripheral::GpioH, Bus::Ahb2, 7),
+        (Peripheral::Pka, Bus::Ahb3, 16),
+        (Peripheral::Aes, Bus::Ahb3, 17),
+        (Peripheral::Rng, Bus::Ahb3, 18),
+        (Peripheral::Hsem, Bus::Ahb3, 19),
+        #[cfg(not(feature = "stm32wle5"))]
+        (Peripheral::Ipcc, Bus::Ahb3, 20),
+        (Peripheral::Tim2, Bus::Apb1r1, 0),
+        (Peripheral::Wwdg, Bus::Apb1r1, 11),
+        (Peripheral::Spi2, Bus::Apb1r1, 14),
+        (Peripheral::Usart2, Bus::Apb1r1, 17),
+        (Peripheral::I2c1, Bus::Apb1r1, 21),
+        (Peripheral::I2c2, Bus::Apb1r1, 22),
+        (Peripheral::I2c3, Bus::Apb1r1, 23),
+        (Peripheral::Dac, Bus::Apb1r1, 29),
+        (Peripheral::Lptim1, Bus::Apb1r1, 31),
+        (Peripheral::Lpuart, Bus::Apb1r2, 0),
+        (Peripheral::Lptim2, Bus::Apb1r2, 5),
+        (Peripheral::Lptim3, Bus::Apb1r2, 6),
+        (Peripheral::Adc, Bus::Apb2, 9),
+        (Peripheral::Tim1, Bus::Apb2, 11),
+        (Peripheral::Spi1, Bus::Apb2, 12),
+        (Peripheral::Usart1, Bus::Apb2, 14),
+        (Peripheral::Tim16, Bus::Apb2, 17),
+        (Peripheral::Tim17, Bus::Apb2, 18),
+        (Peripheral::Spi3, Bus::Apb3, 0),
+    ];
+
+    #[test]
+    fn peripheral_bits() {
+        PERIPHERALS.iter().for_each(|&(periph, bus, bit)| {
+            assert_eq!(periph.bus(), bus, "{periph:?}");
+            assert_eq!(periph.bit(), bit, "{periph:?}");
+            assert_eq!(periph.mask(), 1 << bit, "{periph:?}");
+        });
+
+        // no two peripherals share a bit
+        PERIPHERALS.iter().enumerate().for_each(|(n, &(a, ..))| {
+            PERIPHERALS.iter().skip(n + 1).for_each(|&(b, ..)| {
+                assert!((a.bus(), a.bit()) != (b.bus(), b.bit()), "{a:?} {b:?}");
+            })
+        });
+    }
+}
@@ -13,7 +13,10 @@
 //!
 //! [chacha]: https://crates.io/crates/chacha20
 
-use crate::pac;
+use crate::{
+    pac,
+    rcc::{self, Peripheral},
+};
 
 use core::num::NonZeroU32;
 
@@ -33,6 +36,11 @@ pub enum Error {
     ///
     /// Check that the RNG is configured correctly.
     Clock,
+    /// The raw entropy failed a health test.
+    ///
+    /// Returned by [`Rng::fill_bytes_conditioned`], see that method for
+    /// details.
+    HealthTest,
 }
 
 impl From<Error> for rand_core::Error {
@@ -42,17 +50,119 @@ impl From<Error> for rand_core::Error {
             Error::Seed => unsafe { NonZeroU32::new_unchecked(1) }.into(),
             // safety: 2 is non-zero
             Error::Clock => unsafe { NonZeroU32::new_unchecked(2) }.into(),
+            // safety: 3 is non-zero
+            Error::HealthTest => unsafe { NonZeroU32::new_unchecked(3) }.into(),
         }
     }
 }
 
 pub use pac::rcc::ccipr::RNGSEL_A as Clk;
 
+/// Repetition count test cutoff.
+///
+/// NIST SP 800-90B section 4.4.1, `1 + ceil(30 / 8)` for a false positive
+/// probability of 2<sup>-30</sup> with 8 bits of entropy per byte.
+const RCT_CUTOFF: u16 = 5;
+
+/// Adaptive proportion test window size.
+///
+/// NIST SP 800-90B section 4.4.2, for non-binary samples.
+const APT_WINDOW: u16 = 512;
+
+/// Adaptive proportion test cutoff.
+///
+/// NIST SP 800-90B section 4.4.2, occurrences of the first sample in a
+/// window (including the first sample) for a false positive probability of
+/// 2<sup>-30</sup> with 8 bits of entropy per byte.
+const APT_CUTOFF: u16 = 17;
+
+/// Continuous health tests on raw bytes of entropy.
+#[derive(Debug, Clone, Copy)]
+struct HealthTest {
+    rct_sample: u8,
+    rct_count: u16,
+    apt_sample: u8,
+    apt_count: u16,
+    apt_idx: u16,
+}
+
+impl HealthTest {
+    const fn new() -> HealthTest {
+        HealthTest {
+            rct_sample: 0,
+            rct_count: 0,
+            apt_sample: 0,
+            apt_count: 0,
+            apt_idx: 0,
+        }
+    }
+
+    /// Returns `false` if the sample fails a health test.
+    fn check(&mut self, sample: u8) -> bool {
+        // repetition count test
+        if self.rct_count != 0 && sample == self.rct_sample {
+            self.rct_count += 1;
+            if self.rct_count >= RCT_CUTOFF {
+                return false;
+            }
+        } else {
+            self.rct_sample = sample;
+            self.rct_count = 1;
+        }
+
+        // adaptive proportion test
+        if self.apt_idx == 0 {
+            self.apt_sample = sample;
+            self.apt_count = 1;
+        } else if sample == self.apt_sample {
+            self.apt_count += 1;
+            if self.apt_count >= APT_CUTOFF {
+                return false;
+            }
+        }
+        self.apt_idx += 1;
+        if self.apt_idx == APT_WINDOW {
+            self.apt_idx = 0;
+        }
+
+        true
+    }
+}
+
+/// Fill `dst` with pairs of raw words from `src` XOR-folded together,
+/// health testing every byte of the raw words.
+///
+/// The health test state is reset on failure.
+fn fill_conditioned<F>(health: &mut HealthTest, dst: &mut [u8], mut src: F) -> Result<(), Error>
+where
+    F: FnMut() -> Result<u32, Error>,
+{
+    for chunk in dst.chunks_mut(4) {
+        let mut folded: u32 = 0;
+        for _ in 0..2 {
+            let raw: u32 = src()?;
+            if !raw.to_be_bytes().iter().all(|byte| health.check(*byte)) {
+                *health = HealthTest::new();
+                return Err(Error::HealthTest);
+            }
+            folded ^= raw;
+        }
+
+        chunk
+            .iter_mut()
+            .zip(folded.to_be_bytes().iter())
+            .for_each(|(byte, folded)| *byte = *folded);
+    }
+
+    Ok(())
+}
+
 /// RNG driver.
 #[derive(Debug)]
 pub struct Rng {
     rng: pac::RNG,
     err_cnt: u32,
+    health: HealthTest,
 }
 
 impl Rng {
@@ -79,8 +189,8 @@ impl Rng {
     pub fn new(rng: pac::RNG, clk: Clk, rcc: &mut pac::RCC) -> Rng {
         rcc.ccipr.modify(|_, w| w.rngsel().variant(clk));
         Self::enable_clock(rcc);
-        rcc.ahb3rstr.modify(|_, w| w.rngrst().set_bit());
-        rcc.ahb3rstr.modify(|_, w| w.rngrst().clear_bit());
+        // safety: ownership of the peripheral was moved in, nothing else is using it
+        unsafe { rcc::reset(rcc, Peripheral::Rng) };
 
         // RNG configuration A
         // see table 131 "RNG configurations" in the reference manual
@@ -113,7 +223,11 @@ impl Rng {
         // It takes about 2 AHB clock cycles + 2 RNG clock cycles
         while rng.cr.read().condrst().bit_is_set() {}
 
-        Rng { rng, err_cnt: 0 }
+        Rng {
+            rng,
+            err_cnt: 0,
+            health: HealthTest::new(),
+        }
     }
 
     /// Free the RNG peripheral from the driver.
@@ -171,6 +285,7 @@ impl Rng {
         Rng {
             rng: dp.RNG,
             err_cnt: 0,
+            health: HealthTest::new(),
         }
     }
 
@@ -205,14 +320,13 @@ impl Rng {
     ///    while the clock was disabled.
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb3enr.modify(|_, w| w.rngen().disabled());
+        rcc::disable(rcc, Peripheral::Rng);
     }
 
     /// Enable the RNG clock.
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.ahb3enr.modify(|_, w| w.rngen().enabled());
-        rcc.ahb3enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Rng);
     }
 
     /// Returns the number of correctable seed errors that have occurred.
@@ -299,6 +413,51 @@ impl Rng {
         Ok(())
     }
 
+    /// Fill the destination buffer with health tested and conditioned
+    /// random data.
+    ///
+    /// The raw output of the RNG is checked with the continuous health tests
+    /// from NIST SP 800-90B section 4.4:
+    ///
+    /// * Repetition count test: fails if a byte repeats 5 times in a row.
+    /// * Adaptive proportion test: fails if the first byte of a 512 byte
+    ///   window occurs 17 or more times in the window.
+    ///
+    /// The cutoffs assume full entropy from the RNG, with a false positive
+    /// rate of 2<sup>-30</sup> per sample.
+    /// The test state is kept between calls, windows span multiple calls.
+    ///
+    /// Each output word is two raw words XOR-folded together, this uses
+    /// twice as much raw entropy as [`try_fill_u8`](Self::try_fill_u8).
+    ///
+    /// Returns [`Error::HealthTest`] if the raw entropy fails a health test,
+    /// the health test state is reset and the contents of `dst` should be
+    /// discarded.
+    /// This indicates a hardware fault, or a very unlikely false positive,
+    /// retrying once is reasonable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     pac,
+    ///     rng::{Clk, Rng},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let mut rng = Rng::new(dp.RNG, Clk::Msi, &mut dp.RCC);
+    ///
+    /// let mut key: [u8; 32] = [0; 32];
+    /// rng.fill_bytes_conditioned(&mut key)?;
+    /// # Ok::<(), stm32wlxx_hal::rng::Error>(())
+    /// ```
+    pub fn fill_bytes_conditioned(&mut self, dst: &mut [u8]) -> Result<(), Error> {
+        let mut health: HealthTest = self.health;
+        let ret: Result<(), Error> = fill_conditioned(&mut health, dst, || self.try_u32());
+        self.health = health;
+        ret
+    }
+
     /// Try to generate a random `u8`.
     ///
     /// This is not efficient if you need to generate a lot of entropy, this is
@@ -482,3 +641,86 @@ impl rand_core::RngCore for Rng {
 }
 
 impl rand_core::CryptoRng for Rng {}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_conditioned, Error, HealthTest, APT_WINDOW};
+
+    /// xorshift32, a stand-in for a healthy entropy source
+    fn xorshift(state: &mut u32) -> Result<u32, Error> {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        Ok(*state)
+    }
+
+    #[test]
+    fn stuck_at_constant() {
+        let mut health: HealthTest = HealthTest::new();
+        let mut buf: [u8; 32] = [0; 32];
+        assert_eq!(
+            fill_conditioned(&mut health, &mut buf, || Ok(0x5A5A_5A5A)),
+            Err(Error::HealthTest)
+        );
+        // state is reset after a failure
+        assert_eq!(health.rct_count, 0);
+        assert_eq!(health.apt_idx, 0);
+    }
+
+    #[test]
+    fn repetition_count() {
+        let mut health: HealthTest = HealthTest::new();
+        (0..4).for_each(|_| assert!(health.check(0xAB)));
+        assert!(!health.check(0xAB));
+    }
+
+    #[test]
+    fn adaptive_proportion() {
+        // every other sample is the first sample of the window, this never
+        // repeats back-to-back so only the adaptive proportion test fails
+        let mut health: HealthTest = HealthTest::new();
+        let mut failed_at: Option<usize> = None;
+        for n in 0..usize::from(APT_WINDOW) {
+            let sample: u8 = if n % 2 == 0 { 0xAA } else { n as u8 };
+            if !health.check(sample) {
+                failed_at = Some(n);
+                break;
+            }
+        }
+        assert_eq!(failed_at, Some(32));
+    }
+
+    #[test]
+    fn healthy_source() {
+        let mut health: HealthTest = HealthTest::new();
+        let mut state: u32 = 0x1234_5678;
+        let mut buf: [u8; 4096] = [0; 4096];
+        assert_eq!(
+            fill_conditioned(&mut health, &mut buf, || xorshift(&mut state)),
+            Ok(())
+        );
+        assert!(buf.iter().any(|byte| *byte != 0));
+    }
+
+    #[test]
+    fn folding() {
+        let mut health: HealthTest = HealthTest::new();
+        let mut words = [0x0102_0304_u32, 0x1020_3040].into_iter();
+        let mut buf: [u8; 3] = [0; 3];
+        assert_eq!(
+            fill_conditioned(&mut health, &mut buf, || Ok(words.next().unwrap())),
+            Ok(())
+        );
+        assert_eq!(buf, [0x11, 0x22, 0x33]);
+    }
+
+    #[test]
+    fn source_error() {
+        let mut health: HealthTest = HealthTest::new();
+        let mut buf: [u8; 4] = [0; 4];
+        assert_eq!(
+            fill_conditioned(&mut health, &mut buf, || Err(Error::Clock)),
+            Err(Error::Clock)
+        );
+    }
+}
@@ -60,6 +60,35 @@ pub struct Rtc {
     rtc: pac::RTC,
 }
 
+/// Read the calendar with `read_cal` between two reads of the sub-second
+/// counter with `read_ss`, repeating until the counter is unchanged.
+///
+/// If an RTCCLK edge occurs during a read we may see inconsistent values.
+/// See RM0453 Rev 2 32.3.10 page 1002 "Reading the calendar".
+fn read_consistent<T, S, C>(mut read_ss: S, mut read_cal: C) -> (u32, T)
+where
+    S: FnMut() -> u32,
+    C: FnMut() -> T,
+{
+    loop {
+        let ss: u32 = read_ss();
+        let cal: T = read_cal();
+        if ss == read_ss() {
+            return (ss, cal);
+        }
+    }
+}
+
+fn tr_to_time(tr: &pac::rtc::tr::R, micro: u32) -> Option<NaiveTime> {
+    let mut hour: u8 = tr.ht().bits() * 10 + tr.hu().bits();
+    if tr.pm().is_pm() {
+        hour += 12;
+    }
+    let minute: u8 = tr.mnt().bits() * 10 + tr.mnu().bits();
+    let second: u8 = tr.st().bits() * 10 + tr.su().bits();
+    NaiveTime::from_hms_micro_opt(hour as u32, minute as u32, second as u32, micro)
+}
+
 impl Rtc {
     /// Create a new real-time clock driver.
     ///
@@ -415,73 +444,92 @@ impl Rtc {
     ///
     /// Returns `None` if the calendar has not been initialized.
     pub fn time(&self) -> Option<NaiveTime> {
-        loop {
-            self.calendar_initialized()?;
-            let ss: u32 = self.rtc.ssr.read().ss().bits();
-            let data = self.rtc.tr.read();
-
-            // If an RTCCLK edge occurs during read we may see inconsistent values
-            // so read ssr again and see if it has changed
-            // see RM0453 Rev 2 32.3.10 page 1002 "Reading the calendar"
-            let ss_after: u32 = self.rtc.ssr.read().ss().bits();
-            if ss == ss_after {
-                let mut hour: u8 = data.ht().bits() * 10 + data.hu().bits();
-                if data.pm().is_pm() {
-                    hour += 12;
-                }
-                let minute: u8 = data.mnt().bits() * 10 + data.mnu().bits();
-                let second: u8 = data.st().bits() * 10 + data.su().bits();
-                let micro: u32 = self.ss_to_us(ss);
-
-                return NaiveTime::from_hms_micro_opt(
-                    hour as u32,
-                    minute as u32,
-                    second as u32,
-                    micro,
-                );
-            }
-        }
+        self.calendar_initialized()?;
+        let (ss, tr) = read_consistent(|| self.rtc.ssr.read().ss().bits(), || self.rtc.tr.read());
+        tr_to_time(&tr, self.ss_to_us(ss))
     }
 
     /// Calendar Date and Time
     ///
     /// Returns `None` if the calendar has not been initialized.
     pub fn date_time(&self) -> Option<NaiveDateTime> {
-        loop {
-            self.calendar_initialized()?;
-            let ss: u32 = self.rtc.ssr.read().ss().bits();
-            let dr = self.rtc.dr.read();
-            let tr = self.rtc.tr.read();
-
-            // If an RTCCLK edge occurs during a read we may see inconsistent values
-            // so read ssr again and see if it has changed
-            // see RM0453 Rev 2 32.3.10 page 1002 "Reading the calendar"
-            let ss_after: u32 = self.rtc.ssr.read().ss().bits();
-            if ss == ss_after {
-                let year: i32 = 2000 + (dr.yt().bits() as i32) * 10 + (dr.yu().bits() as i32);
-                let month: u8 = dr.mt().bit() as u8 * 10 + dr.mu().bits();
-                let day: u8 = dr.dt().bits() * 10 + dr.du().bits();
-
-                let date: NaiveDate = NaiveDate::from_ymd_opt(year, month as u32, day as u32)?;
-
-                let mut hour: u8 = tr.ht().bits() * 10 + tr.hu().bits();
-                if tr.pm().is_pm() {
-                    hour += 12;
-                }
-                let minute: u8 = tr.mnt().bits() * 10 + tr.mnu().bits();
-                let second: u8 = tr.st().bits() * 10 + tr.su().bits();
-                let micro: u32 = self.ss_to_us(ss);
-
-                let time = NaiveTime::from_hms_micro_opt(
-                    hour as u32,
-                    minute as u32,
-                    second as u32,
-                    micro,
-                )?;
-
-                return Some(date.and_time(time));
-            }
-        }
+        self.instant().map(|(date_time, _)| date_time)
+    }
+
+    /// Sub-second counter.
+    ///
+    /// This is the raw value of the synchronous prescaler counter
+    /// (`RTC_SSR.SS`), it counts down from the synchronous prescaler value
+    /// to `0` once per second.
+    /// The resolution depends on the RTC clock source, with the LSE the
+    /// counter counts down from 255 at 256 Hz.
+    ///
+    /// The RTC is configured to bypass the shadow registers, this reads the
+    /// counter directly and not a value latched by a previous read.
+    /// To time-stamp an event with both the calendar and the sub-second
+    /// counter use [`instant`](Self::instant), separate reads of the
+    /// calendar and the counter can tear at a second boundary.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{pac, rtc};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let rtc = rtc::Rtc::new(dp.RTC, rtc::Clk::Lse, &mut dp.PWR, &mut dp.RCC);
+    /// let ss: u16 = rtc.subsecond();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn subsecond(&self) -> u16 {
+        // running in BCD mode, only 15:0 are used
+        self.rtc.ssr.read().ss().bits() as u16
+    }
+
+    /// Calendar date and time with the sub-second counter.
+    ///
+    /// The sub-second counter is the same value returned by
+    /// [`subsecond`](Self::subsecond), and the same value used for the
+    /// fraction of a second in the date and time.
+    ///
+    /// The registers are read in the order `RTC_SSR`, `RTC_TR`, `RTC_DR`.
+    /// With the shadow registers in use, reading `RTC_SSR` or `RTC_TR` locks
+    /// the higher-order shadow registers until `RTC_DR` is read, this order
+    /// keeps the three values from the same instant.
+    /// With the shadow registers bypassed the registers are not latched, the
+    /// counter (`RTC_SSR`) is read again after `RTC_DR`, and the read is
+    /// repeated if the counter changed.
+    /// See RM0453 Rev 2 32.3.10 page 1002 "Reading the calendar".
+    ///
+    /// Returns `None` if the calendar has not been initialized.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{chrono::NaiveDateTime, pac, rtc};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let rtc = rtc::Rtc::new(dp.RTC, rtc::Clk::Lse, &mut dp.PWR, &mut dp.RCC);
+    /// if let Some((date_time, ss)) = rtc.instant() {
+    ///     // ... log the event
+    /// }
+    /// ```
+    pub fn instant(&self) -> Option<(NaiveDateTime, u16)> {
+        self.calendar_initialized()?;
+        let (ss, (tr, dr)) = read_consistent(
+            || self.rtc.ssr.read().ss().bits(),
+            // TR before DR, reading DR unlocks the shadow registers
+            || (self.rtc.tr.read(), self.rtc.dr.read()),
+        );
+
+        let year: i32 = 2000 + (dr.yt().bits() as i32) * 10 + (dr.yu().bits() as i32);
+        let month: u8 = dr.mt().bit() as u8 * 10 + dr.mu().bits();
+        let day: u8 = dr.dt().bits() * 10 + dr.du().bits();
+
+        let date: NaiveDate = NaiveDate::from_ymd_opt(year, month as u32, day as u32)?;
+        let time: NaiveTime = tr_to_time(&tr, self.ss_to_us(ss))?;
+
+        Some((date.and_time(time), ss as u16))
     }
 
     /// Setup the periodic wakeup timer for `sec + 1` seconds.
@@ -657,3 +705,48 @@ impl Rtc {
         self.rtc.wpr.write(|w| w.key().activate());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::read_consistent;
+    use core::cell::Cell;
+
+    #[test]
+    fn read_consistent_stable() {
+        let reads: Cell<u32> = Cell::new(0);
+        let (ss, cal) = read_consistent(
+            || {
+                reads.set(reads.get() + 1);
+                200
+            },
+            || 42,
+        );
+        assert_eq!((ss, cal), (200, 42));
+        // the counter is read before and after the calendar
+        assert_eq!(reads.get(), 2);
+    }
+
+    #[test]
+    fn read_consistent_rollover() {
+        // the counter and calendar change at a second boundary between the
+        // first counter read and the calendar read
+        let ss_reads: [u32; 4] = [0, 255, 255, 255];
+        let reads: Cell<usize> = Cell::new(0);
+        let seconds: Cell<u32> = Cell::new(9);
+        let (ss, cal) = read_consistent(
+            || {
+                let n: usize = reads.get();
+                reads.set(n + 1);
+                if n == 1 {
+                    // second rolls over
+                    seconds.set(10);
+                }
+                ss_reads[n]
+            },
+            || seconds.get(),
+        );
+        // the torn read (0, 10) is discarded
+        assert_eq!((ss, cal), (255, 10));
+        assert_eq!(reads.get(), 4);
+    }
+}
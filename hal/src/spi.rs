@@ -33,6 +33,7 @@ use crate::{
         Spi1Miso, Spi1Mosi, Spi1Sck, Spi2Miso, Spi2Mosi, Spi2Sck, SpiMiso, SpiMosi, SpiSck,
     },
     pac::{self, SPI1, SPI2},
+    rcc::{self, Peripheral},
 };
 
 pub use embedded_hal::{
@@ -132,6 +133,34 @@ pub enum Error {
     TxDma,
 }
 
+/// Bit order of SPI frames.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BitOrder {
+    /// Most significant bit first, this is the reset value.
+    MsbFirst,
+    /// Least significant bit first.
+    LsbFirst,
+}
+
+const fn lsbfirst_from_bit_order(order: BitOrder) -> bool {
+    match order {
+        BitOrder::MsbFirst => false,
+        BitOrder::LsbFirst => true,
+    }
+}
+
+/// `CR2.DS` and `CR2.FRXTH` values for a frame size in bits.
+const fn ds_frxth_from_frame_size(bits: u8) -> (u8, bool) {
+    core::assert!(
+        bits >= 4 && bits <= 16,
+        "frame size is not in the range 4..=16"
+    );
+    // RXNE is set when the FIFO holds 8 bits for frames up to 8 bits,
+    // and 16 bits for larger frames
+    (bits - 1, bits <= 8)
+}
+
 const fn cpha_from_phase(phase: Phase) -> bool {
     match phase {
         Phase::CaptureOnFirstTransition => false,
@@ -386,6 +415,46 @@ pub(crate) mod sealed {
             }
         }
 
+        fn write_word_u16(&mut self, word: u16) -> Result<(), Error> {
+            loop {
+                if !self.status()?.ftlvl().is_full() {
+                    unsafe { write_volatile(Self::DR as *mut u16, word) };
+                    return Ok(());
+                }
+            }
+        }
+
+        fn read_word_u16(&mut self) -> Result<u16, Error> {
+            loop {
+                if !self.status()?.frlvl().is_empty() {
+                    return Ok(unsafe { read_volatile(Self::DR as *const u16) });
+                }
+            }
+        }
+
+        fn write_simplex_u16(&mut self, words: &[u16]) -> Result<(), Error> {
+            for word in words.iter() {
+                self.write_word_u16(*word)?;
+            }
+            Ok(())
+        }
+
+        fn write_full_duplex_u16(&mut self, words: &[u16]) -> Result<(), Error> {
+            for word in words.iter() {
+                self.write_word_u16(*word)?;
+                let _: u16 = self.read_word_u16()?;
+            }
+            Ok(())
+        }
+
+        fn transfer_u16<'w>(&mut self, words: &'w mut [u16]) -> Result<&'w [u16], Error> {
+            for word in words.iter_mut() {
+                self.write_word_u16(*word)?;
+                *word = self.read_word_u16()?;
+            }
+            Ok(words)
+        }
+
         fn transfer_u8<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Error> {
             for word in words.iter_mut() {
                 self.write_word(*word)?;
@@ -470,8 +539,7 @@ impl<SCK, MISO, MOSI> Spi<pac::SPI1, SCK, MISO, MOSI> {
     /// ```
     #[inline]
     pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb2rstr.modify(|_, w| w.spi1rst().set_bit());
-        rcc.apb2rstr.modify(|_, w| w.spi1rst().clear_bit());
+        rcc::reset(rcc, Peripheral::Spi1);
     }
 
     /// Disable the SPI clock.
@@ -499,7 +567,7 @@ impl<SCK, MISO, MOSI> Spi<pac::SPI1, SCK, MISO, MOSI> {
     /// ```
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb2enr.modify(|_, w| w.spi1en().disabled());
+        rcc::disable(rcc, Peripheral::Spi1);
     }
 
     /// Enable the SPI clock.
@@ -519,8 +587,7 @@ impl<SCK, MISO, MOSI> Spi<pac::SPI1, SCK, MISO, MOSI> {
     /// ```
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb2enr.modify(|_, w| w.spi1en().enabled());
-        rcc.apb2enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Spi1);
     }
 }
 
@@ -545,8 +612,7 @@ impl<SCK, MISO, MOSI> Spi<pac::SPI2, SCK, MISO, MOSI> {
     /// ```
     #[inline]
     pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb1rstr1.modify(|_, w| w.spi2s2rst().set_bit());
-        rcc.apb1rstr1.modify(|_, w| w.spi2s2rst().clear_bit());
+        rcc::reset(rcc, Peripheral::Spi2);
     }
 
     /// Disable the SPI clock.
@@ -574,7 +640,7 @@ impl<SCK, MISO, MOSI> Spi<pac::SPI2, SCK, MISO, MOSI> {
     /// ```
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr1.modify(|_, w| w.spi2s2en().disabled());
+        rcc::disable(rcc, Peripheral::Spi2);
     }
 
     /// Enable the SPI clock.
@@ -594,8 +660,7 @@ impl<SCK, MISO, MOSI> Spi<pac::SPI2, SCK, MISO, MOSI> {
     /// ```
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb1enr1.modify(|_, w| w.spi2s2en().enabled());
-        rcc.apb1enr1.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Spi2);
     }
 }
 
@@ -604,19 +669,17 @@ impl<SCK, MISO, MOSI> Spi<pac::SPI2, SCK, MISO, MOSI> {
 impl<MISO, MOSI> Spi3<MISO, MOSI> {
     #[inline]
     pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-        rcc.apb3rstr.modify(|_, w| w.subghzspirst().set_bit());
-        rcc.apb3rstr.modify(|_, w| w.subghzspirst().clear_bit());
+        rcc::reset(rcc, Peripheral::Spi3);
     }
 
     #[inline]
     pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-        rcc.apb3enr.modify(|_, w| w.subghzspien().disabled());
+        rcc::disable(rcc, Peripheral::Spi3);
     }
 
     #[inline]
     pub fn enable_clock(rcc: &mut pac::RCC) {
-        rcc.apb3enr.modify(|_, w| w.subghzspien().enabled());
-        rcc.apb3enr.read(); // delay after an RCC peripheral clock enabling
+        rcc::enable(rcc, Peripheral::Spi3);
     }
 }
 
@@ -1249,6 +1312,139 @@ impl<SPI: SpiRegs, SCK, MISO, MOSI> Spi<SPI, SCK, MISO, MOSI> {
     pub fn status(&self) -> pac::spi1::sr::R {
         self.spi.sr.read()
     }
+
+    /// Set the frame size (data width) in bits.
+    ///
+    /// The frame size is 8 bits after construction.
+    ///
+    /// Frames of up to 8 bits use byte access to the data register, use the
+    /// `u8` [`Transfer`] and [`Write`] traits.
+    /// Frames larger than 8 bits use half-word access, use
+    /// [`transfer16`](Self::transfer16) and [`write16`](Self::write16).
+    /// Frames smaller than the access size are right-aligned, the unused
+    /// upper bits are ignored on transmit and read as zero on receive.
+    ///
+    /// This also sets the RX FIFO threshold (`CR2.FRXTH`) to match the
+    /// access size, 8 bits for frames up to 8 bits, and 16 bits for larger
+    /// frames.
+    /// The RX FIFO is 32 bits, with sub-byte frames each frame occupies a
+    /// byte in the FIFO, and the FIFO holds 4 frames.
+    ///
+    /// See RM0453 rev 2 section 36.4.9 "Data transmission and reception
+    /// procedures".
+    ///
+    /// # Panics
+    ///
+    /// * `bits` is not in the range `4..=16`
+    /// * (debug) The SPI is busy
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     cortex_m,
+    ///     gpio::PortA,
+    ///     pac,
+    ///     spi::{BaudRate::Div2, Spi, MODE_0},
+    /// };
+    ///
+    /// let mut dp = pac::Peripherals::take().unwrap();
+    ///
+    /// let pa = PortA::split(dp.GPIOA, &mut dp.RCC);
+    /// let mut spi = cortex_m::interrupt::free(|cs| {
+    ///     Spi::new_spi1_full_duplex(
+    ///         dp.SPI1,
+    ///         (pa.a5, pa.a6, pa.a7),
+    ///         MODE_0,
+    ///         Div2,
+    ///         &mut dp.RCC,
+    ///         cs,
+    ///     )
+    /// });
+    /// spi.set_frame_size(16);
+    /// let mut words: [u16; 2] = [0x1234, 0x5678];
+    /// spi.transfer16(&mut words)?;
+    /// # Ok::<(), stm32wlxx_hal::spi::Error>(())
+    /// ```
+    pub fn set_frame_size(&mut self, bits: u8) {
+        let (ds, frxth): (u8, bool) = ds_frxth_from_frame_size(bits);
+        debug_assert!(self.spi.sr.read().bsy().is_not_busy());
+        self.spi.cr1.modify(|_, w| w.spe().clear_bit());
+        // safety: ds is in the range 3..=15, all values are valid
+        self.spi
+            .cr2
+            .modify(|_, w| unsafe { w.ds().bits(ds) }.frxth().bit(frxth));
+        self.spi.cr1.modify(|_, w| w.spe().set_bit());
+    }
+
+    /// Set the bit order of frames.
+    ///
+    /// The bit order is [`BitOrder::MsbFirst`] after construction.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The SPI is busy
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     cortex_m,
+    ///     gpio::PortA,
+    ///     pac,
+    ///     spi::{BaudRate::Div2, BitOrder, Spi, MODE_0},
+    /// };
+    ///
+    /// let mut dp = pac::Peripherals::take().unwrap();
+    ///
+    /// let pa = PortA::split(dp.GPIOA, &mut dp.RCC);
+    /// let mut spi = cortex_m::interrupt::free(|cs| {
+    ///     Spi::new_spi1_mosi_simplex(dp.SPI1, (pa.a5, pa.a7), MODE_0, Div2, &mut dp.RCC, cs)
+    /// });
+    /// spi.set_bit_order(BitOrder::LsbFirst);
+    /// ```
+    pub fn set_bit_order(&mut self, order: BitOrder) {
+        debug_assert!(self.spi.sr.read().bsy().is_not_busy());
+        self.spi.cr1.modify(|_, w| w.spe().clear_bit());
+        self.spi.cr1.modify(|_, w| {
+            w.lsbfirst()
+                .bit(lsbfirst_from_bit_order(order))
+                .spe()
+                .set_bit()
+        });
+    }
+}
+
+impl<SPI: SpiRegs, SCK: SpiSck, MISO: SpiMiso, MOSI: SpiMosi> Spi<SPI, SCK, MISO, MOSI> {
+    /// Transfer frames larger than 8 bits in-place.
+    ///
+    /// The frame size must be set with
+    /// [`set_frame_size`](Self::set_frame_size) before use.
+    ///
+    /// # Example
+    ///
+    /// See [`set_frame_size`](Self::set_frame_size).
+    pub fn transfer16<'w>(&mut self, words: &'w mut [u16]) -> Result<&'w [u16], Error> {
+        self.spi.transfer_u16(words)
+    }
+
+    /// Write frames larger than 8 bits, discarding received data.
+    ///
+    /// The frame size must be set with
+    /// [`set_frame_size`](Self::set_frame_size) before use.
+    pub fn write16(&mut self, words: &[u16]) -> Result<(), Error> {
+        self.spi.write_full_duplex_u16(words)
+    }
+}
+
+impl<SPI: SpiRegs, SCK: SpiSck, MOSI: SpiMosi> Spi<SPI, SCK, NoMiso, MOSI> {
+    /// Write frames larger than 8 bits.
+    ///
+    /// The frame size must be set with
+    /// [`set_frame_size`](Self::set_frame_size) before use.
+    pub fn write16(&mut self, words: &[u16]) -> Result<(), Error> {
+        self.spi.write_simplex_u16(words)
+    }
 }
 
 impl<SPI: SpiRegs, SCK: SpiSck, MISO: SpiMiso, MISODMA: DmaCh> Write<u8>
@@ -1357,3 +1553,37 @@ impl<MISODMA: DmaCh, MOSIDMA: DmaCh> Write<u8> for Spi3<MISODMA, MOSIDMA> {
             .write_full_duplex_u8_dma(&mut self.miso, &mut self.mosi, words)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ds_frxth_from_frame_size, lsbfirst_from_bit_order, BitOrder};
+
+    #[test]
+    fn frame_size_encoding() {
+        // RM0453 rev 2 SPI_CR2 DS field, 0b0011 is 4-bit, 0b1111 is 16-bit
+        assert_eq!(ds_frxth_from_frame_size(4), (0b0011, true));
+        assert_eq!(ds_frxth_from_frame_size(7), (0b0110, true));
+        // reset value
+        assert_eq!(ds_frxth_from_frame_size(8), (0b0111, true));
+        assert_eq!(ds_frxth_from_frame_size(9), (0b1000, false));
+        assert_eq!(ds_frxth_from_frame_size(16), (0b1111, false));
+    }
+
+    #[test]
+    #[should_panic]
+    fn frame_size_too_small() {
+        ds_frxth_from_frame_size(3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn frame_size_too_large() {
+        ds_frxth_from_frame_size(17);
+    }
+
+    #[test]
+    fn bit_order_encoding() {
+        assert!(!lsbfirst_from_bit_order(BitOrder::MsbFirst));
+        assert!(lsbfirst_from_bit_order(BitOrder::LsbFirst));
+    }
+}
@@ -228,3 +228,45 @@ impl Default for CadParams {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CadParams, ExitMode, NbCadSymbol};
+    use crate::subghz::Timeout;
+
+    #[test]
+    fn default_encoding() {
+        assert_eq!(
+            CadParams::new().as_slice(),
+            &[0x88, 0x00, 0x18, 0x10, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn num_symbol_encoding() {
+        const SYMBOLS: [(NbCadSymbol, u8); 5] = [
+            (NbCadSymbol::S1, 0x0),
+            (NbCadSymbol::S2, 0x1),
+            (NbCadSymbol::S4, 0x2),
+            (NbCadSymbol::S8, 0x3),
+            (NbCadSymbol::S16, 0x4),
+        ];
+        for (nb, bits) in SYMBOLS {
+            assert_eq!(CadParams::new().set_num_symbol(nb).as_slice()[1], bits);
+        }
+    }
+
+    #[test]
+    fn full_encoding() {
+        let params: CadParams = CadParams::new()
+            .set_num_symbol(NbCadSymbol::S16)
+            .set_det_peak(0x25)
+            .set_det_min(0x0A)
+            .set_exit_mode(ExitMode::StandbyLoRa)
+            .set_timeout(Timeout::from_raw(0xABCDEF));
+        assert_eq!(
+            params.as_slice(),
+            &[0x88, 0x04, 0x25, 0x0A, 0x01, 0xAB, 0xCD, 0xEF]
+        );
+    }
+}
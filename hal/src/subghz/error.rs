@@ -0,0 +1,43 @@
+/// Sub-GHz radio errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// SPI bus error.
+    Spi(crate::spi::Error),
+    /// Timeout waiting for the radio busy signal to clear.
+    ///
+    /// This can occur if the radio has browned-out, or if the radio is in
+    /// sleep mode.
+    Timeout,
+    /// The data does not fit in the 256 byte radio buffer at the given
+    /// offset.
+    Buffer,
+    /// The radio reported a command failure in the status byte.
+    ///
+    /// Only commands that read a response from the radio receive a status
+    /// byte.
+    /// The command status is either [`CmdStatus::ProcessingError`] or
+    /// [`CmdStatus::ExecutionFailure`].
+    ///
+    /// [`CmdStatus::ProcessingError`]: super::CmdStatus::ProcessingError
+    /// [`CmdStatus::ExecutionFailure`]: super::CmdStatus::ExecutionFailure
+    Command(super::Status),
+}
+
+impl From<crate::spi::Error> for Error {
+    /// Wrap a SPI error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::{spi, subghz};
+    ///
+    /// assert_eq!(
+    ///     subghz::Error::from(spi::Error::Overrun),
+    ///     subghz::Error::Spi(spi::Error::Overrun)
+    /// );
+    /// ```
+    fn from(e: crate::spi::Error) -> Self {
+        Error::Spi(e)
+    }
+}
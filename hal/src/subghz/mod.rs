@@ -12,6 +12,7 @@
 mod bit_sync;
 mod cad_params;
 mod calibrate;
+mod error;
 mod fallback_mode;
 mod hse_trim;
 mod irq;
@@ -37,17 +38,19 @@ mod status;
 mod tcxo_mode;
 mod timeout;
 mod tx_params;
+mod tx_power;
 mod value_error;
 
 use crate::{
     dma::DmaCh,
     pac,
-    spi::{BaudRate, SgMiso, SgMosi, Spi3},
+    spi::{self, BaudRate, SgMiso, SgMosi, Spi3},
 };
 
 pub use bit_sync::BitSync;
 pub use cad_params::{CadParams, ExitMode, NbCadSymbol};
 pub use calibrate::{Calibrate, CalibrateImage};
+pub use error::Error;
 pub use fallback_mode::FallbackMode;
 pub use hse_trim::HseTrim;
 pub use irq::{CfgIrq, Irq, IrqLine};
@@ -78,15 +81,14 @@ pub use status::{CmdStatus, Status, StatusMode};
 pub use tcxo_mode::{TcxoMode, TcxoTrim};
 pub use timeout::Timeout;
 pub use tx_params::{RampTime, TxParams};
+pub use tx_power::Region;
+use tx_power::{tx_power, TxPower};
 pub use value_error::ValueError;
 
 use crate::Ratio;
 
 use embedded_hal::blocking::spi::{Transfer, Write};
 
-/// Passthrough for SPI errors (for now)
-pub type Error = crate::spi::Error;
-
 struct Nss {
     _priv: (),
 }
@@ -379,21 +381,28 @@ impl<MISO, MOSI> SubGhz<MISO, MOSI> {
         Spi3::<SgMiso, SgMosi>::enable_clock(rcc)
     }
 
-    fn poll_not_busy(&self) {
+    fn try_poll_not_busy(&self) -> Result<(), Error> {
         // TODO: this is a terrible timeout
         let mut count: u32 = 1_000_000;
         while rfbusys() {
             count -= 1;
             if count == 0 {
-                let dp = unsafe { pac::Peripherals::steal() };
-                panic!(
-                    "rfbusys timeout pwr.sr2=0x{:X} pwr.subghzspicr=0x{:X} pwr.cr1=0x{:X}",
-                    dp.PWR.sr2.read().bits(),
-                    dp.PWR.subghzspicr.read().bits(),
-                    dp.PWR.cr1.read().bits(),
-                );
+                return Err(Error::Timeout);
             }
         }
+        Ok(())
+    }
+
+    fn poll_not_busy(&self) {
+        if self.try_poll_not_busy().is_err() {
+            let dp = unsafe { pac::Peripherals::steal() };
+            panic!(
+                "rfbusys timeout pwr.sr2=0x{:X} pwr.subghzspicr=0x{:X} pwr.cr1=0x{:X}",
+                dp.PWR.sr2.read().bits(),
+                dp.PWR.subghzspicr.read().bits(),
+                dp.PWR.cr1.read().bits(),
+            );
+        }
     }
 
     /// Free the SPI3 peripheral and DMA channels from the SubGhz driver.
@@ -414,8 +423,8 @@ impl<MISO, MOSI> SubGhz<MISO, MOSI> {
 
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     fn read(&mut self, opcode: OpCode, data: &mut [u8]) -> Result<(), Error> {
         self.poll_not_busy();
@@ -438,6 +447,17 @@ where
         Ok(())
     }
 
+    /// Write to the sub-GHz radio, returning [`Error::Timeout`] instead of
+    /// panicking if the radio remains busy.
+    fn write_checked(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.try_poll_not_busy()?;
+        {
+            let _nss: Nss = Nss::new();
+            self.spi.write(data)?;
+        }
+        self.try_poll_not_busy()
+    }
+
     /// Read one byte from the sub-Ghz radio.
     fn read_1(&mut self, opcode: OpCode) -> Result<u8, Error> {
         let mut buf: [u8; 1] = [0; 1];
@@ -446,6 +466,9 @@ where
     }
 
     /// Read a fixed number of bytes from the sub-Ghz radio.
+    ///
+    /// The first byte of the response is the radio status, use
+    /// [`check_response`] to return [`Error::Command`] for a command failure.
     fn read_n<const N: usize>(&mut self, opcode: OpCode) -> Result<[u8; N], Error> {
         let mut buf: [u8; N] = [0; N];
         self.read(opcode, &mut buf)?;
@@ -631,15 +654,15 @@ impl<MISO: DmaCh, MOSI: DmaCh> SubGhz<MISO, MOSI> {
 /// Synchronous buffer access commands
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     /// Write the radio buffer at the given offset.
     pub fn write_buffer(&mut self, offset: u8, data: &[u8]) -> Result<(), Error> {
         self.poll_not_busy();
         {
             let _nss: Nss = Nss::new();
-            self.spi.write(&[OpCode::WriteBuffer as u8, offset])?;
+            self.spi.write(&write_buffer_header(offset))?;
             self.spi.write(data)?;
         }
         self.poll_not_busy();
@@ -651,20 +674,115 @@ where
     ///
     /// The offset and length of a received packet is provided by
     /// [`rx_buffer_status`](Self::rx_buffer_status).
+    ///
+    /// Returns [`Error::Command`] if the radio status reports a command
+    /// failure.
     pub fn read_buffer(&mut self, offset: u8, buf: &mut [u8]) -> Result<Status, Error> {
-        let mut status_buf: [u8; 1] = [0];
+        let mut header: [u8; 3] = read_buffer_header(offset);
 
         self.poll_not_busy();
         {
             let _nss: Nss = Nss::new();
-            self.spi.write(&[OpCode::ReadBuffer as u8, offset])?;
-            self.spi.transfer(&mut status_buf)?;
+            self.spi.transfer(&mut header)?;
             self.spi.transfer(buf)?;
         }
         self.poll_not_busy();
 
-        Ok(status_buf[0].into())
+        // the radio returns the status during the NOP byte
+        Status::from(header[2]).check()
     }
+
+    /// Write the radio buffer at the given offset, checking that the data
+    /// fits in the buffer.
+    ///
+    /// Unlike [`write_buffer`](Self::write_buffer) this does not wrap around
+    /// to the start of the buffer.
+    /// This is useful for placing back-to-back packets in separate regions
+    /// of the buffer.
+    ///
+    /// Returns [`Error::Buffer`] if `offset + data.len()` is greater than 256,
+    /// nothing is written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut sg = unsafe { stm32wlxx_hal::subghz::SubGhz::steal() };
+    /// use stm32wlxx_hal::subghz::Error;
+    ///
+    /// // ping-pong buffering
+    /// sg.write_buffer_offset(0, b"ping")?;
+    /// sg.write_buffer_offset(128, b"pong")?;
+    ///
+    /// assert_eq!(sg.write_buffer_offset(255, b"pong"), Err(Error::Buffer));
+    /// # Ok::<(), stm32wlxx_hal::subghz::Error>(())
+    /// ```
+    pub fn write_buffer_offset(&mut self, offset: u8, data: &[u8]) -> Result<(), Error> {
+        check_buffer_bounds(offset, data.len())?;
+        self.write_buffer(offset, data)
+    }
+
+    /// Read the radio buffer at the given offset, checking that the read
+    /// fits in the buffer.
+    ///
+    /// Unlike [`read_buffer`](Self::read_buffer) this does not wrap around
+    /// to the start of the buffer.
+    ///
+    /// Returns [`Error::Buffer`] if `offset + buf.len()` is greater than 256,
+    /// nothing is read.
+    /// Returns [`Error::Command`] if the radio status reports a command
+    /// failure.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut sg = unsafe { stm32wlxx_hal::subghz::SubGhz::steal() };
+    /// let mut buf: [u8; 4] = [0; 4];
+    /// sg.read_buffer_offset(128, &mut buf)?;
+    /// # Ok::<(), stm32wlxx_hal::subghz::Error>(())
+    /// ```
+    pub fn read_buffer_offset(&mut self, offset: u8, buf: &mut [u8]) -> Result<Status, Error> {
+        check_buffer_bounds(offset, buf.len())?;
+        self.read_buffer(offset, buf)
+    }
+}
+
+/// Size of the radio data buffer in bytes.
+const BUF_SIZE: usize = 256;
+
+/// Command bytes sent before the data of a `WriteBuffer` command.
+const fn write_buffer_header(offset: u8) -> [u8; 2] {
+    [OpCode::WriteBuffer as u8, offset]
+}
+
+/// Command bytes sent before the data of a `ReadBuffer` command, the radio
+/// returns the status during the last byte.
+const fn read_buffer_header(offset: u8) -> [u8; 3] {
+    [OpCode::ReadBuffer as u8, offset, 0x00]
+}
+
+/// Check that `len` bytes starting at `offset` fit in the radio buffer.
+fn check_buffer_bounds(offset: u8, len: usize) -> Result<(), Error> {
+    if usize::from(offset) + len > BUF_SIZE {
+        Err(Error::Buffer)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the status byte at the start of a response for a command failure.
+fn check_response<const N: usize>(buf: [u8; N]) -> Result<[u8; N], Error> {
+    if let Some(status) = buf.first() {
+        Status::from(*status).check()?;
+    }
+    Ok(buf)
+}
+
+/// Decode the response of the get IRQ status command.
+const fn irq_status_from_raw(data: [u8; 3]) -> (Status, u16) {
+    (
+        Status::from_raw(data[0]),
+        u16::from_be_bytes([data[1], data[2]]),
+    )
 }
 
 // helper to pack register writes into a single buffer to avoid multiple DMA
@@ -684,8 +802,8 @@ macro_rules! wr_reg {
 /// Register access
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     // register write with variable length data
     fn write_register(&mut self, register: Register, data: &[u8]) -> Result<(), Error> {
@@ -874,8 +992,8 @@ where
 /// Operating mode commands
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     /// Put the radio into sleep mode.
     ///
@@ -883,6 +1001,9 @@ where
     /// The cfg argument allows some optional functions to be maintained
     /// in sleep mode.
     ///
+    /// Returns [`Error::Timeout`] if the radio is busy before the command is
+    /// sent.
+    ///
     /// # Safety
     ///
     /// 1. After the `set_sleep` command, the sub-GHz radio NSS must not go low
@@ -915,7 +1036,7 @@ where
     pub unsafe fn set_sleep(&mut self, cfg: SleepCfg) -> Result<(), Error> {
         // poll for busy before, but not after
         // radio idles with busy high while in sleep mode
-        self.poll_not_busy();
+        self.try_poll_not_busy()?;
         {
             let _nss: Nss = Nss::new();
             self.spi.write(&[OpCode::SetSleep as u8, u8::from(cfg)])?;
@@ -924,8 +1045,27 @@ where
     }
 
     /// Put the radio into standby mode.
+    ///
+    /// This will wait for the radio busy signal to clear before and after
+    /// sending the command.
+    /// Returns [`Error::Timeout`] if the radio does not release the busy
+    /// signal, this can indicate a radio brown-out, or that the radio is still
+    /// in sleep mode and must be woken with [`wakeup`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut sg = unsafe { stm32wlxx_hal::subghz::SubGhz::steal() };
+    /// use stm32wlxx_hal::subghz::{Error, StandbyClk};
+    ///
+    /// match sg.set_standby(StandbyClk::Rc) {
+    ///     Ok(()) => (),
+    ///     Err(Error::Timeout) => panic!("radio is not responding"),
+    ///     Err(e) => panic!("SPI error {:?}", e),
+    /// }
+    /// ```
     pub fn set_standby(&mut self, standby_clk: StandbyClk) -> Result<(), Error> {
-        self.write(&[OpCode::SetStandby as u8, u8::from(standby_clk)])
+        self.write_checked(&[OpCode::SetStandby as u8, u8::from(standby_clk)])
     }
 
     /// Put the subghz radio into frequency synthesis mode.
@@ -937,9 +1077,13 @@ where
     /// I honestly do not see any use for it.  Please update this description
     /// if you know more than I do.
     ///
+    /// This will wait for the radio busy signal to clear before and after
+    /// sending the command, returning [`Error::Timeout`] if the radio does
+    /// not release the busy signal.
+    ///
     /// [`set_rf_frequency`]: crate::subghz::SubGhz::set_rf_frequency
     pub fn set_fs(&mut self) -> Result<(), Error> {
-        self.write(&[OpCode::SetFs.into()])
+        self.write_checked(&[OpCode::SetFs.into()])
     }
 
     /// Setup the sub-GHz radio for TX.
@@ -1069,6 +1213,70 @@ where
         self.write(&[OpCode::SetCad.into()])
     }
 
+    /// Run a channel activity detection (CAD) scan.
+    ///
+    /// This configures the CAD with [`set_cad_params`], starts the scan with
+    /// [`set_cad`], and polls the IRQ status until the scan is done.
+    ///
+    /// Returns `true` if a LoRa signal was detected on the channel.
+    ///
+    /// The [`Irq::CadDone`] and [`Irq::CadDetected`] IRQs are cleared before
+    /// the scan is started, and after the scan is done.
+    ///
+    /// This can be used for listen-before-talk, checking that the channel is
+    /// free before transmitting.
+    ///
+    /// # Requirements
+    ///
+    /// 1. The radio must be in standby mode with the LoRa packet type, and
+    ///    the LoRa modulation parameters and RF frequency configured.
+    /// 2. The [`Irq::CadDone`] and [`Irq::CadDetected`] IRQs must be enabled
+    ///    with [`set_irq_cfg`], IRQs that are not enabled are not reported in
+    ///    the IRQ status, and this method will never return.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut sg = unsafe { stm32wlxx_hal::subghz::SubGhz::steal() };
+    /// use stm32wlxx_hal::subghz::{CadParams, CfgIrq, Irq, NbCadSymbol};
+    ///
+    /// const IRQ_CFG: CfgIrq = CfgIrq::new()
+    ///     .irq_enable_all(Irq::CadDone)
+    ///     .irq_enable_all(Irq::CadDetected);
+    /// // recommended values for a spreading factor of 7
+    /// const CAD_PARAMS: CadParams = CadParams::new()
+    ///     .set_num_symbol(NbCadSymbol::S2)
+    ///     .set_det_peak(0x20)
+    ///     .set_det_min(0x10);
+    ///
+    /// sg.set_irq_cfg(&IRQ_CFG)?;
+    /// if !sg.cad(&CAD_PARAMS)? {
+    ///     // channel is free, transmit
+    /// }
+    /// # Ok::<(), stm32wlxx_hal::subghz::Error>(())
+    /// ```
+    ///
+    /// [`set_cad_params`]: crate::subghz::SubGhz::set_cad_params
+    /// [`set_cad`]: crate::subghz::SubGhz::set_cad
+    /// [`set_irq_cfg`]: crate::subghz::SubGhz::set_irq_cfg
+    pub fn cad(&mut self, params: &CadParams) -> Result<bool, Error> {
+        const MASK: u16 = Irq::CadDone.mask() | Irq::CadDetected.mask();
+
+        self.set_cad_params(params)?;
+        self.clear_irq_status(MASK)?;
+        self.set_cad()?;
+
+        let irq_status: u16 = loop {
+            let (_, irq_status) = self.irq_status()?;
+            if irq_status & Irq::CadDone.mask() != 0 {
+                break irq_status;
+            }
+        };
+
+        self.clear_irq_status(MASK)?;
+        Ok(irq_status & Irq::CadDetected.mask() != 0)
+    }
+
     /// Generate a continuous transmit tone at the RF-PLL frequency.
     ///
     /// The sub-GHz radio remains in continuous transmit tone mode until a mode
@@ -1093,8 +1301,8 @@ where
 /// Radio configuration commands
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     /// Set the packet type (modulation scheme).
     pub fn set_packet_type(&mut self, packet_type: PacketType) -> Result<(), Error> {
@@ -1103,7 +1311,7 @@ where
 
     /// Get the packet type.
     pub fn packet_type(&mut self) -> Result<Result<PacketType, u8>, Error> {
-        let pkt_type: [u8; 2] = self.read_n(OpCode::GetPacketType)?;
+        let pkt_type: [u8; 2] = check_response(self.read_n(OpCode::GetPacketType)?)?;
         Ok(PacketType::from_raw(pkt_type[1]))
     }
 
@@ -1124,6 +1332,40 @@ where
         self.write(pa_config.as_slice())
     }
 
+    /// Set the output power within the limit of a region.
+    ///
+    /// This selects the power amplifier configuration, over current
+    /// protection, and power setting for `dbm`, and sends them with
+    /// [`set_pa_config`](Self::set_pa_config),
+    /// [`set_pa_ocp`](Self::set_pa_ocp), and
+    /// [`set_tx_params`](Self::set_tx_params).
+    ///
+    /// The power is clamped to the limit of the region and to the range of
+    /// the PA, -17 dBm to +22 dBm.
+    /// The low-power PA is used up to +15 dBm, the high-power PA above, the
+    /// RF switch on your board must route the selected PA to the antenna.
+    /// The PA ramp time is 40µs.
+    ///
+    /// Returns the output power that was set in dBm.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut sg = unsafe { stm32wlxx_hal::subghz::SubGhz::steal() };
+    /// use stm32wlxx_hal::subghz::Region;
+    ///
+    /// let dbm: i8 = sg.set_tx_power(22, Region::Eu868)?;
+    /// assert_eq!(dbm, 16);
+    /// # Ok::<(), stm32wlxx_hal::subghz::Error>(())
+    /// ```
+    pub fn set_tx_power(&mut self, dbm: i8, region: Region) -> Result<i8, Error> {
+        let power: TxPower = tx_power(dbm, region);
+        self.set_pa_config(&power.pa_config)?;
+        self.set_pa_ocp(power.ocp)?;
+        self.set_tx_params(&power.tx_params)?;
+        Ok(power.dbm)
+    }
+
     /// Operating mode to enter after a successful packet transmission or
     /// packet reception.
     pub fn set_tx_rx_fallback_mode(&mut self, fm: FallbackMode) -> Result<(), Error> {
@@ -1188,11 +1430,14 @@ where
 /// Communication status and information commands
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     /// Get the radio status.
     ///
+    /// Unlike the other read commands this does not return [`Error::Command`] for
+    /// a command failure, the status is returned for inspection.
+    ///
     /// The hardware (or documentation) appears to have many bugs where this
     /// will return reserved values.
     /// See this thread in the ST community for details: [link]
@@ -1206,27 +1451,29 @@ where
     ///
     /// The return tuple is (status, payload_length, buffer_pointer).
     pub fn rx_buffer_status(&mut self) -> Result<(Status, u8, u8), Error> {
-        let data: [u8; 3] = self.read_n(OpCode::GetRxBufferStatus)?;
+        let data: [u8; 3] = check_response(self.read_n(OpCode::GetRxBufferStatus)?)?;
         Ok((data[0].into(), data[1], data[2]))
     }
 
     /// Returns information on the last received (G)FSK packet.
     pub fn fsk_packet_status(&mut self) -> Result<FskPacketStatus, Error> {
-        Ok(FskPacketStatus::from(self.read_n(OpCode::GetPacketStatus)?))
+        Ok(FskPacketStatus::from(check_response(
+            self.read_n(OpCode::GetPacketStatus)?,
+        )?))
     }
 
     /// Returns information on the last received LoRa packet.
     pub fn lora_packet_status(&mut self) -> Result<LoRaPacketStatus, Error> {
-        Ok(LoRaPacketStatus::from(
+        Ok(LoRaPacketStatus::from(check_response(
             self.read_n(OpCode::GetPacketStatus)?,
-        ))
+        )?))
     }
 
     /// Get the instantaneous signal strength during packet reception.
     ///
     /// The units are in dbm.
     pub fn rssi_inst(&mut self) -> Result<(Status, Ratio<i16>), Error> {
-        let data: [u8; 2] = self.read_n(OpCode::GetRssiInst)?;
+        let data: [u8; 2] = check_response(self.read_n(OpCode::GetRssiInst)?)?;
         let status: Status = data[0].into();
         let rssi: Ratio<i16> = Ratio::new_raw(i16::from(data[1]), -2);
 
@@ -1235,13 +1482,13 @@ where
 
     /// (G)FSK packet stats.
     pub fn fsk_stats(&mut self) -> Result<Stats<FskStats>, Error> {
-        let data: [u8; 7] = self.read_n(OpCode::GetStats)?;
+        let data: [u8; 7] = check_response(self.read_n(OpCode::GetStats)?)?;
         Ok(Stats::from_raw_fsk(data))
     }
 
     /// LoRa packet stats.
     pub fn lora_stats(&mut self) -> Result<Stats<LoRaStats>, Error> {
-        let data: [u8; 7] = self.read_n(OpCode::GetStats)?;
+        let data: [u8; 7] = check_response(self.read_n(OpCode::GetStats)?)?;
         Ok(Stats::from_raw_lora(data))
     }
 
@@ -1257,8 +1504,8 @@ where
 /// IRQ commands
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     /// Set the interrupt configuration.
     pub fn set_irq_cfg(&mut self, cfg: &CfgIrq) -> Result<(), Error> {
@@ -1266,10 +1513,11 @@ where
     }
 
     /// Get the IRQ status.
+    ///
+    /// This does not return [`Error::Command`] for a command failure, the IRQ
+    /// status is used to find out how a command ended.
     pub fn irq_status(&mut self) -> Result<(Status, u16), Error> {
-        let data: [u8; 3] = self.read_n(OpCode::GetIrqStatus)?;
-        let irq_status: u16 = u16::from_be_bytes([data[1], data[2]]);
-        Ok((data[0].into(), irq_status))
+        Ok(irq_status_from_raw(self.read_n(OpCode::GetIrqStatus)?))
     }
 
     /// Clear the IRQ status.
@@ -1282,8 +1530,8 @@ where
 /// Miscellaneous commands
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     /// Calibrate one or several blocks at any time when in standby mode.
     pub fn calibrate(&mut self, cal: u8) -> Result<(), Error> {
@@ -1304,6 +1552,9 @@ where
     }
 
     /// Get the radio operational errors.
+    ///
+    /// This does not return [`Error::Command`] for a command failure, the
+    /// operational errors are used to diagnose the failure.
     pub fn op_error(&mut self) -> Result<(Status, u16), Error> {
         let data: [u8; 3] = self.read_n(OpCode::GetError)?;
         Ok((data[0].into(), u16::from_be_bytes([data[1], data[2]])))
@@ -1319,8 +1570,8 @@ where
 /// Set TCXO mode command
 impl<MISO, MOSI> SubGhz<MISO, MOSI>
 where
-    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = Error>
-        + embedded_hal::blocking::spi::Write<u8, Error = Error>,
+    Spi3<MISO, MOSI>: embedded_hal::blocking::spi::Transfer<u8, Error = spi::Error>
+        + embedded_hal::blocking::spi::Write<u8, Error = spi::Error>,
 {
     /// Set the TCXO trim and HSE32 ready timeout.
     pub fn set_tcxo_mode(&mut self, tcxo_mode: &TcxoMode) -> Result<(), Error> {
@@ -1438,3 +1689,58 @@ impl Register {
         self as u16
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_buffer_bounds, check_response, irq_status_from_raw, read_buffer_header,
+        write_buffer_header, Error, Irq, Status,
+    };
+
+    #[test]
+    fn buffer_header() {
+        // opcodes from RM0453 rev 2 section 5.8.2
+        assert_eq!(write_buffer_header(0), [0x0E, 0x00]);
+        assert_eq!(write_buffer_header(0x80), [0x0E, 0x80]);
+        assert_eq!(read_buffer_header(0), [0x1E, 0x00, 0x00]);
+        assert_eq!(read_buffer_header(0xFF), [0x1E, 0xFF, 0x00]);
+    }
+
+    #[test]
+    fn buffer_bounds() {
+        assert_eq!(check_buffer_bounds(0, 256), Ok(()));
+        assert_eq!(check_buffer_bounds(255, 1), Ok(()));
+        assert_eq!(check_buffer_bounds(0, 0), Ok(()));
+        assert_eq!(check_buffer_bounds(255, 0), Ok(()));
+        assert_eq!(check_buffer_bounds(0, 257), Err(Error::Buffer));
+        assert_eq!(check_buffer_bounds(255, 2), Err(Error::Buffer));
+        assert_eq!(check_buffer_bounds(128, 129), Err(Error::Buffer));
+    }
+
+    #[test]
+    fn response_timeout() {
+        // standby RC mode, command timeout
+        const TIMEOUT: u8 = 0x26;
+
+        assert_eq!(
+            check_response([TIMEOUT, 0x12, 0x34]),
+            Ok([TIMEOUT, 0x12, 0x34])
+        );
+        assert_eq!(
+            irq_status_from_raw([TIMEOUT, 0x02, 0x00]),
+            (Status::from_raw(TIMEOUT), Irq::Timeout.mask())
+        );
+    }
+
+    #[test]
+    fn response_failure() {
+        // standby RC mode, command execution failure
+        const FAILURE: u8 = 0x2A;
+
+        assert_eq!(
+            check_response([FAILURE, 0x00]),
+            Err(Error::Command(Status::from_raw(FAILURE)))
+        );
+        assert_eq!(check_response::<0>([]), Ok([]));
+    }
+}
@@ -1,3 +1,5 @@
+use super::Error;
+
 /// sub-GHz radio operating mode.
 ///
 /// See `Get_Status` under section 5.8.5 "Communication status information commands"
@@ -109,6 +111,30 @@ impl CmdStatus {
             _ => Err(bits),
         }
     }
+
+    /// Returns `true` if the command status indicates a failure.
+    ///
+    /// [`Timeout`](CmdStatus::Timeout) is not a failure, the radio reports it
+    /// after a reception or transmission timeout, which is signaled with
+    /// [`Irq::Timeout`](super::Irq::Timeout).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::subghz::CmdStatus;
+    ///
+    /// assert!(!CmdStatus::Available.is_failure());
+    /// assert!(!CmdStatus::Timeout.is_failure());
+    /// assert!(CmdStatus::ProcessingError.is_failure());
+    /// assert!(CmdStatus::ExecutionFailure.is_failure());
+    /// assert!(!CmdStatus::Complete.is_failure());
+    /// ```
+    pub const fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            CmdStatus::ProcessingError | CmdStatus::ExecutionFailure
+        )
+    }
 }
 
 /// Radio status.
@@ -178,6 +204,32 @@ impl Status {
     pub const fn cmd(&self) -> Result<CmdStatus, u8> {
         CmdStatus::from_raw((self.0 >> 1) & 0b111)
     }
+
+    /// Check the command status for a failure.
+    ///
+    /// Returns [`Error::Command`] if the [command status](Self::cmd) is a
+    /// [failure](CmdStatus::is_failure).
+    /// Reserved command status values are not failures.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::subghz::{Error, Status};
+    ///
+    /// // receive mode, data available
+    /// let status: Status = Status::from_raw(0x54);
+    /// assert_eq!(status.check(), Ok(status));
+    ///
+    /// // standby RC mode, command execution failure
+    /// let status: Status = Status::from_raw(0x2A);
+    /// assert_eq!(status.check(), Err(Error::Command(status)));
+    /// ```
+    pub const fn check(self) -> Result<Status, Error> {
+        match self.cmd() {
+            Ok(cmd) if cmd.is_failure() => Err(Error::Command(self)),
+            _ => Ok(self),
+        }
+    }
 }
 
 impl core::fmt::Debug for Status {
@@ -200,3 +252,38 @@ impl defmt::Format for Status {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{CmdStatus, Error, Status, StatusMode};
+
+    type Case = (u8, Result<StatusMode, u8>, Result<CmdStatus, u8>);
+
+    #[test]
+    fn decode() {
+        // (raw, mode, cmd)
+        const CASES: [Case; 8] = [
+            (0x22, Ok(StatusMode::StandbyRc), Err(1)),
+            (0x24, Ok(StatusMode::StandbyRc), Ok(CmdStatus::Available)),
+            (0x36, Ok(StatusMode::StandbyHse), Ok(CmdStatus::Timeout)),
+            (0x48, Ok(StatusMode::Fs), Ok(CmdStatus::ProcessingError)),
+            (0x54, Ok(StatusMode::Rx), Ok(CmdStatus::Available)),
+            (0x5A, Ok(StatusMode::Rx), Ok(CmdStatus::ExecutionFailure)),
+            (0x6C, Ok(StatusMode::Tx), Ok(CmdStatus::Complete)),
+            // reserved mode, bit 7 and bit 0 are reserved
+            (0x81, Err(0), Err(0)),
+        ];
+
+        CASES.iter().for_each(|&(raw, mode, cmd)| {
+            let status: Status = Status::from_raw(raw);
+            assert_eq!(status.mode(), mode, "{raw:#04X}");
+            assert_eq!(status.cmd(), cmd, "{raw:#04X}");
+            match cmd {
+                Ok(cmd) if cmd.is_failure() => {
+                    assert_eq!(status.check(), Err(Error::Command(status)), "{raw:#04X}")
+                }
+                _ => assert_eq!(status.check(), Ok(status), "{raw:#04X}"),
+            }
+        });
+    }
+}
@@ -0,0 +1,192 @@
+use super::{Ocp, PaConfig, RampTime, TxParams};
+
+/// Radio regulatory region.
+///
+/// Argument of [`set_tx_power`].
+///
+/// The limits are the default maximum EIRP from the LoRaWAN regional
+/// parameters, for the default sub-band of each region.
+/// This is a convenience, it is not a substitute for reading the
+/// regulations that apply to your product.
+///
+/// [`set_tx_power`]: super::SubGhz::set_tx_power
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Region {
+    /// Europe 863 - 870 MHz, +16 dBm.
+    Eu868,
+    /// United States 902 - 928 MHz, +30 dBm.
+    Us915,
+    /// Australia 915 - 928 MHz, +30 dBm.
+    Au915,
+    /// Asia 923 MHz, +16 dBm.
+    As923,
+    /// South Korea 920 - 923 MHz, +14 dBm.
+    Kr920,
+    /// India 865 - 867 MHz, +30 dBm.
+    In865,
+    /// Russia 864 - 870 MHz, +16 dBm.
+    Ru864,
+}
+
+impl Region {
+    /// Maximum output power in dBm.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::subghz::Region;
+    ///
+    /// assert_eq!(Region::Eu868.max_dbm(), 16);
+    /// assert_eq!(Region::Us915.max_dbm(), 30);
+    /// ```
+    pub const fn max_dbm(self) -> i8 {
+        match self {
+            Region::Eu868 | Region::As923 | Region::Ru864 => 16,
+            Region::Kr920 => 14,
+            Region::Us915 | Region::Au915 | Region::In865 => 30,
+        }
+    }
+}
+
+/// Commands for an output power, selected by [`tx_power`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct TxPower {
+    pub pa_config: PaConfig,
+    pub ocp: Ocp,
+    pub tx_params: TxParams,
+    /// Output power in dBm.
+    pub dbm: i8,
+}
+
+/// Low-power PA output power range in dBm.
+const LP_MIN: i8 = -17;
+const LP_MAX: i8 = 15;
+/// High-power PA output power range in dBm.
+const HP_MAX: i8 = 22;
+
+/// Power amplifier ramp time used by [`tx_power`].
+///
+/// This is the ramp time ST uses in their HAL implementation.
+const RAMP_TIME: RampTime = RampTime::Micros40;
+
+/// Select the PA configuration, over current protection, and power setting
+/// for an output power.
+///
+/// The requested power is clamped to the region limit and the PA range.
+/// The low-power PA is used up to +15 dBm, the high-power PA above.
+/// The PA configurations are from the optimal settings in the reference
+/// manual, the power setting is in 1 dB steps from the optimal setting.
+pub(crate) const fn tx_power(dbm: i8, region: Region) -> TxPower {
+    let max: i8 = if region.max_dbm() < HP_MAX {
+        region.max_dbm()
+    } else {
+        HP_MAX
+    };
+    let dbm: i8 = if dbm > max {
+        max
+    } else if dbm < LP_MIN {
+        LP_MIN
+    } else {
+        dbm
+    };
+
+    if dbm == LP_MAX {
+        // the LP_15 configuration outputs +15 dBm at the +14 dBm setting
+        TxPower {
+            pa_config: PaConfig::LP_15,
+            ocp: Ocp::Max60m,
+            tx_params: TxParams::LP_15.set_ramp_time(RAMP_TIME),
+            dbm,
+        }
+    } else if dbm < LP_MAX {
+        TxPower {
+            pa_config: PaConfig::LP_14,
+            ocp: Ocp::Max60m,
+            tx_params: TxParams::new()
+                .set_power(dbm as u8)
+                .set_ramp_time(RAMP_TIME),
+            dbm,
+        }
+    } else {
+        TxPower {
+            pa_config: PaConfig::HP_22,
+            ocp: Ocp::Max140m,
+            tx_params: TxParams::new()
+                .set_power(dbm as u8)
+                .set_ramp_time(RAMP_TIME),
+            dbm,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{tx_power, Region, TxPower, RAMP_TIME};
+    use crate::subghz::{Ocp, PaConfig, TxParams};
+
+    #[test]
+    fn low_power() {
+        assert_eq!(
+            tx_power(14, Region::Us915),
+            TxPower {
+                pa_config: PaConfig::LP_14,
+                ocp: Ocp::Max60m,
+                tx_params: TxParams::LP_14.set_ramp_time(RAMP_TIME),
+                dbm: 14,
+            }
+        );
+        assert_eq!(
+            tx_power(15, Region::Us915),
+            TxPower {
+                pa_config: PaConfig::LP_15,
+                ocp: Ocp::Max60m,
+                tx_params: TxParams::LP_15.set_ramp_time(RAMP_TIME),
+                dbm: 15,
+            }
+        );
+        let power: TxPower = tx_power(0, Region::Eu868);
+        assert_eq!(power.pa_config, PaConfig::LP_14);
+        assert_eq!(power.tx_params.as_slice()[1], 0x00);
+        assert_eq!(power.dbm, 0);
+
+        let power: TxPower = tx_power(-17, Region::Eu868);
+        assert_eq!(power.tx_params.as_slice()[1], 0xEF);
+        assert_eq!(power.dbm, -17);
+    }
+
+    #[test]
+    fn high_power() {
+        assert_eq!(
+            tx_power(22, Region::Us915),
+            TxPower {
+                pa_config: PaConfig::HP_22,
+                ocp: Ocp::Max140m,
+                tx_params: TxParams::HP.set_ramp_time(RAMP_TIME),
+                dbm: 22,
+            }
+        );
+        let power: TxPower = tx_power(16, Region::Eu868);
+        assert_eq!(power.pa_config, PaConfig::HP_22);
+        assert_eq!(power.ocp, Ocp::Max140m);
+        assert_eq!(power.tx_params.as_slice()[1], 0x10);
+        assert_eq!(power.dbm, 16);
+    }
+
+    #[test]
+    fn clamp() {
+        // region limit
+        let power: TxPower = tx_power(22, Region::Eu868);
+        assert_eq!(power.dbm, 16);
+        assert_eq!(power.pa_config, PaConfig::HP_22);
+        let power: TxPower = tx_power(22, Region::Kr920);
+        assert_eq!(power.dbm, 14);
+        assert_eq!(power.pa_config, PaConfig::LP_14);
+
+        // PA limits
+        assert_eq!(tx_power(30, Region::Us915).dbm, 22);
+        assert_eq!(tx_power(i8::MAX, Region::Us915).dbm, 22);
+        assert_eq!(tx_power(-18, Region::Us915).dbm, -17);
+        assert_eq!(tx_power(i8::MIN, Region::Us915).dbm, -17);
+    }
+}
@@ -0,0 +1,615 @@
+//! General purpose timers
+//!
+//! Currently this supports PWM generation, quadrature encoder counting, and a
+//! free-running microsecond counter on the 32-bit general purpose timer TIM2.
+//!
+//! # Example
+//!
+//! 1 kHz PWM with a 25% duty cycle on TIM2 channel 1 (pin A0).
+//!
+//! ```no_run
+//! use stm32wlxx_hal::{
+//!     gpio::PortA,
+//!     pac,
+//!     timer::{Channel, Tim2},
+//! };
+//!
+//! let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+//!
+//! let gpioa: PortA = PortA::split(dp.GPIOA, &mut dp.RCC);
+//! let mut tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+//! let a0 = cortex_m::interrupt::free(|cs| tim2.new_ch1_pin(gpioa.a0, cs));
+//! tim2.pwm(Channel::Ch1, 1_000, u16::MAX / 4, &dp.RCC)?;
+//! # Ok::<(), stm32wlxx_hal::timer::Error>(())
+//! ```
+
+use crate::{
+    gpio::sealed::{Tim2Ch1, Tim2Ch2, Tim2Ch3, Tim2Ch4},
+    pac,
+    rcc::{self, Peripheral},
+};
+use cortex_m::interrupt::CriticalSection;
+
+/// Timer errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The PWM frequency is zero or greater than half the timer clock.
+    ///
+    /// Also returned by [`Tim2::monotonic_us`] if the timer clock is not a
+    /// multiple of 1 MHz.
+    Frequency,
+}
+
+/// Timer capture/compare channel.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Channel {
+    /// Channel 1
+    Ch1,
+    /// Channel 2
+    Ch2,
+    /// Channel 3
+    Ch3,
+    /// Channel 4
+    Ch4,
+}
+
+/// Quadrature encoder counting mode.
+///
+/// Used with [`Tim2::encoder`].
+/// Channel 1 (TI1) is the encoder A input and channel 2 (TI2) is the encoder
+/// B input.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EncoderMode {
+    /// Count on both edges of TI1, 2 counts per encoder cycle.
+    X2Ti1,
+    /// Count on both edges of TI2, 2 counts per encoder cycle.
+    X2Ti2,
+    /// Count on both edges of TI1 and TI2, 4 counts per encoder cycle.
+    X4,
+}
+
+/// Counter direction.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Counting up, A leads B.
+    Up,
+    /// Counting down, B leads A.
+    Down,
+}
+
+/// Compute the prescaler and auto-reload values for a PWM frequency.
+///
+/// Returns `None` if the frequency is zero or greater than half the timer
+/// clock, a PWM period must be at least two timer ticks.
+///
+/// The smallest prescaler is selected to maximize the duty cycle
+/// resolution.
+const fn psc_arr(clk_hz: u32, freq_hz: u32) -> Option<(u16, u32)> {
+    if freq_hz == 0 || freq_hz > clk_hz / 2 {
+        return None;
+    }
+
+    // timer ticks per PWM period, rounded to the nearest tick
+    let ticks: u64 = (clk_hz as u64 + (freq_hz as u64) / 2) / (freq_hz as u64);
+    // smallest prescaler that fits the period in the 32-bit auto-reload
+    let psc: u64 = (ticks - 1) >> 32;
+    let arr: u64 = ticks / (psc + 1) - 1;
+
+    Some((psc as u16, arr as u32))
+}
+
+/// Compute the prescaler for a 1 MHz counter.
+///
+/// Returns `None` if the timer clock is not a non-zero multiple of 1 MHz.
+const fn us_psc(clk_hz: u32) -> Option<u16> {
+    const MHZ: u32 = 1_000_000;
+    if clk_hz == 0 || clk_hz % MHZ != 0 {
+        None
+    } else {
+        // the largest u32 clock is 4294 MHz, the prescaler always fits
+        Some((clk_hz / MHZ - 1) as u16)
+    }
+}
+
+/// Compute the compare value for a duty cycle, where `u16::MAX` is 100%.
+const fn duty_ccr(arr: u32, duty: u16) -> u32 {
+    // a compare value greater than ARR holds the output high in PWM mode 1
+    let period: u64 = arr as u64 + 1;
+    let ccr: u64 = (period * (duty as u64) + (u16::MAX as u64) / 2) / (u16::MAX as u64);
+    if ccr > u32::MAX as u64 {
+        u32::MAX
+    } else {
+        ccr as u32
+    }
+}
+
+/// TIM2 output pin.
+///
+/// Constructed with [`Tim2::new_ch1_pin`], [`Tim2::new_ch2_pin`],
+/// [`Tim2::new_ch3_pin`], or [`Tim2::new_ch4_pin`].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tim2Pin<P> {
+    pin: P,
+}
+
+/// TIM2 general purpose timer driver.
+#[derive(Debug)]
+pub struct Tim2 {
+    tim: pac::TIM2,
+}
+
+impl Tim2 {
+    /// Create a new TIM2 driver.
+    ///
+    /// This will enable clocks and reset the timer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{pac, timer::Tim2};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+    /// ```
+    pub fn new(tim: pac::TIM2, rcc: &mut pac::RCC) -> Tim2 {
+        Self::enable_clock(rcc);
+        unsafe { Self::pulse_reset(rcc) };
+        Tim2 { tim }
+    }
+
+    /// Free the TIM2 peripheral from the driver.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{pac, timer::Tim2};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    /// let tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+    /// // ... use tim2
+    /// let tim2: pac::TIM2 = tim2.free();
+    /// ```
+    #[inline]
+    pub fn free(self) -> pac::TIM2 {
+        self.tim
+    }
+
+    /// Reset the timer.
+    ///
+    /// # Safety
+    ///
+    /// 1. The timer will be reset.
+    ///    Any configuration stored in the timer registers will be lost.
+    #[inline]
+    pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
+        rcc::reset(rcc, Peripheral::Tim2);
+    }
+
+    /// Disable the TIM2 clock.
+    ///
+    /// # Safety
+    ///
+    /// 1. You cannot use the timer while the clock is disabled.
+    /// 2. You are responsible for re-enabling the clock before resuming use
+    ///    of the timer.
+    /// 3. You are responsible for setting up anything that may have lost state
+    ///    while the clock was disabled.
+    #[inline]
+    pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
+        rcc::disable(rcc, Peripheral::Tim2);
+    }
+
+    /// Enable the TIM2 clock.
+    ///
+    /// [`new`](Self::new) will enable clocks for you.
+    #[inline]
+    pub fn enable_clock(rcc: &mut pac::RCC) {
+        rcc::enable(rcc, Peripheral::Tim2);
+    }
+
+    /// Setup a channel 1 output pin.
+    #[inline]
+    pub fn new_ch1_pin<P: Tim2Ch1>(&mut self, mut pin: P, cs: &CriticalSection) -> Tim2Pin<P> {
+        pin.set_tim2_ch1_af(cs);
+        Tim2Pin { pin }
+    }
+
+    /// Setup a channel 2 output pin.
+    #[inline]
+    pub fn new_ch2_pin<P: Tim2Ch2>(&mut self, mut pin: P, cs: &CriticalSection) -> Tim2Pin<P> {
+        pin.set_tim2_ch2_af(cs);
+        Tim2Pin { pin }
+    }
+
+    /// Setup a channel 3 output pin.
+    #[inline]
+    pub fn new_ch3_pin<P: Tim2Ch3>(&mut self, mut pin: P, cs: &CriticalSection) -> Tim2Pin<P> {
+        pin.set_tim2_ch3_af(cs);
+        Tim2Pin { pin }
+    }
+
+    /// Setup a channel 4 output pin.
+    #[inline]
+    pub fn new_ch4_pin<P: Tim2Ch4>(&mut self, mut pin: P, cs: &CriticalSection) -> Tim2Pin<P> {
+        pin.set_tim2_ch4_af(cs);
+        Tim2Pin { pin }
+    }
+
+    /// Free an output pin previously created with one of the `new_chN_pin`
+    /// methods.
+    #[inline]
+    pub fn free_pin<P>(&mut self, pin: Tim2Pin<P>) -> P {
+        pin.pin
+    }
+
+    /// Timer clock frequency in hertz.
+    ///
+    /// TIM2 is clocked at PCLK1 when the APB1 prescaler is 1, and at twice
+    /// PCLK1 otherwise.
+    #[inline]
+    #[must_use]
+    pub fn clock_hz(&self, rcc: &pac::RCC) -> u32 {
+        crate::rcc::apb1timx(rcc).to_integer()
+    }
+
+    /// Start PWM generation on a channel.
+    ///
+    /// The duty cycle is a fraction of `u16::MAX`, `0` holds the output low
+    /// and `u16::MAX` holds the output high.
+    ///
+    /// All four channels share the timer period, setting the frequency for
+    /// one channel changes the frequency for all channels.
+    /// Use [`set_duty`](Self::set_duty) to change the duty cycle of a channel
+    /// without changing the frequency.
+    ///
+    /// There is a trade-off between frequency and duty cycle resolution.
+    /// The number of distinct duty cycle steps is the timer clock divided by
+    /// the PWM frequency, for example a 48 MHz timer clock has 48,000 steps
+    /// (about 15.5 bits) at 1 kHz, but only 48 steps at 1 MHz.
+    /// Frequencies that do not evenly divide the timer clock are rounded to
+    /// the nearest achievable frequency.
+    ///
+    /// This replaces any encoder configuration, the counter counts up from
+    /// the prescaled timer clock.
+    ///
+    /// Returns [`Error::Frequency`] if `freq_hz` is zero or greater than half
+    /// the timer clock, the timer is not modified.
+    ///
+    /// # Example
+    ///
+    /// 50 Hz servo signal with a 1.5 ms pulse on TIM2 channel 2 (pin B3).
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     gpio::PortB,
+    ///     pac,
+    ///     timer::{Channel, Tim2},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+    /// let mut tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+    /// let b3 = cortex_m::interrupt::free(|cs| tim2.new_ch2_pin(gpiob.b3, cs));
+    /// // 1.5 ms / 20 ms = 7.5%
+    /// tim2.pwm(Channel::Ch2, 50, 4915, &dp.RCC)?;
+    /// # Ok::<(), stm32wlxx_hal::timer::Error>(())
+    /// ```
+    pub fn pwm(
+        &mut self,
+        ch: Channel,
+        freq_hz: u32,
+        duty: u16,
+        rcc: &pac::RCC,
+    ) -> Result<(), Error> {
+        let (psc, arr): (u16, u32) =
+            psc_arr(self.clock_hz(rcc), freq_hz).ok_or(Error::Frequency)?;
+
+        // leave encoder mode, the counter is clocked by the prescaler
+        self.tim.smcr.write(|w| w.sms().disabled_or_combined());
+        self.tim.psc.write(|w| w.psc().bits(psc));
+        self.tim.arr.write(|w| w.arr().bits(arr));
+        self.set_ccr(ch, duty_ccr(arr, duty));
+
+        // PWM mode 1: output high while the counter is below the compare value
+        match ch {
+            Channel::Ch1 => self
+                .tim
+                .ccmr1_output()
+                .modify(|_, w| w.cc1s().output().oc1m().pwm_mode1().oc1pe().enabled()),
+            Channel::Ch2 => self
+                .tim
+                .ccmr1_output()
+                .modify(|_, w| w.cc2s().output().oc2m().pwm_mode1().oc2pe().enabled()),
+            Channel::Ch3 => self
+                .tim
+                .ccmr2_output()
+                .modify(|_, w| w.cc3s().output().oc3m().pwm_mode1().oc3pe().enabled()),
+            Channel::Ch4 => self
+                .tim
+                .ccmr2_output()
+                .modify(|_, w| w.cc4s().output().oc4m().pwm_mode1().oc4pe().enabled()),
+        }
+
+        // active high output, clearing the polarity set by encoder
+        self.tim.ccer.modify(|_, w| match ch {
+            Channel::Ch1 => w.cc1p().clear_bit().cc1np().clear_bit().cc1e().enabled(),
+            Channel::Ch2 => w.cc2p().clear_bit().cc2np().clear_bit().cc2e().enabled(),
+            Channel::Ch3 => w.cc3p().clear_bit().cc3np().clear_bit().cc3e().enabled(),
+            Channel::Ch4 => w.cc4p().clear_bit().cc4np().clear_bit().cc4e().enabled(),
+        });
+
+        // load the preloaded registers
+        self.tim.egr.write(|w| w.ug().update());
+        self.tim
+            .cr1
+            .modify(|_, w| w.dir().up().arpe().enabled().cen().enabled());
+
+        Ok(())
+    }
+
+    /// Set the duty cycle of a channel.
+    ///
+    /// The duty cycle is a fraction of `u16::MAX`, see [`pwm`](Self::pwm).
+    /// The new duty cycle takes effect at the start of the next period.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     gpio::PortA,
+    ///     pac,
+    ///     timer::{Channel, Tim2},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpioa: PortA = PortA::split(dp.GPIOA, &mut dp.RCC);
+    /// let mut tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+    /// let a0 = cortex_m::interrupt::free(|cs| tim2.new_ch1_pin(gpioa.a0, cs));
+    /// tim2.pwm(Channel::Ch1, 1_000, 0, &dp.RCC)?;
+    /// tim2.set_duty(Channel::Ch1, u16::MAX / 2);
+    /// # Ok::<(), stm32wlxx_hal::timer::Error>(())
+    /// ```
+    pub fn set_duty(&mut self, ch: Channel, duty: u16) {
+        let arr: u32 = self.tim.arr.read().arr().bits();
+        self.set_ccr(ch, duty_ccr(arr, duty))
+    }
+
+    /// Disable the output of a channel.
+    ///
+    /// The timer keeps running for the other channels.
+    #[inline]
+    pub fn disable_channel(&mut self, ch: Channel) {
+        self.tim.ccer.modify(|_, w| match ch {
+            Channel::Ch1 => w.cc1e().disabled(),
+            Channel::Ch2 => w.cc2e().disabled(),
+            Channel::Ch3 => w.cc3e().disabled(),
+            Channel::Ch4 => w.cc4e().disabled(),
+        })
+    }
+
+    /// Start counting a quadrature encoder.
+    ///
+    /// The A and B encoder signals are channel 1 and channel 2, setup the pins
+    /// with [`new_ch1_pin`](Self::new_ch1_pin) and
+    /// [`new_ch2_pin`](Self::new_ch2_pin).
+    ///
+    /// The auto-reload value is set to `u16::MAX`, the counter wraps from
+    /// `u16::MAX` to `0` when counting up and from `0` to `u16::MAX` when
+    /// counting down.
+    /// Wrapping differences of [`count`](Self::count) are correct as long
+    /// as the count changes by less than half the range between reads.
+    ///
+    /// This replaces any PWM configuration on channels 1 and 2.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     gpio::PortA,
+    ///     pac,
+    ///     timer::{EncoderMode, Tim2},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let gpioa: PortA = PortA::split(dp.GPIOA, &mut dp.RCC);
+    /// let mut tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+    /// let (a, b) = cortex_m::interrupt::free(|cs| {
+    ///     (
+    ///         tim2.new_ch1_pin(gpioa.a0, cs),
+    ///         tim2.new_ch2_pin(gpioa.a1, cs),
+    ///     )
+    /// });
+    /// tim2.encoder(EncoderMode::X4);
+    ///
+    /// let start: u16 = tim2.count();
+    /// // ... turn the encoder
+    /// let delta: i16 = tim2.count().wrapping_sub(start) as i16;
+    /// ```
+    pub fn encoder(&mut self, mode: EncoderMode) {
+        self.tim.cr1.modify(|_, w| w.cen().disabled());
+
+        // CCxS = 01 maps IC1 to TI1 and IC2 to TI2
+        // the PAC names this variant after channel 1
+        self.tim
+            .ccmr1_input()
+            .write(|w| w.cc1s().ti1().cc2s().ti1());
+        // non-inverted polarity on both inputs
+        self.tim.ccer.modify(|_, w| {
+            w.cc1e().disabled();
+            w.cc1p().rising_edge();
+            w.cc1np().active_high();
+            w.cc2e().disabled();
+            w.cc2p().rising_edge();
+            w.cc2np().active_high()
+        });
+        self.tim.smcr.write(|w| match mode {
+            EncoderMode::X2Ti1 => w.sms().encoder_mode1(),
+            EncoderMode::X2Ti2 => w.sms().encoder_mode2(),
+            EncoderMode::X4 => w.sms().encoder_mode3(),
+        });
+
+        self.tim.psc.write(|w| w.psc().bits(0));
+        self.tim.arr.write(|w| w.arr().bits(u16::MAX.into()));
+        self.tim.egr.write(|w| w.ug().update());
+        self.tim.cnt.write(|w| w.cnt().bits(0));
+        self.tim.cr1.modify(|_, w| w.cen().enabled());
+    }
+
+    /// Start a free-running 1 MHz counter for timestamps.
+    ///
+    /// The counter ticks once per microsecond, read it with
+    /// [`now_us`](Self::now_us).
+    /// This is a lightweight alternative to the DWT cycle counter, which is
+    /// not available on the Cortex-M0+ core.
+    ///
+    /// This replaces any PWM or encoder configuration.
+    /// The prescaler is computed from the current timer clock, call this
+    /// again after changing the clocks.
+    ///
+    /// Returns [`Error::Frequency`] if the timer clock (see
+    /// [`clock_hz`](Self::clock_hz)) is not a multiple of 1 MHz, the timer is
+    /// not modified.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{pac, timer::Tim2};
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let mut tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+    /// tim2.monotonic_us(&dp.RCC)?;
+    ///
+    /// let start: u32 = tim2.now_us();
+    /// // ... do something
+    /// let elapsed_us: u32 = tim2.now_us().wrapping_sub(start);
+    /// # Ok::<(), stm32wlxx_hal::timer::Error>(())
+    /// ```
+    pub fn monotonic_us(&mut self, rcc: &pac::RCC) -> Result<(), Error> {
+        let psc: u16 = us_psc(self.clock_hz(rcc)).ok_or(Error::Frequency)?;
+
+        self.tim.cr1.modify(|_, w| w.cen().disabled());
+        self.tim.smcr.write(|w| w.sms().disabled_or_combined());
+        self.tim.psc.write(|w| w.psc().bits(psc));
+        self.tim.arr.write(|w| w.arr().bits(u32::MAX));
+        // load the prescaler
+        self.tim.egr.write(|w| w.ug().update());
+        self.tim.cnt.write(|w| w.cnt().bits(0));
+        self.tim.cr1.modify(|_, w| w.dir().up().cen().enabled());
+
+        Ok(())
+    }
+
+    /// Microseconds since [`monotonic_us`](Self::monotonic_us) started the
+    /// counter.
+    ///
+    /// The 32-bit counter wraps from `u32::MAX` to `0` after about 71.6
+    /// minutes.
+    /// Use [`wrapping_sub`](u32::wrapping_sub) to compute durations, the
+    /// difference is correct for durations shorter than the wrap period.
+    ///
+    /// # Example
+    ///
+    /// See [`monotonic_us`](Self::monotonic_us).
+    #[inline]
+    #[must_use]
+    pub fn now_us(&self) -> u32 {
+        self.tim.cnt.read().cnt().bits()
+    }
+
+    /// Current counter value.
+    ///
+    /// In encoder mode this is the encoder position, see
+    /// [`encoder`](Self::encoder).
+    #[inline]
+    #[must_use]
+    pub fn count(&self) -> u16 {
+        self.tim.cnt.read().cnt().bits() as u16
+    }
+
+    /// Current counter direction.
+    ///
+    /// In encoder mode this is the direction of the last encoder edge.
+    #[inline]
+    #[must_use]
+    pub fn direction(&self) -> Direction {
+        if self.tim.cr1.read().dir().is_down() {
+            Direction::Down
+        } else {
+            Direction::Up
+        }
+    }
+
+    fn set_ccr(&mut self, ch: Channel, ccr: u32) {
+        match ch {
+            Channel::Ch1 => self.tim.ccr1.write(|w| w.ccr1().bits(ccr)),
+            Channel::Ch2 => self.tim.ccr2.write(|w| w.ccr2().bits(ccr)),
+            Channel::Ch3 => self.tim.ccr3.write(|w| w.ccr3().bits(ccr)),
+            Channel::Ch4 => self.tim.ccr4.write(|w| w.ccr4().bits(ccr)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{duty_ccr, psc_arr, us_psc};
+
+    #[test]
+    fn psc_arr_frequencies() {
+        // 48 MHz, PCLK1 at 48 MHz or 24 MHz with the timer clock doubled
+        assert_eq!(psc_arr(48_000_000, 1), Some((0, 47_999_999)));
+        assert_eq!(psc_arr(48_000_000, 50), Some((0, 959_999)));
+        assert_eq!(psc_arr(48_000_000, 1_000), Some((0, 47_999)));
+        assert_eq!(psc_arr(48_000_000, 20_000), Some((0, 2_399)));
+        // 6857.14 ticks rounded to 6857
+        assert_eq!(psc_arr(48_000_000, 7_000), Some((0, 6_856)));
+        assert_eq!(psc_arr(48_000_000, 24_000_000), Some((0, 1)));
+        // reset clock of 4 MHz MSI
+        assert_eq!(psc_arr(4_000_000, 1_000), Some((0, 3_999)));
+        // the 32-bit auto-reload fits any integer frequency of a u32 clock
+        assert_eq!(psc_arr(u32::MAX, 1), Some((0, u32::MAX - 1)));
+    }
+
+    #[test]
+    fn psc_arr_invalid() {
+        assert_eq!(psc_arr(48_000_000, 0), None);
+        assert_eq!(psc_arr(48_000_000, 24_000_001), None);
+        assert_eq!(psc_arr(48_000_000, 48_000_000), None);
+        assert_eq!(psc_arr(0, 1), None);
+    }
+
+    #[test]
+    fn duty_ccr_scaling() {
+        assert_eq!(duty_ccr(47_999, 0), 0);
+        assert_eq!(duty_ccr(47_999, u16::MAX), 48_000);
+        assert_eq!(duty_ccr(47_999, u16::MAX / 2), 24_000);
+        assert_eq!(duty_ccr(47_999, u16::MAX / 4), 11_999);
+        assert_eq!(duty_ccr(1, u16::MAX / 2), 1);
+        assert_eq!(duty_ccr(u32::MAX, u16::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn us_psc_clocks() {
+        // PCLK1 at 48 MHz, or 24 MHz with the timer clock doubled
+        assert_eq!(us_psc(48_000_000), Some(47));
+        assert_eq!(us_psc(16_000_000), Some(15));
+        // reset clock of 4 MHz MSI
+        assert_eq!(us_psc(4_000_000), Some(3));
+        assert_eq!(us_psc(1_000_000), Some(0));
+        assert_eq!(us_psc(4_294_000_000), Some(4_293));
+
+        assert_eq!(us_psc(0), None);
+        // MSI ranges below 1 MHz
+        assert_eq!(us_psc(400_000), None);
+        // not a multiple of 1 MHz
+        assert_eq!(us_psc(48_000_001), None);
+    }
+}
@@ -2,7 +2,9 @@
 use crate::{
     dma::{self, DmaCh},
     gpio::{self},
-    pac, rcc, Ratio,
+    pac,
+    rcc::{self, Peripheral},
+    Ratio,
 };
 use cortex_m::interrupt::CriticalSection;
 use embedded_hal::prelude::*;
@@ -279,7 +281,7 @@ impl_clock_hz!(Uart1, USART1SEL_A, usart1, usart1sel, pclk2);
 impl_clock_hz!(Uart2, USART1SEL_A, usart1, usart2sel, pclk1);
 
 macro_rules! impl_pulse_reset {
-    ($uart:ident, $reg:ident, $method:ident) => {
+    ($uart:ident, $periph:ident) => {
         impl $uart<NoRx, NoTx> {
             /// Reset the UART.
             ///
@@ -292,19 +294,18 @@ macro_rules! impl_pulse_reset {
             ///
             /// See [`steal`](Self::steal)
             pub unsafe fn pulse_reset(rcc: &mut pac::RCC) {
-                rcc.$reg.modify(|_, w| w.$method().set_bit());
-                rcc.$reg.modify(|_, w| w.$method().clear_bit());
+                rcc::reset(rcc, Peripheral::$periph)
             }
         }
     };
 }
 
-impl_pulse_reset!(LpUart, apb1rstr2, lpuart1rst);
-impl_pulse_reset!(Uart1, apb2rstr, usart1rst);
-impl_pulse_reset!(Uart2, apb1rstr1, usart2rst);
+impl_pulse_reset!(LpUart, Lpuart);
+impl_pulse_reset!(Uart1, Usart1);
+impl_pulse_reset!(Uart2, Usart2);
 
 macro_rules! impl_clock_en_dis {
-    ($uart:ident, $reg:ident, $method:ident) => {
+    ($uart:ident, $periph:ident) => {
         impl $uart<NoRx, NoTx> {
             /// Enable the UART clock.
             ///
@@ -319,7 +320,7 @@ macro_rules! impl_clock_en_dis {
             /// LpUart::enable_clock(&mut dp.RCC);
             /// ```
             pub fn enable_clock(rcc: &mut pac::RCC) {
-                rcc.$reg.modify(|_, w| w.$method().enabled())
+                rcc::enable(rcc, Peripheral::$periph)
             }
 
             /// Disable the UART clock.
@@ -334,15 +335,15 @@ macro_rules! impl_clock_en_dis {
             /// 4. You are responsible for setting up anything that may have lost
             ///    state while the clock was disabled.
             pub unsafe fn disable_clock(rcc: &mut pac::RCC) {
-                rcc.$reg.modify(|_, w| w.$method().disabled())
+                rcc::disable(rcc, Peripheral::$periph)
             }
         }
     };
 }
 
-impl_clock_en_dis!(LpUart, apb1enr2, lpuart1en);
-impl_clock_en_dis!(Uart1, apb2enr, usart1en);
-impl_clock_en_dis!(Uart2, apb1enr1, usart2en);
+impl_clock_en_dis!(LpUart, Lpuart);
+impl_clock_en_dis!(Uart1, Usart1);
+impl_clock_en_dis!(Uart2, Usart2);
 
 macro_rules! impl_free_steal {
     ($uart:ident, $periph:ident) => {
@@ -2,6 +2,13 @@
 use crate::pac;
 use cortex_m::{delay::Delay, peripheral::syst::SystClkSource};
 
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
 /// Create a new [`cortex_m::delay::Delay`] from the current CPU systick
 /// frequency.
 ///
@@ -22,3 +29,175 @@ pub fn new_delay(syst: pac::SYST, rcc: &pac::RCC) -> Delay {
         crate::rcc::cpu_systick_hz(rcc, SystClkSource::Core),
     )
 }
+
+#[cfg(feature = "async")]
+const NOOP_VTABLE: RawWakerVTable = RawWakerVTable::new(
+    |_| RawWaker::new(core::ptr::null(), &NOOP_VTABLE),
+    |_| {},
+    |_| {},
+    |_| {},
+);
+
+/// A waker that does nothing when woken.
+#[cfg(feature = "async")]
+fn noop_waker() -> Waker {
+    // safety: the vtable functions do not use the data pointer
+    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &NOOP_VTABLE)) }
+}
+
+/// Run a future to completion by polling it in a loop.
+///
+/// This is the executor used in the async examples, it busy-waits instead
+/// of sleeping until the future is woken.
+/// Use the `block_on` of a real executor in applications.
+#[cfg(feature = "async")]
+#[doc(hidden)]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future: F = future;
+    // safety: the future is shadowed and never moved again
+    let mut future: Pin<&mut F> = unsafe { Pin::new_unchecked(&mut future) };
+    let waker: Waker = noop_waker();
+    let mut cx: Context = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Poll an interrupt flag for a future.
+///
+/// If the flag is set it is cleared and the future is ready.
+/// Otherwise `register` stores the waker and unmasks the interrupt, then
+/// the flag is checked again because it may have been set before the
+/// interrupt was unmasked, in which case the interrupt is masked again.
+#[cfg(all(feature = "async", not(feature = "stm32wl5x_cm0p")))]
+pub(crate) fn poll_flag<S, C, R, M>(mut is_set: S, clear: C, register: R, mask: M) -> Poll<()>
+where
+    S: FnMut() -> bool,
+    C: FnOnce(),
+    R: FnOnce(),
+    M: FnOnce(),
+{
+    if is_set() {
+        clear();
+        return Poll::Ready(());
+    }
+
+    register();
+
+    if is_set() {
+        mask();
+        clear();
+        Poll::Ready(())
+    } else {
+        Poll::Pending
+    }
+}
+
+#[cfg(all(test, feature = "async", not(feature = "stm32wl5x_cm0p")))]
+mod tests {
+    use super::{block_on, noop_waker, poll_flag};
+    use core::{
+        cell::Cell,
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    /// Future for an interrupt flag that is set by the test.
+    struct Flag<'a> {
+        set: &'a Cell<bool>,
+        unmasked: &'a Cell<bool>,
+        // set the flag when the interrupt is unmasked
+        race: bool,
+        polls: u32,
+    }
+
+    impl<'a> Future for Flag<'a> {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+            self.polls += 1;
+            let race: bool = self.race;
+            let poll: Poll<()> = poll_flag(
+                || self.set.get(),
+                || self.set.set(false),
+                || {
+                    self.unmasked.set(true);
+                    if race {
+                        self.set.set(true)
+                    }
+                },
+                || self.unmasked.set(false),
+            );
+            poll.map(|()| self.polls)
+        }
+    }
+
+    #[test]
+    fn flag_pending_ready() {
+        let set: Cell<bool> = Cell::new(false);
+        let unmasked: Cell<bool> = Cell::new(false);
+        let mut flag: Flag = Flag {
+            set: &set,
+            unmasked: &unmasked,
+            race: false,
+            polls: 0,
+        };
+        let waker: Waker = noop_waker();
+        let mut cx: Context = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut flag).poll(&mut cx), Poll::Pending);
+        assert!(unmasked.get());
+        assert_eq!(Pin::new(&mut flag).poll(&mut cx), Poll::Pending);
+
+        // interrupt handler masks the interrupt and wakes the task
+        set.set(true);
+        unmasked.set(false);
+        assert_eq!(Pin::new(&mut flag).poll(&mut cx), Poll::Ready(3));
+        assert!(!set.get());
+        assert!(!unmasked.get());
+    }
+
+    #[test]
+    fn flag_set_before_unmask() {
+        let set: Cell<bool> = Cell::new(false);
+        let unmasked: Cell<bool> = Cell::new(false);
+        let mut flag: Flag = Flag {
+            set: &set,
+            unmasked: &unmasked,
+            race: true,
+            polls: 0,
+        };
+        let waker: Waker = noop_waker();
+        let mut cx: Context = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut flag).poll(&mut cx), Poll::Ready(1));
+        assert!(!set.get());
+        assert!(!unmasked.get());
+    }
+
+    /// Future that is ready after it was polled `n` times.
+    struct Countdown(u32);
+
+    impl Future for Countdown {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 == 0 {
+                Poll::Ready(())
+            } else {
+                self.0 -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn block_on_pending() {
+        block_on(Countdown(3));
+        assert_eq!(block_on(async { 7 }), 7);
+    }
+}
@@ -0,0 +1,340 @@
+//! Window watchdog
+//!
+//! The window watchdog (WWDG) resets the device if it is not fed before the
+//! counter reaches `0x3F`, **and** if it is fed before the counter drops
+//! below the window value.
+//!
+//! Unlike the independent watchdog, feeding the WWDG too early (outside of
+//! the window) triggers a reset.
+//! This catches code that runs too fast as well as code that hangs.
+//!
+//! The WWDG is clocked from PCLK1, the timing changes if the PCLK1 frequency
+//! changes.
+//! The WWDG cannot be stopped once started, except by a reset.
+//!
+//! See RM0453 rev 2 section 35 "System window watchdog (WWDG)".
+//!
+//! # Example
+//!
+//! ```no_run
+//! use stm32wlxx_hal::{
+//!     pac,
+//!     wwdg::{Prescaler, Wwdg},
+//! };
+//!
+//! let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+//!
+//! let mut wwdg: Wwdg = Wwdg::start(dp.WWDG, 0x5F, 0x7F, Prescaler::Div8, &mut dp.RCC)?;
+//! loop {
+//!     // ... wait at least wwdg.window_open(&dp.RCC)
+//!     wwdg.feed();
+//! }
+//! # Ok::<(), stm32wlxx_hal::wwdg::Error>(())
+//! ```
+
+use crate::{
+    pac,
+    rcc::{self, Peripheral},
+};
+
+use core::time::Duration;
+
+/// Counter value at which the WWDG resets the device.
+const RESET: u8 = 0x3F;
+/// Maximum counter and window value.
+const MAX: u8 = 0x7F;
+
+/// WWDG errors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The counter value is outside of the range `0x40..=0x7F`.
+    Counter,
+    /// The window value is outside of the range `0x40..=0x7F`.
+    ///
+    /// A window value below `0x40` would reset the device on every feed.
+    Window,
+}
+
+/// WWDG counter clock prescaler.
+///
+/// The counter clock is PCLK1 / 4096 / prescaler.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Prescaler {
+    /// PCLK1 / 4096 / 1
+    Div1 = 0,
+    /// PCLK1 / 4096 / 2
+    Div2 = 1,
+    /// PCLK1 / 4096 / 4
+    Div4 = 2,
+    /// PCLK1 / 4096 / 8
+    Div8 = 3,
+    /// PCLK1 / 4096 / 16
+    Div16 = 4,
+    /// PCLK1 / 4096 / 32
+    Div32 = 5,
+    /// PCLK1 / 4096 / 64
+    Div64 = 6,
+    /// PCLK1 / 4096 / 128
+    Div128 = 7,
+}
+
+impl Prescaler {
+    /// Get the prescaler divisor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stm32wlxx_hal::wwdg::Prescaler;
+    ///
+    /// assert_eq!(Prescaler::Div1.div(), 1);
+    /// assert_eq!(Prescaler::Div16.div(), 16);
+    /// assert_eq!(Prescaler::Div128.div(), 128);
+    /// ```
+    pub const fn div(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+const fn validate(window: u8, counter: u8) -> Result<(), Error> {
+    if counter <= RESET || counter > MAX {
+        Err(Error::Counter)
+    } else if window <= RESET || window > MAX {
+        Err(Error::Window)
+    } else {
+        Ok(())
+    }
+}
+
+/// Duration of `ticks` WWDG counter ticks.
+const fn ticks_duration(ticks: u8, prescaler: Prescaler, pclk1_hz: u32) -> Duration {
+    if pclk1_hz == 0 {
+        Duration::MAX
+    } else {
+        let nanos: u64 =
+            (ticks as u64) * 4096 * (prescaler.div() as u64) * 1_000_000_000 / (pclk1_hz as u64);
+        Duration::from_nanos(nanos)
+    }
+}
+
+/// Time after feeding until the device is reset.
+const fn timeout(counter: u8, prescaler: Prescaler, pclk1_hz: u32) -> Duration {
+    ticks_duration(counter - RESET, prescaler, pclk1_hz)
+}
+
+/// Time after feeding until the window opens.
+const fn window_open(window: u8, counter: u8, prescaler: Prescaler, pclk1_hz: u32) -> Duration {
+    ticks_duration(counter.saturating_sub(window), prescaler, pclk1_hz)
+}
+
+/// Window watchdog driver.
+#[derive(Debug)]
+pub struct Wwdg {
+    wwdg: pac::WWDG,
+    window: u8,
+    counter: u8,
+    prescaler: Prescaler,
+}
+
+impl Wwdg {
+    /// Enable clocks and start the window watchdog.
+    ///
+    /// # Arguments
+    ///
+    /// * `window`: Feeding when the counter is above this value resets the
+    ///   device, range `0x40..=0x7F`.
+    ///   A window greater than or equal to `counter` allows feeding at any
+    ///   time.
+    /// * `counter`: Counter value loaded on every feed, range `0x40..=0x7F`.
+    ///   The device resets when the counter decrements to `0x3F`.
+    /// * `prescaler`: Counter clock prescaler.
+    ///
+    /// Returns [`Error::Counter`] or [`Error::Window`] if the counter or
+    /// window values are out of range, the watchdog is not started.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     pac,
+    ///     wwdg::{Prescaler, Wwdg},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let wwdg: Wwdg = Wwdg::start(dp.WWDG, 0x5F, 0x7F, Prescaler::Div8, &mut dp.RCC)?;
+    /// # Ok::<(), stm32wlxx_hal::wwdg::Error>(())
+    /// ```
+    pub fn start(
+        wwdg: pac::WWDG,
+        window: u8,
+        counter: u8,
+        prescaler: Prescaler,
+        rcc: &mut pac::RCC,
+    ) -> Result<Wwdg, Error> {
+        validate(window, counter)?;
+
+        rcc::enable(rcc, Peripheral::Wwdg);
+
+        wwdg.cfr.write(|w| {
+            w.w().bits(window);
+            // safety: all prescaler values are valid
+            unsafe { w.wdgtb().bits(prescaler as u8) }
+        });
+        wwdg.cr.write(|w| w.wdga().enabled().t().bits(counter));
+
+        Ok(Wwdg {
+            wwdg,
+            window,
+            counter,
+            prescaler,
+        })
+    }
+
+    /// Feed the watchdog, reloading the counter.
+    ///
+    /// This will reset the device if the counter is above the window value.
+    ///
+    /// # Example
+    ///
+    /// See [`start`](Self::start).
+    #[inline]
+    pub fn feed(&mut self) {
+        self.wwdg
+            .cr
+            .write(|w| w.wdga().enabled().t().bits(self.counter))
+    }
+
+    /// Time after feeding until the device is reset.
+    ///
+    /// Returns [`Duration::MAX`] if PCLK1 is not running.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use core::time::Duration;
+    /// use stm32wlxx_hal::{
+    ///     pac,
+    ///     wwdg::{Prescaler, Wwdg},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // without any initialization pclk1 will be 4MHz
+    /// let wwdg: Wwdg = Wwdg::start(dp.WWDG, 0x7F, 0x7F, Prescaler::Div8, &mut dp.RCC)?;
+    /// assert_eq!(wwdg.timeout(&dp.RCC), Duration::from_nanos(524_288_000));
+    /// # Ok::<(), stm32wlxx_hal::wwdg::Error>(())
+    /// ```
+    #[must_use]
+    pub fn timeout(&self, rcc: &pac::RCC) -> Duration {
+        timeout(self.counter, self.prescaler, rcc::pclk1_hz(rcc))
+    }
+
+    /// Time after feeding until the window opens and feeding is allowed.
+    ///
+    /// Returns [`Duration::ZERO`] if the window is greater than or equal to
+    /// the counter.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use core::time::Duration;
+    /// use stm32wlxx_hal::{
+    ///     pac,
+    ///     wwdg::{Prescaler, Wwdg},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// // without any initialization pclk1 will be 4MHz
+    /// let wwdg: Wwdg = Wwdg::start(dp.WWDG, 0x5F, 0x7F, Prescaler::Div8, &mut dp.RCC)?;
+    /// assert_eq!(wwdg.window_open(&dp.RCC), Duration::from_nanos(262_144_000));
+    /// # Ok::<(), stm32wlxx_hal::wwdg::Error>(())
+    /// ```
+    #[must_use]
+    pub fn window_open(&self, rcc: &pac::RCC) -> Duration {
+        window_open(
+            self.window,
+            self.counter,
+            self.prescaler,
+            rcc::pclk1_hz(rcc),
+        )
+    }
+
+    /// Current counter value.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stm32wlxx_hal::{
+    ///     pac,
+    ///     wwdg::{Prescaler, Wwdg},
+    /// };
+    ///
+    /// let mut dp: pac::Peripherals = pac::Peripherals::take().unwrap();
+    ///
+    /// let wwdg: Wwdg = Wwdg::start(dp.WWDG, 0x7F, 0x7F, Prescaler::Div8, &mut dp.RCC)?;
+    /// assert!(wwdg.counter() > 0x3F);
+    /// # Ok::<(), stm32wlxx_hal::wwdg::Error>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn counter(&self) -> u8 {
+        self.wwdg.cr.read().t().bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{timeout, validate, window_open, Error, Prescaler};
+    use core::time::Duration;
+
+    #[test]
+    fn validate_range() {
+        assert_eq!(validate(0x40, 0x40), Ok(()));
+        assert_eq!(validate(0x7F, 0x7F), Ok(()));
+        assert_eq!(validate(0x50, 0x3F), Err(Error::Counter));
+        assert_eq!(validate(0x50, 0x80), Err(Error::Counter));
+        assert_eq!(validate(0x50, 0xFF), Err(Error::Counter));
+        assert_eq!(validate(0x3F, 0x7F), Err(Error::Window));
+        assert_eq!(validate(0x80, 0x7F), Err(Error::Window));
+        assert_eq!(validate(0x00, 0x7F), Err(Error::Window));
+    }
+
+    #[test]
+    fn timing() {
+        // 64 ticks of 4096 / 4 MHz
+        assert_eq!(
+            timeout(0x7F, Prescaler::Div1, 4_000_000),
+            Duration::from_nanos(65_536_000)
+        );
+        // 1 tick of 4096 * 128 / 48 MHz
+        assert_eq!(
+            timeout(0x40, Prescaler::Div128, 48_000_000),
+            Duration::from_nanos(10_922_666)
+        );
+        assert_eq!(
+            timeout(0x7F, Prescaler::Div128, 48_000_000),
+            Duration::from_nanos(699_050_666)
+        );
+        assert_eq!(timeout(0x7F, Prescaler::Div1, 0), Duration::MAX);
+    }
+
+    #[test]
+    fn window_timing() {
+        assert_eq!(
+            window_open(0x5F, 0x7F, Prescaler::Div8, 4_000_000),
+            Duration::from_nanos(262_144_000)
+        );
+        assert_eq!(
+            window_open(0x7F, 0x7F, Prescaler::Div8, 4_000_000),
+            Duration::ZERO
+        );
+        assert_eq!(
+            window_open(0x7F, 0x50, Prescaler::Div8, 4_000_000),
+            Duration::ZERO
+        );
+    }
+}
@@ -5,8 +5,9 @@ use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
 use defmt::unwrap;
 use defmt_rtt as _; // global logger
 use nucleo_wl55jc_bsp::hal::{
-    adc::{self, Adc, Clk, OversampleRatio, OversampleShift},
+    adc::{self, Adc, Alignment, Clk, Millivolts, OversampleRatio, OversampleShift, Raw},
     cortex_m::{self, delay::Delay},
+    embedded_hal::blocking::delay::DelayUs,
     pac::{self, DWT},
     rcc,
     util::new_delay,
@@ -31,6 +32,19 @@ fn validate_vbat(sample: u16, oversample: i16) {
     defmt::assert!(delta < tolerance);
 }
 
+/// Delay that counts calls.
+struct CountDelay<'a> {
+    delay: &'a mut Delay,
+    count: u32,
+}
+
+impl DelayUs<u8> for CountDelay<'_> {
+    fn delay_us(&mut self, us: u8) {
+        self.count += 1;
+        self.delay.delay_us(u32::from(us))
+    }
+}
+
 #[defmt_test::tests]
 mod tests {
     use super::*;
@@ -54,7 +68,7 @@ mod tests {
         let delay = new_delay(cp.SYST, &dp.RCC);
 
         let adc: Adc = Adc::new(dp.ADC, Clk::PClkDiv4, &mut dp.RCC);
-        defmt::assert_eq!(adc.clock_hz(&dp.RCC), ADC_FREQ);
+        defmt::assert_eq!(adc.clock_hz(&dp.RCC), Some(ADC_FREQ));
 
         cp.DCB.enable_trace();
         cp.DWT.enable_cycle_counter();
@@ -204,7 +218,7 @@ mod tests {
         ta.adc.disable();
         ta.adc.set_clock_source(Clk::RccHsi, &mut ta.rcc);
         defmt::assert_eq!(ta.adc.clock_source(&ta.rcc), Some(Clk::RccHsi));
-        defmt::assert_eq!(ta.adc.clock_hz(&ta.rcc), 16_000_000);
+        defmt::assert_eq!(ta.adc.clock_hz(&ta.rcc), Some(16_000_000));
         ta.adc.calibrate(&mut ta.delay);
         ta.adc.enable();
         ta.adc.enable_vref();
@@ -222,6 +236,55 @@ mod tests {
         ta.rcc.cr.modify(|_, w| w.hsion().clear_bit());
     }
 
+    #[test]
+    fn test_no_clock(ta: &mut TestArgs) {
+        let original: Clk = unwrap!(ta.adc.clock_source(&ta.rcc));
+
+        ta.adc.disable();
+        ta.adc.set_clock_source(Clk::RccHsi, &mut ta.rcc);
+        ta.rcc.ccipr.modify(|_, w| w.adcsel().no_clock());
+        defmt::assert_eq!(ta.adc.clock_source(&ta.rcc), None);
+        defmt::assert_eq!(ta.adc.clock_hz(&ta.rcc), None);
+
+        ta.adc.set_clock_source(original, &mut ta.rcc);
+        defmt::assert_eq!(ta.adc.clock_hz(&ta.rcc), Some(ADC_FREQ));
+    }
+
+    #[test]
+    fn recalibrate_and_delta(ta: &mut TestArgs) {
+        ta.adc.disable();
+        ta.adc.calibrate(&mut ta.delay);
+        let prev: u8 = ta.adc.calfact();
+
+        let delta: i8 = ta.adc.recalibrate_and_delta(&mut ta.delay);
+        defmt::info!("calfact {} Δ {}", prev, delta);
+        defmt::assert_eq!(i16::from(ta.adc.calfact()) - i16::from(prev), delta.into());
+        // back-to-back calibrations should be close
+        defmt::assert!(delta.abs() < 4);
+    }
+
+    #[test]
+    fn calibrate_during_conversion(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+
+        ta.adc.start_chsel(adc::Ch::Vbat.mask());
+        ta.adc.wait_chsel_ready();
+        ta.adc.start_conversion();
+        defmt::assert!(ta.adc.is_conversion_active());
+
+        ta.adc.calibrate(&mut ta.delay);
+        defmt::assert!(ta.adc.is_disabled());
+        defmt::assert!(!ta.adc.is_conversion_active());
+        // the interrupted result is discarded
+        defmt::assert!(Adc::isr().eoc().is_not_complete());
+
+        ta.adc.enable();
+        validate_vbat(ta.adc.vbat(), 1);
+        ta.adc.disable_vbat();
+    }
+
     #[test]
     fn temperature(ta: &mut TestArgs) {
         ta.adc.disable();
@@ -238,6 +301,68 @@ mod tests {
         defmt::assert!(temp < 70);
     }
 
+    #[test]
+    fn temperature_checked(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.set_max_sample_time();
+        ta.adc.disable_tsen();
+        defmt::assert_eq!(
+            ta.adc.temperature_checked(adc::TS_START_MAX).err(),
+            Some(adc::Error::NotReady)
+        );
+
+        ta.adc.enable_tsen();
+        defmt::assert_eq!(
+            ta.adc.temperature_checked(adc::TS_START_TYP).err(),
+            Some(adc::Error::NotReady)
+        );
+
+        ta.delay.delay_us(adc::TS_START_MAX.as_micros() as u32);
+        let temp: i16 = unwrap!(ta.adc.temperature_checked(adc::TS_START_MAX)).to_integer();
+        defmt::info!("Temperature: {} °C", temp);
+        defmt::assert!(temp > 25);
+        defmt::assert!(temp < 70);
+
+        ta.adc.disable_tsen();
+    }
+
+    #[test]
+    fn temperature_auto(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.disable_tsen();
+        ta.adc
+            .set_sample_times(adc::Ch::Vbat.mask(), adc::Ts::Cyc1, adc::Ts::Cyc12);
+
+        // safety: only reads
+        let smpr: u32 = unsafe { (*pac::ADC::PTR).smpr.read().bits() };
+
+        let temp: i16 = ta.adc.temperature_auto(&ta.rcc, &mut ta.delay).to_integer();
+        defmt::info!("Temperature: {} °C", temp);
+        defmt::assert!(temp > 25);
+        defmt::assert!(temp < 70);
+
+        defmt::assert_eq!(unsafe { (*pac::ADC::PTR).smpr.read().bits() }, smpr);
+        defmt::assert!(!ta.adc.is_tsen_enabled());
+    }
+
+    #[test]
+    fn sample_time_ok(ta: &mut TestArgs) {
+        // 160.5 cycles at 12 MHz is 13.4 µs
+        ta.adc.set_max_sample_time();
+        defmt::assert!(ta.adc.is_sample_time_ok(adc::Ch::Vts, &ta.rcc));
+        defmt::assert!(ta.adc.is_sample_time_ok(adc::Ch::Vref, &ta.rcc));
+        defmt::assert!(ta.adc.is_sample_time_ok(adc::Ch::Vbat, &ta.rcc));
+
+        // 39.5 cycles at 12 MHz is 3.3 µs
+        ta.adc.set_sample_times(0, adc::Ts::Cyc39, adc::Ts::Cyc39);
+        defmt::assert!(!ta.adc.is_sample_time_ok(adc::Ch::Vts, &ta.rcc));
+        defmt::assert!(!ta.adc.is_sample_time_ok(adc::Ch::Vref, &ta.rcc));
+        defmt::assert!(!ta.adc.is_sample_time_ok(adc::Ch::Vbat, &ta.rcc));
+        defmt::assert!(ta.adc.is_sample_time_ok(adc::Ch::In0, &ta.rcc));
+
+        ta.adc.set_max_sample_time();
+    }
+
     #[test]
     fn vbat(ta: &mut TestArgs) {
         // short form calibration
@@ -255,6 +380,24 @@ mod tests {
         validate_vbat(sample, 1);
     }
 
+    #[test]
+    fn battery_percent(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vbat();
+        ta.adc.enable_vref();
+        ta.adc.set_max_sample_time();
+
+        // V_BAT is connected to the 3.3 V supply on the NUCLEO board
+        let percent: u8 = ta.adc.battery_percent(&adc::BatteryCurve::CR2032);
+        defmt::assert_eq!(percent, 100);
+
+        let percent: u8 = ta.adc.battery_percent(&adc::BatteryCurve::LIPO);
+        defmt::info!("LiPo percent at 3.3 V: {}", percent);
+        defmt::assert!(percent <= 10);
+
+        ta.adc.disable_vbat();
+    }
+
     #[test]
     fn vbat_oversample(ta: &mut TestArgs) {
         ta.adc.disable();
@@ -354,4 +497,468 @@ mod tests {
         // check that stop conversion works without a conversion in-progress
         ta.adc.stop_conversion();
     }
+
+    #[test]
+    fn read_now(ta: &mut TestArgs) {
+        const SCAN: u32 = adc::Ch::Vbat.mask() | adc::Ch::Dac.mask();
+
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.set_max_sample_time();
+
+        ta.adc.start_chsel(SCAN);
+        while Adc::isr().ccrdy().is_not_complete() {}
+        ta.adc.start_conversion();
+
+        let vref: u16 = ta.adc.read_now(adc::Ch::Vref);
+        let vref_cal: u16 = adc::vref_cal();
+        let delta: i16 = ((vref_cal as i16) - (vref as i16)).abs();
+        defmt::info!("vref: {} Δ {}", vref, delta);
+        defmt::assert!(delta < 25);
+
+        // safety: read-only access to the channel selection register
+        let chsel: u32 = unsafe { (*pac::ADC::PTR).chselr0().read().chsel().bits() };
+        defmt::assert_eq!(chsel, SCAN);
+
+        ta.adc.stop_conversion();
+    }
+    #[test]
+    fn read_differential(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+
+        let vref: i16 = ta.adc.vref() as i16;
+        let vbat: i16 = ta.adc.vbat() as i16;
+
+        // the second pass scans backward, converting Vbat (14) before Vref (13)
+        for _ in 0..2 {
+            let pos: i16 = ta.adc.read_differential(adc::Ch::Vref, adc::Ch::Vbat);
+            let neg: i16 = ta.adc.read_differential(adc::Ch::Vbat, adc::Ch::Vref);
+            defmt::info!("vref: {} vbat: {} diff: {} {}", vref, vbat, pos, neg);
+
+            defmt::assert!((pos - (vref - vbat)).abs() < 25);
+            defmt::assert!((neg - (vbat - vref)).abs() < 25);
+
+            // safety: ADSTART = 0, sequence is complete
+            unsafe { (*pac::ADC::PTR).cfgr1.modify(|_, w| w.scandir().backward()) };
+        }
+
+        // safety: ADSTART = 0, sequence is complete
+        unsafe { (*pac::ADC::PTR).cfgr1.modify(|_, w| w.scandir().upward()) };
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn read_raw_sequence(ta: &mut TestArgs) {
+        const MASK: u32 = adc::Ch::Vref.mask() | adc::Ch::Vbat.mask() | adc::Ch::Dac.mask();
+
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+
+        let mut short: [u16; 2] = [0; 2];
+        defmt::assert_eq!(
+            ta.adc.read_raw_sequence(MASK, &mut short),
+            Err(adc::Error::Length)
+        );
+
+        let vref: i16 = ta.adc.vref() as i16;
+        let mut samples: [u16; 3] = [0; 3];
+        defmt::assert_eq!(ta.adc.read_raw_sequence(MASK, &mut samples), Ok(false));
+        defmt::info!("vref: {} samples: {}", vref, samples);
+        defmt::assert!((samples[0] as i16 - vref).abs() < 25);
+
+        // force an overrun with a CPU much slower than the conversions
+        let original: Clk = unwrap!(ta.adc.clock_source(&ta.rcc));
+        ta.rcc.cr.modify(|_, w| w.hsion().set_bit());
+        while ta.rcc.cr.read().hsirdy().is_not_ready() {}
+        ta.adc.disable();
+        ta.adc.set_clock_source(Clk::RccHsi, &mut ta.rcc);
+        ta.adc.enable();
+        ta.adc.set_sample_times(0, adc::Ts::MIN, adc::Ts::MIN);
+
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        cortex_m::interrupt::free(|cs| unsafe {
+            rcc::set_sysclk_msi(
+                &mut dp.FLASH,
+                &mut dp.PWR,
+                &mut ta.rcc,
+                rcc::MsiRange::Range100k,
+                cs,
+            )
+        });
+        let overrun: Result<bool, adc::Error> = ta.adc.read_raw_sequence(MASK, &mut samples);
+        cortex_m::interrupt::free(|cs| unsafe {
+            rcc::set_sysclk_msi_max(&mut dp.FLASH, &mut dp.PWR, &mut ta.rcc, cs)
+        });
+        defmt::assert_eq!(overrun, Ok(true));
+        defmt::assert!(Adc::isr().ovr().is_no_overrun());
+
+        ta.adc.disable();
+        ta.adc.set_clock_source(original, &mut ta.rcc);
+        ta.rcc.cr.modify(|_, w| w.hsion().clear_bit());
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn wait_eosmp(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+        ta.adc.set_isr(adc::irq::ALL);
+
+        ta.adc.start_chsel(adc::Ch::Vbat.mask());
+        while Adc::isr().ccrdy().is_not_complete() {}
+
+        let start: u32 = DWT::cycle_count();
+        ta.adc.start_conversion();
+        ta.adc.wait_eosmp();
+        let end: u32 = DWT::cycle_count();
+        compiler_fence(SeqCst);
+        defmt::assert!(Adc::isr().eosmp().is_not_at_end());
+
+        // sampling takes at least the sample time of 160.5 ADC cycles
+        let elapsed: u32 = end - start;
+        defmt::info!("EOSMP cycles: CPU {} ADC {}", elapsed, elapsed / FREQ_RATIO);
+        defmt::assert!(elapsed / FREQ_RATIO >= 160);
+
+        while Adc::isr().eoc().is_not_complete() {}
+        validate_vbat(ta.adc.data(), 1);
+        defmt::assert!(Adc::isr().eosmp().is_not_at_end());
+
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn data_isr(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+        ta.adc.set_isr(adc::irq::ALL);
+
+        ta.adc.start_chsel(adc::Ch::Vbat.mask());
+        while Adc::isr().ccrdy().is_not_complete() {}
+        ta.adc.start_conversion();
+        while Adc::isr().eoc().is_not_complete() {}
+        defmt::assert!(Adc::isr().eosmp().is_at_end());
+        defmt::assert!(Adc::isr().eos().is_complete());
+
+        validate_vbat(ta.adc.data(), 1);
+
+        // reading DR clears EOC in hardware, the other flags are untouched
+        let isr = Adc::isr();
+        defmt::assert!(isr.eoc().is_not_complete());
+        defmt::assert!(isr.eosmp().is_at_end());
+        defmt::assert!(isr.eos().is_complete());
+
+        ta.adc.set_isr(adc::irq::ALL);
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn wait_chsel_ready(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+        ta.adc.set_isr(adc::irq::ALL);
+
+        ta.adc.start_chsel(adc::Ch::Vbat.mask());
+        ta.adc.wait_chsel_ready();
+        // the flag is cleared so the next wait is not satisfied early
+        defmt::assert!(Adc::isr().ccrdy().is_not_complete());
+
+        ta.adc.start_conversion();
+        while Adc::isr().eoc().is_not_complete() {}
+        validate_vbat(ta.adc.data(), 1);
+
+        // every channel selection write is acknowledged again
+        ta.adc.start_chsel(adc::Ch::Vbat.mask());
+        ta.adc.wait_chsel_ready();
+        defmt::assert!(Adc::isr().ccrdy().is_not_complete());
+
+        ta.adc.set_isr(adc::irq::ALL);
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn irq_scan(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+
+        // the ADC IRQ is masked in the NVIC, poll the EOC flag in place of
+        // the interrupt handler
+        ta.adc
+            .start_irq_scan(adc::Ch::Vref.mask() | adc::Ch::Vbat.mask());
+        defmt::assert!(Adc::isr().eoc().is_not_complete());
+
+        let mut samples: [u16; 6] = [0; 6];
+        let mut n: usize = 0;
+        while n < samples.len() {
+            let isr = Adc::isr();
+            if isr.eoc().is_complete() || isr.eos().is_complete() {
+                ta.adc.on_eoc(|sample| {
+                    samples[n] = sample;
+                    n += 1;
+                });
+            }
+        }
+        defmt::assert!(Adc::isr().ovr().is_no_overrun());
+        ta.adc.stop_irq_scan();
+        defmt::assert!(!ta.adc.is_conversion_active());
+
+        // three complete sequences of Vref (13) then Vbat (14)
+        defmt::info!("irq scan samples: {}", samples);
+        for pair in samples.chunks_exact(2) {
+            defmt::assert!(pair[0] > 1000);
+            validate_vbat(pair[1], 1);
+        }
+
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn sequence(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.enable_vbat();
+        ta.adc.set_max_sample_time();
+
+        let vref: i16 = ta.adc.vref() as i16;
+        let vbat: i16 = ta.adc.vbat() as i16;
+
+        let mut samples: [Option<(adc::Ch, u16)>; 3] = [None; 3];
+        for (idx, sample) in ta
+            .adc
+            .sequence(adc::Ch::Vbat.mask() | adc::Ch::Vref.mask())
+            .enumerate()
+        {
+            samples[idx.min(2)] = Some(sample);
+        }
+        defmt::info!("vref: {} vbat: {}", vref, vbat);
+
+        let (ch, sample) = unwrap!(samples[0]);
+        defmt::assert!(ch == adc::Ch::Vref);
+        defmt::assert!((sample as i16 - vref).abs() < 25);
+
+        let (ch, sample) = unwrap!(samples[1]);
+        defmt::assert!(ch == adc::Ch::Vbat);
+        defmt::assert!((sample as i16 - vbat).abs() < 25);
+
+        defmt::assert!(samples[2].is_none());
+
+        // dropping the iterator early stops the conversion
+        defmt::assert!(ta
+            .adc
+            .sequence(adc::Ch::Vbat.mask() | adc::Ch::Vref.mask())
+            .next()
+            .is_some());
+        defmt::assert!(!ta.adc.is_conversion_active());
+
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn left_align_8bit(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.set_max_sample_time();
+
+        let right: u16 = ta.adc.vref();
+
+        // safety: ADSTART = 0
+        unsafe { (*pac::ADC::PTR).cfgr1.modify(|_, w| w.res().bits8()) };
+        ta.adc.set_alignment(Alignment::Left);
+        defmt::assert!(ta.adc.alignment() == Alignment::Left);
+
+        let left: u16 = ta.adc.vref();
+        defmt::info!("12-bit right: {:#06X} 8-bit left: {:#06X}", right, left);
+
+        // 8-bit data lands in the top byte
+        defmt::assert_eq!(left & 0xFF, 0);
+        let delta: i16 = ((left >> 8) as i16) - ((right >> 4) as i16);
+        defmt::assert!(delta.abs() < 3);
+
+        // safety: ADSTART = 0
+        unsafe { (*pac::ADC::PTR).cfgr1.modify(|_, w| w.res().bits12()) };
+        ta.adc.set_alignment(Alignment::Right);
+    }
+
+    #[test]
+    fn with_power(ta: &mut TestArgs) {
+        ta.adc.disable();
+        ta.adc.disable_vreg();
+        ta.adc.set_max_sample_time();
+
+        let mut delay = CountDelay {
+            delay: &mut ta.delay,
+            count: 0,
+        };
+        // ADVREGEN before, during, and after the closure
+        let mut vreg: [bool; 5] = [ta.adc.is_vreg_enabled(), false, false, false, false];
+        let (vref, vbat): (u16, u16) = ta.adc.with_power(&mut delay, |adc, delay| {
+            defmt::assert!(adc.is_enabled());
+            vreg[1] = adc.is_vreg_enabled();
+            adc.calibrate(delay);
+            vreg[2] = adc.is_vreg_enabled();
+            adc.enable();
+            adc.enable_vref();
+            adc.enable_vbat();
+            let vref: u16 = adc.vref();
+            let vbat: u16 = adc.vbat();
+            defmt::assert!(adc.vref().abs_diff(vref) < 10);
+            vreg[3] = adc.is_vreg_enabled();
+            (vref, vbat)
+        });
+        vreg[4] = ta.adc.is_vreg_enabled();
+        // the voltage regulator is enabled once and disabled once
+        defmt::assert_eq!(vreg, [false, true, true, true, false]);
+        // the voltage regulator setup delay is performed once
+        defmt::assert_eq!(delay.count, 1);
+        defmt::info!("VREF={} VBAT={}", vref, vbat);
+        validate_vbat(vbat, 1);
+
+        // prior power state is restored
+        defmt::assert!(ta.adc.is_disabled());
+        defmt::assert!(!ta.adc.is_vreg_enabled());
+
+        ta.adc.disable_vref();
+        ta.adc.disable_vbat();
+    }
+
+    #[test]
+    fn with_power_enabled(ta: &mut TestArgs) {
+        ta.adc.enable();
+        defmt::assert!(ta.adc.is_vreg_enabled());
+
+        let mut delay = CountDelay {
+            delay: &mut ta.delay,
+            count: 0,
+        };
+        ta.adc.with_power(&mut delay, |adc, delay| {
+            // calibration disables the ADC
+            adc.calibrate(delay);
+            defmt::assert!(!adc.is_enabled());
+            defmt::assert!(adc.is_vreg_enabled());
+        });
+        // the voltage regulator was never disabled
+        defmt::assert_eq!(delay.count, 0);
+
+        // the ADC is enabled again
+        defmt::assert!(ta.adc.is_enabled());
+        defmt::assert!(ta.adc.is_vreg_enabled());
+        ta.adc.disable();
+    }
+
+    #[test]
+    fn read_as(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.set_max_sample_time();
+
+        let vref: u16 = ta.adc.vref();
+        let raw: u16 = ta.adc.read_as::<Raw>(adc::Ch::Vref);
+        defmt::info!("VREF={} raw={}", vref, raw);
+        defmt::assert!(raw.abs_diff(vref) < 10);
+
+        // DS13293 rev 1 table 13, VREFINT is 1.182 V to 1.232 V
+        let mv: u16 = ta.adc.read_as::<Millivolts>(adc::Ch::Vref);
+        defmt::info!("VREFINT={} mV", mv);
+        defmt::assert!((1182..=1232).contains(&mv));
+
+        ta.adc.disable_vref();
+        ta.adc.disable();
+    }
+
+    #[test]
+    fn watchdog1_mv(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.set_max_sample_time();
+
+        // VREFINT is about 1.2 V, within VDDA
+        let vref: u16 = ta.adc.vref();
+        let counts: u16 = ta.adc.mv_to_counts(1210);
+        defmt::info!("VREF={} 1210 mV={}", vref, counts);
+        defmt::assert!(counts.abs_diff(vref) < 100);
+
+        // watchdog range that contains VREFINT
+        ta.adc.set_isr(adc::irq::AWD1);
+        ta.adc.set_watchdog1_mv(Some(adc::Ch::Vref), 1000, 1400);
+        ta.adc.vref();
+        defmt::assert!(Adc::isr().awd1().bit_is_clear());
+
+        // watchdog range below VREFINT
+        ta.adc.set_watchdog1_mv(Some(adc::Ch::Vref), 0, 500);
+        ta.adc.vref();
+        defmt::assert!(Adc::isr().awd1().bit_is_set());
+        ta.adc.set_isr(adc::irq::AWD1);
+
+        ta.adc.disable_watchdog1();
+        ta.adc.vref();
+        defmt::assert!(Adc::isr().awd1().bit_is_clear());
+
+        ta.adc.disable_vref();
+        ta.adc.disable();
+    }
+
+    #[test]
+    fn read_oversampled(ta: &mut TestArgs) {
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.set_max_sample_time();
+
+        let avg: u32 = (0..64).map(|_| u32::from(ta.adc.vref())).sum::<u32>() / 64;
+        let sample: u16 = ta
+            .adc
+            .read_oversampled(adc::Ch::Vref, OversampleRatio::Mul64);
+        defmt::info!("VREF avg={} oversampled={}", avg, sample);
+        defmt::assert!(u32::from(sample).abs_diff(avg) < 10);
+
+        // the prior configuration is restored
+        defmt::assert!(ta.adc.is_enabled());
+        defmt::assert!(!ta.adc.is_oversampling_enabled());
+
+        ta.adc.disable();
+        ta.adc
+            .enable_oversampling(OversampleRatio::Mul2, OversampleShift::NoShift);
+        ta.adc
+            .read_oversampled(adc::Ch::Vref, OversampleRatio::Mul256);
+        defmt::assert!(ta.adc.is_disabled());
+        defmt::assert!(ta.adc.is_oversampling_enabled());
+        ta.adc.disable_oversampling();
+
+        ta.adc.disable_vref();
+    }
+
+    #[test]
+    fn release(ta: &mut TestArgs) {
+        ta.adc.enable_vreg();
+        ta.delay.delay_us(u32::from(adc::T_ADCVREG_SETUP_MICROS));
+        ta.adc.enable();
+        ta.adc.enable_vref();
+        ta.adc.enable_tsen();
+        defmt::assert!(ta.adc.is_enabled());
+        defmt::assert!(ta.adc.is_vreg_enabled());
+
+        // safety: the stolen driver is replaced before it is used
+        let adc: Adc = core::mem::replace(&mut ta.adc, unsafe { Adc::steal() });
+        let adc: pac::ADC = adc.release(&mut ta.rcc);
+        defmt::assert!(!rcc::is_enabled(&ta.rcc, rcc::Peripheral::Adc));
+
+        // the clock is required to read the ADC registers
+        rcc::enable(&mut ta.rcc, rcc::Peripheral::Adc);
+        defmt::assert!(adc.cr.read().aden().bit_is_clear());
+        defmt::assert!(adc.cr.read().advregen().is_disabled());
+        defmt::assert!(adc.ccr.read().vrefen().is_disabled());
+        defmt::assert!(adc.ccr.read().tsen().is_disabled());
+        defmt::assert!(adc.ccr.read().vbaten().is_disabled());
+
+        ta.adc = Adc::new(adc, Clk::PClkDiv4, &mut ta.rcc);
+        defmt::assert!(ta.adc.is_disabled());
+    }
 }
@@ -11,7 +11,7 @@ use defmt::unwrap;
 use defmt_rtt as _; // global logger
 use hex_literal::hex;
 use nucleo_wl55jc_bsp::hal::{
-    aes::{Aes, AesWrapClk, SwapMode},
+    aes::{self, Aes, AesWrapClk, Algorithm, Ciphertext, CtrIv, Mode, Plaintext, SwapMode},
     cortex_m::{self, peripheral::DWT},
     pac, rcc,
 };
@@ -1468,6 +1468,47 @@ const GCM_256: [Gcm<8>; 60] = [
     },
 ];
 
+// test cases 5, 6, 17, and 18 from "The Galois/Counter Mode of Operation"
+// as submitted to NIST, the IVs are 64 and 480 bits
+struct GcmIv {
+    key: &'static [u32],
+    iv: &'static [u8],
+    ct: &'static [u8],
+    tag: [u32; 4],
+}
+
+const GCM_IV_PT: [u8; 60] = hex!("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
+const GCM_IV_AAD: [u8; 20] = hex!("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+const IV_64: [u8; 8] = hex!("cafebabefacedbad");
+const IV_480: [u8; 60] = hex!("9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b");
+
+const GCM_IV: [GcmIv; 4] = [
+    GcmIv {
+        key: &[0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308],
+        iv: &IV_64,
+        ct: &hex!("61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c742373806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f4598"),
+        tag: [0x3612d2e7, 0x9e3b0785, 0x561be14a, 0xaca2fccb],
+    },
+    GcmIv {
+        key: &[0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308],
+        iv: &IV_480,
+        ct: &hex!("8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca701e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5"),
+        tag: [0x619cc5ae, 0xfffe0bfa, 0x462af43c, 0x1699d050],
+    },
+    GcmIv {
+        key: &[0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308, 0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308],
+        iv: &IV_64,
+        ct: &hex!("c3762df1ca787d32ae47c13bf19844cbaf1ae14d0b976afac52ff7d79bba9de0feb582d33934a4f0954cc2363bc73f7862ac430e64abe499f47c9b1f"),
+        tag: [0x3a337dbf, 0x46a792c4, 0x5e454913, 0xfe2ea8f2],
+    },
+    GcmIv {
+        key: &[0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308, 0xfeffe992, 0x8665731c, 0x6d6a8f94, 0x67308308],
+        iv: &IV_480,
+        ct: &hex!("5a8def2f0c9e53f1f75d7853659e2a20eeb2b22aafde6419a058ab4f6f746bf40fc0c3b780f244452da3ebf1c5d82cdea2418997200ef82e44ae7e3f"),
+        tag: [0xa44a8266, 0xee1c8eb0, 0xc8b5d4cf, 0x5ae9f19a],
+    },
+];
+
 const NUM_ECB_128: u32 = (ECB_PT_CT_128.len() + ECB_KEY_CT_128.len()) as u32;
 const NUM_ECB_256: u32 = (ECB_PT_CT_256.len() + ECB_KEY_CT_256.len()) as u32;
 const NUM_GCM_128: u32 = GCM_128.len() as u32;
@@ -1667,6 +1708,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encrypt_decrypt_block(aes: &mut Aes) {
+        aes.set_dataswap(SwapMode::None);
+
+        for (plaintext, ciphertext) in ECB_PT_CT_128.iter() {
+            let ct: Ciphertext = unwrap!(aes.encrypt_block(&ZERO_16B, &Plaintext(*plaintext)));
+            defmt::assert_eq!(ct, Ciphertext(*ciphertext));
+            let pt: Plaintext = unwrap!(aes.decrypt_block(&ZERO_16B, &ct));
+            defmt::assert_eq!(pt, Plaintext(*plaintext));
+        }
+    }
+
+    #[test]
+    fn encrypt_ecb_iter(aes: &mut Aes) {
+        aes.set_dataswap(SwapMode::None);
+
+        let expected: u32 = ECB_PT_CT_128[..3]
+            .iter()
+            .flat_map(|(_, ciphertext)| ciphertext.iter())
+            .fold(0, |acc, dw| acc.wrapping_add(*dw));
+
+        let mut sum: u32 = 0;
+        let mut num_blocks: usize = 0;
+        unwrap!(aes.encrypt_ecb_iter(
+            &ZERO_16B,
+            ECB_PT_CT_128[..3].iter().map(|(plaintext, _)| *plaintext),
+            |ciphertext| {
+                num_blocks += 1;
+                sum = ciphertext.iter().fold(sum, |acc, dw| acc.wrapping_add(*dw));
+            },
+        ));
+
+        defmt::assert_eq!(num_blocks, 3);
+        defmt::assert_eq!(sum, expected);
+    }
+
     #[test]
     fn encrypt_gcm_inplace_128(aes: &mut Aes) {
         let mut total_elapsed: u32 = 0;
@@ -2163,6 +2240,113 @@ mod tests {
         }
     }
 
+    #[test]
+    fn gcm_inplace_iv(aes: &mut Aes) {
+        for gcm in GCM_IV.iter() {
+            let mut buf: [u8; 60] = GCM_IV_PT;
+            let mut tag: [u32; 4] = [0; 4];
+            unwrap!(aes.encrypt_gcm_inplace_iv(gcm.key, gcm.iv, &GCM_IV_AAD, &mut buf, &mut tag));
+            defmt::assert_eq!(tag, gcm.tag);
+            defmt::assert_eq!(buf.as_slice(), gcm.ct);
+
+            let mut tag: [u32; 4] = [0; 4];
+            unwrap!(aes.decrypt_gcm_inplace_iv(gcm.key, gcm.iv, &GCM_IV_AAD, &mut buf, &mut tag));
+            defmt::assert_eq!(tag, gcm.tag);
+            defmt::assert_eq!(buf, GCM_IV_PT);
+        }
+
+        // a 96-bit IV takes the peripheral path
+        let gcm: &Gcm<4> = &GCM_128[GCM_128.len() - 1];
+        let iv: [u8; 16] = aes::block_to_bytes(&[gcm.iv[0], gcm.iv[1], gcm.iv[2], 0]);
+        let mut buf: [u8; 16] = [0; 16];
+        buf[..gcm.pt.len()].copy_from_slice(gcm.pt);
+        let mut tag: [u32; 4] = [0; 4];
+        unwrap!(aes.encrypt_gcm_inplace_iv(
+            &gcm.key,
+            &iv[..12],
+            gcm.aad,
+            &mut buf[..gcm.pt.len()],
+            &mut tag
+        ));
+        defmt::assert_eq!(tag, gcm.tag);
+        defmt::assert_eq!(&buf[..gcm.pt.len()], gcm.ct);
+
+        // a 96-bit IV uses the configured swap mode, like encrypt_gcm_inplace
+        aes.set_dataswap(SwapMode::Byte);
+        let mut expected: [u8; 16] = [0; 16];
+        expected[..gcm.pt.len()].copy_from_slice(gcm.pt);
+        let mut expected_tag: [u32; 4] = [0; 4];
+        unwrap!(aes.encrypt_gcm_inplace(
+            &gcm.key,
+            &gcm.iv,
+            gcm.aad,
+            &mut expected[..gcm.pt.len()],
+            &mut expected_tag
+        ));
+        buf[..gcm.pt.len()].copy_from_slice(gcm.pt);
+        unwrap!(aes.encrypt_gcm_inplace_iv(
+            &gcm.key,
+            &iv[..12],
+            gcm.aad,
+            &mut buf[..gcm.pt.len()],
+            &mut tag
+        ));
+        defmt::assert_eq!(tag, expected_tag);
+        defmt::assert_eq!(buf, expected);
+
+        // other IV lengths override the swap mode and restore it
+        let gcm: &GcmIv = &GCM_IV[0];
+        let mut buf: [u8; 60] = GCM_IV_PT;
+        unwrap!(aes.encrypt_gcm_inplace_iv(gcm.key, gcm.iv, &GCM_IV_AAD, &mut buf, &mut tag));
+        defmt::assert_eq!(tag, gcm.tag);
+        defmt::assert_eq!(buf.as_slice(), gcm.ct);
+        defmt::assert!(aes.dataswap() == SwapMode::Byte);
+        aes.set_dataswap(SwapMode::None);
+
+        defmt::assert_eq!(
+            aes.encrypt_gcm_inplace_iv(&ZERO_16B, &[], &[], &mut [], &mut tag),
+            Err(aes::Error::Length)
+        );
+    }
+
+    #[test]
+    fn gcm_vectored(aes: &mut Aes) {
+        // NIST vector with the AAD and plaintext split off block boundaries
+        let gcm: &Gcm<4> = &GCM_128[GCM_128.len() - 1];
+        defmt::assert_eq!(gcm.aad.len(), 16);
+        defmt::assert_eq!(gcm.pt.len(), 16);
+        let (aad_a, aad_b) = gcm.aad.split_at(5);
+
+        let mut pt_a: [u8; 7] = [0; 7];
+        let mut pt_b: [u8; 9] = [0; 9];
+        pt_a.copy_from_slice(&gcm.pt[..7]);
+        pt_b.copy_from_slice(&gcm.pt[7..]);
+
+        let mut tag: [u32; 4] = [0; 4];
+        unwrap!(aes.encrypt_gcm_vectored(
+            &gcm.key,
+            &gcm.iv,
+            &[aad_a, &[], aad_b],
+            &mut [&mut pt_a, &mut pt_b],
+            &mut tag
+        ));
+        defmt::assert_eq!(tag, gcm.tag);
+        defmt::assert_eq!(pt_a, gcm.ct[..7]);
+        defmt::assert_eq!(pt_b, gcm.ct[7..]);
+
+        let mut tag: [u32; 4] = [0; 4];
+        unwrap!(aes.decrypt_gcm_vectored(
+            &gcm.key,
+            &gcm.iv,
+            &[aad_a, aad_b],
+            &mut [&mut pt_a, &mut pt_b],
+            &mut tag
+        ));
+        defmt::assert_eq!(tag, gcm.tag);
+        defmt::assert_eq!(pt_a, gcm.pt[..7]);
+        defmt::assert_eq!(pt_b, gcm.pt[7..]);
+    }
+
     #[test]
     fn ecb_half_word_swap(aes: &mut Aes) {
         let pt: [u32; 4] = half_word_swap(ECB_PT_CT_128[0].0);
@@ -2202,6 +2386,451 @@ mod tests {
         defmt::assert_eq!(output_ciphertext, ct);
     }
 
+    #[test]
+    fn encrypt_ecb_to_bytes(aes: &mut Aes) {
+        aes.set_dataswap(SwapMode::None);
+        for (plaintext, ciphertext) in ECB_PT_CT_128.iter() {
+            let bytes: [u8; 16] = unwrap!(aes.encrypt_ecb_to_bytes(&ZERO_16B, plaintext));
+            defmt::assert_eq!(bytes, aes::block_to_bytes(ciphertext));
+        }
+    }
+
+    #[test]
+    fn dataswap_default_override(aes: &mut Aes) {
+        // the stored swap mode applies to every operation
+        aes.set_dataswap(SwapMode::Byte);
+        defmt::assert!(aes.dataswap() == SwapMode::Byte);
+        let mut output_ciphertext: [u32; 4] = [0; 4];
+        unwrap!(aes.encrypt_ecb(
+            &ZERO_16B,
+            &byte_swap(ECB_PT_CT_128[0].0),
+            &mut output_ciphertext
+        ));
+        defmt::assert_eq!(output_ciphertext, byte_swap(ECB_PT_CT_128[0].1));
+
+        // a per-call override wins
+        let mut output_ciphertext: [u32; 4] = [0; 4];
+        unwrap!(aes.with_dataswap(SwapMode::None, |aes| aes.encrypt_ecb(
+            &ZERO_16B,
+            &ECB_PT_CT_128[0].0,
+            &mut output_ciphertext
+        )));
+        defmt::assert_eq!(output_ciphertext, ECB_PT_CT_128[0].1);
+
+        // and the default is restored
+        defmt::assert!(aes.dataswap() == SwapMode::Byte);
+        aes.set_dataswap(SwapMode::None);
+    }
+
+    #[test]
+    fn feed_read_block(aes: &mut Aes) {
+        aes.set_dataswap(SwapMode::None);
+
+        aes.configure(Algorithm::Ecb, Mode::Encryption, &ZERO_16B);
+        for (plaintext, ciphertext) in ECB_PT_CT_128.iter() {
+            // safety: the peripheral is configured for ECB encryption
+            unsafe { aes.feed_block(plaintext) };
+            let output: [u32; 4] = unwrap!(unsafe { aes.read_block() });
+            defmt::assert_eq!(&output, ciphertext);
+        }
+
+        aes.configure(Algorithm::Ecb, Mode::KeyDerivationDecryption, &ZERO_16B);
+        for (plaintext, ciphertext) in ECB_PT_CT_128.iter() {
+            // safety: the peripheral is configured for ECB decryption
+            unsafe { aes.feed_block(ciphertext) };
+            let output: [u32; 4] = unwrap!(unsafe { aes.read_block() });
+            defmt::assert_eq!(&output, plaintext);
+        }
+
+        // the high-level functions work after low-level access
+        let mut text: [u32; 4] = ECB_PT_CT_128[0].0;
+        unwrap!(aes.encrypt_ecb_inplace(&ZERO_16B, &mut text));
+        defmt::assert_eq!(text, ECB_PT_CT_128[0].1);
+    }
+
+    #[test]
+    fn configure(aes: &mut Aes) {
+        const ALGORITHMS: [(Algorithm, bool, u8); 5] = [
+            (Algorithm::Ecb, false, 0b00),
+            (Algorithm::Cbc, false, 0b01),
+            (Algorithm::Ctr, false, 0b10),
+            (Algorithm::Gcm, false, 0b11),
+            (Algorithm::Ccm, true, 0b00),
+        ];
+        const MODES: [(Mode, u8); 4] = [
+            (Mode::Encryption, 0b00),
+            (Mode::KeyDerivation, 0b01),
+            (Mode::Decryption, 0b10),
+            (Mode::KeyDerivationDecryption, 0b11),
+        ];
+
+        let dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        aes.set_dataswap(SwapMode::None);
+
+        for (algorithm, chmod2, chmod10) in ALGORITHMS {
+            for (mode, mode_bits) in MODES {
+                // unsupported combinations panic
+                if !algorithm.supports(mode) {
+                    continue;
+                }
+                aes.configure(algorithm, mode, &ZERO_16B);
+                let cr = dp.AES.cr.read();
+                defmt::assert!(cr.en().is_enabled());
+                defmt::assert_eq!(cr.chmod2().bit(), chmod2);
+                defmt::assert_eq!(cr.chmod().bits(), chmod10);
+                defmt::assert_eq!(cr.mode().bits(), mode_bits);
+                defmt::assert!(cr.keysize().is_bits128());
+                defmt::assert!(cr.gcmph().is_init());
+            }
+        }
+
+        aes.configure(Algorithm::Ecb, Mode::Encryption, &[0; 8]);
+        defmt::assert!(dp.AES.cr.read().keysize().is_bits256());
+
+        // high-level functions work after low-level configuration
+        let mut output_ciphertext: [u32; 4] = [0; 4];
+        unwrap!(aes.encrypt_ecb(&ZERO_16B, &ECB_PT_CT_128[0].0, &mut output_ciphertext));
+        defmt::assert_eq!(output_ciphertext, ECB_PT_CT_128[0].1);
+    }
+
+    #[test]
+    fn busy(aes: &mut Aes) {
+        let dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        aes.set_dataswap(SwapMode::None);
+
+        // leave a result in the output register without reading it
+        aes.configure(Algorithm::Ecb, Mode::Encryption, &ZERO_16B);
+        for dw in ECB_PT_CT_128[0].0 {
+            dp.AES.dinr.write(|w| w.din().bits(dw));
+        }
+        while dp.AES.sr.read().ccf().bit_is_clear() {}
+
+        let mut output_ciphertext: [u32; 4] = [0; 4];
+        defmt::assert_eq!(
+            aes.encrypt_ecb(&ZERO_16B, &ECB_PT_CT_128[0].0, &mut output_ciphertext),
+            Err(aes::Error::Busy)
+        );
+
+        // configure returns the peripheral to a clean state
+        aes.configure(Algorithm::Ecb, Mode::Encryption, &ZERO_16B);
+        unwrap!(aes.encrypt_ecb(&ZERO_16B, &ECB_PT_CT_128[0].0, &mut output_ciphertext));
+        defmt::assert_eq!(output_ciphertext, ECB_PT_CT_128[0].1);
+        defmt::assert!(dp.AES.sr.read().ccf().bit_is_clear());
+    }
+
+    #[test]
+    fn auto_clear(aes: &mut Aes) {
+        let dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        aes.set_dataswap(SwapMode::None);
+        defmt::assert!(aes.auto_clear());
+
+        aes.set_auto_clear(false);
+        aes.configure(Algorithm::Ecb, Mode::Encryption, &ZERO_16B);
+        // safety: the peripheral is configured for ECB encryption
+        unsafe { aes.feed_block(&ECB_PT_CT_128[0].0) };
+        let output: [u32; 4] = unwrap!(unsafe { aes.read_block() });
+        defmt::assert_eq!(output, ECB_PT_CT_128[0].1);
+
+        // the flag is left for the interrupt handler
+        defmt::assert!(dp.AES.sr.read().ccf().bit_is_set());
+        aes.configure(Algorithm::Ecb, Mode::Encryption, &ZERO_16B);
+        defmt::assert!(dp.AES.sr.read().ccf().bit_is_set());
+        let mut output_ciphertext: [u32; 4] = [0; 4];
+        defmt::assert_eq!(
+            aes.encrypt_ecb(&ZERO_16B, &ECB_PT_CT_128[0].0, &mut output_ciphertext),
+            Err(aes::Error::Busy)
+        );
+
+        // clear the flag as an interrupt handler would
+        dp.AES.cr.modify(|_, w| w.ccfc().clear());
+        unwrap!(aes.encrypt_ecb(&ZERO_16B, &ECB_PT_CT_128[0].0, &mut output_ciphertext));
+        defmt::assert_eq!(output_ciphertext, ECB_PT_CT_128[0].1);
+
+        aes.set_auto_clear(true);
+        aes.configure(Algorithm::Ecb, Mode::Encryption, &ZERO_16B);
+        // safety: the peripheral is configured for ECB encryption
+        unsafe { aes.feed_block(&ECB_PT_CT_128[0].0) };
+        unwrap!(unsafe { aes.read_block() });
+        defmt::assert!(dp.AES.sr.read().ccf().bit_is_clear());
+    }
+
+    #[test]
+    fn cbc_mac(aes: &mut Aes) {
+        // NIST SP 800-38A F.2.1 and F.2.5 with the IV XORed into the first
+        // block, the CBC output with a zero IV is identical
+        const KEY_128: [u32; 4] = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+        const KEY_256: [u32; 8] = [
+            0x603deb10, 0x15ca71be, 0x2b73aef0, 0x857d7781, 0x1f352c07, 0x3b6108d7, 0x2d9810a3,
+            0x0914dff4,
+        ];
+        const MSG: [u8; 64] = [
+            0x6b, 0xc0, 0xbc, 0xe1, 0x2a, 0x45, 0x99, 0x91, 0xe1, 0x34, 0x74, 0x1a, 0x7f, 0x9e,
+            0x19, 0x25, 0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac,
+            0x45, 0xaf, 0x8e, 0x51, 0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11, 0xe5, 0xfb,
+            0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef, 0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17,
+            0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10,
+        ];
+
+        aes.set_dataswap(SwapMode::None);
+
+        defmt::assert_eq!(
+            unwrap!(aes.cbc_mac(&KEY_128, &MSG[..16])),
+            [
+                0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, 0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9,
+                0x19, 0x7d
+            ]
+        );
+        defmt::assert_eq!(
+            unwrap!(aes.cbc_mac(&KEY_128, &MSG[..32])),
+            [
+                0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee, 0x95, 0xdb, 0x11, 0x3a, 0x91, 0x76,
+                0x78, 0xb2
+            ]
+        );
+        defmt::assert_eq!(
+            unwrap!(aes.cbc_mac(&KEY_128, &MSG)),
+            [
+                0x3f, 0xf1, 0xca, 0xa1, 0x68, 0x1f, 0xac, 0x09, 0x12, 0x0e, 0xca, 0x30, 0x75, 0x86,
+                0xe1, 0xa7
+            ]
+        );
+        defmt::assert_eq!(
+            unwrap!(aes.cbc_mac(&KEY_256, &MSG)),
+            [
+                0xb2, 0xeb, 0x05, 0xe2, 0xc3, 0x9b, 0xe9, 0xfc, 0xda, 0x6c, 0x19, 0x07, 0x8c, 0x6a,
+                0x9d, 0x1b
+            ]
+        );
+
+        defmt::assert_eq!(aes.cbc_mac(&KEY_128, &[]), Err(aes::Error::Length));
+        defmt::assert_eq!(aes.cbc_mac(&KEY_128, &MSG[..17]), Err(aes::Error::Length));
+    }
+
+    #[test]
+    fn ctr(aes: &mut Aes) {
+        // NIST SP 800-38A F.5.1 CTR-AES128.Encrypt
+        const KEY: [u32; 4] = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+        const NONCE: [u8; 12] = [
+            0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb,
+        ];
+        const IV: CtrIv = CtrIv::new(NONCE, 0xfcfdfeff);
+        const PLAINTEXT: [u8; 32] = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93,
+            0x17, 0x2a, 0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac,
+            0x45, 0xaf, 0x8e, 0x51,
+        ];
+        const CIPHERTEXT: [u8; 32] = [
+            0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d,
+            0xb6, 0xce, 0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b,
+            0xb9, 0xff, 0xfd, 0xff,
+        ];
+
+        aes.set_dataswap(SwapMode::None);
+
+        let mut buf: [u8; 32] = PLAINTEXT;
+        unwrap!(aes.encrypt_ctr_inplace(&KEY, &IV, &mut buf));
+        defmt::assert_eq!(buf, CIPHERTEXT);
+
+        unwrap!(aes.decrypt_ctr_inplace(&KEY, &IV, &mut buf));
+        defmt::assert_eq!(buf, PLAINTEXT);
+
+        // partial final block
+        let mut buf: [u8; 21] = [0; 21];
+        buf.copy_from_slice(&PLAINTEXT[..21]);
+        unwrap!(aes.encrypt_ctr_inplace(&KEY, &IV, &mut buf));
+        defmt::assert_eq!(buf, CIPHERTEXT[..21]);
+    }
+
+    #[test]
+    fn cbc_inplace(aes: &mut Aes) {
+        // NIST SP 800-38A F.2.1 CBC-AES128.Encrypt
+        const KEY: [u32; 4] = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+        const IV: [u32; 4] = [0x00010203, 0x04050607, 0x08090a0b, 0x0c0d0e0f];
+        const PLAINTEXT: [[u32; 4]; 2] = [
+            [0x6bc1bee2, 0x2e409f96, 0xe93d7e11, 0x7393172a],
+            [0xae2d8a57, 0x1e03ac9c, 0x9eb76fac, 0x45af8e51],
+        ];
+        const CIPHERTEXT: [[u32; 4]; 2] = [
+            [0x7649abac, 0x8119b246, 0xcee98e9b, 0x12e9197d],
+            [0x5086cb9b, 0x507219ee, 0x95db113a, 0x917678b2],
+        ];
+
+        aes.set_dataswap(SwapMode::None);
+
+        // out-of-place chaining with ECB
+        let mut expected: [[u32; 4]; 2] = [[0; 4]; 2];
+        let mut prev: [u32; 4] = IV;
+        for (pt, ct) in PLAINTEXT.iter().zip(expected.iter_mut()) {
+            let mut block: [u32; 4] = *pt;
+            block.iter_mut().zip(prev).for_each(|(w, p)| *w ^= p);
+            unwrap!(aes.encrypt_ecb(&KEY, &block, ct));
+            prev = *ct;
+        }
+        defmt::assert_eq!(expected, CIPHERTEXT);
+
+        let mut buf: [[u32; 4]; 2] = PLAINTEXT;
+        unwrap!(aes.encrypt_cbc_inplace(&KEY, &IV, &mut buf));
+        defmt::assert_eq!(buf, expected);
+
+        unwrap!(aes.decrypt_cbc_inplace(&KEY, &IV, &mut buf));
+        defmt::assert_eq!(buf, PLAINTEXT);
+
+        // empty buffers are a no-op
+        unwrap!(aes.encrypt_cbc_inplace(&KEY, &IV, &mut []));
+        unwrap!(aes.decrypt_cbc_inplace(&KEY, &IV, &mut []));
+    }
+
+    #[test]
+    fn self_test(aes: &mut Aes) {
+        aes.set_dataswap(SwapMode::Byte);
+        unwrap!(aes.self_test());
+        // the swap mode is restored
+        defmt::assert!(aes.dataswap() == SwapMode::Byte);
+        aes.set_dataswap(SwapMode::None);
+    }
+
+    #[test]
+    fn encrypt_samples(aes: &mut Aes) {
+        const KEY: [u32; 4] = [0x2b7e1516, 0x28aed2a6, 0xabf71588, 0x09cf4f3c];
+        const IV: CtrIv = CtrIv::new([0xA5; 12], 1);
+
+        aes.set_dataswap(SwapMode::None);
+
+        // 12-bit ADC samples, not a multiple of the block size
+        let mut samples: [u16; 13] = [0; 13];
+        samples
+            .iter_mut()
+            .enumerate()
+            .for_each(|(n, sample)| *sample = (n as u16).wrapping_mul(0x0135) & 0x0FFF);
+
+        let mut buf: [u8; 26] = [0; 26];
+        unwrap!(aes.encrypt_samples(&KEY, &IV, &samples, &mut buf));
+        defmt::assert_ne!(buf[..2], samples[0].to_le_bytes());
+
+        unwrap!(aes.decrypt_ctr_inplace(&KEY, &IV, &mut buf));
+        let mut decrypted: [u16; 13] = [0; 13];
+        decrypted
+            .iter_mut()
+            .zip(buf.chunks_exact(2))
+            .for_each(|(sample, bytes)| *sample = u16::from_le_bytes([bytes[0], bytes[1]]));
+        defmt::assert_eq!(decrypted, samples);
+
+        defmt::assert_eq!(
+            aes.encrypt_samples(&KEY, &IV, &samples, &mut [0; 25]),
+            Err(aes::Error::Length)
+        );
+    }
+
+    #[test]
+    fn encrypt_gcm_fixed(aes: &mut Aes) {
+        let (ciphertext, tag): ([u8; 32], [u8; 16]) = unwrap!(aes.encrypt_gcm_fixed(
+            &ZERO_16B,
+            &[0; 3],
+            b"header",
+            b"0123456789ABCDEF0123456789ABCDEF",
+        ));
+        defmt::assert_eq!(
+            ciphertext,
+            hex!("33b9e8fd548395a5cb1183fb32f6bb3ec7a498987d7e6f14cfc4c8bdd7cf84a6")
+        );
+        defmt::assert_eq!(tag, hex!("1a05873f67a4ac89ea814648067605d3"));
+    }
+
+    #[test]
+    fn keyed_alternate(aes: &mut Aes) {
+        aes.set_dataswap(SwapMode::None);
+
+        let mut keyed = aes.keyed(&ZERO_16B);
+        for _ in 0..4 {
+            for (plaintext, ciphertext) in ECB_PT_CT_128.iter() {
+                let mut text: [u32; 4] = *plaintext;
+                unwrap!(keyed.encrypt_ecb_inplace(&mut text));
+                defmt::assert_eq!(&text, ciphertext);
+                unwrap!(keyed.decrypt_ecb_inplace(&mut text));
+                defmt::assert_eq!(&text, plaintext);
+            }
+        }
+
+        // changing the key size reloads the key
+        keyed.set_key(&ZERO_32B);
+        for (plaintext, ciphertext) in ECB_PT_CT_256.iter() {
+            let mut output: [u32; 4] = [0; 4];
+            unwrap!(keyed.decrypt_ecb(ciphertext, &mut output));
+            defmt::assert_eq!(&output, plaintext);
+            unwrap!(keyed.encrypt_ecb(plaintext, &mut output));
+            defmt::assert_eq!(&output, ciphertext);
+        }
+
+        for (key, ciphertext) in ECB_KEY_CT_128.iter() {
+            keyed.set_key(key);
+            let mut text: [u32; 4] = ZERO_16B;
+            unwrap!(keyed.encrypt_ecb_inplace(&mut text));
+            defmt::assert_eq!(&text, ciphertext);
+            unwrap!(keyed.decrypt_ecb_inplace(&mut text));
+            defmt::assert_eq!(text, ZERO_16B);
+        }
+    }
+
+    #[test]
+    fn auto_disable(aes: &mut Aes) {
+        let dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        aes.set_dataswap(SwapMode::None);
+
+        defmt::assert!(!aes.auto_disable());
+        let mut text: [u32; 4] = ECB_PT_CT_128[0].0;
+        unwrap!(aes.encrypt_ecb_inplace(&ZERO_16B, &mut text));
+        defmt::assert!(dp.AES.cr.read().en().is_enabled());
+
+        aes.set_auto_disable(true);
+        defmt::assert!(aes.auto_disable());
+
+        // every operation re-enables the peripheral
+        for (plaintext, ciphertext) in ECB_PT_CT_128.iter() {
+            let mut text: [u32; 4] = *plaintext;
+            unwrap!(aes.encrypt_ecb_inplace(&ZERO_16B, &mut text));
+            defmt::assert!(dp.AES.cr.read().en().is_disabled());
+            defmt::assert_eq!(&text, ciphertext);
+
+            unwrap!(aes.decrypt_ecb_inplace(&ZERO_16B, &mut text));
+            defmt::assert!(dp.AES.cr.read().en().is_disabled());
+            defmt::assert_eq!(&text, plaintext);
+        }
+
+        let mut keyed = aes.keyed(&ZERO_16B);
+        let mut text: [u32; 4] = ECB_PT_CT_128[0].0;
+        unwrap!(keyed.encrypt_ecb_inplace(&mut text));
+        defmt::assert!(dp.AES.cr.read().en().is_disabled());
+        defmt::assert_eq!(text, ECB_PT_CT_128[0].1);
+        drop(keyed);
+
+        let (_, tag): ([u8; 32], [u8; 16]) = unwrap!(aes.encrypt_gcm_fixed(
+            &ZERO_16B,
+            &[0; 3],
+            b"header",
+            b"0123456789ABCDEF0123456789ABCDEF",
+        ));
+        defmt::assert!(dp.AES.cr.read().en().is_disabled());
+        defmt::assert_eq!(tag, hex!("1a05873f67a4ac89ea814648067605d3"));
+
+        aes.set_auto_disable(false);
+    }
+
+    // the clock debug assertion cannot be observed failing here because
+    // panic-probe ends the test run, this checks it tracks the live clock bit
+    #[test]
+    fn clock_reenable(aes: &mut Aes) {
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        unsafe { Aes::disable_clock(&mut dp.RCC) };
+        defmt::assert!(dp.RCC.ahb3enr.read().aesen().is_disabled());
+
+        Aes::enable_clock(&mut dp.RCC);
+        aes.set_dataswap(SwapMode::None);
+
+        let mut output_ciphertext: [u32; 4] = [0; 4];
+        unwrap!(aes.encrypt_ecb(&ZERO_16B, &ECB_PT_CT_128[0].0, &mut output_ciphertext));
+        defmt::assert_eq!(output_ciphertext, ECB_PT_CT_128[0].1);
+    }
+
     #[test]
     fn aes_wrap_clk() {
         let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
@@ -2231,4 +2860,134 @@ mod tests {
             defmt::assert_eq!(output_plaintext, ZERO_16B);
         }
     }
+
+    #[test]
+    fn try_lock_reentry(aes: &mut Aes) {
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        Aes::enable_clock(&mut dp.RCC);
+        aes.set_dataswap(SwapMode::None);
+        let mut reentry: Aes = unsafe { Aes::steal() };
+
+        let mut text: [u32; 4] = ECB_PT_CT_128[0].0;
+        let ret: Result<(), aes::Error> = aes.try_lock(|aes| {
+            // simulate an interrupt handler with a stolen driver
+            defmt::assert_eq!(
+                reentry.try_lock(|aes| aes.encrypt_ecb_inplace(&ZERO_16B, &mut [0; 4])),
+                Err(aes::Error::Contended)
+            );
+            aes.encrypt_ecb_inplace(&ZERO_16B, &mut text)
+        });
+        unwrap!(ret);
+        defmt::assert_eq!(text, ECB_PT_CT_128[0].1);
+
+        // released after the first operation
+        unwrap!(reentry.try_lock(|aes| aes.decrypt_ecb_inplace(&ZERO_16B, &mut text)));
+        defmt::assert_eq!(text, ECB_PT_CT_128[0].0);
+    }
+
+    #[test]
+    fn byte_order(aes: &mut Aes) {
+        // (key, plaintext, ciphertext)
+        // FIPS-197 appendix C.1, and NIST SP 800-38A F.1.1 and F.1.2
+        const VECTORS_128: [([u8; 16], [u8; 16], [u8; 16]); 5] = [
+            (
+                hex!("000102030405060708090a0b0c0d0e0f"),
+                hex!("00112233445566778899aabbccddeeff"),
+                hex!("69c4e0d86a7b0430d8cdb78070b4c55a"),
+            ),
+            (
+                hex!("2b7e151628aed2a6abf7158809cf4f3c"),
+                hex!("6bc1bee22e409f96e93d7e117393172a"),
+                hex!("3ad77bb40d7a3660a89ecaf32466ef97"),
+            ),
+            (
+                hex!("2b7e151628aed2a6abf7158809cf4f3c"),
+                hex!("ae2d8a571e03ac9c9eb76fac45af8e51"),
+                hex!("f5d3d58503b9699de785895a96fdbaaf"),
+            ),
+            (
+                hex!("2b7e151628aed2a6abf7158809cf4f3c"),
+                hex!("30c81c46a35ce411e5fbc1191a0a52ef"),
+                hex!("43b1cd7f598ece23881b00e3ed030688"),
+            ),
+            (
+                hex!("2b7e151628aed2a6abf7158809cf4f3c"),
+                hex!("f69f2445df4f9b17ad2b417be66c3710"),
+                hex!("7b0c785e27e8ad3f8223207104725dd4"),
+            ),
+        ];
+        // FIPS-197 appendix C.3, and NIST SP 800-38A F.1.5 and F.1.6
+        const VECTORS_256: [([u8; 32], [u8; 16], [u8; 16]); 3] = [
+            (
+                hex!("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"),
+                hex!("00112233445566778899aabbccddeeff"),
+                hex!("8ea2b7ca516745bfeafc49904b496089"),
+            ),
+            (
+                hex!("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4"),
+                hex!("6bc1bee22e409f96e93d7e117393172a"),
+                hex!("f3eed1bdb5d2a03c064b5a7e3db181f8"),
+            ),
+            (
+                hex!("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4"),
+                hex!("ae2d8a571e03ac9c9eb76fac45af8e51"),
+                hex!("591ccb10d410ed26dc5ba74a31362870"),
+            ),
+        ];
+
+        fn check(aes: &mut Aes, swap_mode: SwapMode, key: &[u32], pt: &[u8; 16], ct: &[u8; 16]) {
+            let input: [u32; 4] = aes::block_from_bytes(pt).map(|w| aes::swap(w, swap_mode));
+            let mut output: [u32; 4] = [0; 4];
+            unwrap!(aes.encrypt_ecb(key, &input, &mut output));
+            defmt::assert_eq!(
+                aes::block_to_bytes(&output.map(|w| aes::swap(w, swap_mode))),
+                *ct
+            );
+
+            let ciphertext: [u32; 4] = output;
+            unwrap!(aes.decrypt_ecb(key, &ciphertext, &mut output));
+            defmt::assert_eq!(output, input);
+        }
+
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        Aes::enable_clock(&mut dp.RCC);
+
+        for swap_mode in [
+            SwapMode::None,
+            SwapMode::HalfWord,
+            SwapMode::Byte,
+            SwapMode::Bit,
+        ] {
+            aes.set_dataswap(swap_mode);
+            for (key, pt, ct) in VECTORS_128.iter() {
+                check(aes, swap_mode, &aes::block_from_bytes(key), pt, ct);
+            }
+            for (key, pt, ct) in VECTORS_256.iter() {
+                check(aes, swap_mode, &aes::key_256_from_bytes(key), pt, ct);
+            }
+        }
+
+        // byte buffers reinterpreted as words on the little-endian core
+        aes.set_dataswap(SwapMode::Byte);
+        let (key, pt, ct) = VECTORS_128[0];
+        let input: [u32; 4] = {
+            let mut words: [u32; 4] = [0; 4];
+            pt.chunks_exact(4)
+                .zip(words.iter_mut())
+                .for_each(|(chunk, word)| {
+                    *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
+                });
+            words
+        };
+        let mut output: [u32; 4] = [0; 4];
+        unwrap!(aes.encrypt_ecb(&aes::block_from_bytes(&key), &input, &mut output));
+        let mut output_bytes: [u8; 16] = [0; 16];
+        output_bytes
+            .chunks_exact_mut(4)
+            .zip(output.iter())
+            .for_each(|(chunk, word)| chunk.copy_from_slice(&word.to_le_bytes()));
+        defmt::assert_eq!(output_bytes, ct);
+
+        aes.set_dataswap(SwapMode::None);
+    }
 }
@@ -0,0 +1,79 @@
+#![no_std]
+#![no_main]
+
+use defmt::unwrap;
+use defmt_rtt as _; // global logger
+use nucleo_wl55jc_bsp::hal::{
+    crc::{Crc, Poly, PolySize},
+    pac,
+};
+use panic_probe as _;
+
+const CHECK: &[u8] = b"123456789";
+
+#[defmt_test::tests]
+mod tests {
+    use super::*;
+
+    #[init]
+    fn init() -> Crc {
+        let mut dp: pac::Peripherals = unwrap!(pac::Peripherals::take());
+        Crc::new_crc32(dp.CRC, &mut dp.RCC)
+    }
+
+    #[test]
+    fn crc32_check(crc: &mut Crc) {
+        crc.reset();
+        defmt::assert_eq!(crc.feed(CHECK), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn crc32_vectors(crc: &mut Crc) {
+        const VECTORS: [(&[u8], u32); 5] = [
+            (b"", 0x0000_0000),
+            (b"a", 0xE8B7_BE43),
+            (b"abc", 0x3524_41C2),
+            (b"message digest", 0x2015_9D7F),
+            (b"The quick brown fox jumps over the lazy dog", 0x414F_A339),
+        ];
+
+        for (data, expected) in VECTORS {
+            crc.reset();
+            defmt::assert_eq!(crc.feed(data), expected);
+        }
+    }
+
+    #[test]
+    fn crc32_feed_widths(crc: &mut Crc) {
+        // every split of the check string, exercising all feed widths
+        for split in 0..=CHECK.len() {
+            crc.reset();
+            crc.feed(&CHECK[..split]);
+            defmt::assert_eq!(crc.feed(&CHECK[split..]), 0xCBF4_3926);
+        }
+    }
+
+    // tests below reconfigure the CRC, CRC-32 tests must come first
+
+    #[test]
+    fn crc16_ccitt(_crc: &mut Crc) {
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+
+        let mut crc16: Crc = Crc::new_crc16_ccitt(dp.CRC, &mut dp.RCC);
+        defmt::assert_eq!(crc16.feed(CHECK), 0x29B1);
+
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        let mut kermit: Crc = Crc::new(dp.CRC, Poly::CRC16_CCITT, 0, true, true, 0, &mut dp.RCC);
+        defmt::assert_eq!(kermit.feed(CHECK), 0x2189);
+    }
+
+    #[test]
+    fn crc8(_crc: &mut Crc) {
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+
+        // CRC-8/SMBUS
+        let poly: Poly = unwrap!(Poly::new(PolySize::Polysize8, 0x07));
+        let mut crc8: Crc = Crc::new(dp.CRC, poly, 0, false, false, 0, &mut dp.RCC);
+        defmt::assert_eq!(crc8.feed(CHECK), 0xF4);
+    }
+}
@@ -6,7 +6,7 @@ use defmt_rtt as _; // global logger
 use nucleo_wl55jc_bsp::hal::{
     adc::{self, Adc},
     cortex_m::{self, delay::Delay, peripheral::syst::SystClkSource},
-    dac::{Dac, ModeChip, ModePin},
+    dac::{Dac, Error, ModeChip, ModePin},
     gpio::{Analog, PortA},
     pac, rcc,
 };
@@ -81,4 +81,66 @@ mod tests {
 
         defmt::assert!(delta < 20);
     }
+
+    #[test]
+    fn underrun(ta: &mut TestArgs) {
+        ta.dac.disable();
+        // A10 was returned by the loopback test
+        let _ = ta.dac.set_mode_chip(ModeChip::Norm);
+        ta.dac.clear_underrun();
+        defmt::assert!(!ta.dac.is_underrun());
+        defmt::assert_eq!(ta.dac.check_underrun(), Ok(()));
+
+        // DMA requests enabled without a DMA channel to service them,
+        // the second trigger arrives before the first request is acknowledged
+        ta.dac.setup_soft_trigger();
+        let dac: pac::DAC = unsafe { pac::Peripherals::steal() }.DAC;
+        dac.cr.modify(|_, w| w.dmaen1().enabled());
+        ta.dac.soft_trigger(1);
+        ta.dac.soft_trigger(2);
+
+        defmt::assert!(ta.dac.is_underrun());
+        defmt::assert_eq!(ta.dac.check_underrun(), Err(Error::Underrun));
+
+        dac.cr.modify(|_, w| w.dmaen1().disabled());
+        ta.dac.recover_underrun();
+        defmt::assert!(!ta.dac.is_underrun());
+        defmt::assert!(dac.cr.read().en1().is_enabled());
+
+        ta.dac.soft_trigger(3);
+        defmt::assert_eq!(ta.dac.out(), 3);
+        ta.dac.disable();
+    }
+
+    #[test]
+    fn output_enable(ta: &mut TestArgs) {
+        use pac::dac::mcr::MODE1_A;
+
+        let dac: pac::DAC = unsafe { pac::Peripherals::steal() }.DAC;
+        let a10 = unsafe { PortA::steal() }.a10;
+
+        ta.dac.disable();
+        cortex_m::interrupt::free(|cs| ta.dac.set_mode_pin(Analog::new(a10, cs), ModePin::NormBuf));
+        ta.dac.setup_soft_trigger();
+        defmt::assert!(dac.mcr.read().mode1().variant() == MODE1_A::NormalPinBuffer);
+
+        ta.dac.disable_output();
+        defmt::assert!(dac.cr.read().en1().is_disabled());
+        defmt::assert!(dac.cr.read().tsel1().is_swtrig());
+        defmt::assert!(dac.mcr.read().mode1().variant() == MODE1_A::NormalChipNoBuffer);
+
+        // idempotent
+        ta.dac.disable_output();
+        defmt::assert!(dac.mcr.read().mode1().variant() == MODE1_A::NormalChipNoBuffer);
+
+        ta.dac.enable_output();
+        defmt::assert!(dac.cr.read().en1().is_enabled());
+        defmt::assert!(dac.mcr.read().mode1().variant() == MODE1_A::NormalPinBuffer);
+
+        ta.dac.soft_trigger(5);
+        defmt::assert_eq!(ta.dac.out(), 5);
+
+        ta.dac.disable();
+        defmt::assert!(ta.dac.set_mode_chip(ModeChip::Norm).is_some());
+    }
 }
@@ -0,0 +1,64 @@
+#![no_std]
+#![no_main]
+
+use defmt::unwrap;
+use defmt_rtt as _; // global logger
+use nucleo_wl55jc_bsp::hal::{
+    dma::{self, AllDma, DmaCh},
+    pac,
+};
+use panic_probe as _;
+
+const LEN: usize = 256;
+
+#[defmt_test::tests]
+mod tests {
+    use super::*;
+
+    #[init]
+    fn init() -> AllDma {
+        let mut dp: pac::Peripherals = unwrap!(pac::Peripherals::take());
+        AllDma::split(dp.DMAMUX, dp.DMA1, dp.DMA2, &mut dp.RCC)
+    }
+
+    #[test]
+    fn mem_to_mem(dma: &mut AllDma) {
+        let mut src: [u32; LEN] = [0; LEN];
+        src.iter_mut()
+            .enumerate()
+            .for_each(|(n, word)| *word = (n as u32).wrapping_mul(0x9E37_79B9));
+        let mut dst: [u32; LEN] = [0; LEN];
+
+        unwrap!(dma.d1.c1.mem_to_mem(&src, &mut dst));
+        defmt::assert_eq!(src, dst);
+
+        // channels on the second controller
+        let mut dst: [u32; LEN] = [0; LEN];
+        unwrap!(dma.d2.c7.mem_to_mem(&src, &mut dst));
+        defmt::assert_eq!(src, dst);
+    }
+
+    #[test]
+    fn mem_to_mem_repeat(dma: &mut AllDma) {
+        // the channel is reusable after a transfer
+        for fill in [0x0000_0000, 0xFFFF_FFFF, 0xA5A5_A5A5] {
+            let src: [u32; 16] = [fill; 16];
+            let mut dst: [u32; 16] = [!fill; 16];
+            unwrap!(dma.d1.c2.mem_to_mem(&src, &mut dst));
+            defmt::assert_eq!(src, dst);
+        }
+    }
+
+    #[test]
+    fn mem_to_mem_length(dma: &mut AllDma) {
+        let src: [u32; 4] = [1, 2, 3, 4];
+        let mut dst: [u32; 3] = [0; 3];
+        defmt::assert_eq!(
+            dma.d1.c1.mem_to_mem(&src, &mut dst),
+            Err(dma::Error::Length)
+        );
+        defmt::assert_eq!(dst, [0; 3]);
+
+        defmt::assert_eq!(dma.d1.c1.mem_to_mem(&[], &mut []), Ok(()));
+    }
+}
@@ -0,0 +1,135 @@
+#![no_std]
+#![no_main]
+
+use defmt::unwrap;
+use defmt_rtt as _; // global logger
+use nucleo_wl55jc_bsp::hal::{
+    cortex_m,
+    gpio::{
+        self, pins, Analog, AnalogArgs, Input, Output, OutputArgs, PinState, PortB, PortBCfg, Pull,
+    },
+    pac,
+};
+use panic_probe as _;
+
+fn b13_pupdr() -> u8 {
+    let dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+    dp.GPIOB.pupdr.read().pupdr13().bits()
+}
+
+#[defmt_test::tests]
+mod tests {
+    use super::*;
+
+    #[init]
+    fn init() -> Input<pins::B13> {
+        let mut dp: pac::Peripherals = unwrap!(pac::Peripherals::take());
+        let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+        cortex_m::interrupt::free(|cs| Input::new(gpiob.b13, Pull::None, cs))
+    }
+
+    #[test]
+    fn set_pull(b13: &mut Input<pins::B13>) {
+        defmt::assert_eq!(b13_pupdr(), Pull::None as u8);
+
+        for pull in [Pull::Up, Pull::Down, Pull::None] {
+            unwrap!(cortex_m::interrupt::free(|cs| b13.set_pull(pull, cs)));
+            defmt::assert_eq!(b13_pupdr(), pull as u8);
+        }
+    }
+
+    #[test]
+    fn set_pull_analog(b13: &mut Input<pins::B13>) {
+        // put the pin into analog mode behind the back of the input driver
+        let b13_analog: Analog<pins::B13> =
+            cortex_m::interrupt::free(|cs| Analog::new(unsafe { PortB::steal() }.b13, cs));
+
+        defmt::assert_eq!(
+            cortex_m::interrupt::free(|cs| b13.set_pull(Pull::Up, cs)),
+            Err(gpio::Error::Analog)
+        );
+        defmt::assert_eq!(b13_pupdr(), Pull::None as u8);
+
+        // restore input mode
+        let _: Input<pins::B13> =
+            cortex_m::interrupt::free(|cs| Input::default(b13_analog.free(), cs));
+        unwrap!(cortex_m::interrupt::free(|cs| b13.set_pull(Pull::Up, cs)));
+        defmt::assert_eq!(b13_pupdr(), Pull::Up as u8);
+    }
+
+    #[test]
+    fn toggle(_: &mut Input<pins::B13>) {
+        use nucleo_wl55jc_bsp::hal::embedded_hal::digital::v2::{
+            StatefulOutputPin, ToggleableOutputPin,
+        };
+
+        let mut b12: Output<pins::B12> =
+            cortex_m::interrupt::free(|cs| Output::default(unsafe { PortB::steal() }.b12, cs));
+        let initial: PinState = b12.level();
+
+        b12.toggle_level();
+        defmt::assert!(b12.level() == !initial);
+        b12.toggle_level();
+        defmt::assert!(b12.level() == initial);
+
+        // through the embedded-hal traits
+        let initial_high: bool = unwrap!(b12.is_set_high());
+        unwrap!(ToggleableOutputPin::toggle(&mut b12));
+        defmt::assert_eq!(unwrap!(b12.is_set_high()), !initial_high);
+        unwrap!(ToggleableOutputPin::toggle(&mut b12));
+        defmt::assert_eq!(unwrap!(b12.is_set_high()), initial_high);
+
+        let _: Input<pins::B12> = cortex_m::interrupt::free(|cs| Input::default(b12.free(), cs));
+    }
+
+    #[test]
+    fn configure(_: &mut Input<pins::B13>) {
+        // reconfigures the whole port, must run last
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+
+        let gpiob = cortex_m::interrupt::free(|cs| {
+            PortB::configure(
+                dp.GPIOB,
+                &mut dp.RCC,
+                PortBCfg {
+                    b0: (),
+                    b1: (),
+                    b2: (),
+                    b3: (),
+                    b4: (),
+                    b5: (),
+                    b6: (),
+                    b7: (),
+                    b8: (),
+                    b9: (),
+                    b10: (),
+                    b11: Pull::Up,
+                    b12: OutputArgs::new(),
+                    b13: AnalogArgs,
+                    b14: Pull::Down,
+                    b15: (),
+                },
+                cs,
+            )
+        });
+        let _: Input<pins::B11> = gpiob.b11;
+        let _: Output<pins::B12> = gpiob.b12;
+        let _: Analog<pins::B13> = gpiob.b13;
+        let _: Input<pins::B14> = gpiob.b14;
+        let _: pins::B15 = gpiob.b15;
+
+        let gpiob: &pac::gpiob::RegisterBlock = unsafe { &*pac::GPIOB::PTR };
+        let moder: u32 = gpiob.moder.read().bits();
+        let pupdr: u32 = gpiob.pupdr.read().bits();
+        let field = |reg: u32, n: u32| (reg >> (n * 2)) & 0b11;
+
+        defmt::assert_eq!(field(moder, 11), 0b00); // input
+        defmt::assert_eq!(field(moder, 12), 0b01); // output
+        defmt::assert_eq!(field(moder, 13), 0b11); // analog
+        defmt::assert_eq!(field(moder, 14), 0b00); // input
+        defmt::assert_eq!(field(pupdr, 11), Pull::Up as u32);
+        defmt::assert_eq!(field(pupdr, 12), Pull::None as u32);
+        defmt::assert_eq!(field(pupdr, 13), Pull::None as u32);
+        defmt::assert_eq!(field(pupdr, 14), Pull::Down as u32);
+    }
+}
@@ -7,7 +7,7 @@ use nucleo_wl55jc_bsp::hal::{
     cortex_m,
     embedded_hal::blocking::i2c::WriteRead,
     gpio::{pins, PortA, PortB},
-    i2c::{I2c1, I2c2},
+    i2c::{I2c1, I2c2, SlaveEvent},
     pac::{self, interrupt},
     rcc,
 };
@@ -105,6 +105,69 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn slave_write_byte(_i2c: &mut I2c1<(pins::B8, pins::B7)>) {
+        defmt::warn!("I2C1 pins B8 (SCL) and B7 (SDA) must be connected to I2C pins A12 (SCL) and A11 (SDA) for this test to pass");
+
+        // I2C2 is polled with the slave API instead of the loopback IRQ handler
+        pac::NVIC::mask(pac::Interrupt::I2C2_EV);
+        pac::NVIC::mask(pac::Interrupt::I2C2_ER);
+
+        let mut dp: pac::Peripherals = unsafe { pac::Peripherals::steal() };
+        let gpioa: PortA = unsafe { PortA::steal() };
+
+        let mut slave = cortex_m::interrupt::free(|cs| {
+            I2c2::new(
+                dp.I2C2,
+                (gpioa.a12, gpioa.a11),
+                I2C_FREQUENCY,
+                &mut dp.RCC,
+                true,
+                cs,
+            )
+        });
+        slave.listen(LOOPBACK_ADDR);
+
+        // start a 1-byte write on I2C1 without blocking
+        dp.I2C1.cr2.write(|w| {
+            w.add10().bit7();
+            w.sadd().bits((LOOPBACK_ADDR << 1) as u16);
+            w.rd_wrn().write();
+            w.nbytes().bits(1);
+            w.autoend().automatic();
+            w.start().start()
+        });
+
+        let mut events: [Option<SlaveEvent>; 3] = [None; 3];
+        let mut num_events: usize = 0;
+        let mut polls: u32 = 0;
+        while num_events < events.len() {
+            polls += 1;
+            defmt::assert!(polls < 1_000_000, "timeout, events: {}", events);
+
+            if dp.I2C1.isr.read().txis().is_empty() {
+                dp.I2C1.txdr.write(|w| w.txdata().bits(LOOPBACK_DATA_IN));
+            }
+
+            if let Some(event) = slave.slave_event() {
+                events[num_events] = Some(event);
+                num_events += 1;
+            }
+        }
+
+        while dp.I2C1.isr.read().stopf().is_no_stop() {}
+        dp.I2C1.icr.write(|w| w.stopcf().clear());
+
+        defmt::assert_eq!(
+            events,
+            [
+                Some(SlaveEvent::AddrMatch),
+                Some(SlaveEvent::RxByte(LOOPBACK_DATA_IN)),
+                Some(SlaveEvent::Stop),
+            ]
+        );
+    }
 }
 
 #[interrupt]
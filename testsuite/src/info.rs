@@ -5,7 +5,7 @@ use defmt::unwrap;
 use defmt_rtt as _; // global logger
 use nucleo_wl55jc_bsp::hal::{
     cortex_m,
-    info::{self, Core, Uid64},
+    info::{self, Core, FlashSize, Package, Uid64},
     pac::{self, DWT},
     rcc,
 };
@@ -45,6 +45,12 @@ mod tests {
     fn flash_size() {
         defmt::assert_eq!(info::flash_size_kibibyte(), 256);
         defmt::assert_eq!(info::flash_size(), 256 * 1024);
+        defmt::assert_eq!(FlashSize::from_device(), Ok(FlashSize::Kib256));
+    }
+
+    #[test]
+    fn package() {
+        defmt::assert_eq!(Package::from_device(), Ok(Package::UFBGA73));
     }
 
     #[test]
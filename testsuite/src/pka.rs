@@ -7,7 +7,8 @@ use nucleo_wl55jc_bsp::hal::{
     cortex_m,
     pac::{self, DWT},
     pka::{
-        curve::NIST_P256, EcdsaPublicKey, EcdsaSignError, EcdsaSignature, EcdsaVerifyError, Pka,
+        curve::NIST_P256, EcdsaPublicKey, EcdsaSignError, EcdsaSignature, EcdsaVerifyError,
+        ModExpError, Pka,
     },
     rcc,
 };
@@ -19,6 +20,9 @@ const CYC_PER_MICRO: u32 = FREQ / 1000 / 1000;
 // WARNING will wrap-around eventually, use this for relative timing only
 defmt::timestamp!("{=u32:us}", DWT::cycle_count() / CYC_PER_MICRO);
 
+// 0x123456789 ^ 0x10001 mod 0xF123456789ABCDEF
+const MOD_EXP_64_OUT: [u8; 8] = [0x76, 0x82, 0x89, 0x72, 0x25, 0x19, 0x2C, 0xEB];
+
 // Message hash
 const HASH: [u32; 8] = [
     0x44acf6b7, 0xe36c1342, 0xc2c58972, 0x04fe0950, 0x4e1e2efb, 0x1a900377, 0xdbc4e7a6, 0xa133ec56,
@@ -341,4 +345,41 @@ mod tests {
 
         unwrap!(pka.ecdsa_verify(&NIST_P256, &sig, &pub_key, &hash));
     }
+
+    #[test]
+    fn mod_exp(pka: &mut Pka) {
+        // 4 ^ 13 mod 497 = 445
+        let mut out: [u8; 2] = [0; 2];
+        unwrap!(pka.mod_exp(&[4], &[13], &[0x01, 0xF1], &mut out));
+        defmt::assert_eq!(out, [0x01, 0xBD]);
+
+        let mut out: [u8; 8] = [0; 8];
+        unwrap!(pka.mod_exp(
+            &[0x01, 0x23, 0x45, 0x67, 0x89],
+            &[0x01, 0x00, 0x01],
+            &[0xF1, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF],
+            &mut out
+        ));
+        defmt::assert_eq!(out, MOD_EXP_64_OUT);
+    }
+
+    #[test]
+    fn mod_exp_length_err(pka: &mut Pka) {
+        let mut out: [u8; 2] = [0; 2];
+        // even modulus
+        defmt::assert_eq!(
+            pka.mod_exp(&[4], &[13], &[0x01, 0xF0], &mut out),
+            Err(ModExpError::Length)
+        );
+        // empty exponent
+        defmt::assert_eq!(
+            pka.mod_exp(&[4], &[], &[0x01, 0xF1], &mut out),
+            Err(ModExpError::Length)
+        );
+        // output length mismatch
+        defmt::assert_eq!(
+            pka.mod_exp(&[4], &[13], &[0x01, 0xF1], &mut out[..1]),
+            Err(ModExpError::Length)
+        );
+    }
 }
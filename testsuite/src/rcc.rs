@@ -10,7 +10,7 @@ use nucleo_wl55jc_bsp::hal::{
     cortex_m::{self, interrupt::CriticalSection},
     pac,
     pwr::{enter_lprun_msi, exit_lprun, LprunRange},
-    rcc::{self, lsi_hz, set_sysclk_msi_max, setup_lsi, LsiPre, MsiRange, Vos},
+    rcc::{self, lsi_hz, set_sysclk_msi_max, setup_lsi, LsiPre, MsiRange, Peripheral, Vos},
 };
 use panic_probe as _;
 
@@ -133,6 +133,34 @@ mod tests {
         });
     }
 
+    #[test]
+    fn validate(ta: &mut TestArgs) {
+        defmt::assert_eq!(rcc::validate(&ta.rcc, &ta.flash, &ta.pwr), Ok(()));
+
+        // every clock tree set by the HAL is within specification
+        for clk in CLKS.iter() {
+            defmt::info!("validate {}", clk);
+            cortex_m::interrupt::free(|cs| unsafe {
+                clk.set(&mut ta.flash, &mut ta.pwr, &mut ta.rcc, cs)
+            });
+            defmt::assert_eq!(rcc::validate(&ta.rcc, &ta.flash, &ta.pwr), Ok(()));
+        }
+
+        // extra wait states are within specification at 4 MHz
+        cortex_m::interrupt::free(|cs| unsafe {
+            SysClkSrc::Msi(MsiRange::Range4M).set(&mut ta.flash, &mut ta.pwr, &mut ta.rcc, cs)
+        });
+        let acr: u32 = ta.flash.acr.read().bits();
+        ta.flash.acr.modify(|_, w| w.latency().ws2());
+        while !ta.flash.acr.read().latency().is_ws2() {}
+        defmt::assert_eq!(rcc::validate(&ta.rcc, &ta.flash, &ta.pwr), Ok(()));
+        ta.flash.acr.write(|w| unsafe { w.bits(acr) });
+
+        cortex_m::interrupt::free(|cs| unsafe {
+            set_sysclk_msi_max(&mut ta.flash, &mut ta.pwr, &mut ta.rcc, cs)
+        });
+    }
+
     #[test]
     fn enter_exit_lprun(ta: &mut TestArgs) {
         for &lprunrange in LPRUN_RANGES.iter() {
@@ -202,4 +230,152 @@ mod tests {
             assert_eq!(lsi_hz(&ta.rcc), to.hz());
         }
     }
+
+    #[test]
+    fn msi_pll(ta: &mut TestArgs) {
+        // LSE is disabled by a backup domain reset
+        unsafe { rcc::pulse_reset_backup_domain(&mut ta.rcc, &mut ta.pwr) };
+        defmt::assert!(ta.rcc.bdcr.read().lserdy().is_not_ready());
+        defmt::assert_eq!(
+            rcc::enable_msi_pll(&mut ta.rcc),
+            Err(rcc::Error::LseNotReady)
+        );
+        defmt::assert!(!rcc::is_msi_pll_enabled(&ta.rcc));
+
+        ta.pwr.cr1.modify(|_, w| w.dbp().enabled());
+        ta.rcc.bdcr.modify(|_, w| w.lseon().on());
+        while ta.rcc.bdcr.read().lserdy().is_not_ready() {}
+
+        unwrap!(rcc::enable_msi_pll(&mut ta.rcc));
+        defmt::assert!(rcc::is_msi_pll_enabled(&ta.rcc));
+        defmt::assert!(ta.rcc.cr.read().msirdy().is_ready());
+
+        rcc::disable_msi_pll(&mut ta.rcc);
+        defmt::assert!(!rcc::is_msi_pll_enabled(&ta.rcc));
+        unsafe { rcc::pulse_reset_backup_domain(&mut ta.rcc, &mut ta.pwr) };
+    }
+
+    #[test]
+    fn peripheral_enable_reset(ta: &mut TestArgs) {
+        let cases: [(Peripheral, fn(&pac::RCC) -> bool); 31] = [
+            (Peripheral::Dma1, |rcc| {
+                rcc.ahb1enr.read().dma1en().bit_is_set()
+            }),
+            (Peripheral::Dma2, |rcc| {
+                rcc.ahb1enr.read().dma2en().bit_is_set()
+            }),
+            (Peripheral::Dmamux, |rcc| {
+                rcc.ahb1enr.read().dmamux1en().bit_is_set()
+            }),
+            (Peripheral::Crc, |rcc| {
+                rcc.ahb1enr.read().crcen().bit_is_set()
+            }),
+            (Peripheral::GpioA, |rcc| {
+                rcc.ahb2enr.read().gpioaen().bit_is_set()
+            }),
+            (Peripheral::GpioB, |rcc| {
+                rcc.ahb2enr.read().gpioben().bit_is_set()
+            }),
+            (Peripheral::GpioC, |rcc| {
+                rcc.ahb2enr.read().gpiocen().bit_is_set()
+            }),
+            (Peripheral::GpioH, |rcc| {
+                rcc.ahb2enr.read().gpiohen().bit_is_set()
+            }),
+            (Peripheral::Pka, |rcc| {
+                rcc.ahb3enr.read().pkaen().bit_is_set()
+            }),
+            (Peripheral::Aes, |rcc| {
+                rcc.ahb3enr.read().aesen().bit_is_set()
+            }),
+            (Peripheral::Rng, |rcc| {
+                rcc.ahb3enr.read().rngen().bit_is_set()
+            }),
+            (Peripheral::Hsem, |rcc| {
+                rcc.ahb3enr.read().hsemen().bit_is_set()
+            }),
+            (Peripheral::Ipcc, |rcc| {
+                rcc.ahb3enr.read().ipccen().bit_is_set()
+            }),
+            (Peripheral::Tim2, |rcc| {
+                rcc.apb1enr1.read().tim2en().bit_is_set()
+            }),
+            (Peripheral::Spi2, |rcc| {
+                rcc.apb1enr1.read().spi2s2en().bit_is_set()
+            }),
+            (Peripheral::Usart2, |rcc| {
+                rcc.apb1enr1.read().usart2en().bit_is_set()
+            }),
+            (Peripheral::I2c1, |rcc| {
+                rcc.apb1enr1.read().i2c1en().bit_is_set()
+            }),
+            (Peripheral::I2c2, |rcc| {
+                rcc.apb1enr1.read().i2c2en().bit_is_set()
+            }),
+            (Peripheral::I2c3, |rcc| {
+                rcc.apb1enr1.read().i2c3en().bit_is_set()
+            }),
+            (Peripheral::Dac, |rcc| {
+                rcc.apb1enr1.read().dac1en().bit_is_set()
+            }),
+            (Peripheral::Lptim1, |rcc| {
+                rcc.apb1enr1.read().lptim1en().bit_is_set()
+            }),
+            (Peripheral::Lpuart, |rcc| {
+                rcc.apb1enr2.read().lpuart1en().bit_is_set()
+            }),
+            (Peripheral::Lptim2, |rcc| {
+                rcc.apb1enr2.read().lptim2en().bit_is_set()
+            }),
+            (Peripheral::Lptim3, |rcc| {
+                rcc.apb1enr2.read().lptim3en().bit_is_set()
+            }),
+            (Peripheral::Adc, |rcc| {
+                rcc.apb2enr.read().adcen().bit_is_set()
+            }),
+            (Peripheral::Tim1, |rcc| {
+                rcc.apb2enr.read().tim1en().bit_is_set()
+            }),
+            (Peripheral::Spi1, |rcc| {
+                rcc.apb2enr.read().spi1en().bit_is_set()
+            }),
+            (Peripheral::Usart1, |rcc| {
+                rcc.apb2enr.read().usart1en().bit_is_set()
+            }),
+            (Peripheral::Tim16, |rcc| {
+                rcc.apb2enr.read().tim16en().bit_is_set()
+            }),
+            (Peripheral::Tim17, |rcc| {
+                rcc.apb2enr.read().tim17en().bit_is_set()
+            }),
+            (Peripheral::Spi3, |rcc| {
+                rcc.apb3enr.read().subghzspien().bit_is_set()
+            }),
+        ];
+
+        for (periph, is_set) in cases {
+            let was_enabled: bool = is_set(&ta.rcc);
+
+            rcc::enable(&mut ta.rcc, periph);
+            defmt::assert!(is_set(&ta.rcc), "{} not enabled", periph);
+            defmt::assert!(rcc::is_enabled(&ta.rcc, periph));
+
+            unsafe { rcc::disable(&mut ta.rcc, periph) };
+            defmt::assert!(!is_set(&ta.rcc), "{} not disabled", periph);
+            defmt::assert!(!rcc::is_enabled(&ta.rcc, periph));
+
+            if was_enabled {
+                rcc::enable(&mut ta.rcc, periph);
+            }
+        }
+
+        // reset restores the CRC initial value register
+        rcc::enable(&mut ta.rcc, Peripheral::Crc);
+        let crc: &pac::crc::RegisterBlock = unsafe { &*pac::CRC::PTR };
+        crc.init.write(|w| w.init().bits(0x1234_5678));
+        defmt::assert_eq!(crc.init.read().bits(), 0x1234_5678);
+        unsafe { rcc::reset(&mut ta.rcc, Peripheral::Crc) };
+        defmt::assert_eq!(crc.init.read().bits(), 0xFFFF_FFFF);
+        unsafe { rcc::disable(&mut ta.rcc, Peripheral::Crc) };
+    }
 }
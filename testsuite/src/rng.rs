@@ -102,4 +102,11 @@ mod tests {
         unwrap!(rng.try_fill_u8(&mut bytes));
         validate_randomness(&bytes)
     }
+
+    #[test]
+    fn conditioned(rng: &mut Rng) {
+        let mut bytes: [u8; 1024] = [0; 1024];
+        unwrap!(rng.fill_bytes_conditioned(&mut bytes));
+        validate_randomness(&bytes)
+    }
 }
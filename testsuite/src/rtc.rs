@@ -89,6 +89,14 @@ fn test_set_date_time_with_clk(clk: rtc::Clk) -> Rtc {
     );
     defmt::assert!(after > before);
 
+    let (instant, ss): (NaiveDateTime, u16) = unwrap!(rtc.instant());
+    defmt::assert!(instant >= rtc_date_time);
+    // safety: atomic read with no side effects
+    let prediv_s: u16 = unsafe { (*pac::RTC::PTR).prer.read().prediv_s().bits() };
+    defmt::assert!(ss <= prediv_s);
+    let subsecond: u16 = rtc.subsecond();
+    defmt::debug!("SS instant {} subsecond {}", ss, subsecond);
+
     rtc
 }
 
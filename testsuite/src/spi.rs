@@ -15,7 +15,8 @@ use nucleo_wl55jc_bsp::hal::{
     pac::{self, DWT},
     rcc,
     spi::{
-        BaudRate, Mode, NoMiso, NoMosi, NoSck, Phase, Polarity, Spi, MODE_0, MODE_1, MODE_2, MODE_3,
+        BaudRate, BitOrder, Mode, NoMiso, NoMosi, NoSck, Phase, Polarity, Spi, MODE_0, MODE_1,
+        MODE_2, MODE_3,
     },
 };
 use panic_probe as _;
@@ -95,6 +96,33 @@ impl SpiSlave {
         }
     }
 
+    fn set_16bit_lsb_first(&mut self) {
+        self.spi.cr1.modify(|_, w| w.spe().clear_bit());
+        self.spi
+            .cr2
+            .modify(|_, w| w.ds().sixteen_bit().frxth().half());
+        self.spi
+            .cr1
+            .modify(|_, w| w.lsbfirst().set_bit().spe().set_bit());
+    }
+
+    fn read_word16(&mut self) -> u16 {
+        loop {
+            if !self.spi.sr.read().frlvl().is_empty() {
+                return unsafe { read_volatile(self.spi.dr.as_ptr() as *const u16) };
+            }
+        }
+    }
+
+    fn write_word16(&mut self, word: u16) {
+        loop {
+            if !self.spi.sr.read().ftlvl().is_full() {
+                unsafe { write_volatile(self.spi.dr.as_ptr() as *mut u16, word) };
+                return;
+            }
+        }
+    }
+
     fn read(&mut self, words: &mut [u8]) {
         words.iter_mut().for_each(|word| *word = self.read_word())
     }
@@ -316,4 +344,52 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn full_duplex_16bit_lsb_first() {
+        const DATA16: [u16; 3] = [0x1234, 0xABCD, 0x00FF];
+
+        for &mode in SPI_MODES.iter() {
+            defmt::debug!("MODE_{}", mode_num(mode));
+            let mut ta: TestArgs = unsafe { setup() };
+
+            let mut s = cortex_m::interrupt::free(|cs| {
+                SpiSlave::new(ta.spi2, mode, false, &mut ta.rcc, cs)
+            });
+            s.set_16bit_lsb_first();
+
+            let mut m = cortex_m::interrupt::free(|cs| {
+                Spi::new_spi1_full_duplex(
+                    ta.spi1,
+                    (ta.pa.a5, ta.pa.a6, ta.pa.a7),
+                    mode,
+                    BaudRate::Div32,
+                    &mut ta.rcc,
+                    cs,
+                )
+            });
+            m.set_frame_size(16);
+            m.set_bit_order(BitOrder::LsbFirst);
+
+            s.set_ssi(false);
+            unwrap!(m.write16(&DATA16));
+            let mut buf: [u16; 3] = [0; 3];
+            buf.iter_mut().for_each(|word| *word = s.read_word16());
+            s.set_ssi(true);
+            defmt::assert_eq!(buf, DATA16);
+
+            s.set_ssi(false);
+            DATA16.iter().for_each(|word| s.write_word16(*word));
+            let mut buf: [u16; 3] = [0x5555, 0xAAAA, 0x0F0F];
+            unwrap!(m.transfer16(&mut buf));
+            s.set_ssi(true);
+            defmt::assert_eq!(buf, DATA16);
+
+            let mut slave_buf: [u16; 3] = [0; 3];
+            slave_buf
+                .iter_mut()
+                .for_each(|word| *word = s.read_word16());
+            defmt::assert_eq!(slave_buf, [0x5555, 0xAAAA, 0x0F0F]);
+        }
+    }
 }
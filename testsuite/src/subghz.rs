@@ -19,7 +19,7 @@ use bsp::{
         spi::{SgMiso, SgMosi},
         subghz::{
             rfbusys, wakeup, AddrComp, CalibrateImage, CfgIrq, CmdStatus, CodingRate, CrcType,
-            FallbackMode, FskBandwidth, FskBitrate, FskFdev, FskModParams, FskPulseShape,
+            Error, FallbackMode, FskBandwidth, FskBitrate, FskFdev, FskModParams, FskPulseShape,
             GenericPacketParams, HeaderType, Irq, LoRaBandwidth, LoRaModParams, LoRaPacketParams,
             LoRaSyncWord, Ocp, PaConfig, PacketType, PktCtrl, PreambleDetection, RampTime, RegMode,
             RfFreq, SleepCfg, SpreadingFactor, StandbyClk, Startup, Status, StatusMode, SubGhz,
@@ -364,6 +364,24 @@ mod tests {
         defmt::info!("Cycles 0B: {}", end - start);
     }
 
+    #[test]
+    fn buffer_offset(ta: &mut TestArgs) {
+        const PING: [u8; 4] = *b"ping";
+        const PONG: [u8; 4] = *b"pong";
+        while rfbusys() {}
+        unwrap!(ta.sg.write_buffer_offset(0, &PING));
+        unwrap!(ta.sg.write_buffer_offset(252, &PONG));
+
+        let mut buf: [u8; 4] = [0; 4];
+        unwrap!(ta.sg.read_buffer_offset(0, &mut buf));
+        defmt::assert_eq!(buf, PING);
+        unwrap!(ta.sg.read_buffer_offset(252, &mut buf));
+        defmt::assert_eq!(buf, PONG);
+
+        defmt::assert_eq!(ta.sg.write_buffer_offset(253, &PING), Err(Error::Buffer));
+        defmt::assert_eq!(ta.sg.read_buffer_offset(253, &mut buf), Err(Error::Buffer));
+    }
+
     #[test]
     fn sleep_enter_exit(ta: &mut TestArgs) {
         const SLEEP_CFG: SleepCfg = SleepCfg::new()
@@ -382,6 +400,33 @@ mod tests {
         defmt::assert_eq!(status.mode(), Ok(StatusMode::StandbyRc));
     }
 
+    #[test]
+    fn power_state_transitions(ta: &mut TestArgs) {
+        unwrap!(ta.sg.set_standby(StandbyClk::Rc));
+        let status: Status = unwrap!(ta.sg.status());
+        defmt::assert_eq!(status.mode(), Ok(StatusMode::StandbyRc));
+
+        unwrap!(ta.sg.set_rf_frequency(&RF_FREQ));
+        unwrap!(ta.sg.set_fs());
+        let status: Status = unwrap!(ta.sg.status());
+        defmt::assert_eq!(status.mode(), Ok(StatusMode::Fs));
+
+        unwrap!(ta.sg.set_standby(StandbyClk::Rc));
+        let status: Status = unwrap!(ta.sg.status());
+        defmt::assert_eq!(status.mode(), Ok(StatusMode::StandbyRc));
+
+        unwrap!(unsafe { ta.sg.set_sleep(SleepCfg::default()) });
+        ta.delay.delay_us(500);
+
+        // radio idles with busy high in sleep mode
+        defmt::assert_eq!(ta.sg.set_standby(StandbyClk::Rc), Err(Error::Timeout));
+
+        unsafe { wakeup() }
+        unwrap!(ta.sg.set_standby(StandbyClk::Rc));
+        let status: Status = unwrap!(ta.sg.status());
+        defmt::assert_eq!(status.mode(), Ok(StatusMode::StandbyRc));
+    }
+
     #[test]
     fn read_write_register(ta: &mut TestArgs) {
         let original_value = unwrap!(ta.sg.init_whitening());
@@ -0,0 +1,145 @@
+#![no_std]
+#![no_main]
+
+use defmt::unwrap;
+use defmt_rtt as _; // global logger
+use nucleo_wl55jc_bsp::hal::{
+    cortex_m,
+    gpio::{pins, Output, PinState, PortA, PortB},
+    pac, rcc,
+    timer::{Channel, Direction, EncoderMode, Tim2},
+};
+use panic_probe as _;
+
+// encoder A and B phases for one cycle, A leading B
+const CYCLE: [(PinState, PinState); 4] = [
+    (PinState::High, PinState::Low),
+    (PinState::High, PinState::High),
+    (PinState::Low, PinState::High),
+    (PinState::Low, PinState::Low),
+];
+
+struct TestArgs {
+    tim2: Tim2,
+    a: Output<pins::B12>,
+    b: Output<pins::B14>,
+}
+
+impl TestArgs {
+    fn set(&mut self, (a, b): (PinState, PinState)) {
+        self.a.set_level(a);
+        self.b.set_level(b);
+        // allow the edges to pass through the input synchronizers
+        cortex_m::asm::delay(1_000);
+    }
+
+    fn forward(&mut self) {
+        CYCLE.iter().for_each(|&state| self.set(state))
+    }
+
+    fn backward(&mut self) {
+        CYCLE
+            .iter()
+            .rev()
+            .skip(1)
+            .chain(CYCLE.iter().rev().take(1))
+            .for_each(|&state| self.set(state))
+    }
+}
+
+#[defmt_test::tests]
+mod tests {
+    use super::*;
+
+    #[init]
+    fn init() -> TestArgs {
+        let mut dp: pac::Peripherals = unwrap!(pac::Peripherals::take());
+        let gpioa: PortA = PortA::split(dp.GPIOA, &mut dp.RCC);
+        let gpiob: PortB = PortB::split(dp.GPIOB, &mut dp.RCC);
+        let mut tim2: Tim2 = Tim2::new(dp.TIM2, &mut dp.RCC);
+
+        let (a, b) = cortex_m::interrupt::free(|cs| {
+            // the pin wrappers are not needed after setting the alternate function
+            let _ = tim2.new_ch1_pin(gpioa.a0, cs);
+            let _ = tim2.new_ch2_pin(gpioa.a1, cs);
+            (
+                Output::default(gpiob.b12, cs),
+                Output::default(gpiob.b14, cs),
+            )
+        });
+
+        TestArgs { tim2, a, b }
+    }
+
+    #[test]
+    fn encoder_x4(ta: &mut TestArgs) {
+        defmt::warn!("Pins B12 and B14 must be connected to pins A0 and A1 for this test to pass");
+
+        ta.tim2.encoder(EncoderMode::X4);
+        defmt::assert_eq!(ta.tim2.count(), 0);
+
+        ta.forward();
+        defmt::assert_eq!(ta.tim2.count(), 4);
+        defmt::assert_eq!(ta.tim2.direction(), Direction::Up);
+
+        // wraps below zero
+        ta.backward();
+        ta.backward();
+        defmt::assert_eq!(ta.tim2.count(), u16::MAX - 3);
+        defmt::assert_eq!(ta.tim2.direction(), Direction::Down);
+
+        // wraps above u16::MAX
+        ta.forward();
+        ta.forward();
+        defmt::assert_eq!(ta.tim2.count(), 4);
+        defmt::assert_eq!(ta.tim2.direction(), Direction::Up);
+    }
+
+    #[test]
+    fn encoder_x2(ta: &mut TestArgs) {
+        ta.tim2.encoder(EncoderMode::X2Ti1);
+        defmt::assert_eq!(ta.tim2.count(), 0);
+
+        ta.forward();
+        defmt::assert_eq!(ta.tim2.count(), 2);
+
+        ta.backward();
+        defmt::assert_eq!(ta.tim2.count(), 0);
+        defmt::assert_eq!(ta.tim2.direction(), Direction::Down);
+    }
+
+    #[test]
+    fn encoder_then_pwm(ta: &mut TestArgs) {
+        let rcc: pac::RCC = unsafe { pac::Peripherals::steal() }.RCC;
+
+        // leave the counter counting down in encoder mode
+        ta.tim2.encoder(EncoderMode::X4);
+        ta.backward();
+        defmt::assert_eq!(ta.tim2.direction(), Direction::Down);
+
+        // channel 3 has no pin, the encoder inputs are not driven
+        unwrap!(ta.tim2.pwm(Channel::Ch3, 1_000, u16::MAX / 2, &rcc));
+        defmt::assert_eq!(ta.tim2.direction(), Direction::Up);
+
+        // the counter runs without encoder edges
+        let start: u16 = ta.tim2.count();
+        cortex_m::asm::delay(1_000);
+        defmt::assert_ne!(ta.tim2.count(), start);
+
+        ta.tim2.disable_channel(Channel::Ch3);
+    }
+
+    #[test]
+    fn monotonic_us(ta: &mut TestArgs) {
+        let rcc: pac::RCC = unsafe { pac::Peripherals::steal() }.RCC;
+        let sysclk_hz: u32 = rcc::sysclk_hz(&rcc);
+        unwrap!(ta.tim2.monotonic_us(&rcc));
+
+        // at least 1 ms of CPU cycles
+        let start: u32 = ta.tim2.now_us();
+        cortex_m::asm::delay(sysclk_hz / 1_000);
+        let elapsed: u32 = ta.tim2.now_us().wrapping_sub(start);
+        defmt::info!("elapsed {} us", elapsed);
+        defmt::assert!((1_000..3_000).contains(&elapsed));
+    }
+}